@@ -136,6 +136,8 @@ impl App<'_> {
             (Message::EccPage(message), Page::EccClac(page)) => page.update(message),
             (Message::SM2Page(message), Page::SM2Clac(page)) => page.update(message),
 
+            (Message::SecretPage(message), Page::Secret(page)) => page.update(message),
+
              _ => panic!("Message, Page pair not valid."),
 
         }
@@ -149,7 +151,10 @@ impl App<'_> {
     }
 
     pub fn view(&self) -> Element<Message> {
-
+        match &self.current_page {
+            Page::Secret(page) => page.view().map(Message::SecretPage),
+            _ => Container::new(Row::new()).into(),
+        }
     }
 
     pub fn theme(&self) -> Theme {
@@ -0,0 +1,255 @@
+/**************************************************************************************************
+ * @file secret.rs
+ * @authors Lucien
+ * @brief
+ * @n the secret page edits and displays a typed secret.
+ * @n The editor is built from the field specs of the selected kind and the viewer
+ * @n from the view rows of a payload; sensitive values stay masked until revealed.
+ *
+ * @version 0.1.0
+ * @date 2025-06-24
+ *
+ * @copyright
+ * @n Copyright (c) 2021 by Loyss Studio., Division All rights reserved.
+ * @n http://www.loyss.cn
+ *
+**************************************************************************************************/
+
+/**************************************************************************************************
+ * Import External Packages
+**************************************************************************************************/
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use iced::widget::{button, column, pick_list, row, scrollable, text, text_editor, text_input, Column};
+use iced::{Element, Fill, Font};
+
+
+/**************************************************************************************************
+ * Import Internal Packages
+**************************************************************************************************/
+use shared::secret::{SecretKind, SecretPayload};
+
+use crate::logic::secret::{editor_fields, kind_label, viewer_rows, FieldInput, FieldSpec, SecretForm};
+
+
+/**************************************************************************************************
+ * Declaration Types
+**************************************************************************************************/
+// 遮盖后显示的内容
+const MASK: &str = "••••••••";
+// 多行输入框的高度
+const MULTILINE_HEIGHT: f32 = 120.0;
+
+/// 类型下拉框的选项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KindOption(pub SecretKind);
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    KindSelected(KindOption),
+    FieldChanged(&'static str, String),
+    EditorAction(&'static str, text_editor::Action),
+    /// 切换某个敏感字段或查看器行的显示状态
+    RevealToggled(&'static str),
+    Submitted,
+    EditRequested,
+}
+
+pub struct SecretPage {
+    form: SecretForm,
+    /// 多行和列表字段的编辑器内容
+    editors: BTreeMap<&'static str, text_editor::Content>,
+    /// 已显示内容的敏感字段名或查看器行标签
+    revealed: BTreeSet<&'static str>,
+    /// 查看中的密钥内容，为空时处于编辑状态
+    payload: Option<SecretPayload>,
+    /// 编辑已有密钥时不能修改类型
+    existing: bool,
+    error: Option<&'static str>,
+    submitted: Option<SecretPayload>,
+}
+
+
+/**************************************************************************************************
+ * Realize the KindOption Struct
+**************************************************************************************************/
+impl fmt::Display for KindOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(kind_label(self.0))
+    }
+}
+
+
+/**************************************************************************************************
+ * Realize the SecretPage Struct
+**************************************************************************************************/
+impl SecretPage {
+    /// 新建密钥的编辑器
+    pub fn new(kind: SecretKind) -> Self {
+        Self::editor(SecretForm::new(kind), false)
+    }
+
+    /// 查看已有密钥
+    pub fn view_payload(payload: SecretPayload) -> Self {
+        let mut page = Self::editor(SecretForm::from_payload(&payload), true);
+        page.payload = Some(payload);
+        page
+    }
+
+    /// 取出通过校验的提交内容，由调用方发送到服务端
+    pub fn take_submitted(&mut self) -> Option<SecretPayload> {
+        self.submitted.take()
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::KindSelected(KindOption(kind)) => {
+                if !self.existing && kind != self.form.kind {
+                    *self = Self::new(kind);
+                }
+            }
+            Message::FieldChanged(name, value) => {
+                self.form.set(name, &value);
+                self.error = None;
+            }
+            Message::EditorAction(name, action) => {
+                if let Some(content) = self.editors.get_mut(name) {
+                    content.perform(action);
+                    let value = content.text();
+                    self.form.set(name, &value);
+                    self.error = None;
+                }
+            }
+            Message::RevealToggled(name) => {
+                if !self.revealed.remove(name) {
+                    self.revealed.insert(name);
+                }
+            }
+            Message::Submitted => match self.form.to_payload() {
+                Ok(payload) => {
+                    self.submitted = Some(payload.clone());
+                    self.payload = Some(payload);
+                    self.existing = true;
+                    self.revealed.clear();
+                }
+                Err(error) => self.error = Some(error),
+            },
+            Message::EditRequested => {
+                if let Some(payload) = self.payload.take() {
+                    *self = Self::editor(SecretForm::from_payload(&payload), true);
+                }
+            }
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let content = match &self.payload {
+            Some(payload) => self.view_viewer(payload),
+            None => self.view_editor(),
+        };
+        scrollable(content.padding(20).spacing(12).width(Fill)).into()
+    }
+
+    fn editor(form: SecretForm, existing: bool) -> Self {
+        let editors = editor_fields(form.kind)
+            .iter()
+            .filter(|spec| matches!(spec.input, FieldInput::Multiline | FieldInput::List))
+            .map(|spec| (spec.name, text_editor::Content::with_text(form.get(spec.name))))
+            .collect();
+        Self {
+            form,
+            editors,
+            revealed: BTreeSet::new(),
+            payload: None,
+            existing,
+            error: None,
+            submitted: None,
+        }
+    }
+
+    // 按类型的字段定义生成表单
+    fn view_editor(&self) -> Column<'_, Message> {
+        let mut content = if self.existing {
+            column![text(format!("类型：{}", kind_label(self.form.kind)))]
+        } else {
+            let kinds = SecretKind::ALL.map(KindOption).to_vec();
+            column![text("类型"), pick_list(kinds, Some(KindOption(self.form.kind)), Message::KindSelected)]
+        };
+
+        for spec in editor_fields(self.form.kind) {
+            let label = if spec.required { format!("{} *", spec.label) } else { spec.label.to_string() };
+            content = content.push(text(label)).push(self.view_field(spec));
+        }
+
+        if let Some(error) = self.error {
+            content = content.push(text(error).style(text::danger));
+        }
+        content.push(button(text("保存")).on_press(Message::Submitted))
+    }
+
+    fn view_field(&self, spec: &FieldSpec) -> Element<'_, Message> {
+        let name = spec.name;
+        let value = self.form.get(name);
+        match spec.input {
+            FieldInput::Text => text_input(spec.label, value)
+                .on_input(move |value| Message::FieldChanged(name, value))
+                .into(),
+            FieldInput::Secret => {
+                let revealed = self.revealed.contains(name);
+                row![
+                    text_input(spec.label, value)
+                        .secure(!revealed)
+                        .font(Font::MONOSPACE)
+                        .on_input(move |value| Message::FieldChanged(name, value)),
+                    button(text(if revealed { "隐藏" } else { "显示" })).on_press(Message::RevealToggled(name)),
+                ]
+                .spacing(8)
+                .into()
+            }
+            FieldInput::Multiline | FieldInput::List => match self.editors.get(name) {
+                Some(content) => text_editor(content)
+                    .font(Font::MONOSPACE)
+                    .height(MULTILINE_HEIGHT)
+                    .on_action(move |action| Message::EditorAction(name, action))
+                    .into(),
+                None => text(value).into(),
+            },
+            FieldInput::Number => text_input(spec.label, value)
+                .on_input(move |value| {
+                    Message::FieldChanged(name, value.chars().filter(char::is_ascii_digit).collect())
+                })
+                .into(),
+            FieldInput::Choice(choices) => {
+                let selected = choices.iter().copied().find(|choice| *choice == value);
+                pick_list(choices, selected, move |choice: &'static str| {
+                    Message::FieldChanged(name, choice.to_string())
+                })
+                .into()
+            }
+        }
+    }
+
+    // 敏感内容默认遮盖，逐行点击显示
+    fn view_viewer(&self, payload: &SecretPayload) -> Column<'_, Message> {
+        let mut content = column![text(kind_label(payload.kind())).size(20)];
+
+        for view_row in viewer_rows(payload) {
+            let revealed = !view_row.masked || self.revealed.contains(view_row.label);
+            let mut value = text(if revealed { view_row.value } else { MASK.to_string() });
+            if view_row.monospace {
+                value = value.font(Font::MONOSPACE);
+            }
+
+            let mut line = row![text(view_row.label).width(120), value.width(Fill)].spacing(8);
+            if view_row.masked {
+                line = line.push(
+                    button(text(if revealed { "隐藏" } else { "显示" })).on_press(Message::RevealToggled(view_row.label)),
+                );
+            }
+            content = content.push(line);
+        }
+
+        content.push(button(text("编辑")).on_press(Message::EditRequested))
+    }
+}
@@ -0,0 +1,511 @@
+/**************************************************************************************************
+ * @file api.rs
+ * @authors Lucien
+ * @brief
+ * @n the api module wraps the key management HTTP endpoints used by the client.
+ * @n Writes carry the key version as If-Match and creates carry an Idempotency-Key,
+ * @n so retries never duplicate keys and concurrent edits surface as conflicts.
+ * @n Vault secrets are encrypted locally; the server only sees ciphertext.
+ * @n Secrets are exchanged as typed payloads; untyped legacy keys are read as text blobs.
+ * @n Key changes are followed over a Server-Sent Events feed resumable by cursor.
+ * @n Requests run in a team context chosen with X-Team-Id; keys of other teams stay invisible.
+ *
+ * @version 0.1.0
+ * @date 2025-06-24
+ *
+ * @copyright
+ * @n Copyright (c) 2021 by Loyss Studio., Division All rights reserved.
+ * @n http://www.loyss.cn
+ *
+**************************************************************************************************/
+
+/**************************************************************************************************
+ * Import External Packages
+**************************************************************************************************/
+use reqwest::{header, Client, Method, RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+
+/**************************************************************************************************
+ * Import Internal Packages
+**************************************************************************************************/
+use shared::crypto::vault::{self, Ciphertext, KdfParams, VAULT_KEY_SIZE};
+use shared::secret::{SecretKind, SecretPayload};
+
+
+/**************************************************************************************************
+ * Declaration Types
+**************************************************************************************************/
+#[derive(Debug, Clone, Deserialize)]
+pub struct Key {
+    pub id: u64,
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    pub version: u32,
+    /// 为空表示未声明类型的旧密钥
+    pub kind: Option<SecretKind>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateKey {
+    pub name: String,
+    pub payload: SecretPayload,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct UpdateKey {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<SecretPayload>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub labels: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Vault {
+    pub id: u64,
+    pub name: String,
+    pub kdf: KdfParams,
+    pub wrapped_key: Ciphertext,
+    pub version: u32,
+}
+
+/// 已解锁的保险库，保险库密钥只保存在客户端内存中
+pub struct UnlockedVault {
+    pub vault: Vault,
+    key: [u8; VAULT_KEY_SIZE],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum KeyEventKind {
+    #[serde(rename = "key.created")]
+    Created,
+    #[serde(rename = "key.updated")]
+    Updated,
+    #[serde(rename = "key.deleted")]
+    Deleted,
+}
+
+/// 密钥变更事件，`cursor`用于断线后从此处续传
+#[derive(Debug, Clone, Deserialize)]
+pub struct KeyEvent {
+    pub cursor: u64,
+    pub event: KeyEventKind,
+    pub key_id: u64,
+    pub name: String,
+    /// 变更后的版本号，删除事件为空
+    pub version: Option<u32>,
+}
+
+/// 调用方所属的团队
+#[derive(Debug, Clone, Deserialize)]
+pub struct Team {
+    pub id: u64,
+    pub organization_id: u64,
+    pub organization_name: String,
+    pub name: String,
+}
+
+/// 调用方的团队列表及服务器当前使用的团队
+#[derive(Debug, Clone, Deserialize)]
+pub struct CallerTeams {
+    pub current_team_id: Option<u64>,
+    pub teams: Vec<Team>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyMaterial {
+    data: Option<String>,
+    payload: Option<SecretPayload>,
+    nonce: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum ApiError {
+    /// 密钥已被他人修改或删除，需要刷新后重试
+    Conflict(String),
+    NotFound,
+    /// 同一幂等键的请求仍在处理中
+    InProgress,
+    /// 主密码错误，无法解开保险库密钥
+    WrongPassword,
+    Crypto(&'static str),
+    /// 密钥内容未通过本地校验
+    Invalid(&'static str),
+    Server(StatusCode, String),
+    Network(reqwest::Error),
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::Conflict(_) => f.write_str("该密钥已被其他用户修改，请刷新后再试"),
+            ApiError::NotFound => f.write_str("密钥不存在或已被删除"),
+            ApiError::InProgress => f.write_str("相同的请求正在处理中，请稍后刷新"),
+            ApiError::WrongPassword => f.write_str("主密码错误"),
+            ApiError::Crypto(message) => write!(f, "加密错误: {}", message),
+            ApiError::Invalid(message) => write!(f, "内容无效: {}", message),
+            ApiError::Server(status, message) => write!(f, "服务器错误 ({}): {}", status, message),
+            ApiError::Network(e) => write!(f, "网络错误: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<reqwest::Error> for ApiError {
+    fn from(e: reqwest::Error) -> Self {
+        ApiError::Network(e)
+    }
+}
+
+
+/**************************************************************************************************
+ * Declaration KeyClient Struct
+**************************************************************************************************/
+pub struct KeyClient {
+    http: Client,
+    base_url: String,
+    token: String,
+    /// 为空时服务器使用调用方的默认团队
+    team_id: Option<u64>,
+}
+
+
+/**************************************************************************************************
+ * Realize the KeyClient Struct
+**************************************************************************************************/
+impl KeyClient {
+    pub fn new(base_url: impl Into<String>, token: impl Into<String>) -> Self {
+        Self {
+            http: Client::new(),
+            base_url: base_url.into(),
+            token: token.into(),
+            team_id: None,
+        }
+    }
+
+    /// 切换团队上下文，之后的请求只能看到该团队的密钥
+    pub fn set_team(&mut self, team_id: Option<u64>) {
+        self.team_id = team_id;
+    }
+
+    pub fn team(&self) -> Option<u64> {
+        self.team_id
+    }
+
+    /// 列出调用方所属的团队，供切换团队时选择
+    pub async fn teams(&self) -> Result<CallerTeams, ApiError> {
+        let response = self
+            .request(Method::GET, format!("{}/teams", self.base_url))
+            .send()
+            .await?;
+
+        Ok(check(response).await?.json().await?)
+    }
+
+    /// 创建密钥，`idempotency_key`在重试同一次创建时必须保持不变
+    pub async fn create_key(&self, request: &CreateKey, idempotency_key: &str) -> Result<Key, ApiError> {
+        request.payload.validate().map_err(ApiError::Invalid)?;
+
+        let response = self
+            .request(Method::POST, format!("{}/keys", self.base_url))
+            .header("Idempotency-Key", idempotency_key)
+            .json(request)
+            .send()
+            .await?;
+
+        Ok(check(response).await?.json().await?)
+    }
+
+    pub async fn get_key(&self, id: u64) -> Result<Key, ApiError> {
+        let response = self
+            .request(Method::GET, format!("{}/keys/{}", self.base_url, id))
+            .send()
+            .await?;
+
+        Ok(check(response).await?.json().await?)
+    }
+
+    /// 读取密钥内容，未声明类型的旧密钥按文本处理
+    pub async fn get_key_payload(&self, id: u64) -> Result<SecretPayload, ApiError> {
+        let response = self
+            .request(Method::GET, format!("{}/keys/{}/material", self.base_url, id))
+            .send()
+            .await?;
+        let material: KeyMaterial = check(response).await?.json().await?;
+
+        match (material.payload, material.data) {
+            (Some(payload), _) => Ok(payload),
+            (None, Some(data)) if material.nonce.is_none() => Ok(SecretPayload::from_legacy(data)),
+            _ => Err(ApiError::Crypto("key is client-encrypted, unlock its vault first")),
+        }
+    }
+
+    /// 修改密钥，只有服务端版本仍为`key.version`时才会生效
+    pub async fn update_key(&self, key: &Key, request: &UpdateKey) -> Result<Key, ApiError> {
+        if let Some(payload) = &request.payload {
+            payload.validate().map_err(ApiError::Invalid)?;
+        }
+
+        let response = self
+            .request(Method::PUT, format!("{}/keys/{}", self.base_url, key.id))
+            .header(header::IF_MATCH, format!("\"{}\"", key.version))
+            .json(request)
+            .send()
+            .await?;
+
+        Ok(check(response).await?.json().await?)
+    }
+
+    /// 删除密钥，只有服务端版本仍为`key.version`时才会生效
+    pub async fn delete_key(&self, key: &Key) -> Result<(), ApiError> {
+        let response = self
+            .request(Method::DELETE, format!("{}/keys/{}", self.base_url, key.id))
+            .header(header::IF_MATCH, format!("\"{}\"", key.version))
+            .send()
+            .await?;
+
+        check(response).await?;
+        Ok(())
+    }
+
+    /// 订阅密钥变更事件，`cursor`为空时只接收订阅之后的变更
+    pub async fn key_events(&self, cursor: Option<u64>) -> Result<KeyEventStream, ApiError> {
+        let mut request = self
+            .request(Method::GET, format!("{}/events/keys", self.base_url))
+            .header(header::ACCEPT, "text/event-stream");
+        if let Some(cursor) = cursor {
+            request = request.header("Last-Event-ID", cursor.to_string());
+        }
+
+        Ok(KeyEventStream {
+            response: check(request.send().await?).await?,
+            buffer: Vec::new(),
+        })
+    }
+
+    /// 创建保险库：本地生成保险库密钥，用主密码派生的密钥包装后上传
+    pub async fn create_vault(&self, name: &str, password: &str) -> Result<UnlockedVault, ApiError> {
+        let kdf = KdfParams::recommended().ok_or(ApiError::Crypto("failed to generate salt"))?;
+        let kek = derive_key(kdf.clone(), password).await?;
+        let key = vault::generate_vault_key().ok_or(ApiError::Crypto("failed to generate vault key"))?;
+        let wrapped_key = vault::wrap_key(&kek, &key).ok_or(ApiError::Crypto("failed to wrap vault key"))?;
+
+        let response = self
+            .request(Method::POST, format!("{}/vaults", self.base_url))
+            .json(&CreateVault { name, kdf: &kdf, wrapped_key: &wrapped_key })
+            .send()
+            .await?;
+
+        Ok(UnlockedVault { vault: check(response).await?.json().await?, key })
+    }
+
+    /// 用主密码解锁保险库
+    pub async fn unlock_vault(&self, id: u64, password: &str) -> Result<UnlockedVault, ApiError> {
+        let response = self
+            .request(Method::GET, format!("{}/vaults/{}", self.base_url, id))
+            .send()
+            .await?;
+        let vault: Vault = check(response).await?.json().await?;
+
+        let kek = derive_key(vault.kdf.clone(), password).await?;
+        let key = vault::unwrap_key(&kek, &vault.wrapped_key).ok_or(ApiError::WrongPassword)?;
+        Ok(UnlockedVault { vault, key })
+    }
+
+    /// 修改主密码，只重新包装保险库密钥，已有密文保持不变
+    pub async fn change_vault_password(&self, unlocked: &mut UnlockedVault, new_password: &str) -> Result<(), ApiError> {
+        let kdf = KdfParams::recommended().ok_or(ApiError::Crypto("failed to generate salt"))?;
+        let kek = derive_key(kdf.clone(), new_password).await?;
+        let wrapped_key = vault::wrap_key(&kek, &unlocked.key).ok_or(ApiError::Crypto("failed to wrap vault key"))?;
+
+        let response = self
+            .request(Method::PUT, format!("{}/vaults/{}/wrapped-key", self.base_url, unlocked.vault.id))
+            .header(header::IF_MATCH, format!("\"{}\"", unlocked.vault.version))
+            .json(&RewrapVault { kdf: &kdf, wrapped_key: &wrapped_key })
+            .send()
+            .await?;
+
+        unlocked.vault = check(response).await?.json().await?;
+        Ok(())
+    }
+
+    /// 在保险库中创建密钥，密钥值在本地加密后上传
+    pub async fn create_vault_secret(
+        &self,
+        unlocked: &UnlockedVault,
+        name: &str,
+        payload: &SecretPayload,
+        idempotency_key: &str,
+    ) -> Result<Key, ApiError> {
+        // 服务端看不到明文，只能在本地校验
+        payload.validate().map_err(ApiError::Invalid)?;
+        let json = payload.to_json().ok_or(ApiError::Crypto("failed to encode secret"))?;
+        let sealed = vault::seal_secret(&unlocked.key, json.as_bytes())
+            .ok_or(ApiError::Crypto("failed to encrypt secret"))?;
+
+        let response = self
+            .request(Method::POST, format!("{}/vaults/{}/keys", self.base_url, unlocked.vault.id))
+            .header("Idempotency-Key", idempotency_key)
+            .json(&CreateVaultKey {
+                name,
+                encrypted_data: &sealed.ciphertext,
+                nonce: &sealed.nonce,
+                kind: payload.kind(),
+            })
+            .send()
+            .await?;
+
+        Ok(check(response).await?.json().await?)
+    }
+
+    /// 读取保险库中的密钥并在本地解密
+    pub async fn read_vault_secret(&self, unlocked: &UnlockedVault, key_id: u64) -> Result<SecretPayload, ApiError> {
+        let response = self
+            .request(Method::GET, format!("{}/keys/{}/material", self.base_url, key_id))
+            .send()
+            .await?;
+        let material: KeyMaterial = check(response).await?.json().await?;
+
+        let sealed = Ciphertext {
+            nonce: material.nonce.ok_or(ApiError::Crypto("key is not client-encrypted"))?,
+            ciphertext: material.data.ok_or(ApiError::Crypto("key has no ciphertext"))?,
+        };
+        let plaintext = vault::open_secret(&unlocked.key, &sealed).ok_or(ApiError::Crypto("failed to decrypt secret"))?;
+        let plaintext = String::from_utf8(plaintext).map_err(|_| ApiError::Crypto("secret is not valid UTF-8"))?;
+
+        // 早期保险库密钥为纯文本
+        Ok(SecretPayload::from_json(&plaintext).unwrap_or_else(|| SecretPayload::from_legacy(plaintext)))
+    }
+
+    // 所有请求都携带令牌和当前团队
+    fn request(&self, method: Method, url: String) -> RequestBuilder {
+        let builder = self.http.request(method, url).bearer_auth(&self.token);
+        match self.team_id {
+            Some(team_id) => builder.header("X-Team-Id", team_id.to_string()),
+            None => builder,
+        }
+    }
+}
+
+
+/**************************************************************************************************
+ * Declaration KeyEventStream Struct
+**************************************************************************************************/
+/// 服务端推送的事件流，连接断开后用最后一个事件的cursor重新订阅即可续传
+pub struct KeyEventStream {
+    response: Response,
+    // 尚未组成完整事件的字节，按字节缓存以免截断多字节字符
+    buffer: Vec<u8>,
+}
+
+
+/**************************************************************************************************
+ * Realize the KeyEventStream Struct
+**************************************************************************************************/
+impl KeyEventStream {
+    /// 等待下一条事件，服务端关闭连接时返回None
+    pub async fn next(&mut self) -> Result<Option<KeyEvent>, ApiError> {
+        loop {
+            // 事件之间以空行分隔，心跳注释行没有data字段
+            while let Some(end) = self.buffer.windows(2).position(|window| window == b"\n\n") {
+                let frame: Vec<u8> = self.buffer.drain(..end + 2).collect();
+                let frame = String::from_utf8_lossy(&frame);
+                let data: Vec<&str> = frame
+                    .lines()
+                    .filter_map(|line| line.strip_prefix("data:"))
+                    .map(|data| data.strip_prefix(' ').unwrap_or(data))
+                    .collect();
+                if data.is_empty() {
+                    continue;
+                }
+                let event = serde_json::from_str(&data.join("\n"))
+                    .map_err(|_| ApiError::Invalid("malformed key event"))?;
+                return Ok(Some(event));
+            }
+
+            match self.response.chunk().await? {
+                Some(chunk) => self.buffer.extend(chunk.iter().filter(|&&byte| byte != b'\r')),
+                None => return Ok(None),
+            }
+        }
+    }
+}
+
+
+/**************************************************************************************************
+ * Declaration Request Bodies
+**************************************************************************************************/
+#[derive(Serialize)]
+struct CreateVault<'a> {
+    name: &'a str,
+    kdf: &'a KdfParams,
+    wrapped_key: &'a Ciphertext,
+}
+
+#[derive(Serialize)]
+struct RewrapVault<'a> {
+    kdf: &'a KdfParams,
+    wrapped_key: &'a Ciphertext,
+}
+
+#[derive(Serialize)]
+struct CreateVaultKey<'a> {
+    name: &'a str,
+    encrypted_data: &'a str,
+    nonce: &'a str,
+    kind: SecretKind,
+}
+
+
+/**************************************************************************************************
+ * Function: derive_key
+ * Parameter:
+ *    - kdf: KDF参数
+ *    - password: 主密码
+ * Return:
+ *    - Result: 派生出的包装密钥
+ * Description: KDF计算量较大，放到阻塞线程池中执行以免卡住界面
+**************************************************************************************************/
+async fn derive_key(kdf: KdfParams, password: &str) -> Result<[u8; VAULT_KEY_SIZE], ApiError> {
+    let password = password.to_string();
+    tokio::task::spawn_blocking(move || kdf.derive_key(password.as_bytes()))
+        .await
+        .ok()
+        .flatten()
+        .ok_or(ApiError::Crypto("key derivation failed"))
+}
+
+
+/**************************************************************************************************
+ * Function: check
+ * Parameter:
+ *    - response: 服务端响应
+ * Return:
+ *    - Result: 成功时原样返回响应，否则转换为ApiError
+ * Description: 将412等状态码映射为用户可理解的错误
+**************************************************************************************************/
+async fn check(response: Response) -> Result<Response, ApiError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let message = response.text().await.unwrap_or_default();
+    Err(match status {
+        StatusCode::PRECONDITION_FAILED => ApiError::Conflict(message),
+        StatusCode::NOT_FOUND => ApiError::NotFound,
+        StatusCode::CONFLICT => ApiError::InProgress,
+        _ => ApiError::Server(status, message),
+    })
+}
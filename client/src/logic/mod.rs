@@ -1,4 +1,7 @@
 
+pub mod api;
+pub mod secret;
+pub mod sync;
 pub mod util;
 
 
@@ -0,0 +1,343 @@
+/**************************************************************************************************
+ * @file secret.rs
+ * @authors Lucien
+ * @brief
+ * @n the secret module describes how each secret kind is edited and displayed.
+ * @n The secret page builds its form from the field specs of a kind and renders a
+ * @n payload through its view rows, so sensitive fields are masked in one place.
+ *
+ * @version 0.1.0
+ * @date 2025-06-24
+ *
+ * @copyright
+ * @n Copyright (c) 2021 by Loyss Studio., Division All rights reserved.
+ * @n http://www.loyss.cn
+ *
+**************************************************************************************************/
+
+/**************************************************************************************************
+ * Import External Packages
+**************************************************************************************************/
+use std::collections::BTreeMap;
+
+
+/**************************************************************************************************
+ * Import Internal Packages
+**************************************************************************************************/
+use shared::secret::{BlobEncoding, SecretKind, SecretPayload, TotpAlgorithm};
+
+
+/**************************************************************************************************
+ * Declaration Types
+**************************************************************************************************/
+/// 编辑器控件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldInput {
+    /// 单行文本
+    Text,
+    /// 默认隐藏内容的单行文本
+    Secret,
+    /// 多行文本，用于PEM等内容
+    Multiline,
+    /// 每行一项的列表
+    List,
+    Number,
+    /// 下拉选择
+    Choice(&'static [&'static str]),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FieldSpec {
+    pub name: &'static str,
+    pub label: &'static str,
+    pub input: FieldInput,
+    pub required: bool,
+}
+
+/// 查看器中的一行
+#[derive(Debug, Clone)]
+pub struct ViewRow {
+    pub label: &'static str,
+    pub value: String,
+    /// 默认遮盖，需用户点击后显示
+    pub masked: bool,
+    pub copyable: bool,
+    /// 以等宽字体显示
+    pub monospace: bool,
+}
+
+/// 编辑器表单状态，字段值以文本保存，提交时转换为SecretPayload
+#[derive(Debug, Clone)]
+pub struct SecretForm {
+    pub kind: SecretKind,
+    values: BTreeMap<&'static str, String>,
+}
+
+
+/**************************************************************************************************
+ * Declaration Field Specs
+**************************************************************************************************/
+const fn field(name: &'static str, label: &'static str, input: FieldInput, required: bool) -> FieldSpec {
+    FieldSpec { name, label, input, required }
+}
+
+const TOTP_ALGORITHMS: &[&str] = &["SHA1", "SHA256", "SHA512"];
+const BLOB_ENCODINGS: &[&str] = &["utf8", "base64"];
+
+const PASSWORD_FIELDS: &[FieldSpec] = &[
+    field("username", "用户名", FieldInput::Text, false),
+    field("password", "密码", FieldInput::Secret, true),
+    field("url", "网址", FieldInput::Text, false),
+];
+
+const API_TOKEN_FIELDS: &[FieldSpec] = &[
+    field("token", "令牌", FieldInput::Secret, true),
+    field("scopes", "权限范围", FieldInput::List, false),
+];
+
+const CERTIFICATE_FIELDS: &[FieldSpec] = &[
+    field("certificate", "证书 (PEM)", FieldInput::Multiline, true),
+    field("private_key", "私钥 (PEM)", FieldInput::Multiline, true),
+    field("chain", "中间证书 (PEM)", FieldInput::Multiline, false),
+];
+
+const SSH_KEY_FIELDS: &[FieldSpec] = &[
+    field("private_key", "私钥", FieldInput::Multiline, true),
+    field("public_key", "公钥", FieldInput::Text, true),
+    field("passphrase", "私钥口令", FieldInput::Secret, false),
+];
+
+const TOTP_FIELDS: &[FieldSpec] = &[
+    field("secret", "种子 (Base32)", FieldInput::Secret, true),
+    field("issuer", "发行方", FieldInput::Text, false),
+    field("account_name", "账号", FieldInput::Text, false),
+    field("algorithm", "算法", FieldInput::Choice(TOTP_ALGORITHMS), true),
+    field("digits", "位数", FieldInput::Number, true),
+    field("period", "周期 (秒)", FieldInput::Number, true),
+];
+
+const BLOB_FIELDS: &[FieldSpec] = &[
+    field("data", "内容", FieldInput::Multiline, true),
+    field("encoding", "编码", FieldInput::Choice(BLOB_ENCODINGS), true),
+    field("content_type", "MIME类型", FieldInput::Text, false),
+];
+
+
+/**************************************************************************************************
+ * Function: kind_label
+ * Parameter:
+ *    - kind: 密钥类型
+ * Return:
+ *    - &str: 界面上显示的类型名称
+**************************************************************************************************/
+pub fn kind_label(kind: SecretKind) -> &'static str {
+    match kind {
+        SecretKind::Password => "账号密码",
+        SecretKind::ApiToken => "API令牌",
+        SecretKind::Certificate => "证书",
+        SecretKind::SshKey => "SSH密钥",
+        SecretKind::Totp => "动态口令",
+        SecretKind::Blob => "数据",
+    }
+}
+
+
+/**************************************************************************************************
+ * Function: editor_fields
+ * Parameter:
+ *    - kind: 密钥类型
+ * Return:
+ *    - &[FieldSpec]: 该类型编辑器的字段，按显示顺序排列
+**************************************************************************************************/
+pub fn editor_fields(kind: SecretKind) -> &'static [FieldSpec] {
+    match kind {
+        SecretKind::Password => PASSWORD_FIELDS,
+        SecretKind::ApiToken => API_TOKEN_FIELDS,
+        SecretKind::Certificate => CERTIFICATE_FIELDS,
+        SecretKind::SshKey => SSH_KEY_FIELDS,
+        SecretKind::Totp => TOTP_FIELDS,
+        SecretKind::Blob => BLOB_FIELDS,
+    }
+}
+
+
+/**************************************************************************************************
+ * Function: viewer_rows
+ * Parameter:
+ *    - payload: 密钥内容
+ * Return:
+ *    - Vec<ViewRow>: 查看器中依次显示的行，空的可选字段不显示
+**************************************************************************************************/
+pub fn viewer_rows(payload: &SecretPayload) -> Vec<ViewRow> {
+    let mut rows = Vec::new();
+    let mut push = |label, value: &str, masked, monospace| {
+        if !value.is_empty() {
+            rows.push(ViewRow { label, value: value.to_string(), masked, copyable: true, monospace });
+        }
+    };
+
+    match payload {
+        SecretPayload::Password { username, password, url } => {
+            push("用户名", username, false, false);
+            push("密码", password, true, true);
+            push("网址", url.as_deref().unwrap_or_default(), false, false);
+        }
+        SecretPayload::ApiToken { token, scopes } => {
+            push("令牌", token, true, true);
+            push("权限范围", &scopes.join(", "), false, false);
+        }
+        SecretPayload::Certificate { certificate, private_key, chain } => {
+            push("证书", certificate, false, true);
+            push("私钥", private_key, true, true);
+            push("中间证书", chain.as_deref().unwrap_or_default(), false, true);
+        }
+        SecretPayload::SshKey { private_key, public_key, passphrase } => {
+            push("公钥", public_key, false, true);
+            push("私钥", private_key, true, true);
+            push("私钥口令", passphrase.as_deref().unwrap_or_default(), true, true);
+        }
+        SecretPayload::Totp { secret, issuer, account_name, algorithm, digits, period } => {
+            push("发行方", issuer.as_deref().unwrap_or_default(), false, false);
+            push("账号", account_name.as_deref().unwrap_or_default(), false, false);
+            push("种子", secret, true, true);
+            push("参数", &format!("{:?} / {} 位 / {} 秒", algorithm, digits, period), false, false);
+        }
+        SecretPayload::Blob { data, encoding, content_type } => {
+            push("类型", content_type.as_deref().unwrap_or_default(), false, false);
+            push(if *encoding == BlobEncoding::Base64 { "内容 (Base64)" } else { "内容" }, data, true, true);
+        }
+    }
+    rows
+}
+
+
+/**************************************************************************************************
+ * Realize the SecretForm Struct
+**************************************************************************************************/
+impl SecretForm {
+    /// 新建空表单，选择类字段取默认值
+    pub fn new(kind: SecretKind) -> Self {
+        let mut form = Self { kind, values: BTreeMap::new() };
+        if kind == SecretKind::Totp {
+            form.set("algorithm", "SHA1");
+            form.set("digits", "6");
+            form.set("period", "30");
+        }
+        if kind == SecretKind::Blob {
+            form.set("encoding", "utf8");
+        }
+        form
+    }
+
+    /// 以已有内容填充表单，用于编辑
+    pub fn from_payload(payload: &SecretPayload) -> Self {
+        let mut form = Self { kind: payload.kind(), values: BTreeMap::new() };
+        match payload {
+            SecretPayload::Password { username, password, url } => {
+                form.set("username", username);
+                form.set("password", password);
+                form.set("url", url.as_deref().unwrap_or_default());
+            }
+            SecretPayload::ApiToken { token, scopes } => {
+                form.set("token", token);
+                form.set("scopes", &scopes.join("\n"));
+            }
+            SecretPayload::Certificate { certificate, private_key, chain } => {
+                form.set("certificate", certificate);
+                form.set("private_key", private_key);
+                form.set("chain", chain.as_deref().unwrap_or_default());
+            }
+            SecretPayload::SshKey { private_key, public_key, passphrase } => {
+                form.set("private_key", private_key);
+                form.set("public_key", public_key);
+                form.set("passphrase", passphrase.as_deref().unwrap_or_default());
+            }
+            SecretPayload::Totp { secret, issuer, account_name, algorithm, digits, period } => {
+                form.set("secret", secret);
+                form.set("issuer", issuer.as_deref().unwrap_or_default());
+                form.set("account_name", account_name.as_deref().unwrap_or_default());
+                form.set("algorithm", match algorithm {
+                    TotpAlgorithm::Sha1 => "SHA1",
+                    TotpAlgorithm::Sha256 => "SHA256",
+                    TotpAlgorithm::Sha512 => "SHA512",
+                });
+                form.set("digits", &digits.to_string());
+                form.set("period", &period.to_string());
+            }
+            SecretPayload::Blob { data, encoding, content_type } => {
+                form.set("data", data);
+                form.set("encoding", if *encoding == BlobEncoding::Base64 { "base64" } else { "utf8" });
+                form.set("content_type", content_type.as_deref().unwrap_or_default());
+            }
+        }
+        form
+    }
+
+    pub fn get(&self, name: &str) -> &str {
+        self.values.get(name).map(String::as_str).unwrap_or_default()
+    }
+
+    /// 只接受该类型编辑器中存在的字段
+    pub fn set(&mut self, name: &str, value: &str) {
+        if let Some(spec) = editor_fields(self.kind).iter().find(|spec| spec.name == name) {
+            self.values.insert(spec.name, value.to_string());
+        }
+    }
+
+    /// 转换为SecretPayload并做与服务端一致的格式校验
+    pub fn to_payload(&self) -> Result<SecretPayload, &'static str> {
+        let optional = |name| Some(self.get(name).trim().to_string()).filter(|value| !value.is_empty());
+
+        let payload = match self.kind {
+            SecretKind::Password => SecretPayload::Password {
+                username: self.get("username").trim().to_string(),
+                password: self.get("password").to_string(),
+                url: optional("url"),
+            },
+            SecretKind::ApiToken => SecretPayload::ApiToken {
+                token: self.get("token").trim().to_string(),
+                scopes: self
+                    .get("scopes")
+                    .lines()
+                    .map(str::trim)
+                    .filter(|scope| !scope.is_empty())
+                    .map(String::from)
+                    .collect(),
+            },
+            SecretKind::Certificate => SecretPayload::Certificate {
+                certificate: self.get("certificate").trim().to_string(),
+                private_key: self.get("private_key").trim().to_string(),
+                chain: optional("chain"),
+            },
+            SecretKind::SshKey => SecretPayload::SshKey {
+                private_key: self.get("private_key").trim().to_string(),
+                public_key: self.get("public_key").trim().to_string(),
+                passphrase: Some(self.get("passphrase").to_string()).filter(|value| !value.is_empty()),
+            },
+            SecretKind::Totp => SecretPayload::Totp {
+                secret: self.get("secret").trim().to_string(),
+                issuer: optional("issuer"),
+                account_name: optional("account_name"),
+                algorithm: match self.get("algorithm") {
+                    "SHA1" => TotpAlgorithm::Sha1,
+                    "SHA256" => TotpAlgorithm::Sha256,
+                    "SHA512" => TotpAlgorithm::Sha512,
+                    _ => return Err("请选择TOTP算法"),
+                },
+                digits: self.get("digits").trim().parse().map_err(|_| "位数必须是数字")?,
+                period: self.get("period").trim().parse().map_err(|_| "周期必须是数字")?,
+            },
+            SecretKind::Blob => SecretPayload::Blob {
+                data: self.get("data").to_string(),
+                encoding: match self.get("encoding") {
+                    "base64" => BlobEncoding::Base64,
+                    _ => BlobEncoding::Utf8,
+                },
+                content_type: optional("content_type"),
+            },
+        };
+
+        payload.validate()?;
+        Ok(payload)
+    }
+}
@@ -0,0 +1,92 @@
+/**************************************************************************************************
+ * @file sync.rs
+ * @authors Lucien
+ * @brief
+ * @n the sync module keeps a local copy of the key list in step with the server.
+ * @n Change events only carry ids and versions; changed keys are re-read so the
+ * @n view never holds data the caller is no longer allowed to see.
+ *
+ * @version 0.1.0
+ * @date 2025-06-24
+ *
+ * @copyright
+ * @n Copyright (c) 2021 by Loyss Studio., Division All rights reserved.
+ * @n http://www.loyss.cn
+ *
+**************************************************************************************************/
+
+/**************************************************************************************************
+ * Import External Packages
+**************************************************************************************************/
+use reqwest::StatusCode;
+use std::collections::BTreeMap;
+
+
+/**************************************************************************************************
+ * Import Internal Packages
+**************************************************************************************************/
+use super::api::{ApiError, Key, KeyClient, KeyEvent, KeyEventKind};
+
+
+/**************************************************************************************************
+ * Declaration KeyView Struct
+**************************************************************************************************/
+/// 客户端缓存的密钥列表及已处理到的事件游标
+#[derive(Debug, Default)]
+pub struct KeyView {
+    pub keys: BTreeMap<u64, Key>,
+    /// 最后处理的事件游标，重连时从此处续传
+    pub cursor: Option<u64>,
+}
+
+
+/**************************************************************************************************
+ * Realize the KeyView Struct
+**************************************************************************************************/
+impl KeyView {
+    /// 切换团队上下文，缓存的密钥和游标属于原团队，需全部丢弃后重新跟随
+    pub fn switch_team(&mut self, client: &mut KeyClient, team_id: Option<u64>) {
+        client.set_team(team_id);
+        self.keys.clear();
+        self.cursor = None;
+    }
+
+    /// 持续跟随事件流，连接断开后从最后处理的游标重新订阅
+    pub async fn follow(&mut self, client: &KeyClient) -> Result<(), ApiError> {
+        loop {
+            let mut events = client.key_events(self.cursor).await?;
+            while let Some(event) = events.next().await? {
+                self.apply(client, &event).await?;
+            }
+        }
+    }
+
+    /// 应用一条变更事件，返回本地列表是否发生变化
+    pub async fn apply(&mut self, client: &KeyClient, event: &KeyEvent) -> Result<bool, ApiError> {
+        let changed = match event.event {
+            KeyEventKind::Deleted => self.keys.remove(&event.key_id).is_some(),
+            KeyEventKind::Created | KeyEventKind::Updated => {
+                let current = self.keys.get(&event.key_id).map(|key| key.version);
+                // 本地已是该版本或更新的版本时无需重新读取
+                if current.is_some() && current >= event.version {
+                    false
+                } else {
+                    match client.get_key(event.key_id).await {
+                        Ok(key) => {
+                            self.keys.insert(key.id, key);
+                            true
+                        }
+                        // 事件发出后密钥可能已被删除或撤销了读取权限
+                        Err(ApiError::NotFound | ApiError::Server(StatusCode::FORBIDDEN, _)) => {
+                            self.keys.remove(&event.key_id).is_some()
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+            }
+        };
+
+        self.cursor = Some(event.cursor);
+        Ok(changed)
+    }
+}
@@ -0,0 +1,262 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use reqwest::blocking::{Client, Response};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fmt;
+
+// 分页列出密钥时每页的数量
+const PAGE_SIZE: u32 = 100;
+
+/// 服务端可使用的密钥类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyType {
+    Aes,
+    Rsa,
+    EcP256,
+    Ed25519,
+}
+
+/// 服务端签名算法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignAlgorithm {
+    RsaPkcs1Sha256,
+    RsaPkcs1Sha384,
+    RsaPssSha256,
+    RsaPssSha384,
+    EcdsaSha256,
+    EcdsaSha384,
+    Ed25519,
+}
+
+impl SignAlgorithm {
+    pub fn key_type(self) -> KeyType {
+        match self {
+            SignAlgorithm::RsaPkcs1Sha256
+            | SignAlgorithm::RsaPkcs1Sha384
+            | SignAlgorithm::RsaPssSha256
+            | SignAlgorithm::RsaPssSha384 => KeyType::Rsa,
+            SignAlgorithm::EcdsaSha256 | SignAlgorithm::EcdsaSha384 => KeyType::EcP256,
+            SignAlgorithm::Ed25519 => KeyType::Ed25519,
+        }
+    }
+}
+
+/// GET /keys/:id/public-key 的响应，二进制字段已解码
+#[derive(Debug, Clone)]
+pub struct PublicKey {
+    pub key_id: u64,
+    pub name: String,
+    pub key_type: KeyType,
+    pub bits: u32,
+    pub modulus: Option<Vec<u8>>,
+    pub public_exponent: Option<Vec<u8>>,
+    pub ec_point: Option<Vec<u8>>,
+    pub certificate: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PublicKeyBody {
+    key_id: u64,
+    name: String,
+    key_type: KeyType,
+    bits: u32,
+    modulus: Option<String>,
+    public_exponent: Option<String>,
+    ec_point: Option<String>,
+    certificate: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchBody {
+    keys: Vec<KeyBody>,
+    next_after_id: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyBody {
+    id: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct EncryptBody {
+    ciphertext: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DecryptBody {
+    plaintext: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SignBody {
+    signature: String,
+}
+
+#[derive(Debug)]
+pub enum ApiError {
+    /// 凭据无效或已过期
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    /// 密钥类型不支持该操作
+    Unsupported(String),
+    /// 输入无效或解密认证失败
+    Rejected(String),
+    Server(StatusCode, String),
+    /// 响应中的Base64字段无法解码
+    InvalidResponse,
+    Network(reqwest::Error),
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::Unauthorized => f.write_str("credential rejected"),
+            ApiError::Forbidden => f.write_str("access denied"),
+            ApiError::NotFound => f.write_str("key not found"),
+            ApiError::Unsupported(message) => write!(f, "unsupported: {}", message),
+            ApiError::Rejected(message) => write!(f, "rejected: {}", message),
+            ApiError::Server(status, message) => write!(f, "server error ({}): {}", status, message),
+            ApiError::InvalidResponse => f.write_str("invalid response"),
+            ApiError::Network(e) => write!(f, "network error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<reqwest::Error> for ApiError {
+    fn from(e: reqwest::Error) -> Self {
+        ApiError::Network(e)
+    }
+}
+
+/// 调用ecipher服务端HTTP接口的同步客户端
+pub struct EcipherClient {
+    http: Client,
+    base_url: String,
+    credential: String,
+}
+
+impl EcipherClient {
+    pub fn new(base_url: impl Into<String>, credential: impl Into<String>) -> Self {
+        Self {
+            http: Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            credential: credential.into(),
+        }
+    }
+
+    /// 列出调用方可见的全部密钥ID
+    pub fn list_key_ids(&self) -> Result<Vec<u64>, ApiError> {
+        let mut ids = Vec::new();
+        let mut after_id = None;
+        loop {
+            let response = self
+                .http
+                .post(format!("{}/keys/search", self.base_url))
+                .bearer_auth(&self.credential)
+                .json(&json!({ "after_id": after_id, "limit": PAGE_SIZE }))
+                .send()?;
+            let page: SearchBody = check(response)?.json()?;
+            ids.extend(page.keys.into_iter().map(|key| key.id));
+            match page.next_after_id {
+                Some(next) => after_id = Some(next),
+                None => return Ok(ids),
+            }
+        }
+    }
+
+    /// 读取密钥的公开信息；密钥不能由服务端使用时返回Unsupported
+    pub fn public_key(&self, key_id: u64) -> Result<PublicKey, ApiError> {
+        let response = self
+            .http
+            .get(format!("{}/keys/{}/public-key", self.base_url, key_id))
+            .bearer_auth(&self.credential)
+            .send()?;
+        let body: PublicKeyBody = check(response)?.json()?;
+
+        Ok(PublicKey {
+            key_id: body.key_id,
+            name: body.name,
+            key_type: body.key_type,
+            bits: body.bits,
+            modulus: body.modulus.as_deref().map(decode).transpose()?,
+            public_exponent: body.public_exponent.as_deref().map(decode).transpose()?,
+            ec_point: body.ec_point.as_deref().map(decode).transpose()?,
+            certificate: body.certificate.as_deref().map(decode).transpose()?,
+        })
+    }
+
+    /// AES-GCM加密，返回的密文末尾为16字节认证标签
+    pub fn encrypt(&self, key_id: u64, plaintext: &[u8], iv: &[u8], aad: &[u8]) -> Result<Vec<u8>, ApiError> {
+        let response = self
+            .http
+            .post(format!("{}/keys/{}/encrypt", self.base_url, key_id))
+            .bearer_auth(&self.credential)
+            .json(&json!({
+                "plaintext": STANDARD.encode(plaintext),
+                "iv": STANDARD.encode(iv),
+                "aad": STANDARD.encode(aad),
+            }))
+            .send()?;
+        let body: EncryptBody = check(response)?.json()?;
+        decode(&body.ciphertext)
+    }
+
+    /// AES-GCM解密，认证失败时返回Rejected
+    pub fn decrypt(&self, key_id: u64, ciphertext: &[u8], iv: &[u8], aad: &[u8]) -> Result<Vec<u8>, ApiError> {
+        let response = self
+            .http
+            .post(format!("{}/keys/{}/decrypt", self.base_url, key_id))
+            .bearer_auth(&self.credential)
+            .json(&json!({
+                "ciphertext": STANDARD.encode(ciphertext),
+                "iv": STANDARD.encode(iv),
+                "aad": STANDARD.encode(aad),
+            }))
+            .send()?;
+        let body: DecryptBody = check(response)?.json()?;
+        decode(&body.plaintext)
+    }
+
+    pub fn sign(&self, key_id: u64, algorithm: SignAlgorithm, data: &[u8], prehashed: bool) -> Result<Vec<u8>, ApiError> {
+        let response = self
+            .http
+            .post(format!("{}/keys/{}/sign", self.base_url, key_id))
+            .bearer_auth(&self.credential)
+            .json(&json!({
+                "algorithm": algorithm,
+                "data": STANDARD.encode(data),
+                "prehashed": prehashed,
+            }))
+            .send()?;
+        let body: SignBody = check(response)?.json()?;
+        decode(&body.signature)
+    }
+}
+
+fn decode(value: &str) -> Result<Vec<u8>, ApiError> {
+    STANDARD.decode(value).map_err(|_| ApiError::InvalidResponse)
+}
+
+// 将非2xx响应转换为对应的错误
+fn check(response: Response) -> Result<Response, ApiError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let message = response.text().unwrap_or_default();
+    Err(match status {
+        StatusCode::UNAUTHORIZED => ApiError::Unauthorized,
+        StatusCode::FORBIDDEN => ApiError::Forbidden,
+        StatusCode::NOT_FOUND => ApiError::NotFound,
+        StatusCode::UNPROCESSABLE_ENTITY => ApiError::Unsupported(message),
+        StatusCode::BAD_REQUEST => ApiError::Rejected(message),
+        _ => ApiError::Server(status, message),
+    })
+}
@@ -0,0 +1,665 @@
+//! ecipher的PKCS#11提供程序
+//!
+//! 将调用方可使用的服务端密钥暴露为一个令牌中的对象，加解密和签名通过服务端接口完成，密钥内容不离开服务端。
+//!
+//! - `ECIPHER_PKCS11_URL`：服务端地址，默认 `http://127.0.0.1:3000`
+//! - `ECIPHER_PKCS11_CREDENTIAL`：可选，设置后无需C_Login即处于登录状态
+//!
+//! C_Login的PIN为ecipher凭据或API令牌。支持的机制为CKM_AES_GCM（12字节IV、128位标签）、
+//! CKM_RSA_PKCS、CKM_SHA256/384_RSA_PKCS、CKM_RSA_PKCS_PSS、CKM_SHA256/384_RSA_PKCS_PSS、
+//! CKM_ECDSA、CKM_ECDSA_SHA256/384和CKM_EDDSA。
+#![allow(non_snake_case)]
+
+mod client;
+mod object;
+mod session;
+
+use cryptoki_sys::*;
+use std::ptr;
+use std::sync::MutexGuard;
+
+use crate::client::SignAlgorithm;
+use crate::object::AttributeValue;
+use crate::session::{GCM_IV_SIZE, MODULE, Mechanism, Module, SLOT_ID, SignInput};
+
+const CRYPTOKI_VERSION: CK_VERSION = CK_VERSION { major: 2, minor: 40 };
+const LIBRARY_VERSION: CK_VERSION = CK_VERSION { major: 0, minor: 1 };
+const MANUFACTURER: &str = "ecipher";
+
+// 支持的机制及其密钥长度范围和用途
+const MECHANISMS: &[(CK_MECHANISM_TYPE, CK_ULONG, CK_ULONG, CK_FLAGS)] = &[
+    (CKM_AES_GCM, 16, 32, CKF_ENCRYPT | CKF_DECRYPT),
+    (CKM_RSA_PKCS, 2048, 8192, CKF_SIGN),
+    (CKM_SHA256_RSA_PKCS, 2048, 8192, CKF_SIGN),
+    (CKM_SHA384_RSA_PKCS, 2048, 8192, CKF_SIGN),
+    (CKM_RSA_PKCS_PSS, 2048, 8192, CKF_SIGN),
+    (CKM_SHA256_RSA_PKCS_PSS, 2048, 8192, CKF_SIGN),
+    (CKM_SHA384_RSA_PKCS_PSS, 2048, 8192, CKF_SIGN),
+    (CKM_ECDSA, 256, 256, CKF_SIGN),
+    (CKM_ECDSA_SHA256, 256, 256, CKF_SIGN),
+    (CKM_ECDSA_SHA384, 256, 256, CKF_SIGN),
+    (CKM_EDDSA, 256, 256, CKF_SIGN),
+];
+
+static FUNCTION_LIST: CK_FUNCTION_LIST = CK_FUNCTION_LIST {
+    version: CRYPTOKI_VERSION,
+    C_Initialize: Some(initialize),
+    C_Finalize: Some(finalize),
+    C_GetInfo: Some(get_info),
+    C_GetFunctionList: Some(C_GetFunctionList),
+    C_GetSlotList: Some(get_slot_list),
+    C_GetSlotInfo: Some(get_slot_info),
+    C_GetTokenInfo: Some(get_token_info),
+    C_GetMechanismList: Some(get_mechanism_list),
+    C_GetMechanismInfo: Some(get_mechanism_info),
+    C_InitToken: Some(init_token),
+    C_InitPIN: Some(init_pin),
+    C_SetPIN: Some(set_pin),
+    C_OpenSession: Some(open_session),
+    C_CloseSession: Some(close_session),
+    C_CloseAllSessions: Some(close_all_sessions),
+    C_GetSessionInfo: Some(get_session_info),
+    C_GetOperationState: Some(get_operation_state),
+    C_SetOperationState: Some(set_operation_state),
+    C_Login: Some(login),
+    C_Logout: Some(logout),
+    C_CreateObject: Some(create_object),
+    C_CopyObject: Some(copy_object),
+    C_DestroyObject: Some(destroy_object),
+    C_GetObjectSize: Some(get_object_size),
+    C_GetAttributeValue: Some(get_attribute_value),
+    C_SetAttributeValue: Some(set_attribute_value),
+    C_FindObjectsInit: Some(find_objects_init),
+    C_FindObjects: Some(find_objects),
+    C_FindObjectsFinal: Some(find_objects_final),
+    C_EncryptInit: Some(encrypt_init),
+    C_Encrypt: Some(encrypt),
+    C_EncryptUpdate: Some(encrypt_update),
+    C_EncryptFinal: Some(encrypt_final),
+    C_DecryptInit: Some(decrypt_init),
+    C_Decrypt: Some(decrypt),
+    C_DecryptUpdate: Some(decrypt_update),
+    C_DecryptFinal: Some(decrypt_final),
+    C_DigestInit: Some(digest_init),
+    C_Digest: Some(digest),
+    C_DigestUpdate: Some(digest_update),
+    C_DigestKey: Some(digest_key),
+    C_DigestFinal: Some(digest_final),
+    C_SignInit: Some(sign_init),
+    C_Sign: Some(sign),
+    C_SignUpdate: Some(sign_update),
+    C_SignFinal: Some(sign_final),
+    C_SignRecoverInit: Some(sign_recover_init),
+    C_SignRecover: Some(sign_recover),
+    C_VerifyInit: Some(verify_init),
+    C_Verify: Some(verify),
+    C_VerifyUpdate: Some(verify_update),
+    C_VerifyFinal: Some(verify_final),
+    C_VerifyRecoverInit: Some(verify_recover_init),
+    C_VerifyRecover: Some(verify_recover),
+    C_DigestEncryptUpdate: Some(digest_encrypt_update),
+    C_DecryptDigestUpdate: Some(decrypt_digest_update),
+    C_SignEncryptUpdate: Some(sign_encrypt_update),
+    C_DecryptVerifyUpdate: Some(decrypt_verify_update),
+    C_GenerateKey: Some(generate_key),
+    C_GenerateKeyPair: Some(generate_key_pair),
+    C_WrapKey: Some(wrap_key),
+    C_UnwrapKey: Some(unwrap_key),
+    C_DeriveKey: Some(derive_key),
+    C_SeedRandom: Some(seed_random),
+    C_GenerateRandom: Some(generate_random),
+    C_GetFunctionStatus: Some(get_function_status),
+    C_CancelFunction: Some(cancel_function),
+    C_WaitForSlotEvent: Some(wait_for_slot_event),
+};
+
+/// 返回函数列表，是库唯一导出的符号
+///
+/// # Safety
+///
+/// `ppFunctionList`须为可写的指针。
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn C_GetFunctionList(ppFunctionList: CK_FUNCTION_LIST_PTR_PTR) -> CK_RV {
+    if ppFunctionList.is_null() {
+        return CKR_ARGUMENTS_BAD;
+    }
+    unsafe { *ppFunctionList = &FUNCTION_LIST as *const CK_FUNCTION_LIST as CK_FUNCTION_LIST_PTR };
+    CKR_OK
+}
+
+// 模块状态的锁；持有锁的线程崩溃后仍继续使用已有状态
+fn lock() -> MutexGuard<'static, Option<Module>> {
+    MODULE.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+// 在已初始化的模块上执行操作
+fn with_module(f: impl FnOnce(&mut Module) -> Result<(), CK_RV>) -> CK_RV {
+    match lock().as_mut() {
+        Some(module) => f(module).err().unwrap_or(CKR_OK),
+        None => CKR_CRYPTOKI_NOT_INITIALIZED,
+    }
+}
+
+// 调用方传入的输入缓冲区
+unsafe fn input<'a>(data: CK_BYTE_PTR, len: CK_ULONG) -> Result<&'a [u8], CK_RV> {
+    if data.is_null() {
+        return if len == 0 { Ok(&[]) } else { Err(CKR_ARGUMENTS_BAD) };
+    }
+    Ok(unsafe { std::slice::from_raw_parts(data, len as usize) })
+}
+
+// 调用方传入的可写指针
+unsafe fn writable<'a, T>(value: *mut T) -> Result<&'a mut T, CK_RV> {
+    unsafe { value.as_mut() }.ok_or(CKR_ARGUMENTS_BAD)
+}
+
+// 按PKCS#11的约定输出长度已知的结果：缓冲区为空时只返回长度，缓冲区不足时返回CKR_BUFFER_TOO_SMALL
+// 并保留进行中的操作，否则调用`produce`生成结果并写入
+unsafe fn output(
+    expected_len: usize,
+    out: CK_BYTE_PTR,
+    out_len: CK_ULONG_PTR,
+    produce: impl FnOnce() -> Result<Vec<u8>, CK_RV>,
+) -> Result<(), CK_RV> {
+    let out_len = unsafe { writable(out_len) }?;
+    if out.is_null() {
+        *out_len = expected_len as CK_ULONG;
+        return Ok(());
+    }
+    if (*out_len as usize) < expected_len {
+        *out_len = expected_len as CK_ULONG;
+        return Err(CKR_BUFFER_TOO_SMALL);
+    }
+    let value = produce()?;
+    unsafe { ptr::copy_nonoverlapping(value.as_ptr(), out, value.len()) };
+    *out_len = value.len() as CK_ULONG;
+    Ok(())
+}
+
+// 以空格填充的定长字符串字段
+fn padded<const N: usize>(value: &str) -> [CK_UTF8CHAR; N] {
+    let mut field = [b' '; N];
+    let len = value.len().min(N);
+    field[..len].copy_from_slice(&value.as_bytes()[..len]);
+    field
+}
+
+// 机制参数，长度须与结构体一致
+unsafe fn parameter<'a, T>(mechanism: &CK_MECHANISM) -> Result<&'a T, CK_RV> {
+    if mechanism.pParameter.is_null() || mechanism.ulParameterLen as usize != size_of::<T>() {
+        return Err(CKR_MECHANISM_PARAM_INVALID);
+    }
+    Ok(unsafe { &*(mechanism.pParameter as *const T) })
+}
+
+// 解析机制及其参数
+unsafe fn mechanism(mechanism: CK_MECHANISM_PTR) -> Result<Mechanism, CK_RV> {
+    let mechanism = unsafe { mechanism.as_ref() }.ok_or(CKR_ARGUMENTS_BAD)?;
+    let sign = |algorithm: SignAlgorithm, input: SignInput| -> Result<Mechanism, CK_RV> {
+        Ok(Mechanism::Sign { algorithm, input })
+    };
+
+    match mechanism.mechanism {
+        CKM_AES_GCM => {
+            let params = unsafe { parameter::<CK_GCM_PARAMS>(mechanism) }?;
+            if params.ulIvLen as usize != GCM_IV_SIZE || params.ulTagBits != 128 {
+                return Err(CKR_MECHANISM_PARAM_INVALID);
+            }
+            Ok(Mechanism::AesGcm {
+                iv: unsafe { input(params.pIv, params.ulIvLen) }?.to_vec(),
+                aad: unsafe { input(params.pAAD, params.ulAADLen) }?.to_vec(),
+            })
+        }
+        CKM_RSA_PKCS => sign(SignAlgorithm::RsaPkcs1Sha256, SignInput::DigestInfo),
+        CKM_SHA256_RSA_PKCS => sign(SignAlgorithm::RsaPkcs1Sha256, SignInput::Message),
+        CKM_SHA384_RSA_PKCS => sign(SignAlgorithm::RsaPkcs1Sha384, SignInput::Message),
+        CKM_RSA_PKCS_PSS | CKM_SHA256_RSA_PKCS_PSS | CKM_SHA384_RSA_PKCS_PSS => {
+            // 服务端使用与摘要等长的盐和同一摘要算法的MGF1
+            let params = unsafe { parameter::<CK_RSA_PKCS_PSS_PARAMS>(mechanism) }?;
+            let algorithm = match (params.hashAlg, params.mgf, params.sLen) {
+                (CKM_SHA256, CKG_MGF1_SHA256, 32) => SignAlgorithm::RsaPssSha256,
+                (CKM_SHA384, CKG_MGF1_SHA384, 48) => SignAlgorithm::RsaPssSha384,
+                _ => return Err(CKR_MECHANISM_PARAM_INVALID),
+            };
+            match (mechanism.mechanism, algorithm) {
+                (CKM_RSA_PKCS_PSS, _) => sign(algorithm, SignInput::Digest),
+                (CKM_SHA256_RSA_PKCS_PSS, SignAlgorithm::RsaPssSha256)
+                | (CKM_SHA384_RSA_PKCS_PSS, SignAlgorithm::RsaPssSha384) => sign(algorithm, SignInput::Message),
+                _ => Err(CKR_MECHANISM_PARAM_INVALID),
+            }
+        }
+        CKM_ECDSA => sign(SignAlgorithm::EcdsaSha256, SignInput::Digest),
+        CKM_ECDSA_SHA256 => sign(SignAlgorithm::EcdsaSha256, SignInput::Message),
+        CKM_ECDSA_SHA384 => sign(SignAlgorithm::EcdsaSha384, SignInput::Message),
+        CKM_EDDSA => sign(SignAlgorithm::Ed25519, SignInput::Message),
+        _ => Err(CKR_MECHANISM_INVALID),
+    }
+}
+
+fn check_slot(slot_id: CK_SLOT_ID) -> Result<(), CK_RV> {
+    if slot_id == SLOT_ID { Ok(()) } else { Err(CKR_SLOT_ID_INVALID) }
+}
+
+unsafe extern "C" fn initialize(pInitArgs: CK_VOID_PTR) -> CK_RV {
+    if let Some(args) = unsafe { (pInitArgs as CK_C_INITIALIZE_ARGS_PTR).as_ref() } {
+        if !args.pReserved.is_null() {
+            return CKR_ARGUMENTS_BAD;
+        }
+    }
+    let mut module = lock();
+    if module.is_some() {
+        return CKR_CRYPTOKI_ALREADY_INITIALIZED;
+    }
+    *module = Some(Module::new());
+    CKR_OK
+}
+
+unsafe extern "C" fn finalize(pReserved: CK_VOID_PTR) -> CK_RV {
+    if !pReserved.is_null() {
+        return CKR_ARGUMENTS_BAD;
+    }
+    match lock().take() {
+        Some(_) => CKR_OK,
+        None => CKR_CRYPTOKI_NOT_INITIALIZED,
+    }
+}
+
+unsafe extern "C" fn get_info(pInfo: CK_INFO_PTR) -> CK_RV {
+    with_module(|_| {
+        *unsafe { writable(pInfo) }? = CK_INFO {
+            cryptokiVersion: CRYPTOKI_VERSION,
+            manufacturerID: padded(MANUFACTURER),
+            flags: 0,
+            libraryDescription: padded("ecipher PKCS#11 provider"),
+            libraryVersion: LIBRARY_VERSION,
+        };
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn get_slot_list(_tokenPresent: CK_BBOOL, pSlotList: CK_SLOT_ID_PTR, pulCount: CK_ULONG_PTR) -> CK_RV {
+    with_module(|_| {
+        let count = unsafe { writable(pulCount) }?;
+        if !pSlotList.is_null() {
+            if *count < 1 {
+                *count = 1;
+                return Err(CKR_BUFFER_TOO_SMALL);
+            }
+            unsafe { *pSlotList = SLOT_ID };
+        }
+        *count = 1;
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn get_slot_info(slotID: CK_SLOT_ID, pInfo: CK_SLOT_INFO_PTR) -> CK_RV {
+    with_module(|_| {
+        check_slot(slotID)?;
+        *unsafe { writable(pInfo) }? = CK_SLOT_INFO {
+            slotDescription: padded("ecipher server"),
+            manufacturerID: padded(MANUFACTURER),
+            flags: CKF_TOKEN_PRESENT | CKF_HW_SLOT,
+            hardwareVersion: LIBRARY_VERSION,
+            firmwareVersion: LIBRARY_VERSION,
+        };
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn get_token_info(slotID: CK_SLOT_ID, pInfo: CK_TOKEN_INFO_PTR) -> CK_RV {
+    with_module(|module| {
+        check_slot(slotID)?;
+        *unsafe { writable(pInfo) }? = CK_TOKEN_INFO {
+            label: padded("ecipher"),
+            manufacturerID: padded(MANUFACTURER),
+            model: padded("server"),
+            serialNumber: padded("0"),
+            flags: CKF_LOGIN_REQUIRED | CKF_USER_PIN_INITIALIZED | CKF_TOKEN_INITIALIZED,
+            ulMaxSessionCount: CK_EFFECTIVELY_INFINITE,
+            ulSessionCount: module.session_count() as CK_ULONG,
+            ulMaxRwSessionCount: CK_EFFECTIVELY_INFINITE,
+            ulRwSessionCount: CK_UNAVAILABLE_INFORMATION,
+            ulMaxPinLen: 4096,
+            ulMinPinLen: 1,
+            ulTotalPublicMemory: CK_UNAVAILABLE_INFORMATION,
+            ulFreePublicMemory: CK_UNAVAILABLE_INFORMATION,
+            ulTotalPrivateMemory: CK_UNAVAILABLE_INFORMATION,
+            ulFreePrivateMemory: CK_UNAVAILABLE_INFORMATION,
+            hardwareVersion: LIBRARY_VERSION,
+            firmwareVersion: LIBRARY_VERSION,
+            utcTime: padded(""),
+        };
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn get_mechanism_list(
+    slotID: CK_SLOT_ID,
+    pMechanismList: CK_MECHANISM_TYPE_PTR,
+    pulCount: CK_ULONG_PTR,
+) -> CK_RV {
+    with_module(|_| {
+        check_slot(slotID)?;
+        let count = unsafe { writable(pulCount) }?;
+        if !pMechanismList.is_null() {
+            if (*count as usize) < MECHANISMS.len() {
+                *count = MECHANISMS.len() as CK_ULONG;
+                return Err(CKR_BUFFER_TOO_SMALL);
+            }
+            for (index, (mechanism, ..)) in MECHANISMS.iter().enumerate() {
+                unsafe { *pMechanismList.add(index) = *mechanism };
+            }
+        }
+        *count = MECHANISMS.len() as CK_ULONG;
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn get_mechanism_info(slotID: CK_SLOT_ID, type_: CK_MECHANISM_TYPE, pInfo: CK_MECHANISM_INFO_PTR) -> CK_RV {
+    with_module(|_| {
+        check_slot(slotID)?;
+        let &(_, min, max, flags) = MECHANISMS
+            .iter()
+            .find(|(mechanism, ..)| *mechanism == type_)
+            .ok_or(CKR_MECHANISM_INVALID)?;
+        *unsafe { writable(pInfo) }? = CK_MECHANISM_INFO {
+            ulMinKeySize: min,
+            ulMaxKeySize: max,
+            flags,
+        };
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn open_session(
+    slotID: CK_SLOT_ID,
+    flags: CK_FLAGS,
+    _pApplication: CK_VOID_PTR,
+    _Notify: CK_NOTIFY,
+    phSession: CK_SESSION_HANDLE_PTR,
+) -> CK_RV {
+    with_module(|module| {
+        check_slot(slotID)?;
+        if flags & CKF_SERIAL_SESSION == 0 {
+            return Err(CKR_SESSION_PARALLEL_NOT_SUPPORTED);
+        }
+        let session = unsafe { writable(phSession) }?;
+        *session = module.open_session(flags);
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn close_session(hSession: CK_SESSION_HANDLE) -> CK_RV {
+    with_module(|module| module.close_session(hSession))
+}
+
+unsafe extern "C" fn close_all_sessions(slotID: CK_SLOT_ID) -> CK_RV {
+    with_module(|module| {
+        check_slot(slotID)?;
+        module.close_all_sessions();
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn get_session_info(hSession: CK_SESSION_HANDLE, pInfo: CK_SESSION_INFO_PTR) -> CK_RV {
+    with_module(|module| {
+        let flags = module.session(hSession)?.flags;
+        let state = module.session_state(flags);
+        *unsafe { writable(pInfo) }? = CK_SESSION_INFO {
+            slotID: SLOT_ID,
+            state,
+            flags,
+            ulDeviceError: 0,
+        };
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn login(hSession: CK_SESSION_HANDLE, userType: CK_USER_TYPE, pPin: CK_UTF8CHAR_PTR, ulPinLen: CK_ULONG) -> CK_RV {
+    with_module(|module| {
+        module.session(hSession)?;
+        if userType != CKU_USER {
+            return Err(CKR_USER_TYPE_INVALID);
+        }
+        let pin = std::str::from_utf8(unsafe { input(pPin, ulPinLen) }?).map_err(|_| CKR_PIN_INCORRECT)?;
+        module.login(pin)
+    })
+}
+
+unsafe extern "C" fn logout(hSession: CK_SESSION_HANDLE) -> CK_RV {
+    with_module(|module| {
+        module.session(hSession)?;
+        module.logout()
+    })
+}
+
+unsafe extern "C" fn get_attribute_value(
+    hSession: CK_SESSION_HANDLE,
+    hObject: CK_OBJECT_HANDLE,
+    pTemplate: CK_ATTRIBUTE_PTR,
+    ulCount: CK_ULONG,
+) -> CK_RV {
+    with_module(|module| {
+        module.session(hSession)?;
+        let object = module.object(hObject)?;
+        if pTemplate.is_null() && ulCount > 0 {
+            return Err(CKR_ARGUMENTS_BAD);
+        }
+
+        // 逐个处理全部属性，返回遇到的第一个错误
+        let mut result = Ok(());
+        for index in 0..ulCount as usize {
+            let attribute = unsafe { &mut *pTemplate.add(index) };
+            let failure = match object.attribute(attribute.type_) {
+                AttributeValue::Value(value) if attribute.pValue.is_null() => {
+                    attribute.ulValueLen = value.len() as CK_ULONG;
+                    continue;
+                }
+                AttributeValue::Value(value) if (attribute.ulValueLen as usize) >= value.len() => {
+                    unsafe { ptr::copy_nonoverlapping(value.as_ptr(), attribute.pValue as CK_BYTE_PTR, value.len()) };
+                    attribute.ulValueLen = value.len() as CK_ULONG;
+                    continue;
+                }
+                AttributeValue::Value(_) => CKR_BUFFER_TOO_SMALL,
+                AttributeValue::Sensitive => CKR_ATTRIBUTE_SENSITIVE,
+                AttributeValue::Invalid => CKR_ATTRIBUTE_TYPE_INVALID,
+            };
+            attribute.ulValueLen = CK_UNAVAILABLE_INFORMATION;
+            if result.is_ok() {
+                result = Err(failure);
+            }
+        }
+        result
+    })
+}
+
+unsafe extern "C" fn find_objects_init(hSession: CK_SESSION_HANDLE, pTemplate: CK_ATTRIBUTE_PTR, ulCount: CK_ULONG) -> CK_RV {
+    with_module(|module| {
+        if pTemplate.is_null() && ulCount > 0 {
+            return Err(CKR_ARGUMENTS_BAD);
+        }
+        let mut template = Vec::with_capacity(ulCount as usize);
+        for index in 0..ulCount as usize {
+            let attribute = unsafe { &*pTemplate.add(index) };
+            let value = unsafe { input(attribute.pValue as CK_BYTE_PTR, attribute.ulValueLen) }?;
+            template.push((attribute.type_, value.to_vec()));
+        }
+        module.find_init(hSession, &template)
+    })
+}
+
+unsafe extern "C" fn find_objects(
+    hSession: CK_SESSION_HANDLE,
+    phObject: CK_OBJECT_HANDLE_PTR,
+    ulMaxObjectCount: CK_ULONG,
+    pulObjectCount: CK_ULONG_PTR,
+) -> CK_RV {
+    with_module(|module| {
+        let count = unsafe { writable(pulObjectCount) }?;
+        if phObject.is_null() {
+            return Err(CKR_ARGUMENTS_BAD);
+        }
+        let found = module.find(hSession, ulMaxObjectCount as usize)?;
+        unsafe { ptr::copy_nonoverlapping(found.as_ptr(), phObject, found.len()) };
+        *count = found.len() as CK_ULONG;
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn find_objects_final(hSession: CK_SESSION_HANDLE) -> CK_RV {
+    with_module(|module| module.find_final(hSession))
+}
+
+unsafe extern "C" fn encrypt_init(hSession: CK_SESSION_HANDLE, pMechanism: CK_MECHANISM_PTR, hKey: CK_OBJECT_HANDLE) -> CK_RV {
+    with_module(|module| {
+        module.session(hSession)?;
+        let mechanism = unsafe { mechanism(pMechanism) }?;
+        module.cipher_init(hSession, true, mechanism, hKey)
+    })
+}
+
+unsafe extern "C" fn encrypt(
+    hSession: CK_SESSION_HANDLE,
+    pData: CK_BYTE_PTR,
+    ulDataLen: CK_ULONG,
+    pEncryptedData: CK_BYTE_PTR,
+    pulEncryptedDataLen: CK_ULONG_PTR,
+) -> CK_RV {
+    with_module(|module| {
+        let data = unsafe { input(pData, ulDataLen) }?;
+        let expected_len = module.cipher_output_len(hSession, true, data.len())?;
+        unsafe { output(expected_len, pEncryptedData, pulEncryptedDataLen, || module.cipher(hSession, data)) }
+    })
+}
+
+unsafe extern "C" fn decrypt_init(hSession: CK_SESSION_HANDLE, pMechanism: CK_MECHANISM_PTR, hKey: CK_OBJECT_HANDLE) -> CK_RV {
+    with_module(|module| {
+        module.session(hSession)?;
+        let mechanism = unsafe { mechanism(pMechanism) }?;
+        module.cipher_init(hSession, false, mechanism, hKey)
+    })
+}
+
+unsafe extern "C" fn decrypt(
+    hSession: CK_SESSION_HANDLE,
+    pEncryptedData: CK_BYTE_PTR,
+    ulEncryptedDataLen: CK_ULONG,
+    pData: CK_BYTE_PTR,
+    pulDataLen: CK_ULONG_PTR,
+) -> CK_RV {
+    with_module(|module| {
+        let data = unsafe { input(pEncryptedData, ulEncryptedDataLen) }?;
+        let expected_len = module.cipher_output_len(hSession, false, data.len())?;
+        unsafe { output(expected_len, pData, pulDataLen, || module.cipher(hSession, data)) }
+    })
+}
+
+unsafe extern "C" fn sign_init(hSession: CK_SESSION_HANDLE, pMechanism: CK_MECHANISM_PTR, hKey: CK_OBJECT_HANDLE) -> CK_RV {
+    with_module(|module| {
+        module.session(hSession)?;
+        let mechanism = unsafe { mechanism(pMechanism) }?;
+        module.sign_init(hSession, mechanism, hKey)
+    })
+}
+
+unsafe extern "C" fn sign(
+    hSession: CK_SESSION_HANDLE,
+    pData: CK_BYTE_PTR,
+    ulDataLen: CK_ULONG,
+    pSignature: CK_BYTE_PTR,
+    pulSignatureLen: CK_ULONG_PTR,
+) -> CK_RV {
+    with_module(|module| {
+        let data = unsafe { input(pData, ulDataLen) }?;
+        let expected_len = module.signature_len(hSession)?;
+        unsafe { output(expected_len, pSignature, pulSignatureLen, || module.sign(hSession, data)) }
+    })
+}
+
+unsafe extern "C" fn sign_update(hSession: CK_SESSION_HANDLE, pPart: CK_BYTE_PTR, ulPartLen: CK_ULONG) -> CK_RV {
+    with_module(|module| {
+        let part = unsafe { input(pPart, ulPartLen) }?;
+        module.sign_update(hSession, part)
+    })
+}
+
+unsafe extern "C" fn sign_final(hSession: CK_SESSION_HANDLE, pSignature: CK_BYTE_PTR, pulSignatureLen: CK_ULONG_PTR) -> CK_RV {
+    with_module(|module| {
+        let expected_len = module.signature_len(hSession)?;
+        unsafe { output(expected_len, pSignature, pulSignatureLen, || module.sign_final(hSession)) }
+    })
+}
+
+// 令牌只读，且只支持单步加解密；其余函数返回CKR_FUNCTION_NOT_SUPPORTED
+macro_rules! not_supported {
+    ($($name:ident($($arg:ty),*);)*) => {
+        $(
+            #[allow(clippy::too_many_arguments)]
+            unsafe extern "C" fn $name($(_: $arg),*) -> CK_RV {
+                CKR_FUNCTION_NOT_SUPPORTED
+            }
+        )*
+    };
+}
+
+not_supported! {
+    init_token(CK_SLOT_ID, CK_UTF8CHAR_PTR, CK_ULONG, CK_UTF8CHAR_PTR);
+    init_pin(CK_SESSION_HANDLE, CK_UTF8CHAR_PTR, CK_ULONG);
+    set_pin(CK_SESSION_HANDLE, CK_UTF8CHAR_PTR, CK_ULONG, CK_UTF8CHAR_PTR, CK_ULONG);
+    get_operation_state(CK_SESSION_HANDLE, CK_BYTE_PTR, CK_ULONG_PTR);
+    set_operation_state(CK_SESSION_HANDLE, CK_BYTE_PTR, CK_ULONG, CK_OBJECT_HANDLE, CK_OBJECT_HANDLE);
+    create_object(CK_SESSION_HANDLE, CK_ATTRIBUTE_PTR, CK_ULONG, CK_OBJECT_HANDLE_PTR);
+    copy_object(CK_SESSION_HANDLE, CK_OBJECT_HANDLE, CK_ATTRIBUTE_PTR, CK_ULONG, CK_OBJECT_HANDLE_PTR);
+    destroy_object(CK_SESSION_HANDLE, CK_OBJECT_HANDLE);
+    get_object_size(CK_SESSION_HANDLE, CK_OBJECT_HANDLE, CK_ULONG_PTR);
+    set_attribute_value(CK_SESSION_HANDLE, CK_OBJECT_HANDLE, CK_ATTRIBUTE_PTR, CK_ULONG);
+    encrypt_update(CK_SESSION_HANDLE, CK_BYTE_PTR, CK_ULONG, CK_BYTE_PTR, CK_ULONG_PTR);
+    encrypt_final(CK_SESSION_HANDLE, CK_BYTE_PTR, CK_ULONG_PTR);
+    decrypt_update(CK_SESSION_HANDLE, CK_BYTE_PTR, CK_ULONG, CK_BYTE_PTR, CK_ULONG_PTR);
+    decrypt_final(CK_SESSION_HANDLE, CK_BYTE_PTR, CK_ULONG_PTR);
+    digest_init(CK_SESSION_HANDLE, CK_MECHANISM_PTR);
+    digest(CK_SESSION_HANDLE, CK_BYTE_PTR, CK_ULONG, CK_BYTE_PTR, CK_ULONG_PTR);
+    digest_update(CK_SESSION_HANDLE, CK_BYTE_PTR, CK_ULONG);
+    digest_key(CK_SESSION_HANDLE, CK_OBJECT_HANDLE);
+    digest_final(CK_SESSION_HANDLE, CK_BYTE_PTR, CK_ULONG_PTR);
+    sign_recover_init(CK_SESSION_HANDLE, CK_MECHANISM_PTR, CK_OBJECT_HANDLE);
+    sign_recover(CK_SESSION_HANDLE, CK_BYTE_PTR, CK_ULONG, CK_BYTE_PTR, CK_ULONG_PTR);
+    verify_init(CK_SESSION_HANDLE, CK_MECHANISM_PTR, CK_OBJECT_HANDLE);
+    verify(CK_SESSION_HANDLE, CK_BYTE_PTR, CK_ULONG, CK_BYTE_PTR, CK_ULONG);
+    verify_update(CK_SESSION_HANDLE, CK_BYTE_PTR, CK_ULONG);
+    verify_final(CK_SESSION_HANDLE, CK_BYTE_PTR, CK_ULONG);
+    verify_recover_init(CK_SESSION_HANDLE, CK_MECHANISM_PTR, CK_OBJECT_HANDLE);
+    verify_recover(CK_SESSION_HANDLE, CK_BYTE_PTR, CK_ULONG, CK_BYTE_PTR, CK_ULONG_PTR);
+    digest_encrypt_update(CK_SESSION_HANDLE, CK_BYTE_PTR, CK_ULONG, CK_BYTE_PTR, CK_ULONG_PTR);
+    decrypt_digest_update(CK_SESSION_HANDLE, CK_BYTE_PTR, CK_ULONG, CK_BYTE_PTR, CK_ULONG_PTR);
+    sign_encrypt_update(CK_SESSION_HANDLE, CK_BYTE_PTR, CK_ULONG, CK_BYTE_PTR, CK_ULONG_PTR);
+    decrypt_verify_update(CK_SESSION_HANDLE, CK_BYTE_PTR, CK_ULONG, CK_BYTE_PTR, CK_ULONG_PTR);
+    generate_key(CK_SESSION_HANDLE, CK_MECHANISM_PTR, CK_ATTRIBUTE_PTR, CK_ULONG, CK_OBJECT_HANDLE_PTR);
+    generate_key_pair(
+        CK_SESSION_HANDLE,
+        CK_MECHANISM_PTR,
+        CK_ATTRIBUTE_PTR,
+        CK_ULONG,
+        CK_ATTRIBUTE_PTR,
+        CK_ULONG,
+        CK_OBJECT_HANDLE_PTR,
+        CK_OBJECT_HANDLE_PTR
+    );
+    wrap_key(CK_SESSION_HANDLE, CK_MECHANISM_PTR, CK_OBJECT_HANDLE, CK_OBJECT_HANDLE, CK_BYTE_PTR, CK_ULONG_PTR);
+    unwrap_key(
+        CK_SESSION_HANDLE,
+        CK_MECHANISM_PTR,
+        CK_OBJECT_HANDLE,
+        CK_BYTE_PTR,
+        CK_ULONG,
+        CK_ATTRIBUTE_PTR,
+        CK_ULONG,
+        CK_OBJECT_HANDLE_PTR
+    );
+    derive_key(CK_SESSION_HANDLE, CK_MECHANISM_PTR, CK_OBJECT_HANDLE, CK_ATTRIBUTE_PTR, CK_ULONG, CK_OBJECT_HANDLE_PTR);
+    seed_random(CK_SESSION_HANDLE, CK_BYTE_PTR, CK_ULONG);
+    generate_random(CK_SESSION_HANDLE, CK_BYTE_PTR, CK_ULONG);
+    get_function_status(CK_SESSION_HANDLE);
+    cancel_function(CK_SESSION_HANDLE);
+    wait_for_slot_event(CK_FLAGS, CK_SLOT_ID_PTR, CK_VOID_PTR);
+}
@@ -0,0 +1,189 @@
+use cryptoki_sys::*;
+
+use crate::client::{KeyType, PublicKey};
+
+// P-256曲线的OID（1.2.840.10045.3.1.7），DER编码
+const P256_PARAMS: &[u8] = &[0x06, 0x08, 0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x03, 0x01, 0x07];
+// Ed25519的OID（1.3.101.112），DER编码
+const ED25519_PARAMS: &[u8] = &[0x06, 0x03, 0x2B, 0x65, 0x70];
+
+// 秘密钥和私钥中不可读取的属性
+const SENSITIVE_ATTRIBUTES: &[CK_ATTRIBUTE_TYPE] = &[
+    CKA_VALUE,
+    CKA_PRIVATE_EXPONENT,
+    CKA_PRIME_1,
+    CKA_PRIME_2,
+    CKA_EXPONENT_1,
+    CKA_EXPONENT_2,
+    CKA_COEFFICIENT,
+];
+
+// 句柄低两位区分同一密钥对应的对象
+const KIND_KEY: CK_OBJECT_HANDLE = 0;
+const KIND_PUBLIC_KEY: CK_OBJECT_HANDLE = 1;
+const KIND_CERTIFICATE: CK_OBJECT_HANDLE = 2;
+
+/// 由服务端密钥映射得到的PKCS#11对象
+///
+/// 对称密钥映射为一个秘密钥对象；私钥映射为私钥、公钥和（有证书时）证书对象，三者的CKA_ID相同。
+#[derive(Debug, Clone)]
+pub struct Object {
+    pub handle: CK_OBJECT_HANDLE,
+    pub key_id: u64,
+    pub key_type: KeyType,
+    /// 密钥长度，单位为位
+    pub bits: u32,
+    attributes: Vec<(CK_ATTRIBUTE_TYPE, Vec<u8>)>,
+    sensitive: bool,
+}
+
+/// 读取属性的结果
+pub enum AttributeValue<'a> {
+    Value(&'a [u8]),
+    Sensitive,
+    Invalid,
+}
+
+impl Object {
+    pub fn class(&self) -> CK_OBJECT_CLASS {
+        match self.handle & 0b11 {
+            KIND_KEY if self.key_type == KeyType::Aes => CKO_SECRET_KEY,
+            KIND_KEY => CKO_PRIVATE_KEY,
+            KIND_PUBLIC_KEY => CKO_PUBLIC_KEY,
+            _ => CKO_CERTIFICATE,
+        }
+    }
+
+    pub fn attribute(&self, attribute_type: CK_ATTRIBUTE_TYPE) -> AttributeValue<'_> {
+        if self.sensitive && SENSITIVE_ATTRIBUTES.contains(&attribute_type) {
+            return AttributeValue::Sensitive;
+        }
+        match self.attributes.iter().find(|(known, _)| *known == attribute_type) {
+            Some((_, value)) => AttributeValue::Value(value),
+            None => AttributeValue::Invalid,
+        }
+    }
+
+    /// 模板中的每个属性都存在且值相同时匹配，空模板匹配全部对象
+    pub fn matches(&self, template: &[(CK_ATTRIBUTE_TYPE, Vec<u8>)]) -> bool {
+        template.iter().all(|(attribute_type, expected)| {
+            matches!(self.attribute(*attribute_type), AttributeValue::Value(value) if value == expected.as_slice())
+        })
+    }
+}
+
+/// 为一个服务端密钥生成全部对象
+pub fn objects_for(key: &PublicKey) -> Vec<Object> {
+    let base = (key.key_id as CK_OBJECT_HANDLE) << 2;
+    let common = |class: CK_OBJECT_CLASS, private: bool| {
+        vec![
+            (CKA_CLASS, ulong(class)),
+            (CKA_TOKEN, boolean(true)),
+            (CKA_PRIVATE, boolean(private)),
+            (CKA_MODIFIABLE, boolean(false)),
+            (CKA_LABEL, key.name.as_bytes().to_vec()),
+            (CKA_ID, key.key_id.to_string().into_bytes()),
+        ]
+    };
+    let object = |kind: CK_OBJECT_HANDLE, attributes: Vec<(CK_ATTRIBUTE_TYPE, Vec<u8>)>, sensitive: bool| Object {
+        handle: base | kind,
+        key_id: key.key_id,
+        key_type: key.key_type,
+        bits: key.bits,
+        attributes,
+        sensitive,
+    };
+
+    // 密钥内容不离开服务端
+    let protection = [
+        (CKA_SENSITIVE, boolean(true)),
+        (CKA_ALWAYS_SENSITIVE, boolean(true)),
+        (CKA_EXTRACTABLE, boolean(false)),
+        (CKA_NEVER_EXTRACTABLE, boolean(true)),
+        (CKA_LOCAL, boolean(false)),
+    ];
+
+    if key.key_type == KeyType::Aes {
+        let mut attributes = common(CKO_SECRET_KEY, true);
+        attributes.extend(protection);
+        attributes.extend([
+            (CKA_KEY_TYPE, ulong(CKK_AES)),
+            (CKA_VALUE_LEN, ulong((key.bits / 8) as CK_ULONG)),
+            (CKA_ENCRYPT, boolean(true)),
+            (CKA_DECRYPT, boolean(true)),
+            (CKA_SIGN, boolean(false)),
+            (CKA_VERIFY, boolean(false)),
+        ]);
+        return vec![object(KIND_KEY, attributes, true)];
+    }
+
+    let key_type = match key.key_type {
+        KeyType::Rsa => CKK_RSA,
+        KeyType::EcP256 => CKK_EC,
+        _ => CKK_EC_EDWARDS,
+    };
+    let mut public_parts = Vec::new();
+    match key.key_type {
+        KeyType::Rsa => {
+            public_parts.push((CKA_MODULUS, key.modulus.clone().unwrap_or_default()));
+            public_parts.push((CKA_PUBLIC_EXPONENT, key.public_exponent.clone().unwrap_or_default()));
+            public_parts.push((CKA_MODULUS_BITS, ulong(key.bits as CK_ULONG)));
+        }
+        KeyType::EcP256 => public_parts.push((CKA_EC_PARAMS, P256_PARAMS.to_vec())),
+        _ => public_parts.push((CKA_EC_PARAMS, ED25519_PARAMS.to_vec())),
+    }
+
+    let mut private_attributes = common(CKO_PRIVATE_KEY, true);
+    private_attributes.extend(protection);
+    private_attributes.extend([
+        (CKA_KEY_TYPE, ulong(key_type)),
+        (CKA_SIGN, boolean(true)),
+        (CKA_DECRYPT, boolean(false)),
+    ]);
+    private_attributes.extend(public_parts.iter().cloned());
+
+    let mut public_attributes = common(CKO_PUBLIC_KEY, false);
+    public_attributes.extend([
+        (CKA_KEY_TYPE, ulong(key_type)),
+        (CKA_VERIFY, boolean(true)),
+        (CKA_ENCRYPT, boolean(false)),
+    ]);
+    public_attributes.extend(public_parts);
+    if let Some(point) = &key.ec_point {
+        public_attributes.push((CKA_EC_POINT, octet_string(point)));
+    }
+
+    let mut objects = vec![
+        object(KIND_KEY, private_attributes, true),
+        object(KIND_PUBLIC_KEY, public_attributes, false),
+    ];
+    if let Some(certificate) = &key.certificate {
+        let mut attributes = common(CKO_CERTIFICATE, false);
+        attributes.extend([
+            (CKA_CERTIFICATE_TYPE, ulong(CKC_X_509)),
+            (CKA_VALUE, certificate.clone()),
+        ]);
+        objects.push(object(KIND_CERTIFICATE, attributes, false));
+    }
+    objects
+}
+
+fn ulong(value: CK_ULONG) -> Vec<u8> {
+    value.to_ne_bytes().to_vec()
+}
+
+fn boolean(value: bool) -> Vec<u8> {
+    vec![if value { CK_TRUE } else { CK_FALSE }]
+}
+
+// CKA_EC_POINT为DER OCTET STRING包装的点
+fn octet_string(value: &[u8]) -> Vec<u8> {
+    let mut encoded = vec![0x04];
+    if value.len() < 0x80 {
+        encoded.push(value.len() as u8);
+    } else {
+        encoded.extend([0x81, value.len() as u8]);
+    }
+    encoded.extend_from_slice(value);
+    encoded
+}
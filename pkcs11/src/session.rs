@@ -0,0 +1,359 @@
+use cryptoki_sys::*;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::client::{ApiError, EcipherClient, KeyType, SignAlgorithm};
+use crate::object::{self, Object};
+
+/// 唯一的槽位
+pub const SLOT_ID: CK_SLOT_ID = 1;
+/// AES-GCM的IV和认证标签长度
+pub const GCM_IV_SIZE: usize = 12;
+pub const GCM_TAG_SIZE: usize = 16;
+
+// 未配置ECIPHER_PKCS11_URL时使用的服务端地址
+const DEFAULT_URL: &str = "http://127.0.0.1:3000";
+
+// DigestInfo中摘要之前的DER前缀（RFC 8017 9.2）
+const SHA256_DIGEST_INFO: &[u8] = &[
+    0x30, 0x31, 0x30, 0x0D, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05, 0x00, 0x04, 0x20,
+];
+const SHA384_DIGEST_INFO: &[u8] = &[
+    0x30, 0x41, 0x30, 0x0D, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x02, 0x05, 0x00, 0x04, 0x30,
+];
+
+/// C_Initialize到C_Finalize之间的模块状态
+pub static MODULE: Mutex<Option<Module>> = Mutex::new(None);
+
+/// 签名机制的输入形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignInput {
+    /// 原始消息，由服务端计算摘要
+    Message,
+    /// 已计算的摘要
+    Digest,
+    /// CKM_RSA_PKCS的DigestInfo，按其中的摘要算法选择SHA-256或SHA-384
+    DigestInfo,
+}
+
+/// 已解析的机制参数
+pub enum Mechanism {
+    AesGcm { iv: Vec<u8>, aad: Vec<u8> },
+    Sign { algorithm: SignAlgorithm, input: SignInput },
+}
+
+struct CipherOperation {
+    encrypt: bool,
+    key_id: u64,
+    iv: Vec<u8>,
+    aad: Vec<u8>,
+}
+
+struct SignOperation {
+    key_id: u64,
+    algorithm: SignAlgorithm,
+    input: SignInput,
+    signature_len: usize,
+    buffer: Vec<u8>,
+}
+
+#[derive(Default)]
+pub struct Session {
+    pub flags: CK_FLAGS,
+    found: Option<Vec<CK_OBJECT_HANDLE>>,
+    cipher: Option<CipherOperation>,
+    sign: Option<SignOperation>,
+}
+
+pub struct Module {
+    base_url: String,
+    /// 已登录时的客户端；设置了ECIPHER_PKCS11_CREDENTIAL时初始化后即为登录状态
+    client: Option<EcipherClient>,
+    objects: Option<Vec<Object>>,
+    sessions: HashMap<CK_SESSION_HANDLE, Session>,
+    next_session: CK_SESSION_HANDLE,
+}
+
+impl Module {
+    pub fn new() -> Self {
+        let base_url = std::env::var("ECIPHER_PKCS11_URL").unwrap_or_else(|_| DEFAULT_URL.to_string());
+        let client = std::env::var("ECIPHER_PKCS11_CREDENTIAL")
+            .ok()
+            .filter(|credential| !credential.is_empty())
+            .map(|credential| EcipherClient::new(base_url.clone(), credential));
+        Self {
+            base_url,
+            client,
+            objects: None,
+            sessions: HashMap::new(),
+            next_session: 1,
+        }
+    }
+
+    pub fn is_logged_in(&self) -> bool {
+        self.client.is_some()
+    }
+
+    pub fn session_count(&self) -> usize {
+        self.sessions.len()
+    }
+
+    pub fn open_session(&mut self, flags: CK_FLAGS) -> CK_SESSION_HANDLE {
+        let handle = self.next_session;
+        self.next_session += 1;
+        self.sessions.insert(handle, Session { flags, ..Session::default() });
+        handle
+    }
+
+    pub fn close_session(&mut self, handle: CK_SESSION_HANDLE) -> Result<(), CK_RV> {
+        self.sessions.remove(&handle).map(|_| ()).ok_or(CKR_SESSION_HANDLE_INVALID)
+    }
+
+    pub fn close_all_sessions(&mut self) {
+        self.sessions.clear();
+    }
+
+    pub fn session(&mut self, handle: CK_SESSION_HANDLE) -> Result<&mut Session, CK_RV> {
+        self.sessions.get_mut(&handle).ok_or(CKR_SESSION_HANDLE_INVALID)
+    }
+
+    /// 会话状态，登录状态对全部会话生效
+    pub fn session_state(&self, flags: CK_FLAGS) -> CK_STATE {
+        match (self.is_logged_in(), flags & CKF_RW_SESSION != 0) {
+            (true, true) => CKS_RW_USER_FUNCTIONS,
+            (true, false) => CKS_RO_USER_FUNCTIONS,
+            (false, true) => CKS_RW_PUBLIC_SESSION,
+            (false, false) => CKS_RO_PUBLIC_SESSION,
+        }
+    }
+
+    /// 以PIN作为ecipher凭据或API令牌登录，并加载对象
+    pub fn login(&mut self, pin: &str) -> Result<(), CK_RV> {
+        if self.client.is_some() {
+            return Err(CKR_USER_ALREADY_LOGGED_IN);
+        }
+        let client = EcipherClient::new(self.base_url.clone(), pin);
+        let objects = load_objects(&client).map_err(|e| match e {
+            ApiError::Unauthorized => CKR_PIN_INCORRECT,
+            e => to_rv(e),
+        })?;
+        self.client = Some(client);
+        self.objects = Some(objects);
+        Ok(())
+    }
+
+    pub fn logout(&mut self) -> Result<(), CK_RV> {
+        self.client.take().ok_or(CKR_USER_NOT_LOGGED_IN)?;
+        self.objects = None;
+        for session in self.sessions.values_mut() {
+            session.cipher = None;
+            session.sign = None;
+            session.found = None;
+        }
+        Ok(())
+    }
+
+    // 未登录时没有可见对象
+    fn objects(&mut self) -> Result<&[Object], CK_RV> {
+        let Some(client) = &self.client else {
+            return Ok(&[]);
+        };
+        if self.objects.is_none() {
+            self.objects = Some(load_objects(client).map_err(to_rv)?);
+        }
+        Ok(self.objects.as_deref().unwrap_or_default())
+    }
+
+    pub fn object(&mut self, handle: CK_OBJECT_HANDLE) -> Result<&Object, CK_RV> {
+        self.objects()?
+            .iter()
+            .find(|object| object.handle == handle)
+            .ok_or(CKR_OBJECT_HANDLE_INVALID)
+    }
+
+    pub fn find_init(&mut self, handle: CK_SESSION_HANDLE, template: &[(CK_ATTRIBUTE_TYPE, Vec<u8>)]) -> Result<(), CK_RV> {
+        if self.session(handle)?.found.is_some() {
+            return Err(CKR_OPERATION_ACTIVE);
+        }
+        let found = self
+            .objects()?
+            .iter()
+            .filter(|object| object.matches(template))
+            .map(|object| object.handle)
+            .collect();
+        self.session(handle)?.found = Some(found);
+        Ok(())
+    }
+
+    pub fn find(&mut self, handle: CK_SESSION_HANDLE, max: usize) -> Result<Vec<CK_OBJECT_HANDLE>, CK_RV> {
+        let found = self.session(handle)?.found.as_mut().ok_or(CKR_OPERATION_NOT_INITIALIZED)?;
+        let count = max.min(found.len());
+        Ok(found.drain(..count).collect())
+    }
+
+    pub fn find_final(&mut self, handle: CK_SESSION_HANDLE) -> Result<(), CK_RV> {
+        self.session(handle)?.found.take().map(|_| ()).ok_or(CKR_OPERATION_NOT_INITIALIZED)
+    }
+
+    pub fn cipher_init(
+        &mut self,
+        handle: CK_SESSION_HANDLE,
+        encrypt: bool,
+        mechanism: Mechanism,
+        key: CK_OBJECT_HANDLE,
+    ) -> Result<(), CK_RV> {
+        if self.session(handle)?.cipher.is_some() {
+            return Err(CKR_OPERATION_ACTIVE);
+        }
+        let Mechanism::AesGcm { iv, aad } = mechanism else {
+            return Err(CKR_MECHANISM_INVALID);
+        };
+        let object = self.object(key)?;
+        if object.class() != CKO_SECRET_KEY {
+            return Err(CKR_KEY_TYPE_INCONSISTENT);
+        }
+        let key_id = object.key_id;
+        self.session(handle)?.cipher = Some(CipherOperation { encrypt, key_id, iv, aad });
+        Ok(())
+    }
+
+    /// 单步加密或解密的输出长度，`encrypt`须与初始化时一致
+    pub fn cipher_output_len(&mut self, handle: CK_SESSION_HANDLE, encrypt: bool, input_len: usize) -> Result<usize, CK_RV> {
+        let session = self.session(handle)?;
+        match &session.cipher {
+            Some(operation) if operation.encrypt == encrypt => {}
+            _ => return Err(CKR_OPERATION_NOT_INITIALIZED),
+        }
+        if encrypt {
+            return Ok(input_len + GCM_TAG_SIZE);
+        }
+        if input_len < GCM_TAG_SIZE {
+            session.cipher = None;
+            return Err(CKR_ENCRYPTED_DATA_LEN_RANGE);
+        }
+        Ok(input_len - GCM_TAG_SIZE)
+    }
+
+    /// 执行单步加密或解密并结束操作
+    pub fn cipher(&mut self, handle: CK_SESSION_HANDLE, input: &[u8]) -> Result<Vec<u8>, CK_RV> {
+        let operation = self.session(handle)?.cipher.take().ok_or(CKR_OPERATION_NOT_INITIALIZED)?;
+        let client = self.client.as_ref().ok_or(CKR_USER_NOT_LOGGED_IN)?;
+        if operation.encrypt {
+            client.encrypt(operation.key_id, input, &operation.iv, &operation.aad).map_err(to_rv)
+        } else {
+            client
+                .decrypt(operation.key_id, input, &operation.iv, &operation.aad)
+                .map_err(|e| match e {
+                    ApiError::Rejected(_) => CKR_ENCRYPTED_DATA_INVALID,
+                    e => to_rv(e),
+                })
+        }
+    }
+
+    pub fn sign_init(&mut self, handle: CK_SESSION_HANDLE, mechanism: Mechanism, key: CK_OBJECT_HANDLE) -> Result<(), CK_RV> {
+        if self.session(handle)?.sign.is_some() {
+            return Err(CKR_OPERATION_ACTIVE);
+        }
+        let Mechanism::Sign { algorithm, input } = mechanism else {
+            return Err(CKR_MECHANISM_INVALID);
+        };
+        let object = self.object(key)?;
+        if object.class() != CKO_PRIVATE_KEY || object.key_type != algorithm.key_type() {
+            return Err(CKR_KEY_TYPE_INCONSISTENT);
+        }
+        let signature_len = match object.key_type {
+            KeyType::Rsa => object.bits.div_ceil(8) as usize,
+            _ => 64,
+        };
+        let key_id = object.key_id;
+        self.session(handle)?.sign = Some(SignOperation {
+            key_id,
+            algorithm,
+            input,
+            signature_len,
+            buffer: Vec::new(),
+        });
+        Ok(())
+    }
+
+    pub fn signature_len(&mut self, handle: CK_SESSION_HANDLE) -> Result<usize, CK_RV> {
+        let operation = self.session(handle)?.sign.as_ref().ok_or(CKR_OPERATION_NOT_INITIALIZED)?;
+        Ok(operation.signature_len)
+    }
+
+    /// 多步签名时累积输入，只有对原始消息签名的机制支持多步
+    pub fn sign_update(&mut self, handle: CK_SESSION_HANDLE, part: &[u8]) -> Result<(), CK_RV> {
+        let session = self.session(handle)?;
+        let operation = session.sign.as_mut().ok_or(CKR_OPERATION_NOT_INITIALIZED)?;
+        if operation.input != SignInput::Message {
+            session.sign = None;
+            return Err(CKR_FUNCTION_NOT_SUPPORTED);
+        }
+        operation.buffer.extend_from_slice(part);
+        Ok(())
+    }
+
+    /// 对累积的输入签名并结束操作
+    pub fn sign_final(&mut self, handle: CK_SESSION_HANDLE) -> Result<Vec<u8>, CK_RV> {
+        let operation = self.session(handle)?.sign.take().ok_or(CKR_OPERATION_NOT_INITIALIZED)?;
+        let client = self.client.as_ref().ok_or(CKR_USER_NOT_LOGGED_IN)?;
+
+        let (algorithm, data, prehashed) = match operation.input {
+            SignInput::Message => (operation.algorithm, operation.buffer.as_slice(), false),
+            // CKM_ECDSA的输入可以是SHA-256或SHA-384摘要
+            SignInput::Digest if operation.algorithm == SignAlgorithm::EcdsaSha256 && operation.buffer.len() == 48 => {
+                (SignAlgorithm::EcdsaSha384, operation.buffer.as_slice(), true)
+            }
+            SignInput::Digest => (operation.algorithm, operation.buffer.as_slice(), true),
+            SignInput::DigestInfo => {
+                if let Some(digest) = operation.buffer.strip_prefix(SHA256_DIGEST_INFO) {
+                    (SignAlgorithm::RsaPkcs1Sha256, digest, true)
+                } else if let Some(digest) = operation.buffer.strip_prefix(SHA384_DIGEST_INFO) {
+                    (SignAlgorithm::RsaPkcs1Sha384, digest, true)
+                } else {
+                    return Err(CKR_DATA_INVALID);
+                }
+            }
+        };
+        client
+            .sign(operation.key_id, algorithm, data, prehashed)
+            .map_err(|e| match e {
+                ApiError::Rejected(_) => CKR_DATA_INVALID,
+                e => to_rv(e),
+            })
+    }
+
+    /// 单步签名
+    pub fn sign(&mut self, handle: CK_SESSION_HANDLE, data: &[u8]) -> Result<Vec<u8>, CK_RV> {
+        let operation = self.session(handle)?.sign.as_mut().ok_or(CKR_OPERATION_NOT_INITIALIZED)?;
+        if !operation.buffer.is_empty() {
+            return Err(CKR_OPERATION_ACTIVE);
+        }
+        operation.buffer = data.to_vec();
+        self.sign_final(handle)
+    }
+}
+
+// 列出调用方可见的密钥，跳过不能由服务端使用或无权使用的密钥
+fn load_objects(client: &EcipherClient) -> Result<Vec<Object>, ApiError> {
+    let mut objects = Vec::new();
+    for key_id in client.list_key_ids()? {
+        match client.public_key(key_id) {
+            Ok(key) => objects.extend(object::objects_for(&key)),
+            Err(ApiError::Unsupported(_) | ApiError::Forbidden | ApiError::NotFound) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(objects)
+}
+
+fn to_rv(e: ApiError) -> CK_RV {
+    match e {
+        ApiError::Unauthorized => CKR_USER_NOT_LOGGED_IN,
+        ApiError::Forbidden => CKR_KEY_FUNCTION_NOT_PERMITTED,
+        ApiError::NotFound => CKR_OBJECT_HANDLE_INVALID,
+        ApiError::Unsupported(_) => CKR_KEY_TYPE_INCONSISTENT,
+        ApiError::Rejected(_) => CKR_ARGUMENTS_BAD,
+        ApiError::Server(..) | ApiError::InvalidResponse | ApiError::Network(_) => CKR_DEVICE_ERROR,
+    }
+}
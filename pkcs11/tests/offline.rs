@@ -0,0 +1,318 @@
+//! 以本地模拟的HTTP服务端驱动PKCS#11接口的离线测试
+//!
+//! 模拟服务端只实现模块用到的几个端点，用于检查模块生命周期、会话管理和HTTP状态码到CK_RV的映射。
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use cryptoki_sys::*;
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::ptr;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+
+use ecipher_pkcs11::C_GetFunctionList;
+
+// 模拟服务端接受的凭据，与服务账号一样使用API令牌登录
+const PIN: &str = "ekt_0123456789abcdef0123456789abcdef";
+// 服务端TOKEN_PATHS中本模块用到的路由模板，令牌访问其余路由时返回403
+const TOKEN_PATHS: &[&str] = &[
+    "/keys/search",
+    "/keys/{id}/encrypt",
+    "/keys/{id}/decrypt",
+    "/keys/{id}/sign",
+    "/keys/{id}/public-key",
+];
+// 可由模块使用的AES密钥，以及服务端无法使用的密钥
+const AES_KEY_ID: u64 = 7;
+const UNSUPPORTED_KEY_ID: u64 = 8;
+const AES_LABEL: &str = "mock-aes";
+
+// 模块状态是进程级的全局变量，测试须串行执行
+static SERIAL: Mutex<()> = Mutex::new(());
+// 下一次加密请求返回的状态码
+static ENCRYPT_STATUS: AtomicU16 = AtomicU16::new(200);
+
+struct Request {
+    method: String,
+    path: String,
+    authorization: String,
+    body: Value,
+}
+
+// 启动模拟服务端并将模块指向它，返回的锁在测试结束前保持
+fn start_backend() -> MutexGuard<'static, ()> {
+    let guard = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    ENCRYPT_STATUS.store(200, Ordering::SeqCst);
+
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock backend");
+    let url = format!("http://{}", listener.local_addr().expect("mock backend address"));
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let _ = serve(stream);
+        }
+    });
+
+    // SAFETY: 测试由SERIAL串行执行，修改环境变量时没有其他线程读取
+    unsafe {
+        std::env::set_var("ECIPHER_PKCS11_URL", url);
+        std::env::remove_var("ECIPHER_PKCS11_CREDENTIAL");
+        std::env::set_var("NO_PROXY", "127.0.0.1");
+    }
+    guard
+}
+
+// 每个连接处理一个请求
+fn serve(mut stream: TcpStream) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+
+    let mut content_length = 0;
+    let mut authorization = String::new();
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            match name.to_ascii_lowercase().as_str() {
+                "content-length" => content_length = value.trim().parse().unwrap_or(0),
+                "authorization" => authorization = value.trim().to_string(),
+                _ => {}
+            }
+        }
+    }
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body)?;
+
+    let request = Request {
+        method,
+        path,
+        authorization,
+        body: serde_json::from_slice(&body).unwrap_or(Value::Null),
+    };
+    let (status, body) = route(&request);
+    write!(
+        stream,
+        "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+fn route(request: &Request) -> (u16, String) {
+    if request.authorization != format!("Bearer {}", PIN) {
+        return (401, "Invalid credential".to_string());
+    }
+    // 与服务端一致，API令牌只能访问密钥接口
+    if !TOKEN_PATHS.contains(&route_template(&request.path).as_str()) {
+        return (403, "API tokens may only access key routes".to_string());
+    }
+
+    let aes = format!("/keys/{}", AES_KEY_ID);
+    let unsupported = format!("/keys/{}", UNSUPPORTED_KEY_ID);
+    match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/keys/search") => (
+            200,
+            json!({ "keys": [{ "id": AES_KEY_ID }, { "id": UNSUPPORTED_KEY_ID }], "next_after_id": null }).to_string(),
+        ),
+        ("GET", path) if path == format!("{}/public-key", aes) => (
+            200,
+            json!({ "key_id": AES_KEY_ID, "name": AES_LABEL, "key_type": "aes", "bits": 256 }).to_string(),
+        ),
+        ("GET", path) if path == format!("{}/public-key", unsupported) => {
+            (422, "Key cannot be used by the server".to_string())
+        }
+        ("POST", path) if path == format!("{}/encrypt", aes) => match ENCRYPT_STATUS.load(Ordering::SeqCst) {
+            200 => {
+                // 密文为明文加16字节的认证标签
+                let mut ciphertext = STANDARD
+                    .decode(request.body["plaintext"].as_str().unwrap_or_default())
+                    .unwrap_or_default();
+                ciphertext.extend([0xA5; 16]);
+                (200, json!({ "ciphertext": STANDARD.encode(ciphertext) }).to_string())
+            }
+            status => (status, "Encrypt failed".to_string()),
+        },
+        ("POST", path) if path == format!("{}/decrypt", aes) => (400, "Authentication failed".to_string()),
+        _ => (404, "Not found".to_string()),
+    }
+}
+
+// 将路径中的密钥id替换为路由参数
+fn route_template(path: &str) -> String {
+    path.split('/')
+        .map(|segment| if !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit()) { "{id}" } else { segment })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn functions() -> &'static CK_FUNCTION_LIST {
+    let mut functions: CK_FUNCTION_LIST_PTR = ptr::null_mut();
+    unsafe {
+        assert_eq!(C_GetFunctionList(&mut functions), CKR_OK);
+        &*functions
+    }
+}
+
+// 以只读串行会话打开唯一的槽位
+unsafe fn open(functions: &CK_FUNCTION_LIST) -> CK_SESSION_HANDLE {
+    let mut session = 0;
+    let rv = unsafe { functions.C_OpenSession.unwrap()(1, CKF_SERIAL_SESSION, ptr::null_mut(), None, &mut session) };
+    assert_eq!(rv, CKR_OK);
+    session
+}
+
+unsafe fn login(functions: &CK_FUNCTION_LIST, session: CK_SESSION_HANDLE, pin: &str) -> CK_RV {
+    unsafe { functions.C_Login.unwrap()(session, CKU_USER, pin.as_ptr() as CK_UTF8CHAR_PTR, pin.len() as CK_ULONG) }
+}
+
+unsafe fn find_all(functions: &CK_FUNCTION_LIST, session: CK_SESSION_HANDLE, template: &mut [CK_ATTRIBUTE]) -> Vec<CK_OBJECT_HANDLE> {
+    let mut handles = [0 as CK_OBJECT_HANDLE; 8];
+    let mut count = 0;
+    unsafe {
+        let rv = functions.C_FindObjectsInit.unwrap()(session, template.as_mut_ptr(), template.len() as CK_ULONG);
+        assert_eq!(rv, CKR_OK);
+        let rv = functions.C_FindObjects.unwrap()(session, handles.as_mut_ptr(), handles.len() as CK_ULONG, &mut count);
+        assert_eq!(rv, CKR_OK);
+        assert_eq!(functions.C_FindObjectsFinal.unwrap()(session), CKR_OK);
+    }
+    handles[..count as usize].to_vec()
+}
+
+// 以AES-GCM执行单步加密或解密，返回CK_RV
+unsafe fn gcm(functions: &CK_FUNCTION_LIST, session: CK_SESSION_HANDLE, key: CK_OBJECT_HANDLE, encrypt: bool, input: &[u8]) -> CK_RV {
+    let mut iv = [0u8; 12];
+    let mut params = CK_GCM_PARAMS {
+        pIv: iv.as_mut_ptr(),
+        ulIvLen: iv.len() as CK_ULONG,
+        ulIvBits: 96,
+        pAAD: ptr::null_mut(),
+        ulAADLen: 0,
+        ulTagBits: 128,
+    };
+    let mut mechanism = CK_MECHANISM {
+        mechanism: CKM_AES_GCM,
+        pParameter: &mut params as *mut CK_GCM_PARAMS as CK_VOID_PTR,
+        ulParameterLen: size_of::<CK_GCM_PARAMS>() as CK_ULONG,
+    };
+    let mut input = input.to_vec();
+    let mut output = vec![0u8; input.len() + 16];
+    let mut output_len = output.len() as CK_ULONG;
+    unsafe {
+        if encrypt {
+            assert_eq!(functions.C_EncryptInit.unwrap()(session, &mut mechanism, key), CKR_OK);
+            functions.C_Encrypt.unwrap()(
+                session,
+                input.as_mut_ptr(),
+                input.len() as CK_ULONG,
+                output.as_mut_ptr(),
+                &mut output_len,
+            )
+        } else {
+            assert_eq!(functions.C_DecryptInit.unwrap()(session, &mut mechanism, key), CKR_OK);
+            functions.C_Decrypt.unwrap()(
+                session,
+                input.as_mut_ptr(),
+                input.len() as CK_ULONG,
+                output.as_mut_ptr(),
+                &mut output_len,
+            )
+        }
+    }
+}
+
+#[test]
+fn initialize_slots_and_sessions() {
+    let _backend = start_backend();
+    let functions = functions();
+
+    unsafe {
+        let mut count = 0;
+        assert_eq!(functions.C_GetSlotList.unwrap()(CK_TRUE, ptr::null_mut(), &mut count), CKR_CRYPTOKI_NOT_INITIALIZED);
+        assert_eq!(functions.C_Initialize.unwrap()(ptr::null_mut()), CKR_OK);
+        assert_eq!(functions.C_Initialize.unwrap()(ptr::null_mut()), CKR_CRYPTOKI_ALREADY_INITIALIZED);
+
+        // 先查询数量，缓冲区不足时返回所需数量
+        assert_eq!(functions.C_GetSlotList.unwrap()(CK_TRUE, ptr::null_mut(), &mut count), CKR_OK);
+        assert_eq!(count, 1);
+        let mut slot = 0;
+        let mut too_small = 0;
+        assert_eq!(functions.C_GetSlotList.unwrap()(CK_TRUE, &mut slot, &mut too_small), CKR_BUFFER_TOO_SMALL);
+        assert_eq!(too_small, 1);
+        assert_eq!(functions.C_GetSlotList.unwrap()(CK_TRUE, &mut slot, &mut count), CKR_OK);
+        assert_eq!(slot, 1);
+
+        let mut session = 0;
+        let rv = functions.C_OpenSession.unwrap()(slot, 0, ptr::null_mut(), None, &mut session);
+        assert_eq!(rv, CKR_SESSION_PARALLEL_NOT_SUPPORTED);
+        let rv = functions.C_OpenSession.unwrap()(slot + 1, CKF_SERIAL_SESSION, ptr::null_mut(), None, &mut session);
+        assert_eq!(rv, CKR_SLOT_ID_INVALID);
+
+        let session = open(functions);
+        let mut info = std::mem::zeroed::<CK_SESSION_INFO>();
+        assert_eq!(functions.C_GetSessionInfo.unwrap()(session, &mut info), CKR_OK);
+        assert_eq!(info.slotID, slot);
+        assert_eq!(info.state, CKS_RO_PUBLIC_SESSION);
+
+        assert_eq!(functions.C_CloseSession.unwrap()(session), CKR_OK);
+        assert_eq!(functions.C_GetSessionInfo.unwrap()(session, &mut info), CKR_SESSION_HANDLE_INVALID);
+        assert_eq!(functions.C_Finalize.unwrap()(ptr::null_mut()), CKR_OK);
+        assert_eq!(functions.C_Finalize.unwrap()(ptr::null_mut()), CKR_CRYPTOKI_NOT_INITIALIZED);
+    }
+}
+
+#[test]
+fn maps_backend_errors_to_return_values() {
+    let _backend = start_backend();
+    let functions = functions();
+
+    unsafe {
+        assert_eq!(functions.C_Initialize.unwrap()(ptr::null_mut()), CKR_OK);
+        let session = open(functions);
+
+        // 未登录时没有可见对象
+        assert!(find_all(functions, session, &mut []).is_empty());
+        assert_eq!(login(functions, session, "ekt_revoked"), CKR_PIN_INCORRECT);
+        let rv = functions.C_Login.unwrap()(session, CKU_SO, PIN.as_ptr() as CK_UTF8CHAR_PTR, PIN.len() as CK_ULONG);
+        assert_eq!(rv, CKR_USER_TYPE_INVALID);
+        assert_eq!(login(functions, session, PIN), CKR_OK);
+        assert_eq!(login(functions, session, PIN), CKR_USER_ALREADY_LOGGED_IN);
+
+        let mut info = std::mem::zeroed::<CK_SESSION_INFO>();
+        assert_eq!(functions.C_GetSessionInfo.unwrap()(session, &mut info), CKR_OK);
+        assert_eq!(info.state, CKS_RO_USER_FUNCTIONS);
+
+        // 服务端无法使用的密钥不出现在对象列表中
+        let objects = find_all(functions, session, &mut []);
+        assert_eq!(objects.len(), 1);
+        let mut template = [CK_ATTRIBUTE {
+            type_: CKA_LABEL,
+            pValue: AES_LABEL.as_ptr() as CK_VOID_PTR,
+            ulValueLen: AES_LABEL.len() as CK_ULONG,
+        }];
+        assert_eq!(find_all(functions, session, &mut template), objects);
+        let key = objects[0];
+
+        assert_eq!(gcm(functions, session, key, true, b"plaintext"), CKR_OK);
+        assert_eq!(gcm(functions, session, key, false, &[0u8; 32]), CKR_ENCRYPTED_DATA_INVALID);
+        ENCRYPT_STATUS.store(403, Ordering::SeqCst);
+        assert_eq!(gcm(functions, session, key, true, b"plaintext"), CKR_KEY_FUNCTION_NOT_PERMITTED);
+        ENCRYPT_STATUS.store(500, Ordering::SeqCst);
+        assert_eq!(gcm(functions, session, key, true, b"plaintext"), CKR_DEVICE_ERROR);
+        ENCRYPT_STATUS.store(401, Ordering::SeqCst);
+        assert_eq!(gcm(functions, session, key, true, b"plaintext"), CKR_USER_NOT_LOGGED_IN);
+
+        assert_eq!(functions.C_Logout.unwrap()(session), CKR_OK);
+        assert!(find_all(functions, session, &mut []).is_empty());
+        assert_eq!(functions.C_Finalize.unwrap()(ptr::null_mut()), CKR_OK);
+    }
+}
@@ -0,0 +1,226 @@
+//! 通过PKCS#11接口驱动本地ecipher服务端的端到端测试
+//!
+//! 需要运行中的服务端，以及对其有写权限的凭据：
+//!
+//! ```text
+//! ECIPHER_PKCS11_URL=http://127.0.0.1:3000 ECIPHER_PKCS11_CREDENTIAL=<token> \
+//!     cargo test -p ecipher-pkcs11 -- --ignored
+//! ```
+//!
+//! 不依赖服务端的生命周期和错误映射测试见`offline.rs`。
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use cryptoki_sys::*;
+use ed25519_dalek::pkcs8::{EncodePrivateKey, LineEnding};
+use ed25519_dalek::{Signature, SigningKey, Verifier};
+use rand_core::{OsRng, RngCore};
+use serde_json::{json, Value};
+use std::ptr;
+
+use ecipher_pkcs11::C_GetFunctionList;
+
+struct Server {
+    http: reqwest::blocking::Client,
+    url: String,
+    credential: String,
+}
+
+impl Server {
+    fn from_env() -> Self {
+        Self {
+            http: reqwest::blocking::Client::new(),
+            url: std::env::var("ECIPHER_PKCS11_URL").unwrap_or_else(|_| "http://127.0.0.1:3000".to_string()),
+            credential: std::env::var("ECIPHER_PKCS11_CREDENTIAL").expect("ECIPHER_PKCS11_CREDENTIAL must be set"),
+        }
+    }
+
+    fn create_key(&self, name: &str, payload: Value) -> u64 {
+        let response = self
+            .http
+            .post(format!("{}/keys", self.url))
+            .bearer_auth(&self.credential)
+            .json(&json!({ "name": name, "payload": payload }))
+            .send()
+            .expect("create key");
+        assert!(response.status().is_success(), "create key: {}", response.status());
+        response.json::<Value>().expect("key response")["id"].as_u64().expect("key id")
+    }
+
+    fn delete_key(&self, key_id: u64) {
+        let _ = self
+            .http
+            .delete(format!("{}/keys/{}", self.url, key_id))
+            .bearer_auth(&self.credential)
+            .send();
+    }
+}
+
+// 按标签和类别查找唯一的对象
+unsafe fn find(functions: &CK_FUNCTION_LIST, session: CK_SESSION_HANDLE, label: &str, class: CK_OBJECT_CLASS) -> CK_OBJECT_HANDLE {
+    let mut template = [
+        CK_ATTRIBUTE {
+            type_: CKA_LABEL,
+            pValue: label.as_ptr() as CK_VOID_PTR,
+            ulValueLen: label.len() as CK_ULONG,
+        },
+        CK_ATTRIBUTE {
+            type_: CKA_CLASS,
+            pValue: &class as *const CK_OBJECT_CLASS as CK_VOID_PTR,
+            ulValueLen: size_of::<CK_OBJECT_CLASS>() as CK_ULONG,
+        },
+    ];
+    let mut handles = [0 as CK_OBJECT_HANDLE; 2];
+    let mut count = 0;
+    unsafe {
+        assert_eq!(functions.C_FindObjectsInit.unwrap()(session, template.as_mut_ptr(), 2), CKR_OK);
+        assert_eq!(functions.C_FindObjects.unwrap()(session, handles.as_mut_ptr(), 2, &mut count), CKR_OK);
+        assert_eq!(functions.C_FindObjectsFinal.unwrap()(session), CKR_OK);
+    }
+    assert_eq!(count, 1, "expected exactly one object labelled {}", label);
+    handles[0]
+}
+
+#[test]
+#[ignore = "requires a local ecipher server"]
+fn encrypt_decrypt_and_sign_through_pkcs11() {
+    let server = Server::from_env();
+    let suffix = OsRng.next_u32();
+
+    let mut aes_key = [0u8; 32];
+    OsRng.fill_bytes(&mut aes_key);
+    let aes_label = format!("pkcs11-aes-{}", suffix);
+    let aes_id = server.create_key(
+        &aes_label,
+        json!({ "kind": "blob", "data": STANDARD.encode(aes_key), "encoding": "base64" }),
+    );
+
+    let mut seed = [0u8; 32];
+    OsRng.fill_bytes(&mut seed);
+    let signing_key = SigningKey::from_bytes(&seed);
+    let verifying_key = signing_key.verifying_key();
+    let mut public_blob = Vec::new();
+    for field in [b"ssh-ed25519".as_slice(), verifying_key.as_bytes().as_slice()] {
+        public_blob.extend((field.len() as u32).to_be_bytes());
+        public_blob.extend_from_slice(field);
+    }
+    let ed25519_label = format!("pkcs11-ed25519-{}", suffix);
+    let ed25519_id = server.create_key(
+        &ed25519_label,
+        json!({
+            "kind": "ssh_key",
+            "private_key": signing_key.to_pkcs8_pem(LineEnding::LF).expect("PKCS#8 PEM").as_str(),
+            "public_key": format!("ssh-ed25519 {}", STANDARD.encode(public_blob)),
+        }),
+    );
+
+    let mut functions: CK_FUNCTION_LIST_PTR = ptr::null_mut();
+    unsafe {
+        assert_eq!(C_GetFunctionList(&mut functions), CKR_OK);
+        let functions = &*functions;
+        assert_eq!(functions.C_Initialize.unwrap()(ptr::null_mut()), CKR_OK);
+
+        let mut slot = 0;
+        let mut count = 1;
+        assert_eq!(functions.C_GetSlotList.unwrap()(CK_TRUE, &mut slot, &mut count), CKR_OK);
+        let mut session = 0;
+        let flags = CKF_SERIAL_SESSION;
+        assert_eq!(functions.C_OpenSession.unwrap()(slot, flags, ptr::null_mut(), None, &mut session), CKR_OK);
+
+        // ECIPHER_PKCS11_CREDENTIAL已使模块处于登录状态
+        let pin = server.credential.as_bytes();
+        let rv = functions.C_Login.unwrap()(session, CKU_USER, pin.as_ptr() as CK_UTF8CHAR_PTR, pin.len() as CK_ULONG);
+        assert!(rv == CKR_OK || rv == CKR_USER_ALREADY_LOGGED_IN, "login: {:#x}", rv);
+
+        // AES-GCM往返
+        let aes = find(functions, session, &aes_label, CKO_SECRET_KEY);
+        let mut iv = [0u8; 12];
+        OsRng.fill_bytes(&mut iv);
+        let aad = b"pkcs11";
+        let mut params = CK_GCM_PARAMS {
+            pIv: iv.as_mut_ptr(),
+            ulIvLen: iv.len() as CK_ULONG,
+            ulIvBits: 96,
+            pAAD: aad.as_ptr() as CK_BYTE_PTR,
+            ulAADLen: aad.len() as CK_ULONG,
+            ulTagBits: 128,
+        };
+        let mut mechanism = CK_MECHANISM {
+            mechanism: CKM_AES_GCM,
+            pParameter: &mut params as *mut CK_GCM_PARAMS as CK_VOID_PTR,
+            ulParameterLen: size_of::<CK_GCM_PARAMS>() as CK_ULONG,
+        };
+        let mut plaintext = b"hello from pkcs11".to_vec();
+
+        assert_eq!(functions.C_EncryptInit.unwrap()(session, &mut mechanism, aes), CKR_OK);
+        let mut ciphertext_len = 0;
+        let rv = functions.C_Encrypt.unwrap()(
+            session,
+            plaintext.as_mut_ptr(),
+            plaintext.len() as CK_ULONG,
+            ptr::null_mut(),
+            &mut ciphertext_len,
+        );
+        assert_eq!(rv, CKR_OK);
+        let mut ciphertext = vec![0u8; ciphertext_len as usize];
+        let rv = functions.C_Encrypt.unwrap()(
+            session,
+            plaintext.as_mut_ptr(),
+            plaintext.len() as CK_ULONG,
+            ciphertext.as_mut_ptr(),
+            &mut ciphertext_len,
+        );
+        assert_eq!(rv, CKR_OK);
+        assert_eq!(ciphertext_len as usize, plaintext.len() + 16);
+
+        assert_eq!(functions.C_DecryptInit.unwrap()(session, &mut mechanism, aes), CKR_OK);
+        let mut decrypted = vec![0u8; ciphertext.len()];
+        let mut decrypted_len = decrypted.len() as CK_ULONG;
+        let rv = functions.C_Decrypt.unwrap()(
+            session,
+            ciphertext.as_mut_ptr(),
+            ciphertext_len,
+            decrypted.as_mut_ptr(),
+            &mut decrypted_len,
+        );
+        assert_eq!(rv, CKR_OK);
+        assert_eq!(&decrypted[..decrypted_len as usize], plaintext.as_slice());
+
+        // 密钥值不可读取
+        let mut value_template = [CK_ATTRIBUTE {
+            type_: CKA_VALUE,
+            pValue: ptr::null_mut(),
+            ulValueLen: 0,
+        }];
+        let rv = functions.C_GetAttributeValue.unwrap()(session, aes, value_template.as_mut_ptr(), 1);
+        assert_eq!(rv, CKR_ATTRIBUTE_SENSITIVE);
+
+        // Ed25519签名由本地公钥验证
+        let private_key = find(functions, session, &ed25519_label, CKO_PRIVATE_KEY);
+        let mut mechanism = CK_MECHANISM {
+            mechanism: CKM_EDDSA,
+            pParameter: ptr::null_mut(),
+            ulParameterLen: 0,
+        };
+        let mut message = b"signed through pkcs11".to_vec();
+        assert_eq!(functions.C_SignInit.unwrap()(session, &mut mechanism, private_key), CKR_OK);
+        let mut signature = [0u8; 64];
+        let mut signature_len = signature.len() as CK_ULONG;
+        let rv = functions.C_Sign.unwrap()(
+            session,
+            message.as_mut_ptr(),
+            message.len() as CK_ULONG,
+            signature.as_mut_ptr(),
+            &mut signature_len,
+        );
+        assert_eq!(rv, CKR_OK);
+        verifying_key
+            .verify(&message, &Signature::from_bytes(&signature))
+            .expect("signature verifies");
+
+        assert_eq!(functions.C_CloseSession.unwrap()(session), CKR_OK);
+        assert_eq!(functions.C_Finalize.unwrap()(ptr::null_mut()), CKR_OK);
+    }
+
+    server.delete_key(aes_id);
+    server.delete_key(ed25519_id);
+}
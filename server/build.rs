@@ -0,0 +1,9 @@
+// 由shared中的protobuf定义生成gRPC服务端代码
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:rerun-if-changed=../shared/proto");
+
+    tonic_build::configure()
+        .build_client(false)
+        .compile_protos(&["../shared/proto/ecipher/key/v1/key.proto"], &["../shared/proto"])?;
+    Ok(())
+}
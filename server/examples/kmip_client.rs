@@ -0,0 +1,158 @@
+//! 本地KMIP测试客户端：依次执行Create、Activate、Get、GetAttributes、Locate、Revoke和Destroy并打印结果
+//!
+//! 用法：`cargo run -p ecipher-server --example kmip_client -- [host:port] [1.4|2.0]`
+//!
+//! 环境变量：
+//! - KMIP_CA_CERT：签发服务端证书的CA（PEM）
+//! - KMIP_CLIENT_CERT / KMIP_CLIENT_KEY：服务端要求客户端证书时使用
+//! - KMIP_PASSWORD：ecipher凭据或API令牌；KMIP_USERNAME仅作标识
+
+use shared::kmip::tag;
+use shared::kmip::ttlv::{Ttlv, Value, HEADER_SIZE};
+use shared::kmip::{
+    encode_attributes, request_message, Attribute, Operation, ProtocolVersion, ALGORITHM_AES, DEFAULT_PORT,
+    OBJECT_TYPE_SYMMETRIC_KEY, REVOCATION_REASON_KEY_COMPROMISE,
+};
+use std::error::Error;
+use std::fs::File;
+use std::io::BufReader;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let mut args = std::env::args().skip(1);
+    let addr = args.next().unwrap_or_else(|| format!("127.0.0.1:{}", DEFAULT_PORT));
+    let version = match args.next().as_deref() {
+        Some("2.0") => ProtocolVersion::V2_0,
+        _ => ProtocolVersion::V1_4,
+    };
+    let username = std::env::var("KMIP_USERNAME").unwrap_or_else(|_| "kmip".to_string());
+    let password = std::env::var("KMIP_PASSWORD")?;
+    let credential = Some((username.as_str(), password.as_str()));
+
+    let mut stream = connect(&addr).await?;
+
+    // Create：AES-256，未给出Name时服务端生成名称
+    let attributes = vec![
+        Attribute::CryptographicAlgorithm(ALGORITHM_AES),
+        Attribute::CryptographicLength(256),
+        Attribute::CryptographicUsageMask(0x0C),
+    ];
+    let template = match version.is_v2() {
+        true => Ttlv::structure(tag::ATTRIBUTES, attributes.iter().map(|a| a.to_ttlv(version)).collect()),
+        false => Ttlv::structure(tag::TEMPLATE_ATTRIBUTE, attributes.iter().map(|a| a.to_ttlv(version)).collect()),
+    };
+    let create = vec![Ttlv::enumeration(tag::OBJECT_TYPE, OBJECT_TYPE_SYMMETRIC_KEY), template];
+    let response = send(&mut stream, request_message(version, credential, vec![(Operation::Create, create)])).await?;
+    let id = response_identifier(&response).ok_or("Create failed")?;
+    println!("created object {}", id);
+
+    let target = || vec![Ttlv::text(tag::UNIQUE_IDENTIFIER, id.clone())];
+    let revoke = vec![
+        Ttlv::text(tag::UNIQUE_IDENTIFIER, id.clone()),
+        Ttlv::structure(
+            tag::REVOCATION_REASON,
+            vec![
+                Ttlv::enumeration(tag::REVOCATION_REASON_CODE, REVOCATION_REASON_KEY_COMPROMISE),
+                Ttlv::text(tag::REVOCATION_MESSAGE, "kmip_client test"),
+            ],
+        ),
+    ];
+    let batch = vec![
+        (Operation::Activate, target()),
+        (Operation::Get, target()),
+        (Operation::GetAttributes, target()),
+        (Operation::Locate, encode_attributes(&[Attribute::CryptographicAlgorithm(ALGORITHM_AES)], version)),
+        (Operation::Revoke, revoke),
+        (Operation::Destroy, target()),
+    ];
+    send(&mut stream, request_message(version, credential, batch)).await?;
+
+    Ok(())
+}
+
+async fn connect(addr: &str) -> Result<TlsStream<TcpStream>, Box<dyn Error>> {
+    let mut roots = RootCertStore::empty();
+    let ca_path = std::env::var("KMIP_CA_CERT")?;
+    for cert in rustls_pemfile::certs(&mut BufReader::new(File::open(ca_path)?)) {
+        roots.add(cert?)?;
+    }
+
+    let builder = ClientConfig::builder().with_root_certificates(roots);
+    let config = match (std::env::var("KMIP_CLIENT_CERT"), std::env::var("KMIP_CLIENT_KEY")) {
+        (Ok(cert_path), Ok(key_path)) => {
+            let certs =
+                rustls_pemfile::certs(&mut BufReader::new(File::open(cert_path)?)).collect::<Result<Vec<_>, _>>()?;
+            let key = rustls_pemfile::private_key(&mut BufReader::new(File::open(key_path)?))?
+                .ok_or("KMIP_CLIENT_KEY contains no private key")?;
+            builder.with_client_auth_cert(certs, key)?
+        }
+        _ => builder.with_no_client_auth(),
+    };
+
+    let host = addr.rsplit_once(':').map(|(host, _)| host).unwrap_or(addr).to_string();
+    let stream = TcpStream::connect(addr).await?;
+    let connector = TlsConnector::from(Arc::new(config));
+    Ok(connector.connect(ServerName::try_from(host)?, stream).await?)
+}
+
+// 发送一条请求消息并打印响应中每个批量条目的结果
+async fn send(stream: &mut TlsStream<TcpStream>, request: Ttlv) -> Result<Ttlv, Box<dyn Error>> {
+    stream.write_all(&request.to_bytes()).await?;
+
+    let mut header = [0u8; HEADER_SIZE];
+    stream.read_exact(&mut header).await?;
+    let mut message = header.to_vec();
+    message.resize(Ttlv::message_length(&header), 0);
+    stream.read_exact(&mut message[HEADER_SIZE..]).await?;
+    let (response, _) = Ttlv::decode(&message)?;
+
+    for item in response.children(tag::BATCH_ITEM) {
+        let operation = item
+            .child(tag::OPERATION)
+            .and_then(|operation| operation.as_enumeration().ok())
+            .and_then(Operation::from_code)
+            .map(Operation::as_str)
+            .unwrap_or("?");
+        match item.child(tag::RESULT_MESSAGE).and_then(|message| message.as_text().ok()) {
+            Some(message) => println!("{:<14} failed: {}", operation, message),
+            None => println!("{:<14} ok: {}", operation, summarize(item.child(tag::RESPONSE_PAYLOAD))),
+        }
+    }
+    Ok(response)
+}
+
+fn response_identifier(response: &Ttlv) -> Option<String> {
+    response
+        .child(tag::BATCH_ITEM)?
+        .child(tag::RESPONSE_PAYLOAD)?
+        .child(tag::UNIQUE_IDENTIFIER)?
+        .as_text()
+        .ok()
+        .map(str::to_string)
+}
+
+// 概括载荷中的各条目，结构体只显示条目数，字节串只显示标签
+fn summarize(payload: Option<&Ttlv>) -> String {
+    let Some(payload) = payload else {
+        return String::new();
+    };
+    payload
+        .items()
+        .iter()
+        .map(|item| match &item.value {
+            Value::TextString(text) => format!("{}={}", item.tag, text),
+            Value::Integer(value) => format!("{}={}", item.tag, value),
+            Value::Enumeration(value) => format!("{}={}", item.tag, value),
+            Value::Structure(items) => format!("{}{{{} items}}", item.tag, items.len()),
+            _ => item.tag.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
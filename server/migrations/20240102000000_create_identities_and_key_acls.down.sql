@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS key_acls;
+DROP TABLE IF EXISTS identities;
\ No newline at end of file
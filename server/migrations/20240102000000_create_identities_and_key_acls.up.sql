@@ -0,0 +1,20 @@
+CREATE TABLE IF NOT EXISTS identities (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    name VARCHAR(255) NOT NULL,
+    role VARCHAR(32) NOT NULL,
+    credential_hash CHAR(64) NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_name (name),
+    UNIQUE KEY uk_credential_hash (credential_hash)
+);
+
+CREATE TABLE IF NOT EXISTS key_acls (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    key_id BIGINT NOT NULL,
+    subject_type VARCHAR(16) NOT NULL,
+    subject VARCHAR(255) NOT NULL,
+    permission VARCHAR(32) NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_grant (key_id, subject_type, subject, permission),
+    CONSTRAINT fk_key_acls_key FOREIGN KEY (key_id) REFERENCES keys (id) ON DELETE CASCADE
+);
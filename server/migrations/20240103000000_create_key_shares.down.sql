@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS key_shares;
+ALTER TABLE identities DROP COLUMN public_key;
\ No newline at end of file
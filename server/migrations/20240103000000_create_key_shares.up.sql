@@ -0,0 +1,16 @@
+ALTER TABLE identities ADD COLUMN public_key VARCHAR(64) NULL;
+
+CREATE TABLE IF NOT EXISTS key_shares (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    key_id BIGINT NOT NULL,
+    sharer_id BIGINT NOT NULL,
+    recipient_id BIGINT NOT NULL,
+    sealed_data TEXT NOT NULL,
+    expires_at DATETIME NULL,
+    revoked_at DATETIME NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    KEY idx_recipient (recipient_id),
+    CONSTRAINT fk_key_shares_key FOREIGN KEY (key_id) REFERENCES keys (id) ON DELETE CASCADE,
+    CONSTRAINT fk_key_shares_sharer FOREIGN KEY (sharer_id) REFERENCES identities (id) ON DELETE CASCADE,
+    CONSTRAINT fk_key_shares_recipient FOREIGN KEY (recipient_id) REFERENCES identities (id) ON DELETE CASCADE
+);
@@ -0,0 +1,18 @@
+CREATE TABLE IF NOT EXISTS audit_events (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    actor_id BIGINT NULL,
+    actor_name VARCHAR(255) NULL,
+    action VARCHAR(64) NOT NULL,
+    key_id BIGINT NULL,
+    outcome VARCHAR(32) NOT NULL,
+    status_code SMALLINT UNSIGNED NOT NULL,
+    source_ip VARCHAR(64) NULL,
+    request_id VARCHAR(64) NOT NULL,
+    created_at DATETIME(6) NOT NULL,
+    prev_hash CHAR(64) NOT NULL,
+    hash CHAR(64) NOT NULL,
+    KEY idx_actor (actor_id),
+    KEY idx_action (action),
+    KEY idx_key (key_id),
+    KEY idx_created_at (created_at)
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS key_versions;
+DROP TABLE IF EXISTS key_rotation_policies;
\ No newline at end of file
@@ -0,0 +1,22 @@
+CREATE TABLE IF NOT EXISTS key_rotation_policies (
+    key_id BIGINT PRIMARY KEY,
+    interval_days INT UNSIGNED NOT NULL,
+    last_rotated_at DATETIME NULL,
+    next_rotation_at DATETIME NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    KEY idx_next_rotation_at (next_rotation_at),
+    CONSTRAINT fk_rotation_policies_key FOREIGN KEY (key_id) REFERENCES keys (id) ON DELETE CASCADE
+);
+
+-- 轮换后保留的历史密钥值，仅用于解密
+CREATE TABLE IF NOT EXISTS key_versions (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    key_id BIGINT NOT NULL,
+    version INT UNSIGNED NOT NULL,
+    encrypted_data TEXT NOT NULL,
+    trigger_type VARCHAR(16) NOT NULL,
+    rotated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_key_version (key_id, version),
+    CONSTRAINT fk_key_versions_key FOREIGN KEY (key_id) REFERENCES keys (id) ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS webhook_dead_letters;
+DROP TABLE IF EXISTS webhooks;
+ALTER TABLE keys DROP KEY idx_expires_at, DROP COLUMN expiry_notified_at, DROP COLUMN expires_at;
\ No newline at end of file
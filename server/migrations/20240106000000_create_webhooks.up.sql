@@ -0,0 +1,25 @@
+ALTER TABLE keys
+    ADD COLUMN expires_at DATETIME NULL,
+    ADD COLUMN expiry_notified_at DATETIME NULL,
+    ADD KEY idx_expires_at (expires_at);
+
+CREATE TABLE IF NOT EXISTS webhooks (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    url VARCHAR(2048) NOT NULL,
+    encrypted_secret TEXT NOT NULL,
+    events VARCHAR(255) NOT NULL,
+    created_by BIGINT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 重试耗尽后投递失败的事件
+CREATE TABLE IF NOT EXISTS webhook_dead_letters (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    webhook_id BIGINT NOT NULL,
+    event_type VARCHAR(64) NOT NULL,
+    payload TEXT NOT NULL,
+    attempts INT UNSIGNED NOT NULL,
+    last_error TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT fk_dead_letters_webhook FOREIGN KEY (webhook_id) REFERENCES webhooks (id) ON DELETE CASCADE
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS key_labels;
+ALTER TABLE keys DROP KEY ft_description, DROP COLUMN metadata, DROP COLUMN description;
\ No newline at end of file
@@ -0,0 +1,13 @@
+ALTER TABLE keys
+    ADD COLUMN description TEXT NULL,
+    ADD COLUMN metadata JSON NULL,
+    ADD FULLTEXT KEY ft_description (description);
+
+CREATE TABLE IF NOT EXISTS key_labels (
+    key_id BIGINT NOT NULL,
+    label_key VARCHAR(128) NOT NULL,
+    label_value VARCHAR(255) NOT NULL,
+    PRIMARY KEY (key_id, label_key),
+    KEY idx_label (label_key, label_value),
+    CONSTRAINT fk_key_labels_key FOREIGN KEY (key_id) REFERENCES keys (id) ON DELETE CASCADE
+);
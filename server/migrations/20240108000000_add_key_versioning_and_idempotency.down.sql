@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS idempotency_keys;
+ALTER TABLE keys DROP COLUMN version;
\ No newline at end of file
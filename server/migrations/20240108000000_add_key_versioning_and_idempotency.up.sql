@@ -0,0 +1,13 @@
+ALTER TABLE keys ADD COLUMN version INT UNSIGNED NOT NULL DEFAULT 1;
+
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    identity_id BIGINT NOT NULL,
+    idempotency_key VARCHAR(255) NOT NULL,
+    request_hash CHAR(64) NOT NULL,
+    -- 0表示请求仍在处理中
+    status_code SMALLINT UNSIGNED NOT NULL DEFAULT 0,
+    response_body MEDIUMTEXT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (identity_id, idempotency_key),
+    KEY idx_created_at (created_at)
+);
@@ -0,0 +1,26 @@
+CREATE TABLE IF NOT EXISTS service_accounts (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    identity_id BIGINT NOT NULL,
+    description TEXT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_identity (identity_id),
+    CONSTRAINT fk_service_accounts_identity FOREIGN KEY (identity_id) REFERENCES identities (id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS api_tokens (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    service_account_id BIGINT NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    -- 令牌明文的前若干字符，便于在日志和列表中识别
+    token_prefix VARCHAR(16) NOT NULL,
+    token_hash CHAR(64) NOT NULL,
+    scopes JSON NOT NULL,
+    expires_at DATETIME NOT NULL,
+    last_used_at DATETIME NULL,
+    last_used_ip VARCHAR(64) NULL,
+    revoked_at DATETIME NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_token_hash (token_hash),
+    KEY idx_service_account (service_account_id),
+    CONSTRAINT fk_api_tokens_service_account FOREIGN KEY (service_account_id) REFERENCES service_accounts (id) ON DELETE CASCADE
+);
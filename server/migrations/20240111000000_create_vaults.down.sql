@@ -0,0 +1,2 @@
+ALTER TABLE keys DROP FOREIGN KEY fk_keys_vault, DROP COLUMN nonce, DROP COLUMN vault_id;
+DROP TABLE IF EXISTS vaults;
\ No newline at end of file
@@ -0,0 +1,20 @@
+-- 零知识保险库：服务端只保存KDF参数和被包装的保险库密钥
+CREATE TABLE IF NOT EXISTS vaults (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    name VARCHAR(255) NOT NULL,
+    owner_identity_id BIGINT NOT NULL,
+    kdf_params JSON NOT NULL,
+    wrapped_key VARCHAR(255) NOT NULL,
+    wrapped_key_nonce VARCHAR(32) NOT NULL,
+    version INT UNSIGNED NOT NULL DEFAULT 1,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_name (name),
+    CONSTRAINT fk_vaults_owner FOREIGN KEY (owner_identity_id) REFERENCES identities (id)
+);
+
+-- vault_id非空的密钥由客户端加密，encrypted_data为不透明密文
+ALTER TABLE keys
+    ADD COLUMN vault_id BIGINT NULL,
+    ADD COLUMN nonce VARCHAR(32) NULL,
+    ADD CONSTRAINT fk_keys_vault FOREIGN KEY (vault_id) REFERENCES vaults (id);
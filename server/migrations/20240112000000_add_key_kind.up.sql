@@ -0,0 +1,2 @@
+-- 结构化密钥类型；为空表示未声明类型的旧密钥，encrypted_data为原始文本
+ALTER TABLE keys ADD COLUMN kind VARCHAR(32) NULL AFTER encrypted_data;
@@ -0,0 +1,12 @@
+-- 密钥变更事件，id即事件流的游标
+CREATE TABLE IF NOT EXISTS key_events (
+    id BIGINT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+    event_type VARCHAR(32) NOT NULL,
+    key_id BIGINT UNSIGNED NOT NULL,
+    key_name VARCHAR(255) NOT NULL,
+    version INT UNSIGNED NULL,
+    -- 删除事件发生时可读取该密钥元数据的ACL主体，逗号分隔，例如 identity:5,role:operator
+    audience TEXT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    KEY idx_key_events_created_at (created_at)
+);
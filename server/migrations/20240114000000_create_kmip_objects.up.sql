@@ -0,0 +1,19 @@
+-- 通过KMIP管理的对称密钥的生命周期状态及密码学属性，密钥材料仍保存在keys表中
+CREATE TABLE IF NOT EXISTS kmip_objects (
+    key_id BIGINT PRIMARY KEY,
+    -- pre_active、active、deactivated、compromised
+    state VARCHAR(32) NOT NULL,
+    -- KMIP枚举值，例如 3 = AES
+    cryptographic_algorithm INT UNSIGNED NOT NULL,
+    cryptographic_length INT NOT NULL,
+    cryptographic_usage_mask INT NULL,
+    activation_date DATETIME NULL,
+    deactivation_date DATETIME NULL,
+    compromise_date DATETIME NULL,
+    revocation_reason INT UNSIGNED NULL,
+    revocation_message TEXT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    KEY idx_kmip_objects_state (state),
+    CONSTRAINT fk_kmip_objects_key FOREIGN KEY (key_id) REFERENCES keys (id) ON DELETE CASCADE
+);
@@ -0,0 +1 @@
+DELETE FROM key_acls WHERE permission = 'use';
@@ -0,0 +1,5 @@
+-- 已能读取密钥内容的授权同时获得服务端加密和签名的use权限
+INSERT IGNORE INTO key_acls (key_id, subject_type, subject, permission, created_at)
+SELECT key_id, subject_type, subject, 'use', NOW()
+FROM key_acls
+WHERE permission = 'read_material';
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS issued_certificates;
+DROP TABLE IF EXISTS certificate_authorities;
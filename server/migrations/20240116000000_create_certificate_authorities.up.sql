@@ -0,0 +1,43 @@
+-- 内置CA，CA证书和私钥以certificate类型保存在keys表中，证书和上级证书链在此冗余保存供公开下载
+CREATE TABLE IF NOT EXISTS certificate_authorities (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    key_id BIGINT NOT NULL,
+    -- 根CA为NULL
+    parent_id BIGINT NULL,
+    subject VARCHAR(1024) NOT NULL,
+    certificate TEXT NOT NULL,
+    chain TEXT NULL,
+    -- 可签发的下级CA层数，NULL表示不限制
+    path_length TINYINT UNSIGNED NULL,
+    -- 下一次生成CRL使用的序号
+    crl_number BIGINT UNSIGNED NOT NULL DEFAULT 1,
+    not_after DATETIME NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_certificate_authorities_name (name),
+    CONSTRAINT fk_certificate_authorities_key FOREIGN KEY (key_id) REFERENCES keys (id) ON DELETE CASCADE,
+    CONSTRAINT fk_certificate_authorities_parent FOREIGN KEY (parent_id) REFERENCES certificate_authorities (id) ON DELETE SET NULL
+);
+
+-- CA签发的证书，包括下级CA证书
+CREATE TABLE IF NOT EXISTS issued_certificates (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    ca_id BIGINT NOT NULL,
+    -- 小写十六进制序列号
+    serial_number VARCHAR(64) NOT NULL,
+    subject VARCHAR(1024) NOT NULL,
+    -- server、client、code_signing、ca
+    profile VARCHAR(32) NOT NULL,
+    certificate TEXT NOT NULL,
+    -- 由服务端生成私钥时保存证书和私钥的密钥
+    key_id BIGINT NULL,
+    not_before DATETIME NOT NULL,
+    not_after DATETIME NOT NULL,
+    revoked_at DATETIME NULL,
+    revocation_reason VARCHAR(32) NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_issued_certificates_serial (ca_id, serial_number),
+    KEY idx_issued_certificates_revoked (ca_id, revoked_at),
+    CONSTRAINT fk_issued_certificates_ca FOREIGN KEY (ca_id) REFERENCES certificate_authorities (id) ON DELETE CASCADE,
+    CONSTRAINT fk_issued_certificates_key FOREIGN KEY (key_id) REFERENCES keys (id) ON DELETE SET NULL
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS ssh_certificates;
+DROP TABLE IF EXISTS ssh_certificate_authorities;
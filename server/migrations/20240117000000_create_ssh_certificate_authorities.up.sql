@@ -0,0 +1,42 @@
+-- SSH CA，私钥以ssh_key类型保存在keys表中，公钥在此冗余保存供公开下载
+CREATE TABLE IF NOT EXISTS ssh_certificate_authorities (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    key_id BIGINT NOT NULL,
+    -- authorized_keys格式的CA公钥
+    public_key TEXT NOT NULL,
+    allow_user_certificates BOOLEAN NOT NULL DEFAULT TRUE,
+    allow_host_certificates BOOLEAN NOT NULL DEFAULT FALSE,
+    -- 单张证书的最长有效期
+    max_validity_secs BIGINT UNSIGNED NOT NULL,
+    -- 可签入证书的principal，NULL表示不限制；元素可为通配符*
+    allowed_principals JSON NULL,
+    -- 可签入用户证书的扩展和关键选项，NULL表示不限制
+    allowed_extensions JSON NULL,
+    allowed_critical_options JSON NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_ssh_certificate_authorities_name (name),
+    CONSTRAINT fk_ssh_certificate_authorities_key FOREIGN KEY (key_id) REFERENCES keys (id) ON DELETE CASCADE
+);
+
+-- SSH CA签发的证书
+CREATE TABLE IF NOT EXISTS ssh_certificates (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    ca_id BIGINT NOT NULL,
+    serial BIGINT UNSIGNED NOT NULL,
+    -- user或host
+    certificate_type VARCHAR(8) NOT NULL,
+    -- 证书中的key id，sshd会写入日志
+    key_identifier VARCHAR(255) NOT NULL,
+    principals JSON NOT NULL,
+    -- 被签名公钥的SHA256指纹
+    public_key_fingerprint VARCHAR(64) NOT NULL,
+    certificate TEXT NOT NULL,
+    valid_after DATETIME NOT NULL,
+    valid_before DATETIME NOT NULL,
+    issued_by BIGINT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_ssh_certificates_serial (ca_id, serial),
+    CONSTRAINT fk_ssh_certificates_ca FOREIGN KEY (ca_id) REFERENCES ssh_certificate_authorities (id) ON DELETE CASCADE,
+    CONSTRAINT fk_ssh_certificates_issuer FOREIGN KEY (issued_by) REFERENCES identities (id) ON DELETE SET NULL
+);
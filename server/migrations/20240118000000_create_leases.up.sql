@@ -0,0 +1,40 @@
+-- 租约到期或撤销时执行的钩子：本机命令或本机Webhook
+CREATE TABLE IF NOT EXISTS lease_hooks (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    -- command或webhook
+    kind VARCHAR(16) NOT NULL,
+    -- 可执行文件的绝对路径或回环地址上的URL
+    target VARCHAR(2048) NOT NULL,
+    created_by BIGINT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_lease_hooks_name (name),
+    CONSTRAINT fk_lease_hooks_creator FOREIGN KEY (created_by) REFERENCES identities (id) ON DELETE SET NULL
+);
+
+-- 密钥租约，租约结束时其密钥被删除
+CREATE TABLE IF NOT EXISTS leases (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    owner_id BIGINT NULL,
+    -- key为已有密钥，generated为服务端生成的随机凭据
+    kind VARCHAR(16) NOT NULL,
+    key_id BIGINT NULL,
+    hook_id BIGINT NULL,
+    -- 续期时默认延长的时长
+    ttl_secs BIGINT UNSIGNED NOT NULL,
+    expires_at DATETIME NOT NULL,
+    -- 续期不能超过该时间
+    max_expires_at DATETIME NOT NULL,
+    renewed_at DATETIME NULL,
+    ended_at DATETIME NULL,
+    -- revoked或expired
+    end_reason VARCHAR(16) NULL,
+    -- 删除密钥或执行钩子失败的原因
+    revocation_error TEXT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_leases_owner (owner_id),
+    INDEX idx_leases_expiry (ended_at, expires_at),
+    CONSTRAINT fk_leases_owner FOREIGN KEY (owner_id) REFERENCES identities (id) ON DELETE SET NULL,
+    CONSTRAINT fk_leases_key FOREIGN KEY (key_id) REFERENCES keys (id) ON DELETE SET NULL,
+    CONSTRAINT fk_leases_hook FOREIGN KEY (hook_id) REFERENCES lease_hooks (id) ON DELETE SET NULL
+);
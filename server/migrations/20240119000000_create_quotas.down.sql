@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS key_creators;
+DROP TABLE IF EXISTS quotas;
@@ -0,0 +1,36 @@
+-- 调用方配额，subject_type为identity时subject为身份id，为ip时为来源IP；NULL列表示不限制
+CREATE TABLE IF NOT EXISTS quotas (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    subject_type VARCHAR(16) NOT NULL,
+    subject VARCHAR(255) NOT NULL,
+    max_keys BIGINT UNSIGNED NULL,
+    -- 单个密钥data或payload的最大字节数
+    max_data_bytes BIGINT UNSIGNED NULL,
+    requests_per_minute INT UNSIGNED NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_quotas_subject (subject_type, subject)
+);
+
+-- 密钥的创建者及来源IP，用于按调用方统计密钥数
+CREATE TABLE IF NOT EXISTS key_creators (
+    key_id BIGINT PRIMARY KEY,
+    identity_id BIGINT NULL,
+    source_ip VARCHAR(45) NULL,
+    INDEX idx_key_creators_identity (identity_id),
+    INDEX idx_key_creators_source_ip (source_ip),
+    CONSTRAINT fk_key_creators_key FOREIGN KEY (key_id) REFERENCES keys (id) ON DELETE CASCADE,
+    CONSTRAINT fk_key_creators_identity FOREIGN KEY (identity_id) REFERENCES identities (id) ON DELETE SET NULL
+);
+
+-- 已有密钥以最早的身份delete授权作为创建者
+INSERT INTO key_creators (key_id, identity_id)
+SELECT a.key_id, i.id
+FROM key_acls a
+JOIN identities i ON i.id = CAST(a.subject AS UNSIGNED)
+WHERE a.id IN (
+    SELECT MIN(id)
+    FROM key_acls
+    WHERE subject_type = 'identity' AND permission = 'delete'
+    GROUP BY key_id
+);
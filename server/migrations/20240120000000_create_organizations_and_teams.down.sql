@@ -0,0 +1,8 @@
+ALTER TABLE keys
+    DROP FOREIGN KEY fk_keys_team,
+    DROP INDEX uk_keys_team_name,
+    ADD UNIQUE KEY uk_name (name),
+    DROP COLUMN team_id;
+DROP TABLE IF EXISTS team_members;
+DROP TABLE IF EXISTS teams;
+DROP TABLE IF EXISTS organizations;
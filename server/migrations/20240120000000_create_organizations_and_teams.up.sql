@@ -0,0 +1,48 @@
+-- 组织，团队的上级
+CREATE TABLE IF NOT EXISTS organizations (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_organizations_name (name)
+);
+
+-- 团队，每个密钥属于一个团队
+CREATE TABLE IF NOT EXISTS teams (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    organization_id BIGINT NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_teams_name (organization_id, name),
+    CONSTRAINT fk_teams_organization FOREIGN KEY (organization_id) REFERENCES organizations (id)
+);
+
+-- 身份凭据所属的团队，一个身份可属于多个团队
+CREATE TABLE IF NOT EXISTS team_members (
+    team_id BIGINT NOT NULL,
+    identity_id BIGINT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (team_id, identity_id),
+    INDEX idx_team_members_identity (identity_id),
+    CONSTRAINT fk_team_members_team FOREIGN KEY (team_id) REFERENCES teams (id) ON DELETE CASCADE,
+    CONSTRAINT fk_team_members_identity FOREIGN KEY (identity_id) REFERENCES identities (id) ON DELETE CASCADE
+);
+
+-- 已有密钥和身份归入默认组织的默认团队
+INSERT INTO organizations (name) VALUES ('default');
+INSERT INTO teams (organization_id, name)
+SELECT id, 'default' FROM organizations WHERE name = 'default';
+INSERT INTO team_members (team_id, identity_id)
+SELECT t.id, i.id
+FROM teams t
+CROSS JOIN identities i
+WHERE t.name = 'default';
+
+ALTER TABLE keys ADD COLUMN team_id BIGINT NULL AFTER name;
+UPDATE keys SET team_id = (SELECT id FROM teams WHERE name = 'default');
+
+-- 密钥名称在团队内唯一，其它团队的密钥名不会因冲突而暴露
+ALTER TABLE keys
+    MODIFY team_id BIGINT NOT NULL,
+    DROP INDEX uk_name,
+    ADD UNIQUE KEY uk_keys_team_name (team_id, name),
+    ADD CONSTRAINT fk_keys_team FOREIGN KEY (team_id) REFERENCES teams (id);
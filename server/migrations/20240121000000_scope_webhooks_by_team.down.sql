@@ -0,0 +1,4 @@
+ALTER TABLE webhooks
+    DROP FOREIGN KEY fk_webhooks_team,
+    DROP INDEX idx_webhooks_team,
+    DROP COLUMN team_id;
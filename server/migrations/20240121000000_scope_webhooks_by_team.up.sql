@@ -0,0 +1,13 @@
+-- Webhook属于创建者当前的团队，只接收该团队密钥的事件
+ALTER TABLE webhooks ADD COLUMN team_id BIGINT NULL AFTER id;
+UPDATE webhooks SET team_id = (
+    SELECT t.id
+    FROM teams t
+    JOIN organizations o ON o.id = t.organization_id
+    WHERE o.name = 'default' AND t.name = 'default'
+);
+
+ALTER TABLE webhooks
+    MODIFY team_id BIGINT NOT NULL,
+    ADD KEY idx_webhooks_team (team_id),
+    ADD CONSTRAINT fk_webhooks_team FOREIGN KEY (team_id) REFERENCES teams (id) ON DELETE CASCADE;
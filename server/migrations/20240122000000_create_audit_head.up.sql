@@ -0,0 +1,22 @@
+-- 审计哈希链的链尾锚点，与追加的记录在同一事务中更新，用于发现末尾记录被截断
+CREATE TABLE IF NOT EXISTS audit_head (
+    id TINYINT UNSIGNED PRIMARY KEY,
+    last_id BIGINT NOT NULL,
+    last_hash CHAR(64) NOT NULL,
+    entries BIGINT NOT NULL,
+    -- 对last_id、last_hash、entries的HMAC；迁移时无法取得密钥，留空至下一次写入
+    mac VARCHAR(64) NOT NULL,
+    updated_at DATETIME(6) NOT NULL
+);
+
+INSERT INTO audit_head (id, last_id, last_hash, entries, mac, updated_at)
+SELECT 1,
+    COALESCE(MAX(id), 0),
+    COALESCE(
+        (SELECT hash FROM audit_events ORDER BY id DESC LIMIT 1),
+        '0000000000000000000000000000000000000000000000000000000000000000'
+    ),
+    COUNT(*),
+    '',
+    NOW(6)
+FROM audit_events;
@@ -0,0 +1,198 @@
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+    Extension,
+};
+use serde_json::json;
+use sqlx::MySqlPool;
+use utoipa::OpenApi;
+
+use crate::api::Routes;
+use crate::api::team::require_team;
+use crate::model::access::{
+    CreateIdentityRequest, CreateIdentityResponse, Decision, ExplainQuery, GrantRequest, Identity,
+    KeyAcl, Permission, Role,
+};
+use crate::service::access as access_service;
+use crate::utils::middleware::authorize_key;
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .route("/identities", post(handle_create_identity))
+        .route("/keys/{id}/acl", get(handle_list_grants))
+        .route("/keys/{id}/acl", post(handle_grant))
+        .route("/keys/{id}/acl/{acl_id}", delete(handle_revoke))
+        .route("/access/explain", get(handle_explain))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    handle_create_identity,
+    handle_list_grants,
+    handle_grant,
+    handle_revoke,
+    handle_explain,
+))]
+pub struct ApiDoc;
+
+/// 校验调用方是否属于指定角色之一
+pub fn require_role(identity: &Identity, roles: &[Role]) -> Result<(), (StatusCode, String)> {
+    if roles.contains(&identity.role) {
+        Ok(())
+    } else {
+        tracing::warn!(identity_id = identity.id, role = %identity.role, "Access denied: role not permitted");
+        Err((StatusCode::FORBIDDEN, "Access denied".to_string()))
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/identities",
+    tag = "access",
+    request_body = CreateIdentityRequest,
+    responses(
+        (status = 201, description = "Identity created; the API key is returned only once", body = CreateIdentityResponse),
+        (status = 403, description = "Access denied", body = String),
+        (status = 500, description = "Internal error", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_create_identity(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Json(request): Json<CreateIdentityRequest>,
+) -> Result<(StatusCode, Json<CreateIdentityResponse>), (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+    let team_id = require_team(&identity)?;
+
+    let created = access_service::create_identity(&pool, request, team_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to create identity: {:?}", e)))?;
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/keys/{id}/acl",
+    tag = "access",
+    params(
+        ("id" = u64, Path, description = "Key ID")
+    ),
+    responses(
+        (status = 200, description = "Grants on the key", body = Vec<KeyAcl>),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Key not found in the current team", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_list_grants(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(key_id): Path<u64>,
+) -> Result<Json<Vec<KeyAcl>>, (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin, Role::Auditor])?;
+    authorize_key(&identity, None, key_id, Permission::ReadMetadata).await?;
+
+    let grants = access_service::list_grants(&pool, key_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list grants: {:?}", e)))?;
+
+    Ok(Json(grants))
+}
+
+#[utoipa::path(
+    post,
+    path = "/keys/{id}/acl",
+    tag = "access",
+    params(
+        ("id" = u64, Path, description = "Key ID")
+    ),
+    request_body = GrantRequest,
+    responses(
+        (status = 201, description = "Permission granted", body = serde_json::Value),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Key not found in the current team", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_grant(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(key_id): Path<u64>,
+    Json(request): Json<GrantRequest>,
+) -> Result<(StatusCode, Json<serde_json::Value>), (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+    authorize_key(&identity, None, key_id, Permission::ReadMetadata).await?;
+
+    let acl_id = access_service::grant(&pool, key_id, request)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Failed to grant permission: {:?}", e)))?;
+
+    Ok((StatusCode::CREATED, Json(json!({ "acl_id": acl_id }))))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/keys/{id}/acl/{acl_id}",
+    tag = "access",
+    params(
+        ("id" = u64, Path, description = "Key ID"),
+        ("acl_id" = u64, Path, description = "Grant ID")
+    ),
+    responses(
+        (status = 200, description = "Permission revoked", body = serde_json::Value),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Key not found in the current team", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_revoke(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path((key_id, acl_id)): Path<(u64, u64)>,
+) -> Result<(StatusCode, Json<serde_json::Value>), (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+    authorize_key(&identity, None, key_id, Permission::ReadMetadata).await?;
+
+    access_service::revoke(&pool, key_id, acl_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to revoke permission: {:?}", e)))?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({ "message": "Permission revoked successfully" })),
+    ))
+}
+
+/// 演练接口：解释某个身份对密钥的访问为何被允许或拒绝
+#[utoipa::path(
+    get,
+    path = "/access/explain",
+    tag = "access",
+    params(
+        ExplainQuery
+    ),
+    responses(
+        (status = 200, description = "Access decision with the rules that produced it", body = Decision),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Key not found in the current team", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_explain(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Query(query): Query<ExplainQuery>,
+) -> Result<Json<Decision>, (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+    authorize_key(&identity, None, query.key_id, Permission::ReadMetadata).await?;
+
+    let decision = access_service::explain(&pool, query.identity_id, query.key_id, query.permission)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to explain decision: {:?}", e)))?;
+
+    Ok(Json(decision))
+}
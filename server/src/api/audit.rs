@@ -0,0 +1,77 @@
+use axum::{
+    extract::{Json, Query, State},
+    http::StatusCode,
+    routing::{get, post},
+    Extension,
+};
+use sqlx::MySqlPool;
+use utoipa::OpenApi;
+
+use crate::api::Routes;
+use crate::api::access::require_role;
+use crate::model::access::{Identity, Role};
+use crate::model::audit::{AuditEvent, AuditQuery, ChainVerification};
+use crate::service::audit as audit_service;
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .route("/audit/events", get(handle_query_events))
+        .route("/audit/verify-chain", post(handle_verify_chain))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    handle_query_events,
+    handle_verify_chain,
+))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    get,
+    path = "/audit/events",
+    tag = "audit",
+    params(
+        AuditQuery
+    ),
+    responses(
+        (status = 200, description = "Matching audit events", body = Vec<AuditEvent>),
+        (status = 403, description = "Access denied", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_query_events(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Query(query): Query<AuditQuery>,
+) -> Result<Json<Vec<AuditEvent>>, (StatusCode, String)> {
+    require_role(&identity, &[Role::Auditor])?;
+
+    let events = audit_service::query(&pool, query)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to query audit events: {:?}", e)))?;
+
+    Ok(Json(events))
+}
+
+#[utoipa::path(
+    post,
+    path = "/audit/verify-chain",
+    tag = "audit",
+    responses(
+        (status = 200, description = "Hash chain verification result", body = ChainVerification),
+        (status = 403, description = "Access denied", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_verify_chain(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+) -> Result<Json<ChainVerification>, (StatusCode, String)> {
+    require_role(&identity, &[Role::Auditor])?;
+
+    let verification = audit_service::verify_chain(&pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to verify audit chain: {:?}", e)))?;
+
+    Ok(Json(verification))
+}
@@ -0,0 +1,193 @@
+use axum::{
+    body::{Body, Bytes},
+    extract::{DefaultBodyLimit, Json, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Extension,
+};
+use sqlx::MySqlPool;
+use tokio_util::io::ReaderStream;
+use utoipa::OpenApi;
+
+use crate::api::Routes;
+use crate::api::access::require_role;
+use crate::api::team::require_team;
+use crate::model::access::{Identity, Role};
+use crate::model::backup::{BackupScope, RestoreQuery, RestoreReport};
+use crate::service::backup as backup_service;
+
+// 导出管道缓冲区大小
+const EXPORT_BUFFER_SIZE: usize = 64 * 1024;
+// 恢复请求体大小上限
+const RESTORE_BODY_LIMIT: usize = 512 * 1024 * 1024;
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .route("/backup/export", get(handle_export))
+        .route(
+            "/backup/restore",
+            post(handle_restore).layer(DefaultBodyLimit::max(RESTORE_BODY_LIMIT)),
+        )
+        .route("/backup/team/export", get(handle_export_team))
+        .route(
+            "/backup/team/restore",
+            post(handle_restore_team).layer(DefaultBodyLimit::max(RESTORE_BODY_LIMIT)),
+        )
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    handle_export,
+    handle_restore,
+    handle_export_team,
+    handle_restore_team,
+))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    get,
+    path = "/backup/export",
+    tag = "backup",
+    responses(
+        (status = 200, description = "Encrypted backup stream of every key in the store", body = Vec<u8>, content_type = "application/octet-stream"),
+        (status = 403, description = "Access denied", body = String),
+        (status = 500, description = "Internal error", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_export(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+) -> Result<Response, (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+
+    export(pool, BackupScope::All)
+}
+
+#[utoipa::path(
+    post,
+    path = "/backup/restore",
+    tag = "backup",
+    params(
+        RestoreQuery,
+        ("X-Source-Encryption-Key" = Option<String>, Header, description = "ENCRYPTION_KEY of the deployment that produced the backup")
+    ),
+    request_body(content = Vec<u8>, content_type = "application/octet-stream"),
+    responses(
+        (status = 200, description = "Restore report; keys return to the teams recorded in the backup", body = RestoreReport),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 403, description = "Access denied", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_restore(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Query(query): Query<RestoreQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<RestoreReport>, (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+
+    restore(&pool, BackupScope::All, query, &headers, &body).await.map(Json)
+}
+
+#[utoipa::path(
+    get,
+    path = "/backup/team/export",
+    tag = "backup",
+    responses(
+        (status = 200, description = "Encrypted backup stream of the current team's keys", body = Vec<u8>, content_type = "application/octet-stream"),
+        (status = 403, description = "Access denied", body = String),
+        (status = 500, description = "Internal error", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_export_team(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+) -> Result<Response, (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+    let team_id = require_team(&identity)?;
+
+    export(pool, BackupScope::Team(team_id))
+}
+
+#[utoipa::path(
+    post,
+    path = "/backup/team/restore",
+    tag = "backup",
+    params(
+        RestoreQuery,
+        ("X-Source-Encryption-Key" = Option<String>, Header, description = "ENCRYPTION_KEY of the deployment that produced the backup")
+    ),
+    request_body(content = Vec<u8>, content_type = "application/octet-stream"),
+    responses(
+        (status = 200, description = "Restore report; all keys are restored into the current team", body = RestoreReport),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 403, description = "Access denied", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_restore_team(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Query(query): Query<RestoreQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<RestoreReport>, (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+    let team_id = require_team(&identity)?;
+
+    restore(&pool, BackupScope::Team(team_id), query, &headers, &body).await.map(Json)
+}
+
+// 以流的形式返回备份文件
+fn export(pool: MySqlPool, scope: BackupScope) -> Result<Response, (StatusCode, String)> {
+    let backup_key = backup_service::backup_key()
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to start backup: {:?}", e)))?;
+
+    // 导出任务写入管道一端，响应体从另一端流式读取
+    let (reader, writer) = tokio::io::duplex(EXPORT_BUFFER_SIZE);
+    tokio::spawn(async move {
+        match backup_service::export(&pool, scope, writer, &backup_key).await {
+            Ok(manifest) => tracing::info!(key_count = manifest.key_count, ?scope, "Backup exported"),
+            // 中途失败时文件缺少最后一块，恢复时会被识别为截断
+            Err(e) => tracing::error!(error = %e, "Backup export failed"),
+        }
+    });
+
+    let filename = format!("ecipher-backup-{}.ecbk", chrono::Utc::now().format("%Y%m%dT%H%M%SZ"));
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/octet-stream".to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        Body::from_stream(ReaderStream::new(reader)),
+    )
+        .into_response())
+}
+
+async fn restore(
+    pool: &MySqlPool,
+    scope: BackupScope,
+    query: RestoreQuery,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Result<RestoreReport, (StatusCode, String)> {
+    let backup_key = backup_service::backup_key()
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to restore backup: {:?}", e)))?;
+    let target_key = std::env::var("ENCRYPTION_KEY")
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Encryption key not configured".to_string()))?;
+    // 备份来自使用其他ENCRYPTION_KEY的部署时，由请求头提供源密钥
+    let source_key = headers
+        .get("x-source-encryption-key")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+        .unwrap_or_else(|| target_key.clone());
+
+    backup_service::restore(pool, scope, body, &backup_key, &source_key, &target_key, query)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Failed to restore backup: {:?}", e)))
+}
@@ -0,0 +1,64 @@
+use axum::{
+    extract::{Json, State},
+    http::StatusCode,
+    middleware::from_fn,
+    routing::post,
+    Extension,
+};
+use sqlx::MySqlPool;
+use utoipa::OpenApi;
+
+use crate::api::Routes;
+use crate::model::access::Identity;
+use crate::model::batch::{BatchRequest, BatchResponse, MAX_BATCH_OPERATIONS};
+use crate::model::token::TokenGrant;
+use crate::service::batch as batch_service;
+use crate::utils::middleware::idempotency_middleware;
+
+pub fn routes() -> Routes {
+    Routes::new().route("/keys/batch", post(handle_batch).route_layer(from_fn(idempotency_middleware)))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(handle_batch))]
+pub struct ApiDoc;
+
+/// 批量创建和删除密钥，每个操作的结果单独返回
+#[utoipa::path(
+    post,
+    path = "/keys/batch",
+    tag = "keys",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Replays the stored response for a repeated key")
+    ),
+    request_body = BatchRequest,
+    responses(
+        (status = 200, description = "Per-operation results; check each item's status", body = BatchResponse),
+        (status = 400, description = "Empty batch or too many operations", body = String),
+        (status = 500, description = "Internal error", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_batch(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    grant: Option<Extension<TokenGrant>>,
+    Json(request): Json<BatchRequest>,
+) -> Result<Json<BatchResponse>, (StatusCode, String)> {
+    if request.operations.is_empty() || request.operations.len() > MAX_BATCH_OPERATIONS {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("A batch must contain between 1 and {} operations", MAX_BATCH_OPERATIONS),
+        ));
+    }
+
+    let encryption_key = std::env::var("ENCRYPTION_KEY")
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Encryption key not configured".to_string()))?;
+
+    let grant = grant.map(|Extension(grant)| grant);
+    let response = batch_service::execute(&pool, &identity, grant.as_ref(), request, &encryption_key)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to execute batch: {:?}", e)))?;
+
+    Ok(Json(response))
+}
@@ -0,0 +1,160 @@
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    middleware::from_fn,
+    routing::{get, post},
+};
+use sqlx::MySqlPool;
+use utoipa::OpenApi;
+
+use crate::api::Routes;
+use crate::model::crypto::{
+    DecryptRequest, DecryptResponse, EncryptRequest, EncryptResponse, KeyOperation, PublicKeyResponse, SignRequest,
+    SignResponse,
+};
+use crate::service::crypto as crypto_service;
+use crate::utils::middleware::key_acl_middleware;
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .route("/keys/{id}/encrypt", post(handle_encrypt))
+        .route("/keys/{id}/decrypt", post(handle_decrypt))
+        .route("/keys/{id}/sign", post(handle_sign))
+        .route("/keys/{id}/public-key", get(handle_get_public_key))
+        .route_layer(from_fn(key_acl_middleware))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(handle_encrypt, handle_decrypt, handle_sign, handle_get_public_key))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    post,
+    path = "/keys/{id}/encrypt",
+    tag = "crypto",
+    params(
+        ("id" = u64, Path, description = "Key ID")
+    ),
+    request_body = EncryptRequest,
+    responses(
+        (status = 200, description = "AES-GCM ciphertext", body = EncryptResponse),
+        (status = 400, description = "Invalid input", body = String),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Key not found", body = String),
+        (status = 422, description = "Key cannot be used for encryption", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_encrypt(
+    State(pool): State<MySqlPool>,
+    Path(key_id): Path<u64>,
+    Json(request): Json<EncryptRequest>,
+) -> Result<Json<EncryptResponse>, (StatusCode, String)> {
+    let encryption_key = encryption_key()?;
+    let result = crypto_service::encrypt(&pool, key_id, request, &encryption_key)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to encrypt: {:?}", e)))?;
+
+    to_response(result).map(Json)
+}
+
+#[utoipa::path(
+    post,
+    path = "/keys/{id}/decrypt",
+    tag = "crypto",
+    params(
+        ("id" = u64, Path, description = "Key ID")
+    ),
+    request_body = DecryptRequest,
+    responses(
+        (status = 200, description = "Decrypted plaintext", body = DecryptResponse),
+        (status = 400, description = "Invalid input or authentication failed", body = String),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Key not found", body = String),
+        (status = 422, description = "Key cannot be used for decryption", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_decrypt(
+    State(pool): State<MySqlPool>,
+    Path(key_id): Path<u64>,
+    Json(request): Json<DecryptRequest>,
+) -> Result<Json<DecryptResponse>, (StatusCode, String)> {
+    let encryption_key = encryption_key()?;
+    let result = crypto_service::decrypt(&pool, key_id, request, &encryption_key)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to decrypt: {:?}", e)))?;
+
+    to_response(result).map(Json)
+}
+
+#[utoipa::path(
+    post,
+    path = "/keys/{id}/sign",
+    tag = "crypto",
+    params(
+        ("id" = u64, Path, description = "Key ID")
+    ),
+    request_body = SignRequest,
+    responses(
+        (status = 200, description = "Signature", body = SignResponse),
+        (status = 400, description = "Invalid input", body = String),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Key not found", body = String),
+        (status = 422, description = "Key cannot be used with this algorithm", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_sign(
+    State(pool): State<MySqlPool>,
+    Path(key_id): Path<u64>,
+    Json(request): Json<SignRequest>,
+) -> Result<Json<SignResponse>, (StatusCode, String)> {
+    let encryption_key = encryption_key()?;
+    let result = crypto_service::sign(&pool, key_id, request, &encryption_key)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to sign: {:?}", e)))?;
+
+    to_response(result).map(Json)
+}
+
+#[utoipa::path(
+    get,
+    path = "/keys/{id}/public-key",
+    tag = "crypto",
+    params(
+        ("id" = u64, Path, description = "Key ID")
+    ),
+    responses(
+        (status = 200, description = "Public parts of a usable key", body = PublicKeyResponse),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Key not found", body = String),
+        (status = 422, description = "Key cannot be used by the server", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_get_public_key(
+    State(pool): State<MySqlPool>,
+    Path(key_id): Path<u64>,
+) -> Result<Json<PublicKeyResponse>, (StatusCode, String)> {
+    let encryption_key = encryption_key()?;
+    let result = crypto_service::get_public_key(&pool, key_id, &encryption_key)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get public key: {:?}", e)))?;
+
+    to_response(result).map(Json)
+}
+
+fn encryption_key() -> Result<String, (StatusCode, String)> {
+    std::env::var("ENCRYPTION_KEY")
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Encryption key not configured".to_string()))
+}
+
+fn to_response<T>(result: KeyOperation<T>) -> Result<T, (StatusCode, String)> {
+    match result {
+        KeyOperation::Done(response) => Ok(response),
+        KeyOperation::NotFound => Err((StatusCode::NOT_FOUND, "Key not found".to_string())),
+        KeyOperation::Unsupported(reason) => Err((StatusCode::UNPROCESSABLE_ENTITY, reason)),
+        KeyOperation::Rejected(reason) => Err((StatusCode::BAD_REQUEST, reason)),
+    }
+}
@@ -0,0 +1,144 @@
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    http::{HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Response,
+    },
+    routing::get,
+    Extension,
+};
+use futures::stream::{self, Stream};
+use sqlx::MySqlPool;
+use std::convert::Infallible;
+use utoipa::OpenApi;
+
+use crate::api::Routes;
+use crate::model::access::Identity;
+use crate::model::event::{EventStreamQuery, KeyEvent};
+use crate::service::event::{self as event_service, Subscription};
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .route("/events/keys", get(handle_key_events))
+        .route("/events/keys/ws", get(handle_key_events_ws))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    handle_key_events,
+    handle_key_events_ws,
+))]
+pub struct ApiDoc;
+
+/// 以Server-Sent Events推送密钥变更，事件id即游标
+#[utoipa::path(
+    get,
+    path = "/events/keys",
+    tag = "events",
+    params(
+        EventStreamQuery,
+        ("Last-Event-ID" = Option<String>, Header, description = "Cursor of the last received event; overrides the cursor query parameter")
+    ),
+    responses(
+        (status = 200, description = "Stream of key events", body = KeyEvent, content_type = "text/event-stream"),
+        (status = 400, description = "Invalid request", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_key_events(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Query(query): Query<EventStreamQuery>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, String)> {
+    // EventSource断线重连时通过Last-Event-ID携带最后收到的游标
+    let cursor = match headers.get("last-event-id") {
+        Some(value) => Some(
+            value
+                .to_str()
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .ok_or((StatusCode::BAD_REQUEST, "Invalid Last-Event-ID header".to_string()))?,
+        ),
+        None => query.cursor,
+    };
+
+    let subscription = event_service::subscribe(&pool, identity, cursor)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to subscribe to key events: {:?}", e)))?;
+
+    let events = stream::unfold(subscription, |mut subscription| async move {
+        let event = match subscription.next().await.map_err(|e| e.to_string()) {
+            Ok(event) => event,
+            Err(e) => {
+                tracing::error!(error = %e, "Key event stream failed");
+                return None;
+            }
+        };
+        let sse = Event::default()
+            .id(event.cursor.to_string())
+            .event(event.event.as_str())
+            .json_data(&event)
+            .ok()?;
+        Some((Ok(sse), subscription))
+    });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// 以WebSocket文本帧推送密钥变更，每帧为一个JSON事件
+#[utoipa::path(
+    get,
+    path = "/events/keys/ws",
+    tag = "events",
+    params(EventStreamQuery),
+    responses(
+        (status = 101, description = "WebSocket upgrade; each text frame is a KeyEvent", body = KeyEvent),
+        (status = 400, description = "Not a WebSocket request", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_key_events_ws(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Query(query): Query<EventStreamQuery>,
+    upgrade: WebSocketUpgrade,
+) -> Result<Response, (StatusCode, String)> {
+    let subscription = event_service::subscribe(&pool, identity, query.cursor)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to subscribe to key events: {:?}", e)))?;
+
+    Ok(upgrade.on_upgrade(move |socket| forward_events(socket, subscription)))
+}
+
+// 转发事件直到客户端关闭连接或订阅出错
+async fn forward_events(mut socket: WebSocket, mut subscription: Subscription) {
+    loop {
+        tokio::select! {
+            event = subscription.next() => {
+                let event = match event.map_err(|e| e.to_string()) {
+                    Ok(event) => event,
+                    Err(e) => {
+                        tracing::error!(error = %e, "Key event stream failed");
+                        break;
+                    }
+                };
+                let Ok(text) = serde_json::to_string(&event) else {
+                    break;
+                };
+                if socket.send(Message::Text(text.into())).await.is_err() {
+                    break;
+                }
+            }
+            message = socket.recv() => {
+                if matches!(message, None | Some(Err(_)) | Some(Ok(Message::Close(_)))) {
+                    break;
+                }
+            }
+        }
+    }
+}
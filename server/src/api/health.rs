@@ -0,0 +1,92 @@
+use axum::{
+    extract::{Json, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::get,
+};
+use serde_json::json;
+use sqlx::MySqlPool;
+use utoipa::OpenApi;
+
+use crate::api::Routes;
+use crate::model::health::ReadinessReport;
+use crate::service::{access as access_service, health as health_service};
+use crate::utils::metrics::METRICS;
+
+// Prometheus文本格式
+const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .route("/healthz", get(handle_healthz))
+        .route("/readyz", get(handle_readyz))
+        .route("/metrics", get(handle_metrics))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    handle_healthz,
+    handle_readyz,
+    handle_metrics,
+))]
+pub struct ApiDoc;
+
+/// 存活检查，进程能响应即视为存活，不访问数据库
+#[utoipa::path(
+    get,
+    path = "/healthz",
+    tag = "health",
+    responses(
+        (status = 200, description = "Process is alive", body = serde_json::Value)
+    )
+)]
+async fn handle_healthz() -> Json<serde_json::Value> {
+    Json(json!({ "status": "ok" }))
+}
+
+/// 就绪检查，未就绪时返回503
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    tag = "health",
+    responses(
+        (status = 200, description = "Ready to serve traffic", body = ReadinessReport),
+        (status = 503, description = "A dependency is unavailable", body = ReadinessReport)
+    )
+)]
+async fn handle_readyz(State(pool): State<MySqlPool>) -> (StatusCode, Json<ReadinessReport>) {
+    let report = health_service::readiness(&pool).await;
+    if !report.ready {
+        tracing::warn!(?report, "Readiness check failed");
+    }
+    let status = if report.ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (status, Json(report))
+}
+
+/// 导出Prometheus指标；配置了METRICS_TOKEN时需以Bearer方式提供
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "health",
+    responses(
+        (status = 200, description = "Prometheus metrics", body = String, content_type = "text/plain"),
+        (status = 401, description = "Invalid metrics token", body = String)
+    )
+)]
+async fn handle_metrics(
+    State(pool): State<MySqlPool>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    if let Some(expected) = std::env::var("METRICS_TOKEN").ok().filter(|token| !token.is_empty()) {
+        let provided = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .unwrap_or_default();
+        if access_service::hash_credential(provided) != access_service::hash_credential(&expected) {
+            return Err((StatusCode::UNAUTHORIZED, "Invalid metrics token".to_string()));
+        }
+    }
+
+    Ok(([(header::CONTENT_TYPE, METRICS_CONTENT_TYPE)], METRICS.render(&pool)))
+}
@@ -1,56 +1,282 @@
 use axum::{
     extract::{Json, Path, State},
-    http::StatusCode,
-    routing::{delete, get, post},
-    Router,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::from_fn,
+    routing::{delete, get, post, put},
+    Extension,
 };
 use serde_json::json;
 use sqlx::MySqlPool;
+use utoipa::OpenApi;
 
-use crate::model::key::{CreateKeyRequest, KeyResponse};
-use crate::service::{self as key_service};
+use crate::api::Routes;
+use crate::model::access::Identity;
+use crate::model::token::{TokenGrant, TokenOperation};
+use crate::model::key::{
+    ConditionalWrite, CreateKeyRequest, KeyMaterialResponse, KeyResponse, KeySearchRequest, KeySearchResponse,
+    UpdateKeyRequest,
+};
+use crate::service as key_service;
+use crate::utils::middleware::{idempotency_middleware, key_acl_middleware, service_error};
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .route("/keys", post(handle_create_key).route_layer(from_fn(idempotency_middleware)))
+        .route("/keys/search", post(handle_search_keys))
+        .merge(
+            Routes::new()
+                .route("/keys/{id}", get(handle_get_key))
+                .route("/keys/{id}", put(handle_update_key))
+                .route("/keys/{id}", delete(handle_delete_key))
+                .route("/keys/{id}/material", get(handle_get_key_material))
+                .route_layer(from_fn(key_acl_middleware)),
+        )
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    handle_create_key,
+    handle_search_keys,
+    handle_get_key,
+    handle_update_key,
+    handle_get_key_material,
+    handle_delete_key,
+))]
+pub struct ApiDoc;
+
+/// 版本号对应的强ETag
+pub fn etag(version: u32) -> [(header::HeaderName, HeaderValue); 1] {
+    let value = HeaderValue::from_str(&format!("\"{}\"", version)).expect("numeric etag is a valid header value");
+    [(header::ETAG, value)]
+}
+
+/// 解析If-Match请求头，`*`或缺省表示不限制版本
+pub fn parse_if_match(headers: &HeaderMap) -> Result<Option<u32>, (StatusCode, String)> {
+    let Some(value) = headers.get(header::IF_MATCH) else {
+        return Ok(None);
+    };
+    let value = value
+        .to_str()
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid If-Match header".to_string()))?
+        .trim();
+    if value == "*" {
+        return Ok(None);
+    }
 
-pub fn routes() -> Router<MySqlPool> {
-    Router::new()
-        .route("/keys", post(handle_create_key))
-        .route("/keys/:id", get(handle_get_key))
-        .route("/keys/:id", delete(handle_delete_key))
+    value
+        .trim_start_matches("W/")
+        .trim_matches('"')
+        .parse()
+        .map(Some)
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid If-Match header".to_string()))
 }
 
+/// 版本不一致时返回412并附带当前ETag
+pub fn precondition_failed(current: u32) -> (StatusCode, String) {
+    (
+        StatusCode::PRECONDITION_FAILED,
+        format!("Resource was modified concurrently, current version is \"{}\"", current),
+    )
+}
+
+#[utoipa::path(
+    post,
+    path = "/keys",
+    tag = "keys",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Replays the stored response for a repeated key")
+    ),
+    request_body = CreateKeyRequest,
+    responses(
+        (status = 201, description = "Key created", body = KeyResponse),
+        (status = 403, description = "Access denied", body = String),
+        (status = 429, description = "Quota exceeded", body = String),
+        (status = 500, description = "Internal error", body = String)
+    ),
+    security(("bearer" = []))
+)]
 async fn handle_create_key(
     State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    grant: Option<Extension<TokenGrant>>,
     Json(request): Json<CreateKeyRequest>,
 ) -> Result<(StatusCode, Json<KeyResponse>), (StatusCode, String)> {
+    if !identity.role.can_create_keys() {
+        tracing::warn!(identity_id = identity.id, role = %identity.role, "Access denied: create key");
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+    if grant.is_some_and(|Extension(grant)| !grant.allows(&request.name, TokenOperation::Create)) {
+        tracing::warn!(identity_id = identity.id, "Access denied: create key outside token scope");
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+
     // 获取加密密钥（实际应用中应从安全存储获取）
     let encryption_key = std::env::var("ENCRYPTION_KEY")
         .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Encryption key not configured".to_string()))?;
     
-    let key = key_service::create_key(&pool, request, &encryption_key)
+    // 创建者自动获得该密钥的全部权限
+    let key = key_service::create_key(&pool, &identity, request, &encryption_key)
         .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to create key: {:?}", e)))?;
-    
+        .map_err(service_error("Failed to create key"))?;
+
     Ok((StatusCode::CREATED, Json(key)))
 }
 
+#[utoipa::path(
+    post,
+    path = "/keys/search",
+    tag = "keys",
+    request_body = KeySearchRequest,
+    responses(
+        (status = 200, description = "Matching keys visible to the caller", body = KeySearchResponse),
+        (status = 500, description = "Internal error", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_search_keys(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    grant: Option<Extension<TokenGrant>>,
+    Json(request): Json<KeySearchRequest>,
+) -> Result<Json<KeySearchResponse>, (StatusCode, String)> {
+    let grant = grant.map(|Extension(grant)| grant);
+    let results = key_service::search_keys(&pool, &identity, grant.as_ref(), request)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to search keys: {:?}", e)))?;
+
+    Ok(Json(results))
+}
+
+#[utoipa::path(
+    get,
+    path = "/keys/{id}",
+    tag = "keys",
+    params(
+        ("id" = u64, Path, description = "Key ID")
+    ),
+    responses(
+        (status = 200, description = "Key metadata", body = KeyResponse, headers(("ETag" = String, description = "Current resource version"))),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Key not found", body = String)
+    ),
+    security(("bearer" = []))
+)]
 async fn handle_get_key(
     State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
     Path(id): Path<u64>,
-) -> Result<Json<KeyResponse>, (StatusCode, String)> {
-    let key = key_service::get_key(&pool, id)
+) -> Result<([(header::HeaderName, HeaderValue); 1], Json<KeyResponse>), (StatusCode, String)> {
+    let key = key_service::get_key(&pool, &identity, id)
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get key: {:?}", e)))?
         .ok_or((StatusCode::NOT_FOUND, "Key not found".to_string()))?;
     
+    Ok((etag(key.version), Json(key)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/keys/{id}",
+    tag = "keys",
+    params(
+        ("id" = u64, Path, description = "Key ID"),
+        ("If-Match" = Option<String>, Header, description = "Expected ETag; `*` or omitted skips the version check")
+    ),
+    request_body = UpdateKeyRequest,
+    responses(
+        (status = 200, description = "Key updated", body = KeyResponse, headers(("ETag" = String, description = "Current resource version"))),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Key not found", body = String),
+        (status = 412, description = "Version does not match If-Match", body = String),
+        (status = 429, description = "Quota exceeded", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_update_key(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(id): Path<u64>,
+    headers: HeaderMap,
+    Json(request): Json<UpdateKeyRequest>,
+) -> Result<([(header::HeaderName, HeaderValue); 1], Json<KeyResponse>), (StatusCode, String)> {
+    let expected_version = parse_if_match(&headers)?;
+    let encryption_key = std::env::var("ENCRYPTION_KEY")
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Encryption key not configured".to_string()))?;
+
+    let result = key_service::update_key(&pool, &identity, id, request, expected_version, &encryption_key)
+        .await
+        .map_err(service_error("Failed to update key"))?;
+
+    match result {
+        ConditionalWrite::Done(key) => Ok((etag(key.version), Json(key))),
+        ConditionalWrite::NotFound => Err((StatusCode::NOT_FOUND, "Key not found".to_string())),
+        ConditionalWrite::VersionMismatch { current } => Err(precondition_failed(current)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/keys/{id}/material",
+    tag = "keys",
+    params(
+        ("id" = u64, Path, description = "Key ID")
+    ),
+    responses(
+        (status = 200, description = "Decrypted key material", body = KeyMaterialResponse),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Key not found", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_get_key_material(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(id): Path<u64>,
+) -> Result<Json<KeyMaterialResponse>, (StatusCode, String)> {
+    let encryption_key = std::env::var("ENCRYPTION_KEY")
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Encryption key not configured".to_string()))?;
+
+    let key = key_service::get_key_material(&pool, &identity, id, &encryption_key)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get key material: {:?}", e)))?
+        .ok_or((StatusCode::NOT_FOUND, "Key not found".to_string()))?;
+
     Ok(Json(key))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/keys/{id}",
+    tag = "keys",
+    params(
+        ("id" = u64, Path, description = "Key ID"),
+        ("If-Match" = Option<String>, Header, description = "Expected ETag; `*` or omitted skips the version check")
+    ),
+    responses(
+        (status = 200, description = "Key deleted", body = serde_json::Value),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Key not found", body = String),
+        (status = 412, description = "Version does not match If-Match", body = String)
+    ),
+    security(("bearer" = []))
+)]
 async fn handle_delete_key(
     State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
     Path(id): Path<u64>,
+    headers: HeaderMap,
 ) -> Result<(StatusCode, Json<serde_json::Value>), (StatusCode, String)> {
-    key_service::delete_key(&pool, id)
+    let expected_version = parse_if_match(&headers)?;
+
+    let result = key_service::delete_key(&pool, &identity, id, expected_version)
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete key: {:?}", e)))?;
+
+    match result {
+        ConditionalWrite::Done(()) => {}
+        ConditionalWrite::NotFound => return Err((StatusCode::NOT_FOUND, "Key not found".to_string())),
+        ConditionalWrite::VersionMismatch { current } => return Err(precondition_failed(current)),
+    }
     
     Ok((
         StatusCode::OK,
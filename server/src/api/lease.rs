@@ -0,0 +1,279 @@
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+    Extension,
+};
+use serde_json::json;
+use sqlx::MySqlPool;
+use utoipa::OpenApi;
+
+use crate::api::Routes;
+use crate::api::access::require_role;
+use crate::model::access::{Identity, Permission, Role};
+use crate::model::lease::{
+    CreateLeaseHookRequest, CreateLeaseRequest, CreateLeaseResponse, LeaseHookResponse, LeaseListQuery,
+    LeaseOperation, LeaseRecord, LeaseResponse, RenewLeaseRequest,
+};
+use crate::service::lease as lease_service;
+use crate::utils::middleware::{authorize_key, service_error};
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .route("/leases", post(handle_create_lease).get(handle_list_leases))
+        .route("/leases/hooks", post(handle_create_hook).get(handle_list_hooks))
+        .route("/leases/hooks/{id}", delete(handle_delete_hook))
+        .route("/leases/{id}", get(handle_get_lease).delete(handle_revoke_lease))
+        .route("/leases/{id}/renew", post(handle_renew_lease))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    handle_create_lease,
+    handle_list_leases,
+    handle_get_lease,
+    handle_renew_lease,
+    handle_revoke_lease,
+    handle_create_hook,
+    handle_list_hooks,
+    handle_delete_hook
+))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    post,
+    path = "/leases",
+    tag = "leases",
+    request_body = CreateLeaseRequest,
+    responses(
+        (status = 201, description = "Lease created; a generated credential is returned only once", body = CreateLeaseResponse),
+        (status = 400, description = "Invalid input", body = String),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Key not found", body = String),
+        (status = 409, description = "Key already leased or key name already exists", body = String),
+        (status = 429, description = "Key count quota exceeded", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_create_lease(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Json(request): Json<CreateLeaseRequest>,
+) -> Result<(StatusCode, Json<CreateLeaseResponse>), (StatusCode, String)> {
+    // 租约结束时密钥会被删除，租用已有密钥需要delete权限
+    match request.key_id {
+        Some(key_id) => authorize_key(&identity, None, key_id, Permission::Delete).await?,
+        None if !identity.role.can_create_keys() => {
+            tracing::warn!(identity_id = identity.id, role = %identity.role, "Access denied: generate leased credential");
+            return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+        }
+        None => {}
+    }
+
+    let encryption_key = std::env::var("ENCRYPTION_KEY")
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Encryption key not configured".to_string()))?;
+    let result = lease_service::create_lease(&pool, &identity, request, &encryption_key)
+        .await
+        .map_err(service_error("Failed to create lease"))?;
+
+    to_response(result).map(|response| (StatusCode::CREATED, Json(response)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/leases",
+    tag = "leases",
+    params(LeaseListQuery),
+    responses(
+        (status = 200, description = "Leases owned by the caller, ordered by id", body = [LeaseResponse])
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_list_leases(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Query(query): Query<LeaseListQuery>,
+) -> Result<Json<Vec<LeaseResponse>>, (StatusCode, String)> {
+    let leases = lease_service::list_leases(&pool, &identity, query)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list leases: {:?}", e)))?;
+
+    Ok(Json(leases))
+}
+
+#[utoipa::path(
+    get,
+    path = "/leases/{id}",
+    tag = "leases",
+    params(
+        ("id" = u64, Path, description = "Lease ID")
+    ),
+    responses(
+        (status = 200, description = "Lease details", body = LeaseResponse),
+        (status = 404, description = "Lease not found", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_get_lease(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(id): Path<u64>,
+) -> Result<Json<LeaseResponse>, (StatusCode, String)> {
+    let lease = get_owned_lease(&pool, &identity, id).await?;
+    let response = LeaseResponse::try_from(lease)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get lease: {:?}", e)))?;
+
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/leases/{id}/renew",
+    tag = "leases",
+    params(
+        ("id" = u64, Path, description = "Lease ID")
+    ),
+    request_body = RenewLeaseRequest,
+    responses(
+        (status = 200, description = "Lease renewed up to its maximum expiry", body = LeaseResponse),
+        (status = 400, description = "Invalid input", body = String),
+        (status = 404, description = "Lease not found", body = String),
+        (status = 409, description = "Lease has ended", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_renew_lease(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(id): Path<u64>,
+    Json(request): Json<RenewLeaseRequest>,
+) -> Result<Json<LeaseResponse>, (StatusCode, String)> {
+    get_owned_lease(&pool, &identity, id).await?;
+
+    let result = lease_service::renew_lease(&pool, id, request)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to renew lease: {:?}", e)))?;
+
+    to_response(result).map(Json)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/leases/{id}",
+    tag = "leases",
+    params(
+        ("id" = u64, Path, description = "Lease ID")
+    ),
+    responses(
+        (status = 200, description = "Lease revoked; its key was deleted and the revocation hook run", body = LeaseResponse),
+        (status = 404, description = "Lease not found", body = String),
+        (status = 409, description = "Lease has already ended", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_revoke_lease(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(id): Path<u64>,
+) -> Result<Json<LeaseResponse>, (StatusCode, String)> {
+    get_owned_lease(&pool, &identity, id).await?;
+
+    let result = lease_service::revoke_lease(&pool, id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to revoke lease: {:?}", e)))?;
+
+    to_response(result).map(Json)
+}
+
+#[utoipa::path(
+    post,
+    path = "/leases/hooks",
+    tag = "leases",
+    request_body = CreateLeaseHookRequest,
+    responses(
+        (status = 201, description = "Revocation hook registered", body = LeaseHookResponse),
+        (status = 400, description = "Target is not an allowed local command or loopback URL", body = String),
+        (status = 403, description = "Access denied", body = String),
+        (status = 409, description = "Hook name already exists", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_create_hook(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Json(request): Json<CreateLeaseHookRequest>,
+) -> Result<(StatusCode, Json<LeaseHookResponse>), (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+
+    let result = lease_service::create_hook(&pool, &identity, request)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to create lease hook: {:?}", e)))?;
+
+    to_response(result).map(|response| (StatusCode::CREATED, Json(response)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/leases/hooks",
+    tag = "leases",
+    responses(
+        (status = 200, description = "Registered revocation hooks", body = [LeaseHookResponse])
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_list_hooks(State(pool): State<MySqlPool>) -> Result<Json<Vec<LeaseHookResponse>>, (StatusCode, String)> {
+    let hooks = lease_service::list_hooks(&pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list lease hooks: {:?}", e)))?;
+
+    Ok(Json(hooks))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/leases/hooks/{id}",
+    tag = "leases",
+    params(
+        ("id" = u64, Path, description = "Hook ID")
+    ),
+    responses(
+        (status = 200, description = "Hook deleted; leases using it no longer run a hook", body = serde_json::Value),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Hook not found", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_delete_hook(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(id): Path<u64>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+
+    let deleted = lease_service::delete_hook(&pool, id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete lease hook: {:?}", e)))?;
+    if !deleted {
+        return Err((StatusCode::NOT_FOUND, "Hook not found".to_string()));
+    }
+
+    Ok(Json(json!({ "message": "Lease hook deleted successfully" })))
+}
+
+// 只有租约所有者和管理员可以访问租约，其他调用方得到404
+async fn get_owned_lease(pool: &MySqlPool, identity: &Identity, id: u64) -> Result<LeaseRecord, (StatusCode, String)> {
+    lease_service::get_lease(pool, id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get lease: {:?}", e)))?
+        .filter(|lease| lease.owner_id == Some(identity.id) || identity.role == Role::Admin)
+        .ok_or((StatusCode::NOT_FOUND, "Lease not found".to_string()))
+}
+
+fn to_response<T>(result: LeaseOperation<T>) -> Result<T, (StatusCode, String)> {
+    match result {
+        LeaseOperation::Done(response) => Ok(response),
+        LeaseOperation::NotFound => Err((StatusCode::NOT_FOUND, "Not found".to_string())),
+        LeaseOperation::Invalid(reason) => Err((StatusCode::BAD_REQUEST, reason)),
+        LeaseOperation::Conflict(reason) => Err((StatusCode::CONFLICT, reason)),
+    }
+}
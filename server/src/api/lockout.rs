@@ -0,0 +1,74 @@
+use axum::{
+    extract::{Json, Path},
+    http::StatusCode,
+    routing::{delete, get},
+    Extension,
+};
+use serde_json::json;
+use sqlx::MySqlPool;
+use std::net::IpAddr;
+use utoipa::OpenApi;
+
+use crate::api::Routes;
+use crate::api::access::require_role;
+use crate::model::access::{Identity, Role};
+use crate::utils::rate_limit::{LockoutStatus, LOCKOUTS};
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .route("/lockouts", get(handle_list_lockouts))
+        .route("/lockouts/{ip}", delete(handle_unlock))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    handle_list_lockouts,
+    handle_unlock,
+))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    get,
+    path = "/lockouts",
+    tag = "lockouts",
+    responses(
+        (status = 200, description = "Currently locked out clients", body = Vec<LockoutStatus>),
+        (status = 403, description = "Access denied", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_list_lockouts(
+    Extension(identity): Extension<Identity>,
+) -> Result<Json<Vec<LockoutStatus>>, (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+
+    Ok(Json(LOCKOUTS.list()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/lockouts/{ip}",
+    tag = "lockouts",
+    params(
+        ("ip" = String, Path, description = "Client IP address")
+    ),
+    responses(
+        (status = 200, description = "Lockout cleared", body = serde_json::Value),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Lockout not found", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_unlock(
+    Extension(identity): Extension<Identity>,
+    Path(ip): Path<IpAddr>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+
+    if !LOCKOUTS.unlock(ip) {
+        return Err((StatusCode::NOT_FOUND, "No lockout for this client".to_string()));
+    }
+    tracing::warn!(%ip, admin_id = identity.id, "Client lockout cleared by admin");
+
+    Ok(Json(json!({ "message": "Client unlocked" })))
+}
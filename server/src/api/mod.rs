@@ -1 +1,104 @@
-pub mod key;
\ No newline at end of file
+use axum::{
+    extract::Request,
+    response::IntoResponse,
+    routing::{MethodRouter, Route},
+    Router,
+};
+use sqlx::MySqlPool;
+use std::convert::Infallible;
+use tower::{Layer, Service};
+
+pub mod key;
+pub mod access;
+pub mod share;
+pub mod audit;
+pub mod rotation;
+pub mod webhook;
+pub mod backup;
+pub mod lockout;
+pub mod user;
+pub mod token;
+pub mod vault;
+pub mod health;
+pub mod event;
+pub mod batch;
+pub mod crypto;
+pub mod pki;
+pub mod ssh;
+pub mod lease;
+pub mod quota;
+pub mod team;
+pub mod openapi;
+
+/// 记录已注册路径的路由表，服务启动和OpenAPI测试共用同一份
+#[derive(Default)]
+pub struct Routes {
+    router: Router<MySqlPool>,
+    paths: Vec<&'static str>,
+}
+
+impl Routes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn route(mut self, path: &'static str, method_router: MethodRouter<MySqlPool>) -> Self {
+        self.router = self.router.route(path, method_router);
+        self.paths.push(path);
+        self
+    }
+
+    pub fn merge(mut self, other: Routes) -> Self {
+        self.router = self.router.merge(other.router);
+        self.paths.extend(other.paths);
+        self
+    }
+
+    /// 为已注册的路由添加中间件，与[`Router::route_layer`]相同
+    pub fn route_layer<L>(mut self, layer: L) -> Self
+    where
+        L: Layer<Route> + Clone + Send + Sync + 'static,
+        L::Service: Service<Request> + Clone + Send + Sync + 'static,
+        <L::Service as Service<Request>>::Response: IntoResponse + 'static,
+        <L::Service as Service<Request>>::Error: Into<Infallible> + 'static,
+        <L::Service as Service<Request>>::Future: Send + 'static,
+    {
+        self.router = self.router.route_layer(layer);
+        self
+    }
+
+    /// 已注册的路由模板，同一路径按方法分开注册时会重复出现
+    pub fn paths(&self) -> &[&'static str] {
+        &self.paths
+    }
+
+    pub fn into_router(self) -> Router<MySqlPool> {
+        self.router
+    }
+}
+
+/// 全部接口路由，不含中间件
+pub fn router() -> Routes {
+    Routes::new()
+        .merge(key::routes())
+        .merge(access::routes())
+        .merge(share::routes())
+        .merge(audit::routes())
+        .merge(rotation::routes())
+        .merge(webhook::routes())
+        .merge(backup::routes())
+        .merge(lockout::routes())
+        .merge(user::routes())
+        .merge(token::routes())
+        .merge(vault::routes())
+        .merge(health::routes())
+        .merge(event::routes())
+        .merge(batch::routes())
+        .merge(crypto::routes())
+        .merge(pki::routes())
+        .merge(ssh::routes())
+        .merge(lease::routes())
+        .merge(quota::routes())
+        .merge(team::routes())
+        .merge(openapi::routes())
+}
@@ -0,0 +1,195 @@
+use axum::{extract::Json, routing::get};
+use once_cell::sync::Lazy;
+use sqlx::MySqlPool;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::openapi::{Components, OpenApi as OpenApiDocument};
+use utoipa::OpenApi;
+
+use crate::api::{self, Routes};
+
+// OpenAPI文档的访问路径
+const OPENAPI_PATH: &str = "/api/v1/openapi.json";
+
+static DOCUMENT: Lazy<OpenApiDocument> = Lazy::new(document);
+
+pub fn routes() -> Routes {
+    Routes::new().route(OPENAPI_PATH, get(handle_openapi))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(handle_openapi),
+    info(
+        title = "eCipher API",
+        description = "Key management API. Errors are returned as plain text with the matching status code. \
+                       Keys belong to teams; select the team with the X-Team-Id header, otherwise the caller's first team is used."
+    )
+)]
+struct ApiDoc;
+
+// 汇总各模块的接口描述并加入Bearer认证方案
+fn document() -> OpenApiDocument {
+    let mut document = ApiDoc::openapi();
+    for module in [
+        api::key::ApiDoc::openapi(),
+        api::access::ApiDoc::openapi(),
+        api::share::ApiDoc::openapi(),
+        api::audit::ApiDoc::openapi(),
+        api::rotation::ApiDoc::openapi(),
+        api::webhook::ApiDoc::openapi(),
+        api::backup::ApiDoc::openapi(),
+        api::lockout::ApiDoc::openapi(),
+        api::user::ApiDoc::openapi(),
+        api::token::ApiDoc::openapi(),
+        api::vault::ApiDoc::openapi(),
+        api::health::ApiDoc::openapi(),
+        api::event::ApiDoc::openapi(),
+        api::batch::ApiDoc::openapi(),
+        api::crypto::ApiDoc::openapi(),
+        api::pki::ApiDoc::openapi(),
+        api::ssh::ApiDoc::openapi(),
+        api::lease::ApiDoc::openapi(),
+        api::quota::ApiDoc::openapi(),
+        api::team::ApiDoc::openapi(),
+    ] {
+        document.merge(module);
+    }
+
+    document.components.get_or_insert_with(Components::new).add_security_scheme(
+        "bearer",
+        SecurityScheme::Http(
+            HttpBuilder::new()
+                .scheme(HttpAuthScheme::Bearer)
+                .description(Some("Identity API key, service account token or login session token"))
+                .build(),
+        ),
+    );
+    document
+}
+
+/// 本服务的OpenAPI文档，无需认证
+#[utoipa::path(
+    get,
+    path = "/api/v1/openapi.json",
+    tag = "openapi",
+    responses(
+        (status = 200, description = "OpenAPI document of this service", body = serde_json::Value)
+    )
+)]
+async fn handle_openapi() -> Json<&'static OpenApiDocument> {
+    Json(&*DOCUMENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        http::{Method, Request, StatusCode},
+        Router,
+    };
+    use sqlx::mysql::MySqlPoolOptions;
+    use std::collections::BTreeSet;
+    use std::time::Duration;
+    use tower::ServiceExt;
+
+    // 标记未匹配任何路由的响应头
+    const UNMATCHED_HEADER: &str = "x-openapi-unmatched";
+    // 路由可能使用的全部方法
+    const METHODS: [Method; 5] = [Method::GET, Method::PUT, Method::POST, Method::DELETE, Method::PATCH];
+
+    // 不带中间件的完整路由，连接池不会主动连接数据库，个别访问数据库的处理函数很快失败
+    fn router() -> Router {
+        let pool = MySqlPoolOptions::new()
+            .acquire_timeout(Duration::from_millis(100))
+            .connect_lazy("mysql://127.0.0.1:1/ecipher")
+            .expect("database URL is valid");
+        api::router()
+            .into_router()
+            .fallback(handle_unmatched)
+            .method_not_allowed_fallback(handle_unmatched)
+            .with_state(pool)
+    }
+
+    async fn handle_unmatched() -> (StatusCode, [(&'static str, &'static str); 1]) {
+        (StatusCode::NOT_FOUND, [(UNMATCHED_HEADER, "1")])
+    }
+
+    fn documented_operations(document: &OpenApiDocument) -> Vec<(Method, String)> {
+        let mut operations = Vec::new();
+        for (path, item) in &document.paths.paths {
+            for (method, operation) in [
+                (Method::GET, &item.get),
+                (Method::PUT, &item.put),
+                (Method::POST, &item.post),
+                (Method::DELETE, &item.delete),
+                (Method::PATCH, &item.patch),
+            ] {
+                if operation.is_some() {
+                    operations.push((method, path.clone()));
+                }
+            }
+        }
+        operations
+    }
+
+    // 请求落入fallback即说明该方法和路径没有路由
+    async fn is_routed(router: &Router, method: &Method, path: &str) -> bool {
+        // 路径参数统一替换为1，参数格式错误时路由仍然匹配
+        let uri = path
+            .split('/')
+            .map(|segment| if segment.starts_with('{') { "1" } else { segment })
+            .collect::<Vec<_>>()
+            .join("/");
+        let request = Request::builder()
+            .method(method.clone())
+            .uri(&uri)
+            .body(Body::empty())
+            .expect("documented path is a valid URI");
+        let response = router.clone().oneshot(request).await.expect("router is infallible");
+        !response.headers().contains_key(UNMATCHED_HEADER)
+    }
+
+    #[tokio::test]
+    async fn documented_operations_are_routed() {
+        let router = router();
+        let mut missing = Vec::new();
+        for (method, path) in documented_operations(&DOCUMENT) {
+            if !is_routed(&router, &method, &path).await {
+                missing.push(format!("{} {}", method, path));
+            }
+        }
+        assert!(missing.is_empty(), "documented but not routed: {:?}", missing);
+    }
+
+    // 路由表和文档的路径集合必须一致
+    #[test]
+    fn routed_paths_match_documented_paths() {
+        let routed: BTreeSet<String> = api::router().paths().iter().map(|path| path.to_string()).collect();
+        let documented: BTreeSet<String> = DOCUMENT.paths.paths.keys().cloned().collect();
+        let undocumented: Vec<_> = routed.difference(&documented).collect();
+        let unrouted: Vec<_> = documented.difference(&routed).collect();
+        assert!(undocumented.is_empty(), "routed but not documented: {:?}", undocumented);
+        assert!(unrouted.is_empty(), "documented but not routed: {:?}", unrouted);
+    }
+
+    // 路由表中每个路径上的全部方法都要有文档，覆盖 get(..).post(..) 链式声明的方法
+    #[tokio::test]
+    async fn routed_methods_are_documented() {
+        let router = router();
+        let documented = documented_operations(&DOCUMENT);
+        let paths: BTreeSet<&str> = api::router().paths().iter().copied().collect();
+        let mut undocumented = Vec::new();
+        for path in paths {
+            for method in &METHODS {
+                if documented.contains(&(method.clone(), path.to_string())) {
+                    continue;
+                }
+                if is_routed(&router, method, path).await {
+                    undocumented.push(format!("{} {}", method, path));
+                }
+            }
+        }
+        assert!(undocumented.is_empty(), "routed but not documented: {:?}", undocumented);
+    }
+}
@@ -0,0 +1,369 @@
+use axum::{
+    body::Bytes,
+    extract::{Json, Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Extension,
+};
+use sqlx::MySqlPool;
+use utoipa::OpenApi;
+
+use crate::api::Routes;
+use crate::model::access::{Identity, Permission};
+use crate::model::pki::{
+    CaOperation, CaResponse, CertificateAuthority, CertificateListQuery, CreateCaRequest, IssueCertificateRequest,
+    IssuedCertificateResponse, RevokeCertificateRequest,
+};
+use crate::service::pki as pki_service;
+use crate::utils::middleware::{authorize_key, service_error};
+use crate::utils::x509::{self, OcspResponseStatus};
+
+// 公开接口的响应类型
+const PEM_CONTENT_TYPE: &str = "application/x-pem-file";
+const CRL_CONTENT_TYPE: &str = "application/pkix-crl";
+const OCSP_RESPONSE_CONTENT_TYPE: &str = "application/ocsp-response";
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .route("/pki/cas", post(handle_create_ca).get(handle_list_cas))
+        .route("/pki/cas/{id}", get(handle_get_ca))
+        .route("/pki/cas/{id}/certificate", get(handle_get_ca_certificate))
+        .route("/pki/cas/{id}/certificates", post(handle_issue_certificate).get(handle_list_certificates))
+        .route("/pki/cas/{id}/certificates/{serial}", get(handle_get_certificate))
+        .route("/pki/cas/{id}/certificates/{serial}/revoke", post(handle_revoke_certificate))
+        .route("/pki/cas/{id}/crl", get(handle_get_crl))
+        .route("/pki/cas/{id}/ocsp", post(handle_ocsp))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    handle_create_ca,
+    handle_list_cas,
+    handle_get_ca,
+    handle_get_ca_certificate,
+    handle_issue_certificate,
+    handle_list_certificates,
+    handle_get_certificate,
+    handle_revoke_certificate,
+    handle_get_crl,
+    handle_ocsp
+))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    post,
+    path = "/pki/cas",
+    tag = "pki",
+    request_body = CreateCaRequest,
+    responses(
+        (status = 201, description = "CA created; its private key is stored as a certificate key owned by the caller", body = CaResponse),
+        (status = 400, description = "Invalid request or parent CA constraint violated", body = String),
+        (status = 403, description = "Access denied", body = String),
+        (status = 409, description = "CA name already exists", body = String),
+        (status = 429, description = "Key count quota exceeded", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_create_ca(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Json(request): Json<CreateCaRequest>,
+) -> Result<(StatusCode, Json<CaResponse>), (StatusCode, String)> {
+    if !identity.role.can_create_keys() {
+        tracing::warn!(identity_id = identity.id, role = %identity.role, "Access denied: create CA");
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+    // 由上级CA签发需要使用其私钥
+    if let Some(parent_id) = request.parent_id {
+        if let Some(parent) = get_ca(&pool, parent_id).await? {
+            authorize_key(&identity, None, parent.key_id, Permission::Use).await?;
+        }
+    }
+
+    let encryption_key = encryption_key()?;
+    let result = pki_service::create_ca(&pool, &identity, request, &encryption_key)
+        .await
+        .map_err(service_error("Failed to create CA"))?;
+
+    to_response(result).map(|response| (StatusCode::CREATED, Json(response)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/pki/cas",
+    tag = "pki",
+    responses(
+        (status = 200, description = "All CAs", body = [CaResponse])
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_list_cas(State(pool): State<MySqlPool>) -> Result<Json<Vec<CaResponse>>, (StatusCode, String)> {
+    let cas = pki_service::list_cas(&pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list CAs: {:?}", e)))?;
+
+    Ok(Json(cas))
+}
+
+#[utoipa::path(
+    get,
+    path = "/pki/cas/{id}",
+    tag = "pki",
+    params(
+        ("id" = u64, Path, description = "CA ID")
+    ),
+    responses(
+        (status = 200, description = "CA details", body = CaResponse),
+        (status = 404, description = "CA not found", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_get_ca(
+    State(pool): State<MySqlPool>,
+    Path(ca_id): Path<u64>,
+) -> Result<Json<CaResponse>, (StatusCode, String)> {
+    let ca = get_ca(&pool, ca_id).await?.ok_or((StatusCode::NOT_FOUND, "CA not found".to_string()))?;
+
+    Ok(Json(ca.into()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/pki/cas/{id}/certificate",
+    tag = "pki",
+    params(
+        ("id" = u64, Path, description = "CA ID")
+    ),
+    responses(
+        (status = 200, description = "PEM CA certificate followed by its chain; no authentication required", body = String, content_type = "application/x-pem-file"),
+        (status = 404, description = "CA not found", body = String)
+    )
+)]
+async fn handle_get_ca_certificate(
+    State(pool): State<MySqlPool>,
+    Path(ca_id): Path<u64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let chain = pki_service::ca_certificate_chain(&pool, ca_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get CA certificate: {:?}", e)))?
+        .ok_or((StatusCode::NOT_FOUND, "CA not found".to_string()))?;
+
+    Ok(([(header::CONTENT_TYPE, PEM_CONTENT_TYPE)], chain))
+}
+
+#[utoipa::path(
+    post,
+    path = "/pki/cas/{id}/certificates",
+    tag = "pki",
+    params(
+        ("id" = u64, Path, description = "CA ID")
+    ),
+    request_body = IssueCertificateRequest,
+    responses(
+        (status = 201, description = "Certificate issued", body = IssuedCertificateResponse),
+        (status = 400, description = "Invalid request, CSR or validity", body = String),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "CA not found", body = String),
+        (status = 409, description = "Key name already exists", body = String),
+        (status = 429, description = "Key count quota exceeded", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_issue_certificate(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(ca_id): Path<u64>,
+    Json(request): Json<IssueCertificateRequest>,
+) -> Result<(StatusCode, Json<IssuedCertificateResponse>), (StatusCode, String)> {
+    let ca = get_ca(&pool, ca_id).await?.ok_or((StatusCode::NOT_FOUND, "CA not found".to_string()))?;
+    authorize_key(&identity, None, ca.key_id, Permission::Use).await?;
+    // 服务端生成的私钥会保存为调用方的新密钥
+    if request.csr.is_none() && !identity.role.can_create_keys() {
+        tracing::warn!(identity_id = identity.id, role = %identity.role, "Access denied: issue certificate with server key");
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+
+    let encryption_key = encryption_key()?;
+    let result = pki_service::issue_certificate(&pool, &identity, ca_id, request, &encryption_key)
+        .await
+        .map_err(service_error("Failed to issue certificate"))?;
+
+    to_response(result).map(|response| (StatusCode::CREATED, Json(response)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/pki/cas/{id}/certificates",
+    tag = "pki",
+    params(
+        ("id" = u64, Path, description = "CA ID"),
+        CertificateListQuery
+    ),
+    responses(
+        (status = 200, description = "Certificates issued by the CA, ordered by id", body = [IssuedCertificateResponse]),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "CA not found", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_list_certificates(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(ca_id): Path<u64>,
+    Query(query): Query<CertificateListQuery>,
+) -> Result<Json<Vec<IssuedCertificateResponse>>, (StatusCode, String)> {
+    let ca = get_ca(&pool, ca_id).await?.ok_or((StatusCode::NOT_FOUND, "CA not found".to_string()))?;
+    authorize_key(&identity, None, ca.key_id, Permission::ReadMetadata).await?;
+
+    let certificates = pki_service::list_certificates(&pool, ca_id, query)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list certificates: {:?}", e)))?
+        .ok_or((StatusCode::NOT_FOUND, "CA not found".to_string()))?;
+
+    Ok(Json(certificates))
+}
+
+#[utoipa::path(
+    get,
+    path = "/pki/cas/{id}/certificates/{serial}",
+    tag = "pki",
+    params(
+        ("id" = u64, Path, description = "CA ID"),
+        ("serial" = String, Path, description = "Hexadecimal serial number")
+    ),
+    responses(
+        (status = 200, description = "Issued certificate", body = IssuedCertificateResponse),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "CA or certificate not found", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_get_certificate(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path((ca_id, serial)): Path<(u64, String)>,
+) -> Result<Json<IssuedCertificateResponse>, (StatusCode, String)> {
+    let ca = get_ca(&pool, ca_id).await?.ok_or((StatusCode::NOT_FOUND, "CA not found".to_string()))?;
+    authorize_key(&identity, None, ca.key_id, Permission::ReadMetadata).await?;
+
+    let certificate = pki_service::get_certificate(&pool, ca_id, &serial)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get certificate: {:?}", e)))?
+        .ok_or((StatusCode::NOT_FOUND, "Certificate not found".to_string()))?;
+
+    Ok(Json(certificate))
+}
+
+#[utoipa::path(
+    post,
+    path = "/pki/cas/{id}/certificates/{serial}/revoke",
+    tag = "pki",
+    params(
+        ("id" = u64, Path, description = "CA ID"),
+        ("serial" = String, Path, description = "Hexadecimal serial number")
+    ),
+    request_body = RevokeCertificateRequest,
+    responses(
+        (status = 200, description = "Certificate revoked", body = IssuedCertificateResponse),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "CA or certificate not found", body = String),
+        (status = 409, description = "Certificate already revoked", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_revoke_certificate(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path((ca_id, serial)): Path<(u64, String)>,
+    Json(request): Json<RevokeCertificateRequest>,
+) -> Result<Json<IssuedCertificateResponse>, (StatusCode, String)> {
+    let ca = get_ca(&pool, ca_id).await?.ok_or((StatusCode::NOT_FOUND, "CA not found".to_string()))?;
+    authorize_key(&identity, None, ca.key_id, Permission::Use).await?;
+
+    let result = pki_service::revoke_certificate(&pool, ca_id, &serial, request.reason)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to revoke certificate: {:?}", e)))?;
+
+    to_response(result).map(Json)
+}
+
+#[utoipa::path(
+    get,
+    path = "/pki/cas/{id}/crl",
+    tag = "pki",
+    params(
+        ("id" = u64, Path, description = "CA ID")
+    ),
+    responses(
+        (status = 200, description = "DER CRL valid for 24 hours; no authentication required", body = Vec<u8>, content_type = "application/pkix-crl"),
+        (status = 404, description = "CA not found", body = String)
+    )
+)]
+async fn handle_get_crl(
+    State(pool): State<MySqlPool>,
+    Path(ca_id): Path<u64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let encryption_key = encryption_key()?;
+    let crl = pki_service::generate_crl(&pool, ca_id, &encryption_key)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to generate CRL: {:?}", e)))?
+        .ok_or((StatusCode::NOT_FOUND, "CA not found".to_string()))?;
+
+    Ok(([(header::CONTENT_TYPE, CRL_CONTENT_TYPE)], crl))
+}
+
+#[utoipa::path(
+    post,
+    path = "/pki/cas/{id}/ocsp",
+    tag = "pki",
+    params(
+        ("id" = u64, Path, description = "CA ID")
+    ),
+    request_body(content = Vec<u8>, content_type = "application/ocsp-request", description = "DER OCSP request"),
+    responses(
+        (status = 200, description = "DER OCSP response signed by the CA; no authentication required", body = Vec<u8>, content_type = "application/ocsp-response"),
+        (status = 404, description = "CA not found", body = String)
+    )
+)]
+async fn handle_ocsp(
+    State(pool): State<MySqlPool>,
+    Path(ca_id): Path<u64>,
+    body: Bytes,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let encryption_key = encryption_key()?;
+    // OCSP客户端只理解OCSP响应，内部错误也以internalError状态返回
+    let response = match pki_service::ocsp_response(&pool, ca_id, &body, &encryption_key).await {
+        Ok(Some(response)) => response,
+        Ok(None) => return Err((StatusCode::NOT_FOUND, "CA not found".to_string())),
+        Err(e) => {
+            tracing::error!(ca_id, "Failed to answer OCSP request: {:?}", e);
+            x509::ocsp_error(OcspResponseStatus::InternalError)
+        }
+    };
+
+    Ok(([(header::CONTENT_TYPE, OCSP_RESPONSE_CONTENT_TYPE)], response))
+}
+
+async fn get_ca(
+    pool: &MySqlPool,
+    ca_id: u64,
+) -> Result<Option<CertificateAuthority>, (StatusCode, String)> {
+    pki_service::get_ca(pool, ca_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get CA: {:?}", e)))
+}
+
+fn encryption_key() -> Result<String, (StatusCode, String)> {
+    std::env::var("ENCRYPTION_KEY")
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Encryption key not configured".to_string()))
+}
+
+fn to_response<T>(result: CaOperation<T>) -> Result<T, (StatusCode, String)> {
+    match result {
+        CaOperation::Done(response) => Ok(response),
+        CaOperation::NotFound => Err((StatusCode::NOT_FOUND, "Not found".to_string())),
+        CaOperation::Invalid(reason) => Err((StatusCode::BAD_REQUEST, reason)),
+        CaOperation::Conflict(reason) => Err((StatusCode::CONFLICT, reason)),
+    }
+}
@@ -0,0 +1,174 @@
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    routing::get,
+    Extension,
+};
+use serde_json::json;
+use sqlx::MySqlPool;
+use utoipa::OpenApi;
+
+use crate::api::Routes;
+use crate::api::access::require_role;
+use crate::model::access::{Identity, Role};
+use crate::model::quota::{CallerUsageResponse, QuotaLimits, QuotaResponse, QuotaSubjectType, QuotaUsage};
+use crate::service::quota as quota_service;
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .route("/quotas", get(handle_list_quotas))
+        .route("/quotas/usage", get(handle_caller_usage))
+        .route(
+            "/quotas/{subject_type}/{subject}",
+            get(handle_get_usage).put(handle_set_quota).delete(handle_delete_quota),
+        )
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    handle_caller_usage,
+    handle_list_quotas,
+    handle_get_usage,
+    handle_set_quota,
+    handle_delete_quota
+))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    get,
+    path = "/quotas/usage",
+    tag = "quotas",
+    responses(
+        (status = 200, description = "Usage and effective limits of the caller and its source IP", body = CallerUsageResponse)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_caller_usage(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+) -> Result<Json<CallerUsageResponse>, (StatusCode, String)> {
+    let usage = quota_service::caller_usage(&pool, &identity)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get quota usage: {:?}", e)))?;
+
+    Ok(Json(usage))
+}
+
+#[utoipa::path(
+    get,
+    path = "/quotas",
+    tag = "quotas",
+    responses(
+        (status = 200, description = "Configured quotas; identities without one use the server defaults", body = [QuotaResponse]),
+        (status = 403, description = "Access denied", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_list_quotas(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+) -> Result<Json<Vec<QuotaResponse>>, (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+
+    let quotas = quota_service::list_quotas(&pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list quotas: {:?}", e)))?;
+
+    Ok(Json(quotas))
+}
+
+#[utoipa::path(
+    get,
+    path = "/quotas/{subject_type}/{subject}",
+    tag = "quotas",
+    params(
+        ("subject_type" = QuotaSubjectType, Path, description = "identity or ip"),
+        ("subject" = String, Path, description = "Identity ID or IP address")
+    ),
+    responses(
+        (status = 200, description = "Usage and effective limits of the subject", body = QuotaUsage),
+        (status = 400, description = "Invalid subject", body = String),
+        (status = 403, description = "Access denied", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_get_usage(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path((subject_type, subject)): Path<(QuotaSubjectType, String)>,
+) -> Result<Json<QuotaUsage>, (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+    let subject = quota_service::parse_subject(subject_type, &subject).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let usage = quota_service::subject_usage(&pool, subject_type, &subject)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get quota usage: {:?}", e)))?;
+
+    Ok(Json(usage))
+}
+
+#[utoipa::path(
+    put,
+    path = "/quotas/{subject_type}/{subject}",
+    tag = "quotas",
+    params(
+        ("subject_type" = QuotaSubjectType, Path, description = "identity or ip"),
+        ("subject" = String, Path, description = "Identity ID or IP address")
+    ),
+    request_body = QuotaLimits,
+    responses(
+        (status = 200, description = "Quota set; omitted limits are unlimited", body = QuotaResponse),
+        (status = 400, description = "Invalid subject", body = String),
+        (status = 403, description = "Access denied", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_set_quota(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path((subject_type, subject)): Path<(QuotaSubjectType, String)>,
+    Json(limits): Json<QuotaLimits>,
+) -> Result<Json<QuotaResponse>, (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+    let subject = quota_service::parse_subject(subject_type, &subject).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let quota = quota_service::set_quota(&pool, subject_type, &subject, limits)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to set quota: {:?}", e)))?;
+
+    Ok(Json(quota))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/quotas/{subject_type}/{subject}",
+    tag = "quotas",
+    params(
+        ("subject_type" = QuotaSubjectType, Path, description = "identity or ip"),
+        ("subject" = String, Path, description = "Identity ID or IP address")
+    ),
+    responses(
+        (status = 200, description = "Quota deleted; an identity falls back to the server defaults", body = serde_json::Value),
+        (status = 400, description = "Invalid subject", body = String),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Quota not found", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_delete_quota(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path((subject_type, subject)): Path<(QuotaSubjectType, String)>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+    let subject = quota_service::parse_subject(subject_type, &subject).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let deleted = quota_service::delete_quota(&pool, subject_type, &subject)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete quota: {:?}", e)))?;
+    if !deleted {
+        return Err((StatusCode::NOT_FOUND, "Quota not found".to_string()));
+    }
+
+    Ok(Json(json!({ "message": "Quota deleted successfully" })))
+}
@@ -0,0 +1,218 @@
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    middleware::from_fn,
+    routing::{get, post, put},
+    Extension,
+};
+use serde_json::json;
+use sqlx::MySqlPool;
+use utoipa::OpenApi;
+
+use crate::api::Routes;
+use crate::api::access::require_role;
+use crate::api::team::require_team;
+use crate::model::access::{Identity, Role};
+use crate::model::key::KeyMaterialResponse;
+use crate::model::rotation::{
+    DueRotation, DueRotationQuery, KeyVersion, RotationPolicy, RotationTrigger, SetRotationPolicyRequest,
+};
+use crate::service::rotation as rotation_service;
+use crate::utils::middleware::key_acl_middleware;
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .route("/rotations/due", get(handle_list_due))
+        .merge(
+            Routes::new()
+                .route("/keys/{id}/rotation-policy", get(handle_get_policy))
+                .route("/keys/{id}/rotation-policy", put(handle_set_policy))
+                .route("/keys/{id}/rotate", post(handle_rotate_key))
+                .route("/keys/{id}/versions", get(handle_list_versions))
+                .route("/keys/{id}/versions/{version}/material", get(handle_get_version_material))
+                .route_layer(from_fn(key_acl_middleware)),
+        )
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    handle_get_policy,
+    handle_set_policy,
+    handle_rotate_key,
+    handle_list_versions,
+    handle_get_version_material,
+    handle_list_due,
+))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    get,
+    path = "/keys/{id}/rotation-policy",
+    tag = "rotation",
+    params(
+        ("id" = u64, Path, description = "Key ID")
+    ),
+    responses(
+        (status = 200, description = "Rotation policy", body = RotationPolicy),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Rotation policy not found", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_get_policy(
+    State(pool): State<MySqlPool>,
+    Path(key_id): Path<u64>,
+) -> Result<Json<RotationPolicy>, (StatusCode, String)> {
+    let policy = rotation_service::get_policy(&pool, key_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get rotation policy: {:?}", e)))?
+        .ok_or((StatusCode::NOT_FOUND, "Rotation policy not found".to_string()))?;
+
+    Ok(Json(policy))
+}
+
+#[utoipa::path(
+    put,
+    path = "/keys/{id}/rotation-policy",
+    tag = "rotation",
+    params(
+        ("id" = u64, Path, description = "Key ID")
+    ),
+    request_body = SetRotationPolicyRequest,
+    responses(
+        (status = 200, description = "Rotation policy saved", body = serde_json::Value),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 403, description = "Access denied", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_set_policy(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(key_id): Path<u64>,
+    Json(request): Json<SetRotationPolicyRequest>,
+) -> Result<(StatusCode, Json<serde_json::Value>), (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin, Role::Operator])?;
+
+    let policy = rotation_service::set_policy(&pool, key_id, request.interval_days)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Failed to set rotation policy: {:?}", e)))?;
+
+    Ok((StatusCode::OK, Json(json!({ "policy": policy }))))
+}
+
+#[utoipa::path(
+    post,
+    path = "/keys/{id}/rotate",
+    tag = "rotation",
+    params(
+        ("id" = u64, Path, description = "Key ID")
+    ),
+    responses(
+        (status = 200, description = "Key rotated; retired_version is the key version (ETag) the old value had", body = serde_json::Value),
+        (status = 403, description = "Access denied", body = String),
+        (status = 500, description = "Internal error", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_rotate_key(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(key_id): Path<u64>,
+) -> Result<(StatusCode, Json<serde_json::Value>), (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin, Role::Operator])?;
+
+    let encryption_key = std::env::var("ENCRYPTION_KEY")
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Encryption key not configured".to_string()))?;
+
+    let retired_version = rotation_service::rotate_key(&pool, key_id, RotationTrigger::Manual, &encryption_key)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to rotate key: {:?}", e)))?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({ "message": "Key rotated successfully", "retired_version": retired_version })),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/keys/{id}/versions",
+    tag = "rotation",
+    params(
+        ("id" = u64, Path, description = "Key ID")
+    ),
+    responses(
+        (status = 200, description = "Retired key versions", body = Vec<KeyVersion>),
+        (status = 403, description = "Access denied", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_list_versions(
+    State(pool): State<MySqlPool>,
+    Path(key_id): Path<u64>,
+) -> Result<Json<Vec<KeyVersion>>, (StatusCode, String)> {
+    let versions = rotation_service::list_versions(&pool, key_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list key versions: {:?}", e)))?;
+
+    Ok(Json(versions))
+}
+
+#[utoipa::path(
+    get,
+    path = "/keys/{id}/versions/{version}/material",
+    tag = "rotation",
+    params(
+        ("id" = u64, Path, description = "Key ID"),
+        ("version" = u32, Path, description = "Key version")
+    ),
+    responses(
+        (status = 200, description = "Decrypted material of the version", body = KeyMaterialResponse),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Key version not found", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_get_version_material(
+    State(pool): State<MySqlPool>,
+    Path((key_id, version)): Path<(u64, u32)>,
+) -> Result<Json<KeyMaterialResponse>, (StatusCode, String)> {
+    let encryption_key = std::env::var("ENCRYPTION_KEY")
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Encryption key not configured".to_string()))?;
+
+    let material = rotation_service::get_version_material(&pool, key_id, version, &encryption_key)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get key version: {:?}", e)))?
+        .ok_or((StatusCode::NOT_FOUND, "Key version not found".to_string()))?;
+
+    Ok(Json(material))
+}
+
+#[utoipa::path(
+    get,
+    path = "/rotations/due",
+    tag = "rotation",
+    params(
+        DueRotationQuery
+    ),
+    responses(
+        (status = 200, description = "Keys of the current team due for rotation", body = Vec<DueRotation>),
+        (status = 403, description = "Access denied", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_list_due(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Query(query): Query<DueRotationQuery>,
+) -> Result<Json<Vec<DueRotation>>, (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin, Role::Operator, Role::Auditor])?;
+    let team_id = require_team(&identity)?;
+
+    let due = rotation_service::list_due(&pool, team_id, query.within_days.unwrap_or(0))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list due rotations: {:?}", e)))?;
+
+    Ok(Json(due))
+}
@@ -0,0 +1,207 @@
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    middleware::from_fn,
+    routing::{delete, get, post, put},
+    Extension,
+};
+use serde_json::json;
+use sqlx::MySqlPool;
+use utoipa::OpenApi;
+
+use crate::api::Routes;
+use crate::model::access::Identity;
+use crate::model::share::{CreateShareRequest, PublicKeyResponse, SetPublicKeyRequest, ShareResponse};
+use crate::service::share as share_service;
+use crate::utils::middleware::key_acl_middleware;
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .route("/identities/me/public-key", put(handle_set_public_key))
+        .route("/identities/{id}/public-key", get(handle_get_public_key))
+        .route("/shares/received", get(handle_list_received))
+        .route("/shares/sent", get(handle_list_sent))
+        .route("/shares/{share_id}", delete(handle_revoke_share))
+        .merge(
+            Routes::new()
+                .route("/keys/{id}/shares", post(handle_share_key))
+                .route_layer(from_fn(key_acl_middleware)),
+        )
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    handle_set_public_key,
+    handle_get_public_key,
+    handle_share_key,
+    handle_list_received,
+    handle_list_sent,
+    handle_revoke_share,
+))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    put,
+    path = "/identities/me/public-key",
+    tag = "shares",
+    request_body = SetPublicKeyRequest,
+    responses(
+        (status = 200, description = "Public key registered", body = PublicKeyResponse),
+        (status = 400, description = "Invalid request", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_set_public_key(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Json(request): Json<SetPublicKeyRequest>,
+) -> Result<Json<PublicKeyResponse>, (StatusCode, String)> {
+    let public_key = share_service::set_public_key(&pool, &identity, &request.public_key)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Failed to set public key: {:?}", e)))?;
+
+    Ok(Json(public_key))
+}
+
+#[utoipa::path(
+    get,
+    path = "/identities/{id}/public-key",
+    tag = "shares",
+    params(
+        ("id" = u64, Path, description = "Identity ID")
+    ),
+    responses(
+        (status = 200, description = "Public key of the identity", body = PublicKeyResponse),
+        (status = 404, description = "Public key not found", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_get_public_key(
+    State(pool): State<MySqlPool>,
+    Path(id): Path<u64>,
+) -> Result<Json<PublicKeyResponse>, (StatusCode, String)> {
+    let public_key = share_service::get_public_key(&pool, id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get public key: {:?}", e)))?
+        .ok_or((StatusCode::NOT_FOUND, "Public key not found".to_string()))?;
+
+    Ok(Json(public_key))
+}
+
+#[utoipa::path(
+    post,
+    path = "/keys/{id}/shares",
+    tag = "shares",
+    params(
+        ("id" = u64, Path, description = "Key ID")
+    ),
+    request_body = CreateShareRequest,
+    responses(
+        (status = 201, description = "Key shared", body = ShareResponse),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 403, description = "Access denied", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_share_key(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(key_id): Path<u64>,
+    Json(request): Json<CreateShareRequest>,
+) -> Result<(StatusCode, Json<ShareResponse>), (StatusCode, String)> {
+    let encryption_key = std::env::var("ENCRYPTION_KEY")
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Encryption key not configured".to_string()))?;
+
+    let share = share_service::share_key(&pool, &identity, key_id, request, &encryption_key)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Failed to share key: {:?}", e)))?;
+
+    tracing::info!(
+        share_id = share.id,
+        key_id,
+        sharer_id = identity.id,
+        recipient_id = share.recipient_id,
+        "Key shared"
+    );
+
+    Ok((StatusCode::CREATED, Json(share)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/shares/received",
+    tag = "shares",
+    responses(
+        (status = 200, description = "Shares received by the caller", body = Vec<ShareResponse>)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_list_received(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+) -> Result<Json<Vec<ShareResponse>>, (StatusCode, String)> {
+    let shares = share_service::list_received(&pool, &identity)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list shares: {:?}", e)))?;
+
+    Ok(Json(shares))
+}
+
+#[utoipa::path(
+    get,
+    path = "/shares/sent",
+    tag = "shares",
+    responses(
+        (status = 200, description = "Shares sent by the caller", body = Vec<ShareResponse>)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_list_sent(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+) -> Result<Json<Vec<ShareResponse>>, (StatusCode, String)> {
+    let shares = share_service::list_sent(&pool, &identity)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list shares: {:?}", e)))?;
+
+    Ok(Json(shares))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/shares/{share_id}",
+    tag = "shares",
+    params(
+        ("share_id" = u64, Path, description = "Share ID")
+    ),
+    responses(
+        (status = 200, description = "Share revoked", body = serde_json::Value),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Share not found", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_revoke_share(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(share_id): Path<u64>,
+) -> Result<(StatusCode, Json<serde_json::Value>), (StatusCode, String)> {
+    let share = share_service::get_share(&pool, share_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get share: {:?}", e)))?
+        .ok_or((StatusCode::NOT_FOUND, "Share not found".to_string()))?;
+
+    if !share_service::can_revoke(&identity, &share) {
+        tracing::warn!(identity_id = identity.id, share_id, "Access denied: revoke share");
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+
+    share_service::revoke(&pool, share_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to revoke share: {:?}", e)))?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({ "message": "Share revoked successfully" })),
+    ))
+}
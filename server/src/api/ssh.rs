@@ -0,0 +1,283 @@
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Extension,
+};
+use sqlx::MySqlPool;
+use utoipa::OpenApi;
+
+use crate::api::Routes;
+use crate::model::access::{Identity, Permission};
+use crate::model::pki::CaOperation;
+use crate::model::ssh::{
+    CreateSshCaRequest, GenerateSshKeyRequest, GeneratedSshKeyResponse, SignSshKeyRequest, SshCaResponse,
+    SshCertificateAuthority, SshCertificateListQuery, SshCertificateResponse,
+};
+use crate::service::ssh as ssh_service;
+use crate::utils::middleware::{authorize_key, service_error};
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .route("/ssh/keys", post(handle_generate_key))
+        .route("/ssh/cas", post(handle_create_ca).get(handle_list_cas))
+        .route("/ssh/cas/{id}", get(handle_get_ca))
+        .route("/ssh/cas/{id}/public-key", get(handle_get_ca_public_key))
+        .route("/ssh/cas/{id}/sign", post(handle_sign_key))
+        .route("/ssh/cas/{id}/certificates", get(handle_list_certificates))
+        .route("/ssh/cas/{id}/certificates/{serial}", get(handle_get_certificate))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    handle_generate_key,
+    handle_create_ca,
+    handle_list_cas,
+    handle_get_ca,
+    handle_get_ca_public_key,
+    handle_sign_key,
+    handle_list_certificates,
+    handle_get_certificate
+))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    post,
+    path = "/ssh/keys",
+    tag = "ssh",
+    request_body = GenerateSshKeyRequest,
+    responses(
+        (status = 201, description = "SSH key pair generated and stored as an ssh_key key", body = GeneratedSshKeyResponse),
+        (status = 400, description = "Invalid input", body = String),
+        (status = 403, description = "Access denied", body = String),
+        (status = 409, description = "Key name already exists", body = String),
+        (status = 429, description = "Key count quota exceeded", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_generate_key(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Json(request): Json<GenerateSshKeyRequest>,
+) -> Result<(StatusCode, Json<GeneratedSshKeyResponse>), (StatusCode, String)> {
+    if !identity.role.can_create_keys() {
+        tracing::warn!(identity_id = identity.id, role = %identity.role, "Access denied: generate SSH key");
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+
+    let encryption_key = encryption_key()?;
+    let result = ssh_service::generate_key(&pool, &identity, request, &encryption_key)
+        .await
+        .map_err(service_error("Failed to generate SSH key"))?;
+
+    to_response(result).map(|response| (StatusCode::CREATED, Json(response)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/ssh/cas",
+    tag = "ssh",
+    request_body = CreateSshCaRequest,
+    responses(
+        (status = 201, description = "SSH CA created; its private key is stored as an ssh_key key owned by the caller", body = SshCaResponse),
+        (status = 400, description = "Invalid input", body = String),
+        (status = 403, description = "Access denied", body = String),
+        (status = 409, description = "SSH CA name already exists", body = String),
+        (status = 429, description = "Key count quota exceeded", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_create_ca(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Json(request): Json<CreateSshCaRequest>,
+) -> Result<(StatusCode, Json<SshCaResponse>), (StatusCode, String)> {
+    if !identity.role.can_create_keys() {
+        tracing::warn!(identity_id = identity.id, role = %identity.role, "Access denied: create SSH CA");
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+
+    let encryption_key = encryption_key()?;
+    let result = ssh_service::create_ca(&pool, &identity, request, &encryption_key)
+        .await
+        .map_err(service_error("Failed to create SSH CA"))?;
+
+    to_response(result).map(|response| (StatusCode::CREATED, Json(response)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/ssh/cas",
+    tag = "ssh",
+    responses(
+        (status = 200, description = "All SSH CAs", body = [SshCaResponse])
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_list_cas(State(pool): State<MySqlPool>) -> Result<Json<Vec<SshCaResponse>>, (StatusCode, String)> {
+    let cas = ssh_service::list_cas(&pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list SSH CAs: {:?}", e)))?;
+
+    Ok(Json(cas))
+}
+
+#[utoipa::path(
+    get,
+    path = "/ssh/cas/{id}",
+    tag = "ssh",
+    params(
+        ("id" = u64, Path, description = "SSH CA ID")
+    ),
+    responses(
+        (status = 200, description = "SSH CA details", body = SshCaResponse),
+        (status = 404, description = "SSH CA not found", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_get_ca(
+    State(pool): State<MySqlPool>,
+    Path(ca_id): Path<u64>,
+) -> Result<Json<SshCaResponse>, (StatusCode, String)> {
+    let ca = get_ca(&pool, ca_id).await?.ok_or((StatusCode::NOT_FOUND, "SSH CA not found".to_string()))?;
+
+    Ok(Json(ca.into()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/ssh/cas/{id}/public-key",
+    tag = "ssh",
+    params(
+        ("id" = u64, Path, description = "SSH CA ID")
+    ),
+    responses(
+        (status = 200, description = "CA public key in authorized_keys format; no authentication required", body = String, content_type = "text/plain"),
+        (status = 404, description = "SSH CA not found", body = String)
+    )
+)]
+async fn handle_get_ca_public_key(
+    State(pool): State<MySqlPool>,
+    Path(ca_id): Path<u64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let ca = get_ca(&pool, ca_id).await?.ok_or((StatusCode::NOT_FOUND, "SSH CA not found".to_string()))?;
+
+    Ok(([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], format!("{}\n", ca.public_key)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/ssh/cas/{id}/sign",
+    tag = "ssh",
+    params(
+        ("id" = u64, Path, description = "SSH CA ID")
+    ),
+    request_body = SignSshKeyRequest,
+    responses(
+        (status = 201, description = "OpenSSH certificate issued", body = SshCertificateResponse),
+        (status = 400, description = "Invalid public key or request outside the CA limits", body = String),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "SSH CA not found", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_sign_key(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(ca_id): Path<u64>,
+    Json(request): Json<SignSshKeyRequest>,
+) -> Result<(StatusCode, Json<SshCertificateResponse>), (StatusCode, String)> {
+    let ca = get_ca(&pool, ca_id).await?.ok_or((StatusCode::NOT_FOUND, "SSH CA not found".to_string()))?;
+    authorize_key(&identity, None, ca.key_id, Permission::Use).await?;
+
+    let encryption_key = encryption_key()?;
+    let result = ssh_service::sign_key(&pool, &identity, ca_id, request, &encryption_key)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to sign SSH key: {:?}", e)))?;
+
+    to_response(result).map(|response| (StatusCode::CREATED, Json(response)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/ssh/cas/{id}/certificates",
+    tag = "ssh",
+    params(
+        ("id" = u64, Path, description = "SSH CA ID"),
+        SshCertificateListQuery
+    ),
+    responses(
+        (status = 200, description = "Certificates issued by the SSH CA, ordered by id", body = [SshCertificateResponse]),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "SSH CA not found", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_list_certificates(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(ca_id): Path<u64>,
+    Query(query): Query<SshCertificateListQuery>,
+) -> Result<Json<Vec<SshCertificateResponse>>, (StatusCode, String)> {
+    let ca = get_ca(&pool, ca_id).await?.ok_or((StatusCode::NOT_FOUND, "SSH CA not found".to_string()))?;
+    authorize_key(&identity, None, ca.key_id, Permission::ReadMetadata).await?;
+
+    let certificates = ssh_service::list_certificates(&pool, ca_id, query)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list SSH certificates: {:?}", e)))?
+        .ok_or((StatusCode::NOT_FOUND, "SSH CA not found".to_string()))?;
+
+    Ok(Json(certificates))
+}
+
+#[utoipa::path(
+    get,
+    path = "/ssh/cas/{id}/certificates/{serial}",
+    tag = "ssh",
+    params(
+        ("id" = u64, Path, description = "SSH CA ID"),
+        ("serial" = u64, Path, description = "Certificate serial number")
+    ),
+    responses(
+        (status = 200, description = "Issued SSH certificate", body = SshCertificateResponse),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "SSH CA or certificate not found", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_get_certificate(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path((ca_id, serial)): Path<(u64, u64)>,
+) -> Result<Json<SshCertificateResponse>, (StatusCode, String)> {
+    let ca = get_ca(&pool, ca_id).await?.ok_or((StatusCode::NOT_FOUND, "SSH CA not found".to_string()))?;
+    authorize_key(&identity, None, ca.key_id, Permission::ReadMetadata).await?;
+
+    let certificate = ssh_service::get_certificate(&pool, ca_id, serial)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get SSH certificate: {:?}", e)))?
+        .ok_or((StatusCode::NOT_FOUND, "Certificate not found".to_string()))?;
+
+    Ok(Json(certificate))
+}
+
+async fn get_ca(pool: &MySqlPool, ca_id: u64) -> Result<Option<SshCertificateAuthority>, (StatusCode, String)> {
+    ssh_service::get_ca(pool, ca_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get SSH CA: {:?}", e)))
+}
+
+fn encryption_key() -> Result<String, (StatusCode, String)> {
+    std::env::var("ENCRYPTION_KEY")
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Encryption key not configured".to_string()))
+}
+
+fn to_response<T>(result: CaOperation<T>) -> Result<T, (StatusCode, String)> {
+    match result {
+        CaOperation::Done(response) => Ok(response),
+        CaOperation::NotFound => Err((StatusCode::NOT_FOUND, "Not found".to_string())),
+        CaOperation::Invalid(reason) => Err((StatusCode::BAD_REQUEST, reason)),
+        CaOperation::Conflict(reason) => Err((StatusCode::CONFLICT, reason)),
+    }
+}
@@ -0,0 +1,311 @@
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+    Extension,
+};
+use serde_json::json;
+use sqlx::MySqlPool;
+use utoipa::OpenApi;
+
+use crate::api::Routes;
+use crate::api::access::require_role;
+use crate::model::access::{Identity, Role};
+use crate::model::team::{
+    AddMemberRequest, CallerTeamsResponse, CreateOrganizationRequest, CreateTeamRequest, Organization, Team,
+    TeamMember, TeamOperation,
+};
+use crate::service::team as team_service;
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .route("/organizations", post(handle_create_organization).get(handle_list_organizations))
+        .route("/organizations/{id}/teams", post(handle_create_team).get(handle_list_teams))
+        .route("/teams", get(handle_caller_teams))
+        .route("/teams/{id}", delete(handle_delete_team))
+        .route("/teams/{id}/members", post(handle_add_member).get(handle_list_members))
+        .route("/teams/{id}/members/{identity_id}", delete(handle_remove_member))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    handle_create_organization,
+    handle_list_organizations,
+    handle_create_team,
+    handle_list_teams,
+    handle_caller_teams,
+    handle_delete_team,
+    handle_add_member,
+    handle_list_members,
+    handle_remove_member
+))]
+pub struct ApiDoc;
+
+/// 取得请求的团队上下文，调用方不属于任何团队时拒绝
+pub fn require_team(identity: &Identity) -> Result<u64, (StatusCode, String)> {
+    identity.team_id.ok_or_else(|| {
+        tracing::warn!(identity_id = identity.id, "Access denied: no team context");
+        (StatusCode::FORBIDDEN, "No team context".to_string())
+    })
+}
+
+#[utoipa::path(
+    post,
+    path = "/organizations",
+    tag = "teams",
+    request_body = CreateOrganizationRequest,
+    responses(
+        (status = 201, description = "Organization created", body = Organization),
+        (status = 400, description = "Invalid name", body = String),
+        (status = 403, description = "Access denied", body = String),
+        (status = 409, description = "Organization name already exists", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_create_organization(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Json(request): Json<CreateOrganizationRequest>,
+) -> Result<(StatusCode, Json<Organization>), (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+
+    let result = team_service::create_organization(&pool, request)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to create organization: {:?}", e)))?;
+
+    to_response(result).map(|organization| (StatusCode::CREATED, Json(organization)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/organizations",
+    tag = "teams",
+    responses(
+        (status = 200, description = "Organizations", body = [Organization]),
+        (status = 403, description = "Access denied", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_list_organizations(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+) -> Result<Json<Vec<Organization>>, (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+
+    let organizations = team_service::list_organizations(&pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list organizations: {:?}", e)))?;
+
+    Ok(Json(organizations))
+}
+
+#[utoipa::path(
+    post,
+    path = "/organizations/{id}/teams",
+    tag = "teams",
+    params(
+        ("id" = u64, Path, description = "Organization ID")
+    ),
+    request_body = CreateTeamRequest,
+    responses(
+        (status = 201, description = "Team created", body = Team),
+        (status = 400, description = "Invalid name", body = String),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Organization not found", body = String),
+        (status = 409, description = "Team name already exists in the organization", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_create_team(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(id): Path<u64>,
+    Json(request): Json<CreateTeamRequest>,
+) -> Result<(StatusCode, Json<Team>), (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+
+    let result = team_service::create_team(&pool, id, request)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to create team: {:?}", e)))?;
+
+    to_response(result).map(|team| (StatusCode::CREATED, Json(team)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/organizations/{id}/teams",
+    tag = "teams",
+    params(
+        ("id" = u64, Path, description = "Organization ID")
+    ),
+    responses(
+        (status = 200, description = "Teams of the organization", body = [Team]),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Organization not found", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_list_teams(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(id): Path<u64>,
+) -> Result<Json<Vec<Team>>, (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+
+    let result = team_service::list_teams(&pool, id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list teams: {:?}", e)))?;
+
+    to_response(result).map(Json)
+}
+
+/// 调用方所属的团队，客户端据此切换X-Team-Id
+#[utoipa::path(
+    get,
+    path = "/teams",
+    tag = "teams",
+    responses(
+        (status = 200, description = "Teams of the caller and the team used for this request", body = CallerTeamsResponse)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_caller_teams(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+) -> Result<Json<CallerTeamsResponse>, (StatusCode, String)> {
+    let teams = team_service::caller_teams(&pool, &identity)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list teams: {:?}", e)))?;
+
+    Ok(Json(teams))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/teams/{id}",
+    tag = "teams",
+    params(
+        ("id" = u64, Path, description = "Team ID")
+    ),
+    responses(
+        (status = 200, description = "Team deleted", body = serde_json::Value),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Team not found", body = String),
+        (status = 409, description = "Team still owns keys", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_delete_team(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(id): Path<u64>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+
+    let result = team_service::delete_team(&pool, id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete team: {:?}", e)))?;
+    to_response(result)?;
+
+    Ok(Json(json!({ "message": "Team deleted successfully" })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/teams/{id}/members",
+    tag = "teams",
+    params(
+        ("id" = u64, Path, description = "Team ID")
+    ),
+    request_body = AddMemberRequest,
+    responses(
+        (status = 200, description = "Identity added; the team's members are returned", body = [TeamMember]),
+        (status = 400, description = "Identity does not exist", body = String),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Team not found", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_add_member(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(id): Path<u64>,
+    Json(request): Json<AddMemberRequest>,
+) -> Result<Json<Vec<TeamMember>>, (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+
+    let result = team_service::add_member(&pool, id, request)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to add team member: {:?}", e)))?;
+
+    to_response(result).map(Json)
+}
+
+#[utoipa::path(
+    get,
+    path = "/teams/{id}/members",
+    tag = "teams",
+    params(
+        ("id" = u64, Path, description = "Team ID")
+    ),
+    responses(
+        (status = 200, description = "Team members", body = [TeamMember]),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Team not found", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_list_members(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(id): Path<u64>,
+) -> Result<Json<Vec<TeamMember>>, (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+
+    let result = team_service::list_members(&pool, id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list team members: {:?}", e)))?;
+
+    to_response(result).map(Json)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/teams/{id}/members/{identity_id}",
+    tag = "teams",
+    params(
+        ("id" = u64, Path, description = "Team ID"),
+        ("identity_id" = u64, Path, description = "Identity ID")
+    ),
+    responses(
+        (status = 200, description = "Identity removed from the team", body = serde_json::Value),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Membership not found", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_remove_member(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path((id, identity_id)): Path<(u64, u64)>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+
+    let removed = team_service::remove_member(&pool, id, identity_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to remove team member: {:?}", e)))?;
+    if !removed {
+        return Err((StatusCode::NOT_FOUND, "Membership not found".to_string()));
+    }
+
+    Ok(Json(json!({ "message": "Team member removed successfully" })))
+}
+
+fn to_response<T>(result: TeamOperation<T>) -> Result<T, (StatusCode, String)> {
+    match result {
+        TeamOperation::Done(response) => Ok(response),
+        TeamOperation::NotFound => Err((StatusCode::NOT_FOUND, "Not found".to_string())),
+        TeamOperation::Invalid(reason) => Err((StatusCode::BAD_REQUEST, reason)),
+        TeamOperation::Conflict(reason) => Err((StatusCode::CONFLICT, reason)),
+    }
+}
@@ -0,0 +1,178 @@
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+    Extension,
+};
+use serde_json::json;
+use sqlx::MySqlPool;
+use utoipa::OpenApi;
+
+use crate::api::Routes;
+use crate::api::access::require_role;
+use crate::api::team::require_team;
+use crate::model::access::{Identity, Role};
+use crate::model::token::{
+    ApiTokenResponse, CreateServiceAccountRequest, CreateTokenRequest, CreateTokenResponse, ServiceAccount,
+};
+use crate::service::token as token_service;
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .route("/service-accounts", post(handle_create_service_account))
+        .route("/service-accounts", get(handle_list_service_accounts))
+        .route("/service-accounts/{id}/tokens", post(handle_create_token))
+        .route("/service-accounts/{id}/tokens", get(handle_list_tokens))
+        .route("/service-accounts/{id}/tokens/{token_id}", delete(handle_revoke_token))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    handle_create_service_account,
+    handle_list_service_accounts,
+    handle_create_token,
+    handle_list_tokens,
+    handle_revoke_token,
+))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    post,
+    path = "/service-accounts",
+    tag = "tokens",
+    request_body = CreateServiceAccountRequest,
+    responses(
+        (status = 201, description = "Service account created", body = ServiceAccount),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 403, description = "Access denied", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_create_service_account(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Json(request): Json<CreateServiceAccountRequest>,
+) -> Result<(StatusCode, Json<ServiceAccount>), (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+    let team_id = require_team(&identity)?;
+
+    let account = token_service::create_service_account(&pool, request, team_id)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Failed to create service account: {}", e)))?;
+
+    Ok((StatusCode::CREATED, Json(account)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/service-accounts",
+    tag = "tokens",
+    responses(
+        (status = 200, description = "Service accounts", body = Vec<ServiceAccount>),
+        (status = 403, description = "Access denied", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_list_service_accounts(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+) -> Result<Json<Vec<ServiceAccount>>, (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin, Role::Auditor])?;
+
+    let accounts = token_service::list_service_accounts(&pool)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list service accounts: {:?}", e)))?;
+
+    Ok(Json(accounts))
+}
+
+#[utoipa::path(
+    post,
+    path = "/service-accounts/{id}/tokens",
+    tag = "tokens",
+    params(
+        ("id" = u64, Path, description = "Service account ID")
+    ),
+    request_body = CreateTokenRequest,
+    responses(
+        (status = 201, description = "Token created; the token is returned only once", body = CreateTokenResponse),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Service account not found", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_create_token(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(id): Path<u64>,
+    Json(request): Json<CreateTokenRequest>,
+) -> Result<(StatusCode, Json<CreateTokenResponse>), (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+
+    let token = token_service::create_token(&pool, id, request)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Failed to create token: {}", e)))?
+        .ok_or((StatusCode::NOT_FOUND, "Service account not found".to_string()))?;
+
+    Ok((StatusCode::CREATED, Json(token)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/service-accounts/{id}/tokens",
+    tag = "tokens",
+    params(
+        ("id" = u64, Path, description = "Service account ID")
+    ),
+    responses(
+        (status = 200, description = "Tokens of the service account", body = Vec<ApiTokenResponse>),
+        (status = 403, description = "Access denied", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_list_tokens(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(id): Path<u64>,
+) -> Result<Json<Vec<ApiTokenResponse>>, (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin, Role::Auditor])?;
+
+    let tokens = token_service::list_tokens(&pool, id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list tokens: {:?}", e)))?;
+
+    Ok(Json(tokens))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/service-accounts/{id}/tokens/{token_id}",
+    tag = "tokens",
+    params(
+        ("id" = u64, Path, description = "Service account ID"),
+        ("token_id" = u64, Path, description = "Token ID")
+    ),
+    responses(
+        (status = 200, description = "Token revoked", body = serde_json::Value),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Active token not found", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_revoke_token(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path((id, token_id)): Path<(u64, u64)>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+
+    let revoked = token_service::revoke_token(&pool, id, token_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to revoke token: {:?}", e)))?;
+    if !revoked {
+        return Err((StatusCode::NOT_FOUND, "Active token not found".to_string()));
+    }
+
+    Ok(Json(json!({ "message": "Token revoked successfully" })))
+}
@@ -0,0 +1,148 @@
+use axum::{
+    extract::{Json, State},
+    http::StatusCode,
+    routing::post,
+    Extension,
+};
+use sqlx::MySqlPool;
+use utoipa::OpenApi;
+
+use crate::api::Routes;
+use crate::api::access::require_role;
+use crate::api::team::require_team;
+use crate::model::access::{Identity, Role};
+use crate::model::user::{
+    CreateUserRequest, LoginOutcome, LoginRequest, LoginResponse, RecoveryCodesResponse, TotpConfirmRequest,
+    TotpEnrollment, UserResponse,
+};
+use crate::service::user as user_service;
+use crate::utils::metrics::METRICS;
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .route("/users", post(handle_create_user))
+        .route("/auth/login", post(handle_login))
+        .route("/users/me/totp", post(handle_enroll_totp))
+        .route("/users/me/totp/confirm", post(handle_confirm_totp))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    handle_create_user,
+    handle_login,
+    handle_enroll_totp,
+    handle_confirm_totp,
+))]
+pub struct ApiDoc;
+
+fn encryption_key() -> Result<String, (StatusCode, String)> {
+    std::env::var("ENCRYPTION_KEY")
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Encryption key not configured".to_string()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/users",
+    tag = "users",
+    request_body = CreateUserRequest,
+    responses(
+        (status = 201, description = "User created", body = UserResponse),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 403, description = "Access denied", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_create_user(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Json(request): Json<CreateUserRequest>,
+) -> Result<(StatusCode, Json<UserResponse>), (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+    let team_id = require_team(&identity)?;
+
+    let user = user_service::create_user(&pool, request, team_id)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Failed to create user: {}", e)))?;
+
+    Ok((StatusCode::CREATED, Json(user)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/login",
+    tag = "users",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Logged in", body = LoginResponse),
+        (status = 401, description = "Invalid credentials or TOTP code required", body = String)
+    )
+)]
+async fn handle_login(
+    State(pool): State<MySqlPool>,
+    Json(request): Json<LoginRequest>,
+) -> Result<Json<LoginResponse>, (StatusCode, String)> {
+    let outcome = user_service::login(&pool, request, &encryption_key()?)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to log in: {:?}", e)))?;
+
+    match outcome {
+        LoginOutcome::Success(response) => Ok(Json(response)),
+        LoginOutcome::InvalidCredentials => {
+            METRICS.record_auth_failure("invalid_password");
+            Err((StatusCode::UNAUTHORIZED, "Invalid username or password".to_string()))
+        }
+        LoginOutcome::SecondFactorRequired => Err((StatusCode::UNAUTHORIZED, "TOTP code required".to_string())),
+        LoginOutcome::InvalidSecondFactor => {
+            METRICS.record_auth_failure("invalid_second_factor");
+            Err((StatusCode::UNAUTHORIZED, "Invalid TOTP or recovery code".to_string()))
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/me/totp",
+    tag = "users",
+    responses(
+        (status = 200, description = "TOTP secret to confirm", body = TotpEnrollment),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 409, description = "TOTP is already enabled", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_enroll_totp(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+) -> Result<Json<TotpEnrollment>, (StatusCode, String)> {
+    let enrollment = user_service::enroll_totp(&pool, &identity, &encryption_key()?)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Failed to enroll TOTP: {}", e)))?
+        .ok_or((StatusCode::CONFLICT, "TOTP is already enabled".to_string()))?;
+
+    Ok(Json(enrollment))
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/me/totp/confirm",
+    tag = "users",
+    request_body = TotpConfirmRequest,
+    responses(
+        (status = 200, description = "TOTP enabled; recovery codes are returned only once", body = RecoveryCodesResponse),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 422, description = "Invalid TOTP code", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_confirm_totp(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Json(request): Json<TotpConfirmRequest>,
+) -> Result<Json<RecoveryCodesResponse>, (StatusCode, String)> {
+    let codes = user_service::confirm_totp(&pool, &identity, &request.code, &encryption_key()?)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Failed to confirm TOTP: {}", e)))?
+        .ok_or((StatusCode::UNPROCESSABLE_ENTITY, "Invalid TOTP code".to_string()))?;
+
+    Ok(Json(codes))
+}
@@ -0,0 +1,175 @@
+use axum::{
+    extract::{Json, Path, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::from_fn,
+    routing::{get, post, put},
+    Extension,
+};
+use sqlx::MySqlPool;
+use utoipa::OpenApi;
+
+use crate::api::Routes;
+use crate::api::key::{etag, parse_if_match, precondition_failed};
+use crate::model::access::Identity;
+use crate::model::key::{ConditionalWrite, KeyResponse};
+use crate::model::vault::{CreateVaultKeyRequest, CreateVaultRequest, RewrapVaultRequest, VaultResponse};
+use crate::model::quota::QuotaExceeded;
+use crate::service::vault as vault_service;
+use crate::utils::middleware::idempotency_middleware;
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .route("/vaults", post(handle_create_vault))
+        .route("/vaults/{id}", get(handle_get_vault))
+        .route("/vaults/{id}/wrapped-key", put(handle_rewrap))
+        .route(
+            "/vaults/{id}/keys",
+            post(handle_create_vault_key).route_layer(from_fn(idempotency_middleware)),
+        )
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    handle_create_vault,
+    handle_get_vault,
+    handle_rewrap,
+    handle_create_vault_key,
+))]
+pub struct ApiDoc;
+
+/// 读取保险库并校验调用方为所有者
+async fn load_owned_vault(pool: &MySqlPool, identity: &Identity, id: u64) -> Result<VaultResponse, (StatusCode, String)> {
+    let vault = vault_service::get_vault(pool, id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get vault: {:?}", e)))?
+        .ok_or((StatusCode::NOT_FOUND, "Vault not found".to_string()))?;
+
+    if vault.owner_identity_id != identity.id {
+        tracing::warn!(identity_id = identity.id, vault_id = id, "Access denied: not vault owner");
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+    Ok(vault)
+}
+
+#[utoipa::path(
+    post,
+    path = "/vaults",
+    tag = "vaults",
+    request_body = CreateVaultRequest,
+    responses(
+        (status = 201, description = "Vault created", body = VaultResponse),
+        (status = 400, description = "Invalid request", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_create_vault(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Json(request): Json<CreateVaultRequest>,
+) -> Result<(StatusCode, Json<VaultResponse>), (StatusCode, String)> {
+    let vault = vault_service::create_vault(&pool, &identity, request)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Failed to create vault: {}", e)))?;
+
+    Ok((StatusCode::CREATED, Json(vault)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/vaults/{id}",
+    tag = "vaults",
+    params(
+        ("id" = u64, Path, description = "Vault ID")
+    ),
+    responses(
+        (status = 200, description = "Vault with its wrapped key", body = VaultResponse, headers(("ETag" = String, description = "Current resource version"))),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Vault not found", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_get_vault(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(id): Path<u64>,
+) -> Result<([(header::HeaderName, HeaderValue); 1], Json<VaultResponse>), (StatusCode, String)> {
+    let vault = load_owned_vault(&pool, &identity, id).await?;
+
+    Ok((etag(vault.version), Json(vault)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/vaults/{id}/wrapped-key",
+    tag = "vaults",
+    params(
+        ("id" = u64, Path, description = "Vault ID"),
+        ("If-Match" = Option<String>, Header, description = "Expected ETag; `*` or omitted skips the version check")
+    ),
+    request_body = RewrapVaultRequest,
+    responses(
+        (status = 200, description = "Vault key rewrapped", body = VaultResponse, headers(("ETag" = String, description = "Current resource version"))),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Vault not found", body = String),
+        (status = 412, description = "Version does not match If-Match", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_rewrap(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(id): Path<u64>,
+    headers: HeaderMap,
+    Json(request): Json<RewrapVaultRequest>,
+) -> Result<([(header::HeaderName, HeaderValue); 1], Json<VaultResponse>), (StatusCode, String)> {
+    let expected_version = parse_if_match(&headers)?;
+    load_owned_vault(&pool, &identity, id).await?;
+
+    let result = vault_service::rewrap(&pool, id, request, expected_version)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Failed to rewrap vault key: {}", e)))?;
+
+    match result {
+        ConditionalWrite::Done(vault) => Ok((etag(vault.version), Json(vault))),
+        ConditionalWrite::NotFound => Err((StatusCode::NOT_FOUND, "Vault not found".to_string())),
+        ConditionalWrite::VersionMismatch { current } => Err(precondition_failed(current)),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/vaults/{id}/keys",
+    tag = "vaults",
+    params(
+        ("id" = u64, Path, description = "Vault ID"),
+        ("Idempotency-Key" = Option<String>, Header, description = "Replays the stored response for a repeated key")
+    ),
+    request_body = CreateVaultKeyRequest,
+    responses(
+        (status = 201, description = "Client-encrypted key created", body = KeyResponse),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Vault not found", body = String),
+        (status = 429, description = "Quota exceeded", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_create_vault_key(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(id): Path<u64>,
+    Json(request): Json<CreateVaultKeyRequest>,
+) -> Result<(StatusCode, Json<KeyResponse>), (StatusCode, String)> {
+    load_owned_vault(&pool, &identity, id).await?;
+
+    // 创建者自动获得该密钥的全部权限
+    let key = vault_service::create_key(&pool, &identity, id, request)
+        .await
+        .map_err(|e| match e.downcast_ref::<QuotaExceeded>() {
+            Some(exceeded) => (StatusCode::TOO_MANY_REQUESTS, exceeded.to_string()),
+            None => (StatusCode::BAD_REQUEST, format!("Failed to create key: {}", e)),
+        })?;
+
+    Ok((StatusCode::CREATED, Json(key)))
+}
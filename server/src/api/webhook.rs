@@ -0,0 +1,178 @@
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+    Extension,
+};
+use serde_json::json;
+use sqlx::MySqlPool;
+use utoipa::OpenApi;
+
+use crate::api::Routes;
+use crate::api::access::require_role;
+use crate::api::team::require_team;
+use crate::model::access::{Identity, Role};
+use crate::model::webhook::{CreateWebhookRequest, CreateWebhookResponse, DeadLetter, WebhookResponse};
+use crate::service::webhook as webhook_service;
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .route("/webhooks", post(handle_create_webhook))
+        .route("/webhooks", get(handle_list_webhooks))
+        .route("/webhooks/{id}", delete(handle_delete_webhook))
+        .route("/webhooks/dead-letters", get(handle_list_dead_letters))
+        .route("/webhooks/dead-letters/{id}/retry", post(handle_retry_dead_letter))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    handle_create_webhook,
+    handle_list_webhooks,
+    handle_delete_webhook,
+    handle_list_dead_letters,
+    handle_retry_dead_letter,
+))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    post,
+    path = "/webhooks",
+    tag = "webhooks",
+    request_body = CreateWebhookRequest,
+    responses(
+        (status = 201, description = "Webhook created; the signing secret is returned only once", body = CreateWebhookResponse),
+        (status = 400, description = "Invalid request", body = String),
+        (status = 403, description = "Access denied", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_create_webhook(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Json(request): Json<CreateWebhookRequest>,
+) -> Result<(StatusCode, Json<CreateWebhookResponse>), (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+    let team_id = require_team(&identity)?;
+
+    let encryption_key = std::env::var("ENCRYPTION_KEY")
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Encryption key not configured".to_string()))?;
+
+    let webhook = webhook_service::create(&pool, &identity, team_id, request, &encryption_key)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Failed to create webhook: {:?}", e)))?;
+
+    Ok((StatusCode::CREATED, Json(webhook)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/webhooks",
+    tag = "webhooks",
+    responses(
+        (status = 200, description = "Webhooks", body = Vec<WebhookResponse>),
+        (status = 403, description = "Access denied", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_list_webhooks(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+) -> Result<Json<Vec<WebhookResponse>>, (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+    let team_id = require_team(&identity)?;
+
+    let webhooks = webhook_service::list(&pool, team_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list webhooks: {:?}", e)))?;
+
+    Ok(Json(webhooks))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/webhooks/{id}",
+    tag = "webhooks",
+    params(
+        ("id" = u64, Path, description = "Webhook ID")
+    ),
+    responses(
+        (status = 200, description = "Webhook deleted", body = serde_json::Value),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Webhook not found", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_delete_webhook(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(id): Path<u64>,
+) -> Result<(StatusCode, Json<serde_json::Value>), (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+    let team_id = require_team(&identity)?;
+
+    let deleted = webhook_service::delete(&pool, team_id, id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete webhook: {:?}", e)))?;
+    if !deleted {
+        return Err((StatusCode::NOT_FOUND, "Webhook not found".to_string()));
+    }
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({ "message": "Webhook deleted successfully" })),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/webhooks/dead-letters",
+    tag = "webhooks",
+    responses(
+        (status = 200, description = "Deliveries that exhausted their retries", body = Vec<DeadLetter>),
+        (status = 403, description = "Access denied", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_list_dead_letters(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+) -> Result<Json<Vec<DeadLetter>>, (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+    let team_id = require_team(&identity)?;
+
+    let dead_letters = webhook_service::list_dead_letters(&pool, team_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list dead letters: {:?}", e)))?;
+
+    Ok(Json(dead_letters))
+}
+
+#[utoipa::path(
+    post,
+    path = "/webhooks/dead-letters/{id}/retry",
+    tag = "webhooks",
+    params(
+        ("id" = u64, Path, description = "Dead letter ID")
+    ),
+    responses(
+        (status = 200, description = "Retry result", body = serde_json::Value),
+        (status = 403, description = "Access denied", body = String),
+        (status = 404, description = "Dead letter not found", body = String)
+    ),
+    security(("bearer" = []))
+)]
+async fn handle_retry_dead_letter(
+    State(pool): State<MySqlPool>,
+    Extension(identity): Extension<Identity>,
+    Path(id): Path<u64>,
+) -> Result<(StatusCode, Json<serde_json::Value>), (StatusCode, String)> {
+    require_role(&identity, &[Role::Admin])?;
+    let team_id = require_team(&identity)?;
+
+    let delivered = webhook_service::retry_dead_letter(&pool, team_id, id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to retry dead letter: {:?}", e)))?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Dead letter not found".to_string()))?;
+
+    Ok((StatusCode::OK, Json(json!({ "delivered": delivered }))))
+}
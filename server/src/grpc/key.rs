@@ -0,0 +1,172 @@
+use axum::http::StatusCode;
+use shared::secret::SecretPayload;
+use sqlx::MySqlPool;
+use tonic::{Request, Response, Status};
+
+use crate::api::key::precondition_failed;
+use crate::grpc::{self, Caller};
+use crate::model::access::Permission;
+use crate::model::key::{ConditionalWrite, CreateKeyRequest, KeyResponse};
+use crate::model::token::TokenOperation;
+use crate::service as key_service;
+use crate::utils::middleware::{authorize_key, service_error};
+
+pub mod proto {
+    tonic::include_proto!("ecipher.key.v1");
+}
+
+use proto::key_service_server::{KeyService, KeyServiceServer};
+
+pub fn service(pool: MySqlPool) -> KeyServiceServer<KeyGrpcService> {
+    KeyServiceServer::new(KeyGrpcService { pool })
+}
+
+/// 密钥gRPC服务，与api::key中的处理函数调用相同的service函数并返回对应的状态码
+pub struct KeyGrpcService {
+    pool: MySqlPool,
+}
+
+#[tonic::async_trait]
+impl KeyService for KeyGrpcService {
+    async fn create_key(&self, request: Request<proto::CreateKeyRequest>) -> Result<Response<proto::Key>, Status> {
+        let pool = self.pool.clone();
+        grpc::handle(request, "/ecipher.key.v1.KeyService/CreateKey", "key.create", None, |caller, request| async move {
+            create_key(&pool, caller, request).await
+        })
+        .await
+    }
+
+    async fn get_key(&self, request: Request<proto::GetKeyRequest>) -> Result<Response<proto::Key>, Status> {
+        let pool = self.pool.clone();
+        let key_id = request.get_ref().id;
+        grpc::handle(request, "/ecipher.key.v1.KeyService/GetKey", "key.read", Some(key_id), |caller, request| async move {
+            get_key(&pool, caller, request).await
+        })
+        .await
+    }
+
+    async fn delete_key(
+        &self,
+        request: Request<proto::DeleteKeyRequest>,
+    ) -> Result<Response<proto::DeleteKeyResponse>, Status> {
+        let pool = self.pool.clone();
+        let key_id = request.get_ref().id;
+        grpc::handle(request, "/ecipher.key.v1.KeyService/DeleteKey", "key.delete", Some(key_id), |caller, request| async move {
+            delete_key(&pool, caller, request).await
+        })
+        .await
+    }
+}
+
+async fn create_key(
+    pool: &MySqlPool,
+    caller: Caller,
+    request: proto::CreateKeyRequest,
+) -> Result<(StatusCode, proto::Key), (StatusCode, String)> {
+    let identity = caller.identity;
+    if !identity.role.can_create_keys() {
+        tracing::warn!(identity_id = identity.id, role = %identity.role, "Access denied: create key");
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+    if caller.grant.is_some_and(|grant| !grant.allows(&request.name, TokenOperation::Create)) {
+        tracing::warn!(identity_id = identity.id, "Access denied: create key outside token scope");
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+
+    let request = from_proto_request(request)?;
+    let encryption_key = std::env::var("ENCRYPTION_KEY")
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Encryption key not configured".to_string()))?;
+
+    // 创建者自动获得该密钥的全部权限
+    let key = key_service::create_key(pool, &identity, request, &encryption_key)
+        .await
+        .map_err(service_error("Failed to create key"))?;
+
+    Ok((StatusCode::CREATED, to_proto_key(key)))
+}
+
+async fn get_key(
+    pool: &MySqlPool,
+    caller: Caller,
+    request: proto::GetKeyRequest,
+) -> Result<(StatusCode, proto::Key), (StatusCode, String)> {
+    authorize_key(&caller.identity, caller.grant.as_ref(), request.id, Permission::ReadMetadata).await?;
+
+    let key = key_service::get_key(pool, &caller.identity, request.id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get key: {:?}", e)))?
+        .ok_or((StatusCode::NOT_FOUND, "Key not found".to_string()))?;
+
+    Ok((StatusCode::OK, to_proto_key(key)))
+}
+
+async fn delete_key(
+    pool: &MySqlPool,
+    caller: Caller,
+    request: proto::DeleteKeyRequest,
+) -> Result<(StatusCode, proto::DeleteKeyResponse), (StatusCode, String)> {
+    authorize_key(&caller.identity, caller.grant.as_ref(), request.id, Permission::Delete).await?;
+
+    let result = key_service::delete_key(pool, &caller.identity, request.id, request.expected_version)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete key: {:?}", e)))?;
+
+    match result {
+        ConditionalWrite::Done(()) => Ok((StatusCode::OK, proto::DeleteKeyResponse {})),
+        ConditionalWrite::NotFound => Err((StatusCode::NOT_FOUND, "Key not found".to_string())),
+        ConditionalWrite::VersionMismatch { current } => Err(precondition_failed(current)),
+    }
+}
+
+// protobuf请求转换为REST接口使用的请求结构，JSON字段格式错误时返回400
+fn from_proto_request(request: proto::CreateKeyRequest) -> Result<CreateKeyRequest, (StatusCode, String)> {
+    let payload = request
+        .payload_json
+        .map(|json| serde_json::from_str::<SecretPayload>(&json))
+        .transpose()
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid payload_json: {}", e)))?;
+    let metadata = request
+        .metadata_json
+        .map(|json| serde_json::from_str::<serde_json::Value>(&json))
+        .transpose()
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid metadata_json: {}", e)))?;
+    let expires_at = request
+        .expires_at
+        .map(|timestamp| {
+            chrono::DateTime::from_timestamp(timestamp.seconds, timestamp.nanos.try_into().unwrap_or(0))
+                .ok_or((StatusCode::BAD_REQUEST, "Invalid expires_at".to_string()))
+        })
+        .transpose()?;
+
+    Ok(CreateKeyRequest {
+        name: request.name,
+        data: request.data,
+        payload,
+        expires_at,
+        description: request.description,
+        labels: request.labels.into_iter().collect(),
+        metadata,
+    })
+}
+
+fn to_proto_key(key: KeyResponse) -> proto::Key {
+    proto::Key {
+        id: key.id,
+        name: key.name,
+        expires_at: key.expires_at.map(to_timestamp),
+        description: key.description,
+        labels: key.labels.into_iter().collect(),
+        metadata_json: key.metadata.map(|metadata| metadata.to_string()),
+        version: key.version,
+        kind: key.kind.map(|kind| kind.to_string()),
+        vault_id: key.vault_id,
+        created_at: Some(to_timestamp(key.created_at)),
+    }
+}
+
+fn to_timestamp(time: chrono::DateTime<chrono::Utc>) -> prost_types::Timestamp {
+    prost_types::Timestamp {
+        seconds: time.timestamp(),
+        nanos: time.timestamp_subsec_nanos() as i32,
+    }
+}
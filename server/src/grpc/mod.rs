@@ -0,0 +1,156 @@
+pub mod key;
+
+use axum::http::StatusCode;
+use sqlx::MySqlPool;
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Instant;
+use tonic::{Code, Request, Response, Status};
+
+use crate::config::database;
+use crate::model::access::Identity;
+use crate::model::audit::NewAuditEvent;
+use crate::model::team::TEAM_HEADER;
+use crate::model::token::TokenGrant;
+use crate::service::audit as audit_service;
+use crate::utils::metrics::METRICS;
+use crate::utils::middleware::{authenticate_credential, generate_request_id, record_enumeration_failure};
+use crate::utils::rate_limit::{LOCKOUTS, RATE_LIMITER};
+
+// 默认gRPC端口
+const DEFAULT_GRPC_PORT: u16 = 50051;
+
+/// 已认证的gRPC调用方
+pub struct Caller {
+    pub identity: Identity,
+    pub grant: Option<TokenGrant>,
+}
+
+/// 在独立端口上启动gRPC服务，端口由GRPC_PORT配置
+pub fn spawn_server(pool: &'static MySqlPool) {
+    let port = std::env::var("GRPC_PORT")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_GRPC_PORT);
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+
+    tokio::spawn(async move {
+        tracing::info!("gRPC server running on {}", addr);
+        let result = tonic::transport::Server::builder()
+            .add_service(key::service(pool.clone()))
+            .serve(addr)
+            .await;
+        if let Err(e) = result {
+            tracing::error!(error = %e, "gRPC server stopped");
+        }
+    });
+}
+
+/// 将HTTP处理函数使用的状态码转换为对应的gRPC状态码
+pub fn to_status((status, message): (StatusCode, String)) -> Status {
+    let code = match status {
+        StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => Code::InvalidArgument,
+        StatusCode::UNAUTHORIZED => Code::Unauthenticated,
+        StatusCode::FORBIDDEN => Code::PermissionDenied,
+        StatusCode::NOT_FOUND => Code::NotFound,
+        StatusCode::CONFLICT => Code::Aborted,
+        StatusCode::PRECONDITION_FAILED => Code::FailedPrecondition,
+        StatusCode::TOO_MANY_REQUESTS => Code::ResourceExhausted,
+        StatusCode::SERVICE_UNAVAILABLE => Code::Unavailable,
+        _ => Code::Internal,
+    };
+    Status::new(code, message)
+}
+
+/// 执行一次gRPC调用：与REST中间件相同地锁定检查、限流、认证，并记录指标和审计日志
+///
+/// `route`为完整的gRPC方法名，`key_id`非空时按/keys/{id}的规则统计枚举失败
+pub async fn handle<T, R, F, Fut>(
+    request: Request<T>,
+    route: &'static str,
+    action: &'static str,
+    key_id: Option<u64>,
+    handler: F,
+) -> Result<Response<R>, Status>
+where
+    F: FnOnce(Caller, T) -> Fut,
+    Fut: Future<Output = Result<(StatusCode, R), (StatusCode, String)>>,
+{
+    let source_ip = request.remote_addr().map(|addr| addr.ip());
+    let request_id = request
+        .metadata()
+        .get("x-request-id")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+        .unwrap_or_else(generate_request_id);
+    let started = Instant::now();
+
+    let mut identity = None;
+    let result = match authenticate(&request, source_ip, route).await {
+        Ok(caller) => {
+            identity = Some(caller.identity.clone());
+            handler(caller, request.into_inner()).await
+        }
+        Err(e) => Err(e),
+    };
+
+    let status = match &result {
+        Ok((status, _)) | Err((status, _)) => *status,
+    };
+    METRICS.observe_request("GRPC", route, status, started.elapsed());
+
+    if let Some(ip) = source_ip {
+        if key_id.is_some() && matches!(status, StatusCode::UNAUTHORIZED | StatusCode::NOT_FOUND) {
+            record_enumeration_failure(ip, &request_id).await;
+        }
+    }
+
+    let event = NewAuditEvent {
+        actor_id: identity.as_ref().map(|identity| identity.id),
+        actor_name: identity.map(|identity| identity.name),
+        action: action.to_string(),
+        key_id,
+        outcome: audit_service::outcome_for(status.as_u16()).to_string(),
+        status_code: status.as_u16(),
+        source_ip: source_ip.map(|ip| ip.to_string()),
+        request_id: request_id.clone(),
+    };
+    if let Err(e) = audit_service::record(database::get_pool(), event).await {
+        tracing::error!(error = %e, request_id = %request_id, "Failed to record audit event");
+    }
+
+    result.map(|(_, body)| Response::new(body)).map_err(to_status)
+}
+
+// 锁定检查、限流，并校验metadata中的Bearer凭据
+async fn authenticate<T>(
+    request: &Request<T>,
+    source_ip: Option<IpAddr>,
+    route: &str,
+) -> Result<Caller, (StatusCode, String)> {
+    if let Some(ip) = source_ip {
+        if let Err(retry_after) = LOCKOUTS.check(ip) {
+            tracing::warn!(%ip, retry_after_secs = retry_after.as_secs(), "Request rejected: client locked out");
+            return Err((StatusCode::TOO_MANY_REQUESTS, "Client temporarily locked out".to_string()));
+        }
+        if RATE_LIMITER.check(ip, route).is_err() {
+            tracing::warn!(%ip, route, "Rate limit exceeded");
+            return Err((StatusCode::TOO_MANY_REQUESTS, "Too many requests".to_string()));
+        }
+    }
+
+    let credential = request
+        .metadata()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or_else(|| {
+            METRICS.record_auth_failure("missing_credential");
+            (StatusCode::UNAUTHORIZED, "Missing bearer credential".to_string())
+        })?;
+
+    let source_ip = source_ip.map(|ip| ip.to_string());
+    let team = request.metadata().get(TEAM_HEADER).and_then(|value| value.to_str().ok());
+    let (identity, grant) = authenticate_credential(credential, source_ip.as_deref(), team).await?;
+    Ok(Caller { identity, grant })
+}
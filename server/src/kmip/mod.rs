@@ -0,0 +1,400 @@
+pub mod operation;
+
+use axum::http::StatusCode;
+use shared::kmip::tag;
+use shared::kmip::ttlv::{Ttlv, HEADER_SIZE};
+use shared::kmip::{
+    Operation, ProtocolVersion, ResultReason, CREDENTIAL_TYPE_USERNAME_AND_PASSWORD, DEFAULT_PORT,
+    RESULT_STATUS_OPERATION_FAILED, RESULT_STATUS_SUCCESS,
+};
+use sqlx::MySqlPool;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio_rustls::rustls::server::WebPkiClientVerifier;
+use tokio_rustls::rustls::{RootCertStore, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+
+use crate::config::database;
+use crate::grpc::Caller;
+use crate::model::audit::NewAuditEvent;
+use crate::service::audit as audit_service;
+use crate::utils::metrics::METRICS;
+use crate::utils::middleware::{authenticate_credential, generate_request_id, record_enumeration_failure};
+use crate::utils::rate_limit::{LOCKOUTS, RATE_LIMITER};
+
+// 单条KMIP消息的最大长度
+const MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+// 单条消息允许的最大批量条目数
+const MAX_BATCH_ITEMS: usize = 64;
+// 限流时使用的路由名
+const RATE_LIMIT_ROUTE: &str = "kmip";
+
+/// 在独立端口上启动KMIP（TTLV over TLS）监听，端口由KMIP_PORT配置
+///
+/// 服务端证书由KMIP_TLS_CERT和KMIP_TLS_KEY给出，未配置时不启动；配置KMIP_CLIENT_CA时要求客户端证书。
+pub fn spawn_server(pool: &'static MySqlPool) {
+    let config = match tls_config() {
+        Ok(Some(config)) => config,
+        Ok(None) => {
+            tracing::info!("KMIP listener disabled: KMIP_TLS_CERT and KMIP_TLS_KEY are not configured");
+            return;
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to load KMIP TLS configuration");
+            return;
+        }
+    };
+    let port = std::env::var("KMIP_PORT")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_PORT);
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    let acceptor = TlsAcceptor::from(Arc::new(config));
+
+    tokio::spawn(async move {
+        let listener = match TcpListener::bind(addr).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                tracing::error!(error = %e, %addr, "Failed to bind KMIP listener");
+                return;
+            }
+        };
+        tracing::info!("KMIP server running on {}", addr);
+
+        loop {
+            let (stream, peer) = match listener.accept().await {
+                Ok(connection) => connection,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to accept KMIP connection");
+                    continue;
+                }
+            };
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                match acceptor.accept(stream).await {
+                    Ok(stream) => {
+                        if let Err(e) = serve_connection(pool, stream, peer.ip()).await {
+                            tracing::debug!(%peer, error = %e, "KMIP connection closed");
+                        }
+                    }
+                    Err(e) => tracing::warn!(%peer, error = %e, "KMIP TLS handshake failed"),
+                }
+            });
+        }
+    });
+}
+
+// 读取证书配置，未配置服务端证书时返回None
+fn tls_config() -> Result<Option<ServerConfig>, Box<dyn Error>> {
+    let setting = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+    let (Some(cert_path), Some(key_path)) = (setting("KMIP_TLS_CERT"), setting("KMIP_TLS_KEY")) else {
+        return Ok(None);
+    };
+
+    let certs = rustls_pemfile::certs(&mut BufReader::new(File::open(cert_path)?)).collect::<Result<Vec<_>, _>>()?;
+    let key = rustls_pemfile::private_key(&mut BufReader::new(File::open(key_path)?))?
+        .ok_or("KMIP_TLS_KEY contains no private key")?;
+
+    let builder = ServerConfig::builder();
+    let config = match setting("KMIP_CLIENT_CA") {
+        Some(ca_path) => {
+            let mut roots = RootCertStore::empty();
+            for cert in rustls_pemfile::certs(&mut BufReader::new(File::open(ca_path)?)) {
+                roots.add(cert?)?;
+            }
+            builder
+                .with_client_cert_verifier(WebPkiClientVerifier::builder(Arc::new(roots)).build()?)
+                .with_single_cert(certs, key)?
+        }
+        None => builder.with_no_client_auth().with_single_cert(certs, key)?,
+    };
+    Ok(Some(config))
+}
+
+// 在同一连接上依次处理请求消息，直到客户端关闭连接
+async fn serve_connection<S>(pool: &MySqlPool, mut stream: S, source_ip: IpAddr) -> io::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    loop {
+        let mut header = [0u8; HEADER_SIZE];
+        match stream.read_exact(&mut header).await {
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(e) => return Err(e),
+        }
+        let length = Ttlv::message_length(&header);
+        if length > MAX_MESSAGE_SIZE {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "KMIP message too large"));
+        }
+
+        let mut message = header.to_vec();
+        message.resize(length, 0);
+        stream.read_exact(&mut message[HEADER_SIZE..]).await?;
+
+        let response = process(pool, &message, source_ip).await;
+        stream.write_all(&response.to_bytes()).await?;
+        stream.flush().await?;
+    }
+}
+
+/// 处理一条请求消息并返回响应消息，消息内的批量条目依次执行
+pub async fn process(pool: &MySqlPool, message: &[u8], source_ip: IpAddr) -> Ttlv {
+    let request = match Ttlv::decode(message) {
+        Ok((request, _)) if request.tag == tag::REQUEST_MESSAGE => request,
+        _ => return failure_message(ProtocolVersion::V1_4, "Malformed request message"),
+    };
+    let header = request.child(tag::REQUEST_HEADER);
+    let version = header
+        .and_then(|header| header.child(tag::PROTOCOL_VERSION))
+        .and_then(|version| ProtocolVersion::from_ttlv(version).ok())
+        .unwrap_or(ProtocolVersion::V1_4);
+
+    let items: Vec<&Ttlv> = request.children(tag::BATCH_ITEM).collect();
+    if header.is_none() || items.is_empty() || items.len() > MAX_BATCH_ITEMS {
+        return failure_message(version, "Request must have a header and between 1 and 64 batch items");
+    }
+
+    // 整条消息只认证一次，认证失败时每个条目都返回该错误
+    let request_id = generate_request_id();
+    let caller = authenticate(header, source_ip).await;
+
+    let mut batch = Vec::with_capacity(items.len());
+    for item in items {
+        batch.push(process_item(pool, item, &caller, version, source_ip, &request_id).await);
+    }
+    response_message(version, batch)
+}
+
+// 执行单个批量条目，并与gRPC一样记录指标、枚举失败和审计日志
+async fn process_item(
+    pool: &MySqlPool,
+    item: &Ttlv,
+    caller: &Result<Caller, (StatusCode, String)>,
+    version: ProtocolVersion,
+    source_ip: IpAddr,
+    request_id: &str,
+) -> Ttlv {
+    let started = Instant::now();
+    let operation_code = item.child(tag::OPERATION).and_then(|operation| operation.as_enumeration().ok());
+    let operation = operation_code.and_then(Operation::from_code);
+    let payload = item.child(tag::REQUEST_PAYLOAD);
+    let key_id = payload.and_then(operation::target);
+
+    let result = match (caller, operation, payload) {
+        (Err(e), _, _) => Err(e.clone()),
+        (_, None, _) => Err((StatusCode::NOT_IMPLEMENTED, "Operation not supported".to_string())),
+        (_, _, None) => Err((StatusCode::BAD_REQUEST, "Missing request payload".to_string())),
+        (Ok(caller), Some(operation), Some(payload)) => operation::execute(pool, caller, operation, payload, version).await,
+    };
+
+    let status = match &result {
+        Ok((status, _)) | Err((status, _)) => *status,
+    };
+    let operation_name = operation.map(Operation::as_str).unwrap_or("Unknown");
+    METRICS.observe_request("KMIP", operation_name, status, started.elapsed());
+
+    if key_id.is_some() && matches!(status, StatusCode::UNAUTHORIZED | StatusCode::NOT_FOUND) {
+        record_enumeration_failure(source_ip, request_id).await;
+    }
+
+    let identity = caller.as_ref().ok().map(|caller| &caller.identity);
+    let event = NewAuditEvent {
+        actor_id: identity.map(|identity| identity.id),
+        actor_name: identity.map(|identity| identity.name.clone()),
+        action: operation.map(action).unwrap_or("kmip.unknown").to_string(),
+        key_id,
+        outcome: audit_service::outcome_for(status.as_u16()).to_string(),
+        status_code: status.as_u16(),
+        source_ip: Some(source_ip.to_string()),
+        request_id: request_id.to_string(),
+    };
+    if let Err(e) = audit_service::record(database::get_pool(), event).await {
+        tracing::error!(error = %e, request_id = %request_id, "Failed to record audit event");
+    }
+
+    let mut fields = Vec::new();
+    if let Some(code) = operation_code {
+        fields.push(Ttlv::enumeration(tag::OPERATION, code));
+    }
+    if let Some(batch_item_id) = item.child(tag::UNIQUE_BATCH_ITEM_ID) {
+        fields.push(batch_item_id.clone());
+    }
+    match result {
+        Ok((_, payload)) => {
+            fields.push(Ttlv::enumeration(tag::RESULT_STATUS, RESULT_STATUS_SUCCESS));
+            fields.push(Ttlv::structure(tag::RESPONSE_PAYLOAD, payload));
+        }
+        Err((status, message)) => {
+            fields.push(Ttlv::enumeration(tag::RESULT_STATUS, RESULT_STATUS_OPERATION_FAILED));
+            fields.push(Ttlv::enumeration(tag::RESULT_REASON, to_reason(status).code()));
+            fields.push(Ttlv::text(tag::RESULT_MESSAGE, message));
+        }
+    }
+    Ttlv::structure(tag::BATCH_ITEM, fields)
+}
+
+// 锁定检查、限流，并校验请求头中的用户名口令凭据；口令为ecipher凭据或API令牌
+async fn authenticate(header: Option<&Ttlv>, source_ip: IpAddr) -> Result<Caller, (StatusCode, String)> {
+    if let Err(retry_after) = LOCKOUTS.check(source_ip) {
+        tracing::warn!(ip = %source_ip, retry_after_secs = retry_after.as_secs(), "Request rejected: client locked out");
+        return Err((StatusCode::TOO_MANY_REQUESTS, "Client temporarily locked out".to_string()));
+    }
+    if RATE_LIMITER.check(source_ip, RATE_LIMIT_ROUTE).is_err() {
+        tracing::warn!(ip = %source_ip, route = RATE_LIMIT_ROUTE, "Rate limit exceeded");
+        return Err((StatusCode::TOO_MANY_REQUESTS, "Too many requests".to_string()));
+    }
+
+    let credential = header
+        .and_then(|header| header.child(tag::AUTHENTICATION))
+        .and_then(|authentication| authentication.child(tag::CREDENTIAL))
+        .filter(|credential| {
+            credential
+                .child(tag::CREDENTIAL_TYPE)
+                .and_then(|credential_type| credential_type.as_enumeration().ok())
+                == Some(CREDENTIAL_TYPE_USERNAME_AND_PASSWORD)
+        })
+        .and_then(|credential| credential.child(tag::CREDENTIAL_VALUE))
+        .and_then(|value| value.child(tag::PASSWORD))
+        .and_then(|password| password.as_text().ok())
+        .ok_or_else(|| {
+            METRICS.record_auth_failure("missing_credential");
+            (StatusCode::UNAUTHORIZED, "Missing username and password credential".to_string())
+        })?;
+
+    let source_ip = source_ip.to_string();
+    // KMIP请求头没有团队字段，使用调用方的默认团队
+    let (identity, grant) = authenticate_credential(credential, Some(&source_ip), None).await?;
+    Ok(Caller { identity, grant })
+}
+
+/// 将处理函数使用的HTTP状态码转换为KMIP结果原因
+pub fn to_reason(status: StatusCode) -> ResultReason {
+    match status {
+        StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => ResultReason::InvalidField,
+        StatusCode::UNAUTHORIZED => ResultReason::AuthenticationNotSuccessful,
+        StatusCode::FORBIDDEN => ResultReason::PermissionDenied,
+        StatusCode::NOT_FOUND => ResultReason::ItemNotFound,
+        StatusCode::CONFLICT => ResultReason::IllegalOperation,
+        StatusCode::NOT_IMPLEMENTED => ResultReason::OperationNotSupported,
+        _ => ResultReason::GeneralFailure,
+    }
+}
+
+// 审计日志中的操作名
+fn action(operation: Operation) -> &'static str {
+    match operation {
+        Operation::Create => "kmip.create",
+        Operation::Register => "kmip.register",
+        Operation::Locate => "kmip.locate",
+        Operation::Get => "kmip.get",
+        Operation::GetAttributes => "kmip.get_attributes",
+        Operation::Activate => "kmip.activate",
+        Operation::Revoke => "kmip.revoke",
+        Operation::Destroy => "kmip.destroy",
+    }
+}
+
+fn response_message(version: ProtocolVersion, batch: Vec<Ttlv>) -> Ttlv {
+    let header = Ttlv::structure(
+        tag::RESPONSE_HEADER,
+        vec![
+            version.to_ttlv(),
+            Ttlv::date_time(tag::TIME_STAMP, chrono::Utc::now().timestamp()),
+            Ttlv::integer(tag::BATCH_COUNT, batch.len() as i32),
+        ],
+    );
+    let mut items = vec![header];
+    items.extend(batch);
+    Ttlv::structure(tag::RESPONSE_MESSAGE, items)
+}
+
+// 无法解析请求时返回只含一个失败条目的响应
+fn failure_message(version: ProtocolVersion, message: &str) -> Ttlv {
+    let item = Ttlv::structure(
+        tag::BATCH_ITEM,
+        vec![
+            Ttlv::enumeration(tag::RESULT_STATUS, RESULT_STATUS_OPERATION_FAILED),
+            Ttlv::enumeration(tag::RESULT_REASON, ResultReason::InvalidMessage.code()),
+            Ttlv::text(tag::RESULT_MESSAGE, message),
+        ],
+    );
+    response_message(version, vec![item])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::kmip::request_message;
+    use sqlx::mysql::MySqlPoolOptions;
+    use std::net::Ipv4Addr;
+    use std::time::Duration;
+
+    const SOURCE_IP: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+    // 连接池不会主动连接数据库，这里测试的请求都在认证之前被拒绝
+    fn pool() -> MySqlPool {
+        MySqlPoolOptions::new()
+            .acquire_timeout(Duration::from_millis(100))
+            .connect_lazy("mysql://127.0.0.1:1/ecipher")
+            .expect("database URL is valid")
+    }
+
+    fn get_item() -> (Operation, Vec<Ttlv>) {
+        (Operation::Get, vec![Ttlv::text(tag::UNIQUE_IDENTIFIER, "1")])
+    }
+
+    // 响应中唯一的批量条目必须是InvalidMessage失败
+    fn assert_invalid_message(response: &Ttlv) {
+        assert_eq!(response.tag, tag::RESPONSE_MESSAGE);
+        let items: Vec<&Ttlv> = response.children(tag::BATCH_ITEM).collect();
+        assert_eq!(items.len(), 1);
+        let status = items[0].require(tag::RESULT_STATUS).and_then(Ttlv::as_enumeration);
+        assert_eq!(status, Ok(RESULT_STATUS_OPERATION_FAILED));
+        let reason = items[0].require(tag::RESULT_REASON).and_then(Ttlv::as_enumeration);
+        assert_eq!(reason, Ok(ResultReason::InvalidMessage.code()));
+    }
+
+    #[tokio::test]
+    async fn rejects_messages_over_the_size_limit() {
+        let pool = pool();
+        let (mut client, server) = tokio::io::duplex(HEADER_SIZE);
+
+        // 只发送条目头，声明的长度比上限多一个字节
+        let mut header = Ttlv::structure(tag::REQUEST_MESSAGE, Vec::new()).to_bytes();
+        header[4..8].copy_from_slice(&((MAX_MESSAGE_SIZE - HEADER_SIZE + 1) as u32).to_be_bytes());
+        client.write_all(&header).await.unwrap();
+
+        let error = serve_connection(&pool, server, SOURCE_IP).await.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[tokio::test]
+    async fn rejects_more_than_the_batch_limit() {
+        let pool = pool();
+        let batch = std::iter::repeat_with(get_item).take(MAX_BATCH_ITEMS + 1).collect();
+        let message = request_message(ProtocolVersion::V1_4, None, batch).to_bytes();
+
+        assert_invalid_message(&process(&pool, &message, SOURCE_IP).await);
+    }
+
+    #[tokio::test]
+    async fn rejects_malformed_messages() {
+        let pool = pool();
+
+        let message = request_message(ProtocolVersion::V1_4, None, vec![get_item()]).to_bytes();
+        assert_invalid_message(&process(&pool, &message[..message.len() - 8], SOURCE_IP).await);
+
+        let empty = request_message(ProtocolVersion::V1_4, None, Vec::new()).to_bytes();
+        assert_invalid_message(&process(&pool, &empty, SOURCE_IP).await);
+
+        let response = Ttlv::structure(tag::RESPONSE_MESSAGE, Vec::new()).to_bytes();
+        assert_invalid_message(&process(&pool, &response, SOURCE_IP).await);
+    }
+}
@@ -0,0 +1,726 @@
+use axum::http::StatusCode;
+use shared::kmip::tag::{self, Tag};
+use shared::kmip::ttlv::{Ttlv, TtlvError};
+use shared::kmip::{
+    encode_attributes, key_material, parse_attributes, symmetric_key, Attribute, Operation, ProtocolVersion, State,
+    ALGORITHM_AES, KEY_FORMAT_RAW, KEY_FORMAT_TRANSPARENT_SYMMETRIC_KEY, OBJECT_TYPE_SYMMETRIC_KEY,
+};
+use sqlx::MySqlPool;
+
+use crate::grpc::Caller;
+use crate::model::access::{Identity, Permission};
+use crate::model::kmip::{CreateObject, KmipObjectRecord, LocateFilter, NewKmipObject, StateChange};
+use crate::model::token::TokenOperation;
+use crate::service::kmip as kmip_service;
+use crate::utils::middleware::{authorize_key, service_error};
+
+type OperationResult = Result<(StatusCode, Vec<Ttlv>), (StatusCode, String)>;
+type StoreResult<T> = Result<T, (StatusCode, String)>;
+
+/// 操作处理依赖的权限检查和对象存储，服务端由数据库实现，测试使用内存实现
+pub trait ObjectStore {
+    async fn authorize(&self, caller: &Caller, key_id: u64, permission: Permission) -> StoreResult<()>;
+    async fn create_object(&self, owner: &Identity, object: NewKmipObject) -> StoreResult<CreateObject>;
+    async fn get_object(&self, key_id: u64) -> StoreResult<Option<KmipObjectRecord>>;
+    async fn get_material(&self, key_id: u64) -> StoreResult<Option<Vec<u8>>>;
+    async fn activate(&self, key_id: u64) -> StoreResult<StateChange>;
+    async fn revoke(
+        &self,
+        key_id: u64,
+        reason: u32,
+        message: Option<&str>,
+        compromise_occurrence_date: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> StoreResult<StateChange>;
+    async fn destroy(&self, key_id: u64) -> StoreResult<StateChange>;
+    async fn locate(&self, identity: &Identity, filter: LocateFilter) -> StoreResult<Vec<u64>>;
+}
+
+impl ObjectStore for MySqlPool {
+    async fn authorize(&self, caller: &Caller, key_id: u64, permission: Permission) -> StoreResult<()> {
+        store.authorize(caller, key_id, permission).await
+    }
+
+    async fn create_object(&self, owner: &Identity, object: NewKmipObject) -> StoreResult<CreateObject> {
+        let encryption_key = encryption_key()?;
+        kmip_service::create_object(self, owner, object, &encryption_key)
+            .await
+            .map_err(service_error("Failed to create object"))
+    }
+
+    async fn get_object(&self, key_id: u64) -> StoreResult<Option<KmipObjectRecord>> {
+        kmip_service::get_object(self, key_id)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get object: {:?}", e)))
+    }
+
+    async fn get_material(&self, key_id: u64) -> StoreResult<Option<Vec<u8>>> {
+        let encryption_key = encryption_key()?;
+        kmip_service::get_material(self, key_id, &encryption_key)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get key material: {:?}", e)))
+    }
+
+    async fn activate(&self, key_id: u64) -> StoreResult<StateChange> {
+        kmip_service::activate(self, key_id)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to activate object: {:?}", e)))
+    }
+
+    async fn revoke(
+        &self,
+        key_id: u64,
+        reason: u32,
+        message: Option<&str>,
+        compromise_occurrence_date: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> StoreResult<StateChange> {
+        kmip_service::revoke(self, key_id, reason, message, compromise_occurrence_date)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to revoke object: {:?}", e)))
+    }
+
+    async fn destroy(&self, key_id: u64) -> StoreResult<StateChange> {
+        kmip_service::destroy(self, key_id)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to destroy object: {:?}", e)))
+    }
+
+    async fn locate(&self, identity: &Identity, filter: LocateFilter) -> StoreResult<Vec<u64>> {
+        kmip_service::locate(self, identity, filter)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to locate objects: {:?}", e)))
+    }
+}
+
+/// 执行单个批量条目，返回值与REST处理函数一样携带HTTP状态码，由调用方转换为KMIP结果
+pub async fn execute<S: ObjectStore>(
+    store: &S,
+    caller: &Caller,
+    operation: Operation,
+    payload: &Ttlv,
+    version: ProtocolVersion,
+) -> OperationResult {
+    match operation {
+        Operation::Create => create(store, caller, payload).await,
+        Operation::Register => register(store, caller, payload).await,
+        Operation::Locate => locate(store, caller, payload, version).await,
+        Operation::Get => get(store, caller, payload).await,
+        Operation::GetAttributes => get_attributes(store, caller, payload, version).await,
+        Operation::Activate => activate(store, caller, payload).await,
+        Operation::Revoke => revoke(store, caller, payload).await,
+        Operation::Destroy => destroy(store, caller, payload).await,
+    }
+}
+
+/// 请求载荷中的目标对象ID，用于审计和枚举失败统计
+pub fn target(payload: &Ttlv) -> Option<u64> {
+    payload.child(tag::UNIQUE_IDENTIFIER)?.as_text().ok()?.parse().ok()
+}
+
+async fn create<S: ObjectStore>(store: &S, caller: &Caller, payload: &Ttlv) -> OperationResult {
+    require_symmetric_key(payload)?;
+    let attributes = parse_attributes(payload).map_err(invalid)?;
+
+    let length = attribute_length(&attributes)?;
+    let mut material = vec![0u8; (length / 8) as usize];
+    getrandom::getrandom(&mut material)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to generate key material: {:?}", e)))?;
+
+    let object = new_object(&attributes, length, material)?;
+    create_object(store, caller, object).await
+}
+
+async fn register<S: ObjectStore>(store: &S, caller: &Caller, payload: &Ttlv) -> OperationResult {
+    require_symmetric_key(payload)?;
+    let attributes = parse_attributes(payload).map_err(invalid)?;
+
+    let key_block = payload
+        .require(tag::SYMMETRIC_KEY)
+        .and_then(|key| key.require(tag::KEY_BLOCK))
+        .map_err(invalid)?;
+    let format = key_block
+        .require(tag::KEY_FORMAT_TYPE)
+        .and_then(Ttlv::as_enumeration)
+        .map_err(invalid)?;
+    if format != KEY_FORMAT_RAW && format != KEY_FORMAT_TRANSPARENT_SYMMETRIC_KEY {
+        return Err((StatusCode::NOT_IMPLEMENTED, "Only Raw and TransparentSymmetricKey formats are supported".to_string()));
+    }
+    let material = key_material(key_block).map_err(invalid)?.to_vec();
+
+    // 密钥块中的算法和长度可以代替模板属性
+    let mut attributes = attributes;
+    if !attributes.iter().any(|attribute| matches!(attribute, Attribute::CryptographicAlgorithm(_))) {
+        if let Some(algorithm) = key_block.child(tag::CRYPTOGRAPHIC_ALGORITHM) {
+            attributes.push(Attribute::CryptographicAlgorithm(algorithm.as_enumeration().map_err(invalid)?));
+        }
+    }
+    let length = match attributes.iter().any(|attribute| matches!(attribute, Attribute::CryptographicLength(_))) {
+        true => attribute_length(&attributes)?,
+        false => (material.len() * 8) as i32,
+    };
+    if material.len() * 8 != length as usize {
+        return Err((StatusCode::BAD_REQUEST, "Cryptographic Length does not match the key material".to_string()));
+    }
+
+    let object = new_object(&attributes, length, material)?;
+    create_object(store, caller, object).await
+}
+
+async fn get<S: ObjectStore>(store: &S, caller: &Caller, payload: &Ttlv) -> OperationResult {
+    let key_id = require_target(payload)?;
+    store.authorize(caller, key_id, Permission::ReadMaterial).await?;
+
+    let object = find_object(store, key_id).await?;
+    let material = store
+        .get_material(key_id)
+        .await?
+        .ok_or((StatusCode::NOT_FOUND, "Object not found".to_string()))?;
+
+    Ok((
+        StatusCode::OK,
+        vec![
+            Ttlv::enumeration(tag::OBJECT_TYPE, OBJECT_TYPE_SYMMETRIC_KEY),
+            Ttlv::text(tag::UNIQUE_IDENTIFIER, key_id.to_string()),
+            symmetric_key(object.cryptographic_algorithm, object.cryptographic_length, material),
+        ],
+    ))
+}
+
+async fn get_attributes<S: ObjectStore>(store: &S, caller: &Caller, payload: &Ttlv, version: ProtocolVersion) -> OperationResult {
+    let key_id = require_target(payload)?;
+    store.authorize(caller, key_id, Permission::ReadMetadata).await?;
+
+    let object = find_object(store, key_id).await?;
+    let mut attributes = object
+        .attributes()
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Invalid stored object: {}", e)))?;
+
+    // 1.x按AttributeName，2.0按AttributeReference筛选；均未给出时返回全部属性
+    let mut requested: Vec<Tag> = Vec::new();
+    for name in payload.children(tag::ATTRIBUTE_NAME) {
+        if let Some(tag) = shared::kmip::attribute_tag(name.as_text().map_err(invalid)?) {
+            requested.push(tag);
+        }
+    }
+    for reference in payload.children(tag::ATTRIBUTE_REFERENCE) {
+        if let Ok(value) = reference.as_enumeration() {
+            requested.push(Tag(value));
+        }
+    }
+    let filtered = payload.child(tag::ATTRIBUTE_NAME).is_some() || payload.child(tag::ATTRIBUTE_REFERENCE).is_some();
+    if filtered {
+        attributes.retain(|attribute| requested.contains(&attribute.tag()));
+    }
+
+    let mut response = vec![Ttlv::text(tag::UNIQUE_IDENTIFIER, key_id.to_string())];
+    response.extend(encode_attributes(&attributes, version));
+    Ok((StatusCode::OK, response))
+}
+
+async fn activate<S: ObjectStore>(store: &S, caller: &Caller, payload: &Ttlv) -> OperationResult {
+    let key_id = require_target(payload)?;
+    store.authorize(caller, key_id, Permission::Update).await?;
+
+    let result = store.activate(key_id).await?;
+    state_change(result, key_id)
+}
+
+async fn revoke<S: ObjectStore>(store: &S, caller: &Caller, payload: &Ttlv) -> OperationResult {
+    let key_id = require_target(payload)?;
+    store.authorize(caller, key_id, Permission::Update).await?;
+
+    let reason = payload.require(tag::REVOCATION_REASON).map_err(invalid)?;
+    let code = reason
+        .require(tag::REVOCATION_REASON_CODE)
+        .and_then(Ttlv::as_enumeration)
+        .map_err(invalid)?;
+    let message = reason
+        .child(tag::REVOCATION_MESSAGE)
+        .map(Ttlv::as_text)
+        .transpose()
+        .map_err(invalid)?;
+    let compromise_occurrence_date = payload
+        .child(tag::COMPROMISE_OCCURRENCE_DATE)
+        .map(|date| date.as_date_time().map_err(invalid).and_then(to_date_time))
+        .transpose()?;
+
+    let result = store.revoke(key_id, code, message, compromise_occurrence_date).await?;
+    state_change(result, key_id)
+}
+
+async fn destroy<S: ObjectStore>(store: &S, caller: &Caller, payload: &Ttlv) -> OperationResult {
+    let key_id = require_target(payload)?;
+    store.authorize(caller, key_id, Permission::Delete).await?;
+
+    let result = store.destroy(key_id).await?;
+    state_change(result, key_id)
+}
+
+async fn locate<S: ObjectStore>(store: &S, caller: &Caller, payload: &Ttlv, version: ProtocolVersion) -> OperationResult {
+    let mut filter = LocateFilter {
+        maximum_items: payload
+            .child(tag::MAXIMUM_ITEMS)
+            .map(Ttlv::as_integer)
+            .transpose()
+            .map_err(invalid)?
+            .map(|value| value.max(1) as u32),
+        ..LocateFilter::default()
+    };
+    for attribute in parse_attributes(payload).map_err(invalid)? {
+        match attribute {
+            Attribute::Name(name) => filter.name = Some(name),
+            Attribute::CryptographicAlgorithm(algorithm) => filter.cryptographic_algorithm = Some(algorithm),
+            Attribute::State(state) => filter.state = Some(state),
+            // 只保存对称密钥，其他对象类型不会匹配
+            Attribute::ObjectType(object_type) if object_type != OBJECT_TYPE_SYMMETRIC_KEY => {
+                return Ok((StatusCode::OK, located_items(Vec::new(), version)));
+            }
+            _ => {}
+        }
+    }
+
+    // API令牌只能看到作用范围内的密钥名，因此先取全部候选再按上限截断
+    let maximum_items = match caller.grant {
+        Some(_) => filter.maximum_items.take(),
+        None => None,
+    };
+    let mut located = store.locate(&caller.identity, filter).await?;
+    if let Some(grant) = &caller.grant {
+        let mut allowed = Vec::new();
+        for key_id in located {
+            let object = store.get_object(key_id).await?;
+            if object.is_some_and(|object| grant.allows(&object.name, TokenOperation::ReadMetadata)) {
+                allowed.push(key_id);
+            }
+        }
+        located = allowed;
+        if let Some(maximum_items) = maximum_items {
+            located.truncate(maximum_items as usize);
+        }
+    }
+
+    Ok((StatusCode::OK, located_items(located, version)))
+}
+
+// 1.3起响应中带有LocatedItems
+fn located_items(located: Vec<u64>, version: ProtocolVersion) -> Vec<Ttlv> {
+    let mut response = Vec::with_capacity(located.len() + 1);
+    if version.major > 1 || version.minor >= 3 {
+        response.push(Ttlv::integer(tag::LOCATED_ITEMS, located.len() as i32));
+    }
+    response.extend(
+        located
+            .into_iter()
+            .map(|key_id| Ttlv::text(tag::UNIQUE_IDENTIFIER, key_id.to_string())),
+    );
+    response
+}
+
+// 检查创建权限后写入对象
+async fn create_object<S: ObjectStore>(store: &S, caller: &Caller, object: NewKmipObject) -> OperationResult {
+    let identity = &caller.identity;
+    if !identity.role.can_create_keys() {
+        tracing::warn!(identity_id = identity.id, role = %identity.role, "Access denied: create KMIP object");
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+    if caller.grant.as_ref().is_some_and(|grant| !grant.allows(&object.name, TokenOperation::Create)) {
+        tracing::warn!(identity_id = identity.id, "Access denied: create KMIP object outside token scope");
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+
+    let result = store.create_object(identity, object).await?;
+
+    match result {
+        CreateObject::Created(key_id) => Ok((
+            StatusCode::CREATED,
+            vec![
+                Ttlv::enumeration(tag::OBJECT_TYPE, OBJECT_TYPE_SYMMETRIC_KEY),
+                Ttlv::text(tag::UNIQUE_IDENTIFIER, key_id.to_string()),
+            ],
+        )),
+        CreateObject::NameTaken => Err((StatusCode::CONFLICT, "Key name already exists".to_string())),
+    }
+}
+
+// 由属性组装新对象；未给出Name时生成随机名称
+fn new_object(attributes: &[Attribute], length: i32, material: Vec<u8>) -> Result<NewKmipObject, (StatusCode, String)> {
+    let mut object = NewKmipObject {
+        name: String::new(),
+        cryptographic_algorithm: 0,
+        cryptographic_length: length,
+        cryptographic_usage_mask: None,
+        material,
+        activation_date: None,
+    };
+    for attribute in attributes {
+        match attribute {
+            Attribute::Name(name) => object.name = name.clone(),
+            Attribute::CryptographicAlgorithm(algorithm) => object.cryptographic_algorithm = *algorithm,
+            Attribute::CryptographicUsageMask(mask) => object.cryptographic_usage_mask = Some(*mask),
+            Attribute::ActivationDate(date) => object.activation_date = Some(to_date_time(*date)?),
+            _ => {}
+        }
+    }
+
+    if object.cryptographic_algorithm == 0 {
+        return Err((StatusCode::BAD_REQUEST, "Missing Cryptographic Algorithm".to_string()));
+    }
+    if object.cryptographic_algorithm == ALGORITHM_AES && ![128, 192, 256].contains(&length) {
+        return Err((StatusCode::BAD_REQUEST, "AES keys must be 128, 192 or 256 bits".to_string()));
+    }
+    if object.name.is_empty() {
+        let mut suffix = [0u8; 8];
+        getrandom::getrandom(&mut suffix)
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to generate name: {:?}", e)))?;
+        object.name = format!("kmip-{}", hex::encode(suffix));
+    }
+    Ok(object)
+}
+
+// 读取密钥长度，必须为8的正整数倍
+fn attribute_length(attributes: &[Attribute]) -> Result<i32, (StatusCode, String)> {
+    let length = attributes
+        .iter()
+        .find_map(|attribute| match attribute {
+            Attribute::CryptographicLength(length) => Some(*length),
+            _ => None,
+        })
+        .ok_or((StatusCode::BAD_REQUEST, "Missing Cryptographic Length".to_string()))?;
+    if length <= 0 || length % 8 != 0 {
+        return Err((StatusCode::BAD_REQUEST, "Cryptographic Length must be a positive multiple of 8".to_string()));
+    }
+    Ok(length)
+}
+
+fn require_symmetric_key(payload: &Ttlv) -> Result<(), (StatusCode, String)> {
+    // 2.0的Create可以省略ObjectType，此时按对称密钥处理
+    match payload.child(tag::OBJECT_TYPE).map(Ttlv::as_enumeration).transpose().map_err(invalid)? {
+        None | Some(OBJECT_TYPE_SYMMETRIC_KEY) => Ok(()),
+        Some(_) => Err((StatusCode::NOT_IMPLEMENTED, "Only symmetric keys are supported".to_string())),
+    }
+}
+
+fn require_target(payload: &Ttlv) -> Result<u64, (StatusCode, String)> {
+    let identifier = payload
+        .require(tag::UNIQUE_IDENTIFIER)
+        .and_then(Ttlv::as_text)
+        .map_err(invalid)?;
+    // 不存在的ID与格式错误的ID同样返回ItemNotFound
+    identifier
+        .parse()
+        .map_err(|_| (StatusCode::NOT_FOUND, "Object not found".to_string()))
+}
+
+async fn find_object<S: ObjectStore>(store: &S, key_id: u64) -> Result<KmipObjectRecord, (StatusCode, String)> {
+    store
+        .get_object(key_id)
+        .await?
+        .ok_or((StatusCode::NOT_FOUND, "Object not found".to_string()))
+}
+
+fn state_change(result: StateChange, key_id: u64) -> OperationResult {
+    match result {
+        StateChange::Done => Ok((StatusCode::OK, vec![Ttlv::text(tag::UNIQUE_IDENTIFIER, key_id.to_string())])),
+        StateChange::NotFound => Err((StatusCode::NOT_FOUND, "Object not found".to_string())),
+        StateChange::IllegalState(state) => Err(illegal_state(state)),
+    }
+}
+
+fn illegal_state(state: State) -> (StatusCode, String) {
+    (StatusCode::CONFLICT, format!("Operation not allowed in state {}", state.as_str()))
+}
+
+fn to_date_time(timestamp: i64) -> Result<chrono::DateTime<chrono::Utc>, (StatusCode, String)> {
+    chrono::DateTime::from_timestamp(timestamp, 0).ok_or((StatusCode::BAD_REQUEST, "Invalid date".to_string()))
+}
+
+fn encryption_key() -> Result<String, (StatusCode, String)> {
+    std::env::var("ENCRYPTION_KEY")
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Encryption key not configured".to_string()))
+}
+
+fn invalid(e: TtlvError) -> (StatusCode, String) {
+    (StatusCode::BAD_REQUEST, e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::access::Role;
+    use shared::kmip::{request_message, REVOCATION_REASON_KEY_COMPROMISE};
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+
+    const VERSION: ProtocolVersion = ProtocolVersion::V1_4;
+    // 吊销原因CessationOfOperation
+    const REVOCATION_REASON_CESSATION_OF_OPERATION: u32 = 0x05;
+
+    struct MemoryObject {
+        name: String,
+        state: State,
+        cryptographic_algorithm: u32,
+        cryptographic_length: i32,
+        material: Vec<u8>,
+    }
+
+    // 内存对象存储，状态转换与kmip服务一致；不做ACL检查，只由处理函数校验角色
+    #[derive(Default)]
+    struct MemoryStore {
+        objects: Mutex<BTreeMap<u64, MemoryObject>>,
+    }
+
+    impl ObjectStore for MemoryStore {
+        async fn authorize(&self, _caller: &Caller, _key_id: u64, _permission: Permission) -> StoreResult<()> {
+            Ok(())
+        }
+
+        async fn create_object(&self, _owner: &Identity, object: NewKmipObject) -> StoreResult<CreateObject> {
+            let mut objects = self.objects.lock().unwrap();
+            if objects.values().any(|existing| existing.name == object.name) {
+                return Ok(CreateObject::NameTaken);
+            }
+            let key_id = objects.keys().next_back().map_or(1, |key_id| key_id + 1);
+            let state = match object.activation_date {
+                Some(date) if date <= chrono::Utc::now() => State::Active,
+                _ => State::PreActive,
+            };
+            objects.insert(
+                key_id,
+                MemoryObject {
+                    name: object.name,
+                    state,
+                    cryptographic_algorithm: object.cryptographic_algorithm,
+                    cryptographic_length: object.cryptographic_length,
+                    material: object.material,
+                },
+            );
+            Ok(CreateObject::Created(key_id))
+        }
+
+        async fn get_object(&self, key_id: u64) -> StoreResult<Option<KmipObjectRecord>> {
+            let now = chrono::Utc::now();
+            Ok(self.objects.lock().unwrap().get(&key_id).map(|object| KmipObjectRecord {
+                key_id,
+                name: object.name.clone(),
+                state: object.state.as_str().to_string(),
+                cryptographic_algorithm: object.cryptographic_algorithm,
+                cryptographic_length: object.cryptographic_length,
+                cryptographic_usage_mask: None,
+                activation_date: None,
+                deactivation_date: None,
+                compromise_date: None,
+                revocation_reason: None,
+                revocation_message: None,
+                created_at: now,
+                updated_at: now,
+            }))
+        }
+
+        async fn get_material(&self, key_id: u64) -> StoreResult<Option<Vec<u8>>> {
+            Ok(self.objects.lock().unwrap().get(&key_id).map(|object| object.material.clone()))
+        }
+
+        async fn activate(&self, key_id: u64) -> StoreResult<StateChange> {
+            let mut objects = self.objects.lock().unwrap();
+            let Some(object) = objects.get_mut(&key_id) else {
+                return Ok(StateChange::NotFound);
+            };
+            if object.state != State::PreActive {
+                return Ok(StateChange::IllegalState(object.state));
+            }
+            object.state = State::Active;
+            Ok(StateChange::Done)
+        }
+
+        async fn revoke(
+            &self,
+            key_id: u64,
+            reason: u32,
+            _message: Option<&str>,
+            _compromise_occurrence_date: Option<chrono::DateTime<chrono::Utc>>,
+        ) -> StoreResult<StateChange> {
+            let mut objects = self.objects.lock().unwrap();
+            let Some(object) = objects.get_mut(&key_id) else {
+                return Ok(StateChange::NotFound);
+            };
+            object.state = match (reason, object.state) {
+                (_, State::Compromised) => return Ok(StateChange::IllegalState(State::Compromised)),
+                (REVOCATION_REASON_KEY_COMPROMISE, _) => State::Compromised,
+                (_, State::PreActive | State::Active) => State::Deactivated,
+                (_, state) => return Ok(StateChange::IllegalState(state)),
+            };
+            Ok(StateChange::Done)
+        }
+
+        async fn destroy(&self, key_id: u64) -> StoreResult<StateChange> {
+            let mut objects = self.objects.lock().unwrap();
+            match objects.get(&key_id).map(|object| object.state) {
+                None => Ok(StateChange::NotFound),
+                Some(State::Active) => Ok(StateChange::IllegalState(State::Active)),
+                Some(_) => {
+                    objects.remove(&key_id);
+                    Ok(StateChange::Done)
+                }
+            }
+        }
+
+        async fn locate(&self, _identity: &Identity, filter: LocateFilter) -> StoreResult<Vec<u64>> {
+            let objects = self.objects.lock().unwrap();
+            Ok(objects
+                .iter()
+                .filter(|(_, object)| filter.name.as_ref().is_none_or(|name| &object.name == name))
+                .map(|(key_id, _)| *key_id)
+                .collect())
+        }
+    }
+
+    fn caller(role: Role) -> Caller {
+        Caller {
+            identity: Identity {
+                id: 1,
+                name: "kmip-client".to_string(),
+                role,
+                source_ip: None,
+                team_id: Some(1),
+            },
+            grant: None,
+        }
+    }
+
+    fn create_payload(attributes: &[Attribute]) -> Vec<Ttlv> {
+        vec![
+            Ttlv::enumeration(tag::OBJECT_TYPE, OBJECT_TYPE_SYMMETRIC_KEY),
+            Ttlv::structure(tag::TEMPLATE_ATTRIBUTE, encode_attributes(attributes, VERSION)),
+        ]
+    }
+
+    fn aes_key(name: &str) -> Vec<Attribute> {
+        vec![
+            Attribute::Name(name.to_string()),
+            Attribute::CryptographicAlgorithm(ALGORITHM_AES),
+            Attribute::CryptographicLength(256),
+        ]
+    }
+
+    fn target(key_id: &str) -> Vec<Ttlv> {
+        vec![Ttlv::text(tag::UNIQUE_IDENTIFIER, key_id)]
+    }
+
+    fn revoke_payload(key_id: &str, reason: u32) -> Vec<Ttlv> {
+        let mut payload = target(key_id);
+        payload.push(Ttlv::structure(
+            tag::REVOCATION_REASON,
+            vec![Ttlv::enumeration(tag::REVOCATION_REASON_CODE, reason)],
+        ));
+        payload
+    }
+
+    fn identifier(payload: &[Ttlv]) -> Option<&str> {
+        payload
+            .iter()
+            .find(|item| item.tag == tag::UNIQUE_IDENTIFIER)
+            .and_then(|item| item.as_text().ok())
+    }
+
+    // 编码请求消息后与process一样逐条解码并执行批量条目
+    async fn run_batch(store: &MemoryStore, caller: &Caller, batch: Vec<(Operation, Vec<Ttlv>)>) -> Vec<OperationResult> {
+        let message = request_message(VERSION, Some(("kmip-client", "secret")), batch).to_bytes();
+        let (request, _) = Ttlv::decode(&message).expect("request message decodes");
+
+        let mut results = Vec::new();
+        for item in request.children(tag::BATCH_ITEM) {
+            let operation = item
+                .require(tag::OPERATION)
+                .and_then(Ttlv::as_enumeration)
+                .ok()
+                .and_then(Operation::from_code)
+                .expect("operation is supported");
+            let payload = item.require(tag::REQUEST_PAYLOAD).expect("batch item has a payload");
+            results.push(execute(store, caller, operation, payload, VERSION).await);
+        }
+        results
+    }
+
+    fn status(result: &OperationResult) -> StatusCode {
+        match result {
+            Ok((status, _)) | Err((status, _)) => *status,
+        }
+    }
+
+    #[tokio::test]
+    async fn create_get_destroy_batch() {
+        let store = MemoryStore::default();
+        let results = run_batch(
+            &store,
+            &caller(Role::App),
+            vec![
+                (Operation::Create, create_payload(&aes_key("kmip-test"))),
+                (Operation::Get, target("1")),
+                (Operation::Destroy, target("1")),
+                (Operation::Get, target("1")),
+            ],
+        )
+        .await;
+        let statuses: Vec<StatusCode> = results.iter().map(status).collect();
+        assert_eq!(statuses, [StatusCode::CREATED, StatusCode::OK, StatusCode::OK, StatusCode::NOT_FOUND]);
+
+        let (_, created) = results[0].as_ref().unwrap();
+        assert_eq!(identifier(created), Some("1"));
+
+        let (_, got) = results[1].as_ref().unwrap();
+        assert_eq!(identifier(got), Some("1"));
+        let key_block = got
+            .iter()
+            .find(|item| item.tag == tag::SYMMETRIC_KEY)
+            .and_then(|key| key.child(tag::KEY_BLOCK))
+            .expect("Get returns a symmetric key");
+        assert_eq!(key_material(key_block).map(<[u8]>::len), Ok(32));
+        let algorithm = key_block.require(tag::CRYPTOGRAPHIC_ALGORITHM).and_then(Ttlv::as_enumeration);
+        assert_eq!(algorithm, Ok(ALGORITHM_AES));
+
+        assert_eq!(identifier(&results[2].as_ref().unwrap().1), Some("1"));
+        assert!(store.objects.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn active_objects_must_be_revoked_before_destroy() {
+        let store = MemoryStore::default();
+        let mut attributes = aes_key("kmip-active");
+        attributes.push(Attribute::ActivationDate(chrono::Utc::now().timestamp() - 60));
+
+        let results = run_batch(
+            &store,
+            &caller(Role::App),
+            vec![
+                (Operation::Create, create_payload(&attributes)),
+                (Operation::Destroy, target("1")),
+                (Operation::Revoke, revoke_payload("1", REVOCATION_REASON_CESSATION_OF_OPERATION)),
+                (Operation::Destroy, target("1")),
+            ],
+        )
+        .await;
+        let statuses: Vec<StatusCode> = results.iter().map(status).collect();
+        assert_eq!(statuses, [StatusCode::CREATED, StatusCode::CONFLICT, StatusCode::OK, StatusCode::OK]);
+    }
+
+    #[tokio::test]
+    async fn create_checks_role_and_name() {
+        let store = MemoryStore::default();
+
+        let create = vec![(Operation::Create, create_payload(&aes_key("kmip-test")))];
+        let denied = run_batch(&store, &caller(Role::Auditor), create).await;
+        assert_eq!(status(&denied[0]), StatusCode::FORBIDDEN);
+
+        let results = run_batch(
+            &store,
+            &caller(Role::App),
+            vec![
+                (Operation::Create, create_payload(&aes_key("kmip-test"))),
+                (Operation::Create, create_payload(&aes_key("kmip-test"))),
+                (Operation::Get, target("not-a-number")),
+            ],
+        )
+        .await;
+        let statuses: Vec<StatusCode> = results.iter().map(status).collect();
+        assert_eq!(statuses, [StatusCode::CREATED, StatusCode::CONFLICT, StatusCode::NOT_FOUND]);
+    }
+}
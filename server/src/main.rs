@@ -1,4 +1,4 @@
-use axum::{Router, middleware::from_fn};
+use axum::middleware::from_fn;
 use axum::serve;
 use dotenv::dotenv;
 use std::net::SocketAddr;
@@ -7,6 +7,8 @@ use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, Env
 
 mod api;
 mod config;
+mod grpc;
+mod kmip;
 mod model;
 mod repository;
 mod service;
@@ -24,13 +26,91 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .with(fmt::layer())
         .init();
     
+    // 校验限流配置
+    utils::rate_limit::validate_config()?;
+
     // 初始化数据库连接池
     let db_pool = config::database::init_db_pool().await?;
+
+    // 命令行子命令
+    let args: Vec<String> = std::env::args().collect();
+    match args.get(1).map(String::as_str) {
+        // 校验审计日志哈希链
+        Some("verify-chain") => {
+            let verification = service::audit::verify_chain(db_pool).await?;
+            println!("{}", serde_json::to_string_pretty(&verification)?);
+            std::process::exit(if verification.valid { 0 } else { 1 });
+        }
+        // 导出加密备份：backup-export <path> [--team=<id>]，未指定团队时导出整个存储
+        Some("backup-export") => {
+            let path = args.get(2).ok_or("Usage: backup-export <path> [--team=<id>]")?;
+            let scope = backup_scope(&args[3..])?;
+            let file = tokio::fs::File::create(path).await?;
+            let manifest = service::backup::export(db_pool, scope, file, &service::backup::backup_key()?).await?;
+            println!("{}", serde_json::to_string_pretty(&manifest)?);
+            return Ok(());
+        }
+        // 恢复加密备份：backup-restore <path> [--team=<id>] [--dry-run] [--conflict=skip|overwrite|rename|fail]
+        Some("backup-restore") => {
+            let path = args.get(2).ok_or("Usage: backup-restore <path> [--team=<id>] [--dry-run] [--conflict=<strategy>]")?;
+            let scope = backup_scope(&args[3..])?;
+            let mut query = model::backup::RestoreQuery::default();
+            for arg in &args[3..] {
+                if arg == "--dry-run" {
+                    query.dry_run = true;
+                } else if let Some(strategy) = arg.strip_prefix("--conflict=") {
+                    query.conflict = serde_json::from_value(serde_json::Value::String(strategy.to_string()))?;
+                }
+            }
+            let target_key = std::env::var("ENCRYPTION_KEY")?;
+            let source_key = std::env::var("BACKUP_SOURCE_ENCRYPTION_KEY").unwrap_or_else(|_| target_key.clone());
+            let data = tokio::fs::read(path).await?;
+            let report = service::backup::restore(
+                db_pool,
+                scope,
+                &data,
+                &service::backup::backup_key()?,
+                &source_key,
+                &target_key,
+                query,
+            )
+            .await?;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            std::process::exit(if report.errors.is_empty() { 0 } else { 1 });
+        }
+        _ => {}
+    }
+
+    // 首次启动时创建初始管理员
+    service::access::bootstrap_admin(db_pool).await?;
+
+    // 为审计哈希链的链尾锚点签名
+    service::audit::anchor_head(db_pool).await?;
+
+    // 启动密钥自动轮换调度
+    service::rotation::spawn_scheduler(db_pool);
+
+    // 启动证书过期检查
+    service::webhook::spawn_expiry_monitor(db_pool);
+
+    // 启动过期租约回收
+    service::lease::spawn_reaper(db_pool);
+
+    // 在独立端口上启动gRPC服务
+    grpc::spawn_server(db_pool);
+
+    // 启动KMIP监听，未配置TLS证书时不启动
+    kmip::spawn_server(db_pool);
     
     // 构建路由
-    let app = Router::new()
-        .merge(api::key::routes())
+    let app = api::router()
+        .into_router()
+        .layer(from_fn(utils::middleware::auth_middleware))
+        .layer(from_fn(utils::middleware::lockout_middleware))
+        .layer(from_fn(utils::middleware::rate_limit_middleware))
+        .layer(from_fn(utils::middleware::audit_middleware))
         .layer(from_fn(utils::middleware::cors_middleware))
+        .layer(from_fn(utils::middleware::metrics_middleware))
         .layer(utils::middleware::trace_layer())
         .with_state(db_pool.clone());
     
@@ -39,8 +119,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing::info!("Server running on http://{}", addr);
     
     let listener = TcpListener::bind(&addr).await?;
-    serve(listener, app.into_make_service())
+    serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
         .await?;
     
     Ok(())
-}
\ No newline at end of file
+}
+
+// 解析命令行中的--team=<id>，未给出时为整个存储
+fn backup_scope(args: &[String]) -> Result<model::backup::BackupScope, Box<dyn std::error::Error>> {
+    match args.iter().find_map(|arg| arg.strip_prefix("--team=")) {
+        Some(team_id) => Ok(model::backup::BackupScope::Team(
+            team_id.parse().map_err(|_| format!("Invalid team id: {}", team_id))?,
+        )),
+        None => Ok(model::backup::BackupScope::All),
+    }
+}
@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::fmt;
+use std::str::FromStr;
+use utoipa::{IntoParams, ToSchema};
+
+/// 调用方角色
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Admin,
+    Operator,
+    Auditor,
+    App,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Admin => "admin",
+            Role::Operator => "operator",
+            Role::Auditor => "auditor",
+            Role::App => "app",
+        }
+    }
+
+    /// 是否允许创建新密钥
+    pub fn can_create_keys(&self) -> bool {
+        matches!(self, Role::Admin | Role::Operator | Role::App)
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "admin" => Ok(Role::Admin),
+            "operator" => Ok(Role::Operator),
+            "auditor" => Ok(Role::Auditor),
+            "app" => Ok(Role::App),
+            other => Err(format!("Unknown role: {}", other)),
+        }
+    }
+}
+
+/// 针对单个密钥的操作权限
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    ReadMetadata,
+    ReadMaterial,
+    Update,
+    Delete,
+    /// 由服务端使用密钥加解密或签名，密钥内容不离开服务端
+    Use,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 5] = [
+        Permission::ReadMetadata,
+        Permission::ReadMaterial,
+        Permission::Update,
+        Permission::Delete,
+        Permission::Use,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Permission::ReadMetadata => "read_metadata",
+            Permission::ReadMaterial => "read_material",
+            Permission::Update => "update",
+            Permission::Delete => "delete",
+            Permission::Use => "use",
+        }
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Permission {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read_metadata" => Ok(Permission::ReadMetadata),
+            "read_material" => Ok(Permission::ReadMaterial),
+            "update" => Ok(Permission::Update),
+            "delete" => Ok(Permission::Delete),
+            "use" => Ok(Permission::Use),
+            other => Err(format!("Unknown permission: {}", other)),
+        }
+    }
+}
+
+/// ACL授权对象类型：具体身份或角色
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SubjectType {
+    Identity,
+    Role,
+}
+
+impl SubjectType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SubjectType::Identity => "identity",
+            SubjectType::Role => "role",
+        }
+    }
+}
+
+/// 数据库中的身份记录
+#[derive(Debug, FromRow)]
+pub struct IdentityRecord {
+    pub id: u64,
+    pub name: String,
+    pub role: String,
+    pub credential_hash: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 已认证的调用方身份，由认证中间件写入请求扩展
+#[derive(Debug, Clone, Serialize)]
+pub struct Identity {
+    pub id: u64,
+    pub name: String,
+    pub role: Role,
+    /// 请求的来源IP，用于按IP统计配额；非请求上下文中为空
+    #[serde(skip)]
+    pub source_ip: Option<std::net::IpAddr>,
+    /// 当前团队上下文，只能访问该团队的密钥；不属于任何团队时为空
+    pub team_id: Option<u64>,
+}
+
+/// 数据库中的ACL记录
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct KeyAcl {
+    pub id: u64,
+    pub key_id: u64,
+    pub subject_type: String,
+    pub subject: String,
+    pub permission: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateIdentityRequest {
+    pub name: String,
+    pub role: Role,
+}
+
+/// 创建身份的响应，凭据只在此处返回一次
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreateIdentityResponse {
+    pub id: u64,
+    pub name: String,
+    pub role: Role,
+    pub credential: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct GrantRequest {
+    pub subject_type: SubjectType,
+    pub subject: String,
+    pub permission: Permission,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExplainQuery {
+    pub identity_id: u64,
+    pub key_id: u64,
+    pub permission: Permission,
+}
+
+/// 访问控制判定结果
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Decision {
+    pub allowed: bool,
+    pub reason: String,
+    pub matched_acl_id: Option<u64>,
+}
+
+impl Decision {
+    pub fn allow(reason: impl Into<String>, matched_acl_id: Option<u64>) -> Self {
+        Self { allowed: true, reason: reason.into(), matched_acl_id }
+    }
+
+    pub fn deny(reason: impl Into<String>) -> Self {
+        Self { allowed: false, reason: reason.into(), matched_acl_id: None }
+    }
+}
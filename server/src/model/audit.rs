@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+/// 审计事件记录，hash为对上一条记录hash及本条内容的HMAC
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct AuditEvent {
+    pub id: u64,
+    pub actor_id: Option<u64>,
+    pub actor_name: Option<String>,
+    pub action: String,
+    pub key_id: Option<u64>,
+    pub outcome: String,
+    pub status_code: u16,
+    pub source_ip: Option<String>,
+    pub request_id: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+/// 哈希链链尾锚点，记录最新一条的id、hash及记录总数
+#[derive(Debug, Clone, FromRow)]
+pub struct AuditHead {
+    pub last_id: u64,
+    pub last_hash: String,
+    pub entries: u64,
+    pub mac: String,
+}
+
+/// 待写入的审计事件
+#[derive(Debug, Clone)]
+pub struct NewAuditEvent {
+    pub actor_id: Option<u64>,
+    pub actor_name: Option<String>,
+    pub action: String,
+    pub key_id: Option<u64>,
+    pub outcome: String,
+    pub status_code: u16,
+    pub source_ip: Option<String>,
+    pub request_id: String,
+}
+
+/// 单次请求的上下文信息，由审计中间件写入请求扩展
+#[derive(Debug, Clone)]
+pub struct RequestContext {
+    pub request_id: String,
+    pub source_ip: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditQuery {
+    pub actor_id: Option<u64>,
+    pub action: Option<String>,
+    pub key_id: Option<u64>,
+    pub outcome: Option<String>,
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+    pub limit: Option<u32>,
+}
+
+/// 哈希链校验结果
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChainVerification {
+    pub checked: u64,
+    pub valid: bool,
+    pub first_invalid_id: Option<u64>,
+    pub reason: Option<String>,
+}
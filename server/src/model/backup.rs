@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+use shared::crypto::vault::{Ciphertext, KdfParams};
+use sqlx::FromRow;
+use std::collections::BTreeMap;
+use utoipa::{IntoParams, ToSchema};
+
+/// 备份文件格式版本
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+/// 备份中的单个密钥及其元数据，密钥值保持导出时ENCRYPTION_KEY下的密文
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupKey {
+    pub id: u64,
+    pub name: String,
+    pub encrypted_data: String,
+    /// 密钥类型，旧备份中没有此字段
+    #[serde(default)]
+    pub kind: Option<String>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    #[serde(default)]
+    pub metadata: Option<serde_json::Value>,
+    /// 客户端加密使用的nonce，仅保险库中的密钥有值
+    #[serde(default)]
+    pub nonce: Option<String>,
+    /// 所属保险库，恢复时按名称匹配或重建
+    #[serde(default)]
+    pub vault: Option<BackupVault>,
+    /// 导出时所属的团队，恢复整个存储时按名称匹配或重建；旧备份中没有此字段
+    #[serde(default)]
+    pub team: Option<BackupTeam>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub rotation_policy: Option<BackupRotationPolicy>,
+    pub versions: Vec<BackupKeyVersion>,
+    pub acls: Vec<BackupAcl>,
+}
+
+/// 按名称标识的团队，不同部署中团队id不同
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BackupTeam {
+    pub organization: String,
+    pub name: String,
+}
+
+/// 备份和恢复的范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackupScope {
+    /// 整个存储，恢复时密钥回到备份中记录的团队
+    All,
+    /// 单个团队，恢复时全部密钥进入该团队
+    Team(u64),
+}
+
+impl BackupScope {
+    pub fn team_id(self) -> Option<u64> {
+        match self {
+            BackupScope::All => None,
+            BackupScope::Team(team_id) => Some(team_id),
+        }
+    }
+}
+
+/// 保险库的KDF参数和被包装的保险库密钥，服务端无法解开
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupVault {
+    pub name: String,
+    pub owner_identity_id: u64,
+    pub kdf: KdfParams,
+    pub wrapped_key: Ciphertext,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupRotationPolicy {
+    pub interval_days: u32,
+    pub last_rotated_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub next_rotation_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BackupKeyVersion {
+    pub version: u32,
+    pub encrypted_data: String,
+    pub trigger_type: String,
+    pub rotated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupAcl {
+    pub subject_type: String,
+    pub subject: String,
+    pub permission: String,
+}
+
+/// 备份清单，作为最后一个数据块写入
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub key_count: u64,
+    /// 每个密钥条目明文的SHA-256
+    pub checksums: Vec<BackupChecksum>,
+    /// 全部条目明文依次拼接后的SHA-256
+    pub archive_checksum: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupChecksum {
+    pub name: String,
+    pub sha256: String,
+}
+
+/// 备份文件中的数据块
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BackupEntry {
+    Key(BackupKey),
+    Manifest(BackupManifest),
+}
+
+/// 恢复时名称冲突的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictStrategy {
+    /// 保留现有密钥，跳过备份中的同名密钥
+    #[default]
+    Skip,
+    /// 用备份中的密钥替换现有密钥
+    Overwrite,
+    /// 以新名称恢复备份中的密钥
+    Rename,
+    /// 存在任何冲突时放弃整个恢复
+    Fail,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RestoreQuery {
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(default)]
+    pub conflict: ConflictStrategy,
+}
+
+/// 恢复结果报告
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct RestoreReport {
+    pub dry_run: bool,
+    pub manifest_created_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub total: u64,
+    pub restored: u64,
+    pub skipped: u64,
+    pub overwritten: u64,
+    pub renamed: Vec<String>,
+    pub conflicts: Vec<String>,
+    pub errors: Vec<String>,
+}
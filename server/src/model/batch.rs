@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::model::key::{CreateKeyRequest, KeyResponse};
+
+/// 单个批量请求允许的最大操作数
+pub const MAX_BATCH_OPERATIONS: usize = 100;
+
+/// 批量执行模式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchMode {
+    /// 任一操作失败时回滚全部操作
+    #[default]
+    AllOrNothing,
+    /// 各操作独立提交，失败的操作不影响其他操作
+    BestEffort,
+}
+
+/// 批量中的单个操作，语义与POST /keys、DELETE /keys/{id}相同
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BatchOperation {
+    Create(CreateKeyRequest),
+    Delete {
+        id: u64,
+        /// 与If-Match相同，给出时只在版本一致时删除
+        #[serde(default)]
+        expected_version: Option<u32>,
+    },
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BatchRequest {
+    #[serde(default)]
+    pub mode: BatchMode,
+    pub operations: Vec<BatchOperation>,
+}
+
+/// 单个操作的结果，status为该操作对应的HTTP状态码
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BatchItemResult {
+    /// 操作在请求中的下标
+    pub index: usize,
+    /// 全部回滚模式下未生效的操作为424
+    pub status: u16,
+    pub key_id: Option<u64>,
+    /// 创建成功的密钥
+    pub key: Option<KeyResponse>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BatchResponse {
+    pub mode: BatchMode,
+    /// 是否有操作被提交
+    pub committed: bool,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BatchItemResult>,
+}
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// 服务端AES-GCM加密，二进制字段均为Base64编码
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct EncryptRequest {
+    pub plaintext: String,
+    /// 12字节IV，省略时由服务端随机生成
+    pub iv: Option<String>,
+    /// 附加认证数据
+    pub aad: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EncryptResponse {
+    pub key_id: u64,
+    pub iv: String,
+    /// 密文，末尾16字节为认证标签
+    pub ciphertext: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DecryptRequest {
+    /// 密文，末尾16字节为认证标签
+    pub ciphertext: String,
+    pub iv: String,
+    pub aad: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DecryptResponse {
+    pub key_id: u64,
+    pub plaintext: String,
+}
+
+/// 签名算法，ECDSA仅支持P-256曲线
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SignAlgorithm {
+    RsaPkcs1Sha256,
+    RsaPkcs1Sha384,
+    RsaPssSha256,
+    RsaPssSha384,
+    EcdsaSha256,
+    EcdsaSha384,
+    Ed25519,
+}
+
+impl SignAlgorithm {
+    /// 算法适用的密钥类型
+    pub fn key_type(self) -> KeyType {
+        match self {
+            SignAlgorithm::RsaPkcs1Sha256
+            | SignAlgorithm::RsaPkcs1Sha384
+            | SignAlgorithm::RsaPssSha256
+            | SignAlgorithm::RsaPssSha384 => KeyType::Rsa,
+            SignAlgorithm::EcdsaSha256 | SignAlgorithm::EcdsaSha384 => KeyType::EcP256,
+            SignAlgorithm::Ed25519 => KeyType::Ed25519,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SignRequest {
+    pub algorithm: SignAlgorithm,
+    /// 待签名数据，Base64编码
+    pub data: String,
+    /// data已是对应算法的摘要时为true，Ed25519不支持
+    #[serde(default)]
+    pub prehashed: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SignResponse {
+    pub key_id: u64,
+    pub algorithm: SignAlgorithm,
+    /// RSA为PKCS#1签名，ECDSA为定长r||s，Ed25519为64字节签名
+    pub signature: String,
+}
+
+/// 可由服务端使用的密钥类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyType {
+    Aes,
+    Rsa,
+    EcP256,
+    Ed25519,
+}
+
+/// 密钥的公开信息，不包含任何私密内容；二进制字段均为Base64编码
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PublicKeyResponse {
+    pub key_id: u64,
+    pub name: String,
+    pub key_type: KeyType,
+    pub bits: u32,
+    /// SubjectPublicKeyInfo DER，对称密钥为空
+    pub public_key: Option<String>,
+    /// RSA模数，大端序
+    pub modulus: Option<String>,
+    /// RSA公钥指数，大端序
+    pub public_exponent: Option<String>,
+    /// P-256为未压缩的SEC1点，Ed25519为32字节公钥
+    pub ec_point: Option<String>,
+    /// 证书类密钥的X.509证书DER
+    pub certificate: Option<String>,
+}
+
+/// 服务端密钥操作的结果
+#[derive(Debug)]
+pub enum KeyOperation<T> {
+    Done(T),
+    NotFound,
+    /// 密钥类型或存储方式不支持该操作
+    Unsupported(String),
+    /// 输入无效或认证失败
+    Rejected(String),
+}
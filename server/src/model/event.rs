@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::fmt;
+use std::str::FromStr;
+use utoipa::{IntoParams, ToSchema};
+
+/// 密钥变更事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum KeyEventType {
+    #[serde(rename = "key.created")]
+    Created,
+    #[serde(rename = "key.updated")]
+    Updated,
+    #[serde(rename = "key.deleted")]
+    Deleted,
+}
+
+impl KeyEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeyEventType::Created => "key.created",
+            KeyEventType::Updated => "key.updated",
+            KeyEventType::Deleted => "key.deleted",
+        }
+    }
+}
+
+impl fmt::Display for KeyEventType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for KeyEventType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "key.created" => Ok(KeyEventType::Created),
+            "key.updated" => Ok(KeyEventType::Updated),
+            "key.deleted" => Ok(KeyEventType::Deleted),
+            other => Err(format!("Unknown key event: {}", other)),
+        }
+    }
+}
+
+/// 数据库中的事件记录
+#[derive(Debug, FromRow)]
+pub struct KeyEventRecord {
+    pub id: u64,
+    pub event_type: String,
+    pub key_id: u64,
+    pub key_name: String,
+    pub version: Option<u32>,
+    pub audience: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 推送给客户端的密钥变更事件，cursor单调递增，可用于断线后续传
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct KeyEvent {
+    pub cursor: u64,
+    pub event: KeyEventType,
+    pub key_id: u64,
+    pub name: String,
+    /// 变更后的版本号，删除事件为空
+    pub version: Option<u32>,
+    pub occurred_at: chrono::DateTime<chrono::Utc>,
+    /// 删除事件发生时可读取元数据的ACL主体
+    #[serde(skip)]
+    pub audience: Vec<String>,
+}
+
+impl TryFrom<KeyEventRecord> for KeyEvent {
+    type Error = String;
+
+    fn try_from(record: KeyEventRecord) -> Result<Self, Self::Error> {
+        Ok(KeyEvent {
+            cursor: record.id,
+            event: record.event_type.parse()?,
+            key_id: record.key_id,
+            name: record.key_name,
+            version: record.version,
+            occurred_at: record.created_at,
+            audience: record
+                .audience
+                .map(|audience| audience.split(',').map(str::to_string).collect())
+                .unwrap_or_default(),
+        })
+    }
+}
+
+/// 事件流查询参数；cursor为上次收到的最后一个事件，省略时只推送新事件
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct EventStreamQuery {
+    pub cursor: Option<u64>,
+}
@@ -0,0 +1,18 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// 就绪检查中的单项结果
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReadinessCheck {
+    pub name: &'static str,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 就绪检查结果，全部检查通过时ready为true
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReadinessReport {
+    pub ready: bool,
+    pub checks: Vec<ReadinessCheck>,
+}
@@ -0,0 +1,23 @@
+use sqlx::FromRow;
+
+/// 已保存的幂等请求记录
+#[derive(Debug, FromRow)]
+pub struct IdempotencyRecord {
+    pub request_hash: String,
+    pub status_code: u16,
+    pub response_body: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 幂等键查询结果
+#[derive(Debug)]
+pub enum IdempotencyState {
+    /// 首次出现，已占位，调用方应执行请求
+    New,
+    /// 同一幂等键的请求仍在处理中
+    InProgress,
+    /// 已有完成的响应，直接重放
+    Replay { status_code: u16, body: String },
+    /// 同一幂等键对应了不同的请求内容
+    Mismatch,
+}
@@ -1,24 +1,124 @@
 use serde::{Deserialize, Serialize};
+use shared::secret::{SecretKind, SecretPayload};
 use sqlx::FromRow;
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct Key {
     pub id: Option<u64>,
     pub name: String,
+    /// 所属团队，名称在团队内唯一
+    pub team_id: Option<u64>,
     pub encrypted_data: String,
+    /// 密钥类型，非空时明文为SecretPayload的JSON
+    pub kind: Option<String>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub description: Option<String>,
+    pub metadata: Option<serde_json::Value>,
+    /// 乐观并发版本号，每次修改递增
+    pub version: Option<u32>,
+    /// 所属零知识保险库，非空时encrypted_data为客户端密文
+    pub vault_id: Option<u64>,
+    /// 客户端加密使用的nonce
+    pub nonce: Option<String>,
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
     pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateKeyRequest {
     pub name: String,
-    pub data: String,
+    /// 未声明类型的文本数据，与payload二选一
+    #[serde(default)]
+    pub data: Option<String>,
+    /// 结构化密钥内容
+    #[serde(default)]
+    pub payload: Option<SecretPayload>,
+    /// 过期时间，数据为PEM证书时可省略，由证书有效期推导
+    #[serde(default)]
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// 标签，例如 environment=prod、owner=payments
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    /// 自定义JSON元数据
+    #[serde(default)]
+    pub metadata: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct KeyResponse {
     pub id: u64,
     pub name: String,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub description: Option<String>,
+    pub labels: BTreeMap<String, String>,
+    pub metadata: Option<serde_json::Value>,
+    pub version: u32,
+    pub kind: Option<SecretKind>,
+    pub vault_id: Option<u64>,
     pub created_at: chrono::DateTime<chrono::Utc>,
-}
\ No newline at end of file
+}
+
+/// 修改密钥，省略的字段保持不变；labels给出时整体替换
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct UpdateKeyRequest {
+    pub data: Option<String>,
+    /// 结构化密钥内容，可以改变密钥类型
+    pub payload: Option<SecretPayload>,
+    /// 保险库中的密钥只能以客户端密文修改，需同时给出nonce
+    pub encrypted_data: Option<String>,
+    pub nonce: Option<String>,
+    pub description: Option<String>,
+    pub labels: Option<BTreeMap<String, String>>,
+    pub metadata: Option<serde_json::Value>,
+}
+
+/// 条件写入结果
+#[derive(Debug)]
+pub enum ConditionalWrite<T> {
+    Done(T),
+    NotFound,
+    /// If-Match给出的版本与当前版本不一致
+    VersionMismatch { current: u32 },
+}
+
+/// 密钥内容；结构化密钥返回payload，保险库中的密钥返回客户端密文及nonce，由客户端自行解密
+#[derive(Debug, Serialize, ToSchema)]
+pub struct KeyMaterialResponse {
+    pub id: u64,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<SecretPayload>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vault_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+}
+
+/// 密钥搜索条件，各条件之间为AND关系
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct KeySearchRequest {
+    /// 名称前缀
+    pub name_prefix: Option<String>,
+    /// 描述全文检索
+    pub description: Option<String>,
+    /// 必须全部匹配的标签
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    /// 元数据需包含的JSON片段
+    pub metadata: Option<serde_json::Value>,
+    pub after_id: Option<u64>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct KeySearchResponse {
+    pub keys: Vec<KeyResponse>,
+    /// 下一页的游标，为空表示没有更多结果
+    pub next_after_id: Option<u64>,
+}
@@ -0,0 +1,94 @@
+use shared::kmip::{Attribute, State, OBJECT_TYPE_SYMMETRIC_KEY};
+use sqlx::FromRow;
+
+/// KMIP对称密钥的content_type，密钥材料以Base64 Blob形式保存在keys表中
+pub const KMIP_CONTENT_TYPE: &str = "application/x-kmip-symmetric-key";
+
+/// KMIP对象记录，联表读取密钥名称
+#[derive(Debug, FromRow)]
+pub struct KmipObjectRecord {
+    pub key_id: u64,
+    pub name: String,
+    pub state: String,
+    pub cryptographic_algorithm: u32,
+    pub cryptographic_length: i32,
+    pub cryptographic_usage_mask: Option<i32>,
+    pub activation_date: Option<chrono::DateTime<chrono::Utc>>,
+    pub deactivation_date: Option<chrono::DateTime<chrono::Utc>>,
+    pub compromise_date: Option<chrono::DateTime<chrono::Utc>>,
+    pub revocation_reason: Option<u32>,
+    pub revocation_message: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl KmipObjectRecord {
+    pub fn state(&self) -> Result<State, String> {
+        self.state.parse()
+    }
+
+    /// 对象当前的全部KMIP属性
+    pub fn attributes(&self) -> Result<Vec<Attribute>, String> {
+        let mut attributes = vec![
+            Attribute::UniqueIdentifier(self.key_id.to_string()),
+            Attribute::Name(self.name.clone()),
+            Attribute::ObjectType(OBJECT_TYPE_SYMMETRIC_KEY),
+            Attribute::CryptographicAlgorithm(self.cryptographic_algorithm),
+            Attribute::CryptographicLength(self.cryptographic_length),
+            Attribute::State(self.state()?),
+            Attribute::InitialDate(self.created_at.timestamp()),
+            Attribute::LastChangeDate(self.updated_at.timestamp()),
+        ];
+        if let Some(mask) = self.cryptographic_usage_mask {
+            attributes.push(Attribute::CryptographicUsageMask(mask));
+        }
+        if let Some(date) = self.activation_date {
+            attributes.push(Attribute::ActivationDate(date.timestamp()));
+        }
+        if let Some(date) = self.deactivation_date {
+            attributes.push(Attribute::DeactivationDate(date.timestamp()));
+        }
+        if let Some(date) = self.compromise_date {
+            attributes.push(Attribute::CompromiseDate(date.timestamp()));
+        }
+        Ok(attributes)
+    }
+}
+
+/// 通过Create或Register新建的对称密钥
+#[derive(Debug)]
+pub struct NewKmipObject {
+    pub name: String,
+    pub cryptographic_algorithm: u32,
+    pub cryptographic_length: i32,
+    pub cryptographic_usage_mask: Option<i32>,
+    pub material: Vec<u8>,
+    /// 给出且不晚于当前时间时对象直接进入Active状态
+    pub activation_date: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// 新建对象的结果
+#[derive(Debug)]
+pub enum CreateObject {
+    Created(u64),
+    /// 密钥名称已被占用
+    NameTaken,
+}
+
+/// 生命周期状态变更的结果
+#[derive(Debug)]
+pub enum StateChange {
+    Done,
+    NotFound,
+    /// 当前状态不允许该操作
+    IllegalState(State),
+}
+
+/// Locate的过滤条件，均为空时返回全部对象
+#[derive(Debug, Default)]
+pub struct LocateFilter {
+    pub name: Option<String>,
+    pub cryptographic_algorithm: Option<u32>,
+    pub state: Option<State>,
+    pub maximum_items: Option<u32>,
+}
@@ -0,0 +1,290 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use utoipa::{IntoParams, ToSchema};
+
+/// 租约对象：已有密钥或服务端生成的随机凭据
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaseKind {
+    Key,
+    Generated,
+}
+
+impl LeaseKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LeaseKind::Key => "key",
+            LeaseKind::Generated => "generated",
+        }
+    }
+}
+
+impl fmt::Display for LeaseKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for LeaseKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "key" => Ok(LeaseKind::Key),
+            "generated" => Ok(LeaseKind::Generated),
+            other => Err(format!("Unknown lease kind: {}", other)),
+        }
+    }
+}
+
+/// 租约结束的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaseEndReason {
+    Revoked,
+    Expired,
+}
+
+impl LeaseEndReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LeaseEndReason::Revoked => "revoked",
+            LeaseEndReason::Expired => "expired",
+        }
+    }
+}
+
+impl fmt::Display for LeaseEndReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for LeaseEndReason {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "revoked" => Ok(LeaseEndReason::Revoked),
+            "expired" => Ok(LeaseEndReason::Expired),
+            other => Err(format!("Unknown lease end reason: {}", other)),
+        }
+    }
+}
+
+/// 撤销钩子类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaseHookKind {
+    /// 执行本机命令，租约信息通过环境变量传入
+    Command,
+    /// 向回环地址POST租约信息
+    Webhook,
+}
+
+impl LeaseHookKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LeaseHookKind::Command => "command",
+            LeaseHookKind::Webhook => "webhook",
+        }
+    }
+}
+
+impl fmt::Display for LeaseHookKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for LeaseHookKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "command" => Ok(LeaseHookKind::Command),
+            "webhook" => Ok(LeaseHookKind::Webhook),
+            other => Err(format!("Unknown lease hook kind: {}", other)),
+        }
+    }
+}
+
+/// 数据库中的租约记录
+#[derive(Debug, Clone, FromRow)]
+pub struct LeaseRecord {
+    pub id: u64,
+    pub owner_id: Option<u64>,
+    pub kind: String,
+    pub key_id: Option<u64>,
+    pub hook_id: Option<u64>,
+    pub ttl_secs: u64,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub max_expires_at: chrono::DateTime<chrono::Utc>,
+    pub renewed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub ended_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub end_reason: Option<String>,
+    pub revocation_error: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 待写入的租约记录
+#[derive(Debug)]
+pub struct NewLease {
+    pub owner_id: u64,
+    pub kind: LeaseKind,
+    pub key_id: u64,
+    pub hook_id: Option<u64>,
+    pub ttl_secs: u64,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub max_expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 数据库中的撤销钩子记录
+#[derive(Debug, Clone, FromRow)]
+pub struct LeaseHook {
+    pub id: u64,
+    pub name: String,
+    pub kind: String,
+    pub target: String,
+    pub created_by: Option<u64>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 申请租约，`key_id`与`generate`二选一
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateLeaseRequest {
+    /// 租用已有密钥，租约结束时该密钥被删除；需要对密钥有delete权限
+    pub key_id: Option<u64>,
+    /// 由服务端生成随机凭据并保存为新密钥
+    pub generate: Option<GenerateCredentialRequest>,
+    /// 租约时长（秒），默认为3600
+    pub ttl_secs: Option<u64>,
+    /// 包括续期在内的最长时长（秒），默认为ttl_secs的24倍且不超过服务端上限
+    pub max_ttl_secs: Option<u64>,
+    /// 租约结束时执行的钩子
+    pub hook_id: Option<u64>,
+}
+
+/// 随机凭据参数
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct GenerateCredentialRequest {
+    /// 密钥名称，默认为`lease/<随机后缀>`
+    pub name: Option<String>,
+    /// 凭据长度，默认为32
+    pub length: Option<usize>,
+    /// 指定时凭据保存为password类型，否则保存为未声明类型的数据
+    pub username: Option<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LeaseResponse {
+    pub id: u64,
+    pub owner_id: Option<u64>,
+    pub kind: LeaseKind,
+    /// 租用的密钥，密钥被删除后为空
+    pub key_id: Option<u64>,
+    pub hook_id: Option<u64>,
+    pub ttl_secs: u64,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub max_expires_at: chrono::DateTime<chrono::Utc>,
+    pub renewed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub ended_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub end_reason: Option<LeaseEndReason>,
+    /// 删除密钥或执行钩子失败的原因
+    pub revocation_error: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl TryFrom<LeaseRecord> for LeaseResponse {
+    type Error = String;
+
+    fn try_from(record: LeaseRecord) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: record.id,
+            owner_id: record.owner_id,
+            kind: record.kind.parse()?,
+            key_id: record.key_id,
+            hook_id: record.hook_id,
+            ttl_secs: record.ttl_secs,
+            expires_at: record.expires_at,
+            max_expires_at: record.max_expires_at,
+            renewed_at: record.renewed_at,
+            ended_at: record.ended_at,
+            end_reason: record.end_reason.map(|reason| reason.parse()).transpose()?,
+            revocation_error: record.revocation_error,
+            created_at: record.created_at,
+        })
+    }
+}
+
+/// 申请租约的响应，生成的凭据只在此处返回一次
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreateLeaseResponse {
+    #[serde(flatten)]
+    pub lease: LeaseResponse,
+    pub credential: Option<String>,
+}
+
+/// 续期租约
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct RenewLeaseRequest {
+    /// 从当前时间起延长的时长（秒），默认为租约的ttl_secs，不超过max_expires_at
+    pub increment_secs: Option<u64>,
+}
+
+/// 注册撤销钩子
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateLeaseHookRequest {
+    pub name: String,
+    pub kind: LeaseHookKind,
+    /// command为LEASE_HOOK_DIR下可执行文件的绝对路径；webhook为回环地址上的http(s) URL
+    pub target: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LeaseHookResponse {
+    pub id: u64,
+    pub name: String,
+    pub kind: LeaseHookKind,
+    pub target: String,
+    pub created_by: Option<u64>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl TryFrom<LeaseHook> for LeaseHookResponse {
+    type Error = String;
+
+    fn try_from(hook: LeaseHook) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: hook.id,
+            name: hook.name,
+            kind: hook.kind.parse()?,
+            target: hook.target,
+            created_by: hook.created_by,
+            created_at: hook.created_at,
+        })
+    }
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LeaseListQuery {
+    /// 是否包含已结束的租约，默认为false
+    #[serde(default)]
+    pub include_ended: bool,
+}
+
+/// 租约操作的结果
+#[derive(Debug)]
+pub enum LeaseOperation<T> {
+    Done(T),
+    NotFound,
+    Invalid(String),
+    Conflict(String),
+}
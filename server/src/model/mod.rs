@@ -1,2 +1,42 @@
 // 导出key模块
-pub mod key;
\ No newline at end of file
+pub mod key;
+// 导出访问控制模块
+pub mod access;
+// 导出密钥共享模块
+pub mod share;
+// 导出审计日志模块
+pub mod audit;
+// 导出密钥轮换模块
+pub mod rotation;
+// 导出Webhook通知模块
+pub mod webhook;
+// 导出备份恢复模块
+pub mod backup;
+// 导出幂等请求模块
+pub mod idempotency;
+// 导出用户模块
+pub mod user;
+// 导出服务账号与API令牌模块
+pub mod token;
+// 导出零知识保险库模块
+pub mod vault;
+// 导出健康检查模块
+pub mod health;
+// 导出密钥变更事件模块
+pub mod event;
+// 导出批量操作模块
+pub mod batch;
+// 导出KMIP对象模块
+pub mod kmip;
+// 导出服务端密钥操作模块
+pub mod crypto;
+// 导出证书颁发机构模块
+pub mod pki;
+// 导出SSH证书颁发机构模块
+pub mod ssh;
+// 导出密钥租约模块
+pub mod lease;
+// 导出调用方配额模块
+pub mod quota;
+// 导出组织和团队模块
+pub mod team;
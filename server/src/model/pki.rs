@@ -0,0 +1,355 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+use utoipa::{IntoParams, ToSchema};
+
+/// CA和服务端生成私钥的算法
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyAlgorithm {
+    #[default]
+    EcdsaP256,
+    Ed25519,
+    Rsa2048,
+}
+
+/// 证书模板，决定密钥用途、扩展密钥用途及默认和最长有效期
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CertificateProfile {
+    /// TLS服务端证书，至少需要一个DNS名称或IP地址
+    Server,
+    /// TLS客户端证书
+    Client,
+    CodeSigning,
+    /// 下级CA证书，只在创建中间CA时使用
+    Ca,
+}
+
+impl CertificateProfile {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CertificateProfile::Server => "server",
+            CertificateProfile::Client => "client",
+            CertificateProfile::CodeSigning => "code_signing",
+            CertificateProfile::Ca => "ca",
+        }
+    }
+
+    pub fn default_validity_days(&self) -> u32 {
+        match self {
+            CertificateProfile::Server => 90,
+            CertificateProfile::Client | CertificateProfile::CodeSigning => 365,
+            CertificateProfile::Ca => 1825,
+        }
+    }
+
+    pub fn max_validity_days(&self) -> u32 {
+        match self {
+            CertificateProfile::Server => 397,
+            CertificateProfile::Client => 825,
+            CertificateProfile::CodeSigning => 1095,
+            CertificateProfile::Ca => 3650,
+        }
+    }
+}
+
+impl fmt::Display for CertificateProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for CertificateProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "server" => Ok(CertificateProfile::Server),
+            "client" => Ok(CertificateProfile::Client),
+            "code_signing" => Ok(CertificateProfile::CodeSigning),
+            "ca" => Ok(CertificateProfile::Ca),
+            other => Err(format!("Unknown certificate profile: {}", other)),
+        }
+    }
+}
+
+/// 吊销原因，取值与RFC 5280的CRLReason对应
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RevocationReason {
+    #[default]
+    Unspecified,
+    KeyCompromise,
+    CaCompromise,
+    AffiliationChanged,
+    Superseded,
+    CessationOfOperation,
+    PrivilegeWithdrawn,
+}
+
+impl RevocationReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RevocationReason::Unspecified => "unspecified",
+            RevocationReason::KeyCompromise => "key_compromise",
+            RevocationReason::CaCompromise => "ca_compromise",
+            RevocationReason::AffiliationChanged => "affiliation_changed",
+            RevocationReason::Superseded => "superseded",
+            RevocationReason::CessationOfOperation => "cessation_of_operation",
+            RevocationReason::PrivilegeWithdrawn => "privilege_withdrawn",
+        }
+    }
+
+    /// CRLReason枚举值
+    pub fn code(&self) -> u8 {
+        match self {
+            RevocationReason::Unspecified => 0,
+            RevocationReason::KeyCompromise => 1,
+            RevocationReason::CaCompromise => 2,
+            RevocationReason::AffiliationChanged => 3,
+            RevocationReason::Superseded => 4,
+            RevocationReason::CessationOfOperation => 5,
+            RevocationReason::PrivilegeWithdrawn => 9,
+        }
+    }
+}
+
+impl FromStr for RevocationReason {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "unspecified" => Ok(RevocationReason::Unspecified),
+            "key_compromise" => Ok(RevocationReason::KeyCompromise),
+            "ca_compromise" => Ok(RevocationReason::CaCompromise),
+            "affiliation_changed" => Ok(RevocationReason::AffiliationChanged),
+            "superseded" => Ok(RevocationReason::Superseded),
+            "cessation_of_operation" => Ok(RevocationReason::CessationOfOperation),
+            "privilege_withdrawn" => Ok(RevocationReason::PrivilegeWithdrawn),
+            other => Err(format!("Unknown revocation reason: {}", other)),
+        }
+    }
+}
+
+/// 数据库中的CA记录
+#[derive(Debug, Clone, FromRow)]
+pub struct CertificateAuthority {
+    pub id: u64,
+    pub name: String,
+    pub key_id: u64,
+    pub parent_id: Option<u64>,
+    pub subject: String,
+    pub certificate: String,
+    pub chain: Option<String>,
+    pub path_length: Option<u8>,
+    pub crl_number: u64,
+    pub not_after: chrono::DateTime<chrono::Utc>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 待写入的CA记录
+#[derive(Debug)]
+pub struct NewCertificateAuthority {
+    pub name: String,
+    pub key_id: u64,
+    pub parent_id: Option<u64>,
+    pub subject: String,
+    pub certificate: String,
+    pub chain: Option<String>,
+    pub path_length: Option<u8>,
+    pub not_after: chrono::DateTime<chrono::Utc>,
+}
+
+/// 数据库中的已签发证书记录
+#[derive(Debug, Clone, FromRow)]
+pub struct IssuedCertificate {
+    pub id: u64,
+    pub ca_id: u64,
+    pub serial_number: String,
+    pub subject: String,
+    pub profile: String,
+    pub certificate: String,
+    pub key_id: Option<u64>,
+    pub not_before: chrono::DateTime<chrono::Utc>,
+    pub not_after: chrono::DateTime<chrono::Utc>,
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub revocation_reason: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 待写入的已签发证书记录
+#[derive(Debug)]
+pub struct NewIssuedCertificate {
+    pub ca_id: u64,
+    pub serial_number: String,
+    pub subject: String,
+    pub profile: CertificateProfile,
+    pub certificate: String,
+    pub key_id: Option<u64>,
+    pub not_before: chrono::DateTime<chrono::Utc>,
+    pub not_after: chrono::DateTime<chrono::Utc>,
+}
+
+/// CRL和OCSP使用的吊销记录
+#[derive(Debug, Clone)]
+pub struct RevokedCertificate {
+    pub serial_number: String,
+    pub revoked_at: chrono::DateTime<chrono::Utc>,
+    pub reason: RevocationReason,
+}
+
+/// OCSP查询到的证书状态
+#[derive(Debug, Clone)]
+pub enum CertificateStatus {
+    Good,
+    Revoked(RevokedCertificate),
+    /// 不是该CA签发的证书
+    Unknown,
+}
+
+/// 创建根CA或中间CA，给出parent_id时由该CA签发
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateCaRequest {
+    /// CA名称，同时用作保存CA私钥的密钥名称前缀
+    pub name: String,
+    pub common_name: String,
+    pub organization: Option<String>,
+    pub country: Option<String>,
+    pub parent_id: Option<u64>,
+    #[serde(default)]
+    pub key_algorithm: KeyAlgorithm,
+    /// 根CA默认3650天，中间CA默认1825天，且不超过上级CA的有效期
+    pub validity_days: Option<u32>,
+    /// 可签发的下级CA层数，省略时不限制（中间CA受上级CA限制）
+    pub path_length: Option<u8>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CaResponse {
+    pub id: u64,
+    pub name: String,
+    /// 保存CA证书和私钥的密钥
+    pub key_id: u64,
+    pub parent_id: Option<u64>,
+    pub subject: String,
+    /// PEM格式的CA证书
+    pub certificate: String,
+    /// PEM格式的上级CA证书链，根CA为空
+    pub chain: Option<String>,
+    pub path_length: Option<u8>,
+    pub not_after: chrono::DateTime<chrono::Utc>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<CertificateAuthority> for CaResponse {
+    fn from(ca: CertificateAuthority) -> Self {
+        Self {
+            id: ca.id,
+            name: ca.name,
+            key_id: ca.key_id,
+            parent_id: ca.parent_id,
+            subject: ca.subject,
+            certificate: ca.certificate,
+            chain: ca.chain,
+            path_length: ca.path_length,
+            not_after: ca.not_after,
+            created_at: ca.created_at,
+        }
+    }
+}
+
+/// 签发证书：给出CSR时使用其中的公钥和主题，否则由服务端生成私钥并保存为密钥
+///
+/// CSR中请求的扩展一律忽略，密钥用途由模板决定。
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct IssueCertificateRequest {
+    pub profile: CertificateProfile,
+    /// PEM格式的PKCS#10证书请求
+    pub csr: Option<String>,
+    /// 未给出CSR时必填；给出时覆盖CSR中的主题
+    pub common_name: Option<String>,
+    pub organization: Option<String>,
+    /// 给出任一主题备用名称时替换CSR中的全部备用名称
+    #[serde(default)]
+    pub dns_names: Vec<String>,
+    #[serde(default)]
+    pub ip_addresses: Vec<IpAddr>,
+    #[serde(default)]
+    pub email_addresses: Vec<String>,
+    /// 省略时使用模板的默认有效期，且不超过CA的有效期
+    pub validity_days: Option<u32>,
+    /// 服务端生成私钥时使用的算法
+    #[serde(default)]
+    pub key_algorithm: KeyAlgorithm,
+    /// 服务端生成私钥时保存证书和私钥的密钥名称，默认为`<CA名称>/<序列号>`
+    pub key_name: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct IssuedCertificateResponse {
+    pub id: u64,
+    pub ca_id: u64,
+    /// 小写十六进制序列号
+    pub serial_number: String,
+    pub subject: String,
+    pub profile: CertificateProfile,
+    /// PEM格式的证书
+    pub certificate: String,
+    /// 服务端生成私钥时保存证书和私钥的密钥，私钥通过密钥内容接口读取
+    pub key_id: Option<u64>,
+    pub not_before: chrono::DateTime<chrono::Utc>,
+    pub not_after: chrono::DateTime<chrono::Utc>,
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub revocation_reason: Option<RevocationReason>,
+}
+
+impl TryFrom<IssuedCertificate> for IssuedCertificateResponse {
+    type Error = String;
+
+    fn try_from(certificate: IssuedCertificate) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: certificate.id,
+            ca_id: certificate.ca_id,
+            serial_number: certificate.serial_number,
+            subject: certificate.subject,
+            profile: certificate.profile.parse()?,
+            certificate: certificate.certificate,
+            key_id: certificate.key_id,
+            not_before: certificate.not_before,
+            not_after: certificate.not_after,
+            revoked_at: certificate.revoked_at,
+            revocation_reason: certificate.revocation_reason.as_deref().map(str::parse).transpose()?,
+        })
+    }
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct RevokeCertificateRequest {
+    #[serde(default)]
+    pub reason: RevocationReason,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CertificateListQuery {
+    /// 只返回id大于该值的记录
+    pub after_id: Option<u64>,
+    pub limit: Option<u32>,
+    /// 为true时只返回已吊销的证书
+    #[serde(default)]
+    pub revoked: bool,
+}
+
+/// CA操作的结果
+#[derive(Debug)]
+pub enum CaOperation<T> {
+    Done(T),
+    NotFound,
+    /// 请求内容无效或与CA约束冲突
+    Invalid(String),
+    /// 名称已被占用，或证书已吊销
+    Conflict(String),
+}
@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::fmt;
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+/// 配额对象：身份凭据或来源IP
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotaSubjectType {
+    Identity,
+    Ip,
+}
+
+impl QuotaSubjectType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuotaSubjectType::Identity => "identity",
+            QuotaSubjectType::Ip => "ip",
+        }
+    }
+}
+
+impl fmt::Display for QuotaSubjectType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for QuotaSubjectType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "identity" => Ok(QuotaSubjectType::Identity),
+            "ip" => Ok(QuotaSubjectType::Ip),
+            other => Err(format!("Unknown quota subject type: {}", other)),
+        }
+    }
+}
+
+/// 被超出的配额项
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotaKind {
+    MaxKeys,
+    MaxDataBytes,
+    RequestsPerMinute,
+}
+
+impl QuotaKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuotaKind::MaxKeys => "max_keys",
+            QuotaKind::MaxDataBytes => "max_data_bytes",
+            QuotaKind::RequestsPerMinute => "requests_per_minute",
+        }
+    }
+}
+
+impl fmt::Display for QuotaKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 数据库中的配额记录
+#[derive(Debug, Clone, FromRow)]
+pub struct QuotaRecord {
+    pub id: u64,
+    pub subject_type: String,
+    pub subject: String,
+    pub max_keys: Option<u64>,
+    pub max_data_bytes: Option<u64>,
+    pub requests_per_minute: Option<u32>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 配额限制，空值表示不限制
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, ToSchema)]
+pub struct QuotaLimits {
+    pub max_keys: Option<u64>,
+    /// 单个密钥data或payload的最大字节数
+    pub max_data_bytes: Option<u64>,
+    pub requests_per_minute: Option<u32>,
+}
+
+impl From<&QuotaRecord> for QuotaLimits {
+    fn from(record: &QuotaRecord) -> Self {
+        Self {
+            max_keys: record.max_keys,
+            max_data_bytes: record.max_data_bytes,
+            requests_per_minute: record.requests_per_minute,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QuotaResponse {
+    pub subject_type: QuotaSubjectType,
+    pub subject: String,
+    #[serde(flatten)]
+    pub limits: QuotaLimits,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl TryFrom<QuotaRecord> for QuotaResponse {
+    type Error = String;
+
+    fn try_from(record: QuotaRecord) -> Result<Self, Self::Error> {
+        Ok(Self {
+            subject_type: record.subject_type.parse()?,
+            limits: QuotaLimits::from(&record),
+            subject: record.subject,
+            created_at: record.created_at,
+            updated_at: record.updated_at,
+        })
+    }
+}
+
+/// 配额对象的当前用量及生效的限制
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QuotaUsage {
+    pub subject_type: QuotaSubjectType,
+    pub subject: String,
+    /// 生效的限制；身份没有单独配置时使用服务端默认值
+    pub limits: QuotaLimits,
+    /// 该对象创建且仍存在的密钥数
+    pub keys: u64,
+    /// 当前一分钟窗口内的请求数
+    pub requests_this_minute: u32,
+}
+
+/// 调用方自身的配额用量
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CallerUsageResponse {
+    pub identity: QuotaUsage,
+    /// 来源IP的用量，未对该IP配置配额时为空
+    pub ip: Option<QuotaUsage>,
+}
+
+/// 超出配额，API以429返回并与限流、锁定等其它错误区分
+#[derive(Debug, Clone)]
+pub struct QuotaExceeded {
+    pub kind: QuotaKind,
+    pub subject_type: QuotaSubjectType,
+    pub subject: String,
+    pub limit: u64,
+    /// 请求数配额的窗口重置前剩余秒数
+    pub retry_after_secs: Option<u64>,
+}
+
+impl fmt::Display for QuotaExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Quota exceeded: {} limit of {} for {} {}",
+            self.kind, self.limit, self.subject_type, self.subject
+        )?;
+        if let Some(secs) = self.retry_after_secs {
+            write!(f, ", retry after {} seconds", secs)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for QuotaExceeded {}
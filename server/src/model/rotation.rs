@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+/// 密钥轮换策略
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct RotationPolicy {
+    pub key_id: u64,
+    pub interval_days: u32,
+    pub last_rotated_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub next_rotation_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetRotationPolicyRequest {
+    /// 轮换周期（天），为空表示取消策略
+    pub interval_days: Option<u32>,
+}
+
+/// 到期或逾期待轮换的密钥
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct DueRotation {
+    pub key_id: u64,
+    pub name: String,
+    pub interval_days: u32,
+    pub last_rotated_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub next_rotation_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DueRotationQuery {
+    /// 额外包含未来若干天内到期的密钥
+    pub within_days: Option<u32>,
+}
+
+/// 一次轮换留下的历史版本（不含密钥内容）
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct KeyVersion {
+    pub key_id: u64,
+    /// 该值在keys.version（ETag）中的版本号，不同历史版本之间可能不连续
+    pub version: u32,
+    pub trigger_type: String,
+    pub rotated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct KeyVersionRecord {
+    pub key_id: u64,
+    pub version: u32,
+    pub encrypted_data: String,
+}
+
+/// 轮换触发方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RotationTrigger {
+    Scheduled,
+    Manual,
+}
+
+impl RotationTrigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RotationTrigger::Scheduled => "scheduled",
+            RotationTrigger::Manual => "manual",
+        }
+    }
+}
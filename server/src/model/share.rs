@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetPublicKeyRequest {
+    /// Base64编码的X25519公钥
+    pub public_key: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PublicKeyResponse {
+    pub identity_id: u64,
+    pub public_key: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateShareRequest {
+    pub recipient_id: u64,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// 共享记录，包含来源信息
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct ShareResponse {
+    pub id: u64,
+    pub key_id: u64,
+    pub key_name: String,
+    pub sharer_id: u64,
+    pub sharer_name: String,
+    pub recipient_id: u64,
+    pub recipient_name: String,
+    /// Base64编码的封装数据，只有接收方私钥可以解开
+    pub sealed_data: String,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
@@ -0,0 +1,268 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use utoipa::{IntoParams, ToSchema};
+
+/// SSH CA和服务端生成SSH密钥的算法
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SshKeyAlgorithm {
+    #[default]
+    Ed25519,
+    Rsa3072,
+}
+
+/// SSH证书类型，取值与OpenSSH证书中的type字段对应
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SshCertificateType {
+    #[default]
+    User,
+    Host,
+}
+
+impl SshCertificateType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SshCertificateType::User => "user",
+            SshCertificateType::Host => "host",
+        }
+    }
+
+    /// SSH_CERT_TYPE_USER为1，SSH_CERT_TYPE_HOST为2
+    pub fn code(&self) -> u32 {
+        match self {
+            SshCertificateType::User => 1,
+            SshCertificateType::Host => 2,
+        }
+    }
+}
+
+impl fmt::Display for SshCertificateType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SshCertificateType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "user" => Ok(SshCertificateType::User),
+            "host" => Ok(SshCertificateType::Host),
+            other => Err(format!("Unknown SSH certificate type: {}", other)),
+        }
+    }
+}
+
+/// 数据库中的SSH CA记录
+#[derive(Debug, Clone, FromRow)]
+pub struct SshCertificateAuthority {
+    pub id: u64,
+    pub name: String,
+    pub key_id: u64,
+    pub public_key: String,
+    pub allow_user_certificates: bool,
+    pub allow_host_certificates: bool,
+    pub max_validity_secs: u64,
+    pub allowed_principals: Option<sqlx::types::Json<Vec<String>>>,
+    pub allowed_extensions: Option<sqlx::types::Json<Vec<String>>>,
+    pub allowed_critical_options: Option<sqlx::types::Json<Vec<String>>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 待写入的SSH CA记录
+#[derive(Debug)]
+pub struct NewSshCertificateAuthority {
+    pub name: String,
+    pub key_id: u64,
+    pub public_key: String,
+    pub allow_user_certificates: bool,
+    pub allow_host_certificates: bool,
+    pub max_validity_secs: u64,
+    pub allowed_principals: Option<Vec<String>>,
+    pub allowed_extensions: Option<Vec<String>>,
+    pub allowed_critical_options: Option<Vec<String>>,
+}
+
+/// 数据库中的SSH证书记录
+#[derive(Debug, Clone, FromRow)]
+pub struct SshCertificateRecord {
+    pub id: u64,
+    pub ca_id: u64,
+    pub serial: u64,
+    pub certificate_type: String,
+    pub key_identifier: String,
+    pub principals: sqlx::types::Json<Vec<String>>,
+    pub public_key_fingerprint: String,
+    pub certificate: String,
+    pub valid_after: chrono::DateTime<chrono::Utc>,
+    pub valid_before: chrono::DateTime<chrono::Utc>,
+    pub issued_by: Option<u64>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 待写入的SSH证书记录
+#[derive(Debug)]
+pub struct NewSshCertificate {
+    pub ca_id: u64,
+    pub serial: u64,
+    pub certificate_type: SshCertificateType,
+    pub key_identifier: String,
+    pub principals: Vec<String>,
+    pub public_key_fingerprint: String,
+    pub certificate: String,
+    pub valid_after: chrono::DateTime<chrono::Utc>,
+    pub valid_before: chrono::DateTime<chrono::Utc>,
+    pub issued_by: u64,
+}
+
+/// 创建SSH CA，私钥由服务端生成
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateSshCaRequest {
+    /// CA名称，同时用作保存CA私钥的密钥名称后缀
+    pub name: String,
+    #[serde(default)]
+    pub key_algorithm: SshKeyAlgorithm,
+    /// 默认为true
+    pub allow_user_certificates: Option<bool>,
+    /// 默认为false
+    pub allow_host_certificates: Option<bool>,
+    /// 单张证书的最长有效期（秒），默认为86400
+    pub max_validity_secs: Option<u64>,
+    /// 可签入证书的principal，省略时不限制；`*`匹配任意值，`*.example.com`匹配该域名的子域
+    pub allowed_principals: Option<Vec<String>>,
+    /// 可签入用户证书的扩展，省略时不限制
+    pub allowed_extensions: Option<Vec<String>>,
+    /// 可签入用户证书的关键选项，省略时不限制
+    pub allowed_critical_options: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SshCaResponse {
+    pub id: u64,
+    pub name: String,
+    /// 保存CA私钥的密钥
+    pub key_id: u64,
+    /// authorized_keys格式的CA公钥，可用于TrustedUserCAKeys或known_hosts中的@cert-authority
+    pub public_key: String,
+    pub allow_user_certificates: bool,
+    pub allow_host_certificates: bool,
+    pub max_validity_secs: u64,
+    pub allowed_principals: Option<Vec<String>>,
+    pub allowed_extensions: Option<Vec<String>>,
+    pub allowed_critical_options: Option<Vec<String>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<SshCertificateAuthority> for SshCaResponse {
+    fn from(ca: SshCertificateAuthority) -> Self {
+        Self {
+            id: ca.id,
+            name: ca.name,
+            key_id: ca.key_id,
+            public_key: ca.public_key,
+            allow_user_certificates: ca.allow_user_certificates,
+            allow_host_certificates: ca.allow_host_certificates,
+            max_validity_secs: ca.max_validity_secs,
+            allowed_principals: ca.allowed_principals.map(|principals| principals.0),
+            allowed_extensions: ca.allowed_extensions.map(|extensions| extensions.0),
+            allowed_critical_options: ca.allowed_critical_options.map(|options| options.0),
+            created_at: ca.created_at,
+        }
+    }
+}
+
+/// 将公钥签名为SSH证书
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SignSshKeyRequest {
+    /// authorized_keys格式的待签名公钥
+    pub public_key: String,
+    #[serde(default)]
+    pub certificate_type: SshCertificateType,
+    /// 证书中的key id，sshd会将其写入日志；默认为`<CA名称>/<序列号>`
+    pub key_identifier: Option<String>,
+    /// 用户名或主机名，至少一个
+    pub principals: Vec<String>,
+    /// 生效时间，默认为当前时间
+    pub valid_after: Option<chrono::DateTime<chrono::Utc>>,
+    /// 有效时长（秒），默认为3600且不超过CA的限制
+    pub validity_secs: Option<u64>,
+    /// 关键选项，如force-command、source-address；只用于用户证书
+    #[serde(default)]
+    pub critical_options: BTreeMap<String, String>,
+    /// 扩展，省略时用户证书使用与ssh-keygen相同的默认扩展；只用于用户证书
+    pub extensions: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SshCertificateResponse {
+    pub id: u64,
+    pub ca_id: u64,
+    pub serial: u64,
+    pub certificate_type: SshCertificateType,
+    pub key_identifier: String,
+    pub principals: Vec<String>,
+    /// 被签名公钥的SHA256指纹
+    pub public_key_fingerprint: String,
+    /// OpenSSH格式的证书，可直接保存为`<私钥文件>-cert.pub`
+    pub certificate: String,
+    pub valid_after: chrono::DateTime<chrono::Utc>,
+    pub valid_before: chrono::DateTime<chrono::Utc>,
+    pub issued_by: Option<u64>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl TryFrom<SshCertificateRecord> for SshCertificateResponse {
+    type Error = String;
+
+    fn try_from(record: SshCertificateRecord) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: record.id,
+            ca_id: record.ca_id,
+            serial: record.serial,
+            certificate_type: record.certificate_type.parse()?,
+            key_identifier: record.key_identifier,
+            principals: record.principals.0,
+            public_key_fingerprint: record.public_key_fingerprint,
+            certificate: record.certificate,
+            valid_after: record.valid_after,
+            valid_before: record.valid_before,
+            issued_by: record.issued_by,
+            created_at: record.created_at,
+        })
+    }
+}
+
+/// 由服务端生成SSH密钥对并保存为ssh_key类型的密钥
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct GenerateSshKeyRequest {
+    pub name: String,
+    #[serde(default)]
+    pub algorithm: SshKeyAlgorithm,
+    /// 公钥的注释，默认为密钥名称
+    pub comment: Option<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GeneratedSshKeyResponse {
+    pub key_id: u64,
+    pub name: String,
+    /// authorized_keys格式的公钥
+    pub public_key: String,
+    pub fingerprint: String,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SshCertificateListQuery {
+    /// 只返回id大于该值的记录
+    pub after_id: Option<u64>,
+    pub limit: Option<u32>,
+}
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::fmt;
+use utoipa::ToSchema;
+
+/// 选择团队上下文的请求头，gRPC中为同名metadata；未给出时使用调用方所属的第一个团队
+pub const TEAM_HEADER: &str = "x-team-id";
+
+/// 组织，团队的上级
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct Organization {
+    pub id: u64,
+    pub name: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateOrganizationRequest {
+    pub name: String,
+}
+
+/// 团队及其所属组织
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct Team {
+    pub id: u64,
+    pub organization_id: u64,
+    pub organization_name: String,
+    pub name: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateTeamRequest {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AddMemberRequest {
+    pub identity_id: u64,
+}
+
+/// 团队成员
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct TeamMember {
+    pub identity_id: u64,
+    pub name: String,
+    pub role: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 调用方所属的团队及当前请求使用的团队
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CallerTeamsResponse {
+    pub current_team_id: Option<u64>,
+    pub teams: Vec<Team>,
+}
+
+/// 组织和团队管理操作的结果
+#[derive(Debug)]
+pub enum TeamOperation<T> {
+    Done(T),
+    NotFound,
+    Invalid(String),
+    Conflict(String),
+}
+
+/// 调用方不属于任何团队，无法创建密钥
+#[derive(Debug, Clone, Copy)]
+pub struct NoTeamContext;
+
+impl fmt::Display for NoTeamContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("No team context: the caller is not a member of any team")
+    }
+}
+
+impl std::error::Error for NoTeamContext {}
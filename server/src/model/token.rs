@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+use crate::model::access::Permission;
+
+/// 令牌可执行的密钥操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenOperation {
+    Create,
+    ReadMetadata,
+    ReadMaterial,
+    Update,
+    Delete,
+    Use,
+}
+
+impl From<Permission> for TokenOperation {
+    fn from(permission: Permission) -> Self {
+        match permission {
+            Permission::ReadMetadata => TokenOperation::ReadMetadata,
+            Permission::ReadMaterial => TokenOperation::ReadMaterial,
+            Permission::Update => TokenOperation::Update,
+            Permission::Delete => TokenOperation::Delete,
+            Permission::Use => TokenOperation::Use,
+        }
+    }
+}
+
+/// 令牌作用范围：允许对哪些密钥名执行哪些操作
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TokenScope {
+    /// 密钥名，以`*`结尾表示前缀匹配，例如 `ci/*`
+    pub key_names: Vec<String>,
+    pub operations: Vec<TokenOperation>,
+}
+
+impl TokenScope {
+    fn matches_name(&self, name: &str) -> bool {
+        self.key_names.iter().any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => name.starts_with(prefix),
+            None => pattern == name,
+        })
+    }
+}
+
+/// 通过API令牌认证的请求附带的作用范围，由认证中间件写入请求扩展
+#[derive(Debug, Clone)]
+pub struct TokenGrant {
+    pub token_id: u64,
+    pub scopes: Vec<TokenScope>,
+}
+
+impl TokenGrant {
+    /// 任一作用范围同时匹配密钥名和操作即允许
+    pub fn allows(&self, key_name: &str, operation: TokenOperation) -> bool {
+        self.scopes
+            .iter()
+            .any(|scope| scope.operations.contains(&operation) && scope.matches_name(key_name))
+    }
+}
+
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct ServiceAccount {
+    pub id: u64,
+    pub identity_id: u64,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateServiceAccountRequest {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// 数据库中的API令牌记录
+#[derive(Debug, FromRow)]
+pub struct ApiTokenRecord {
+    pub id: u64,
+    pub service_account_id: u64,
+    pub name: String,
+    pub token_prefix: String,
+    pub scopes: sqlx::types::Json<Vec<TokenScope>>,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_used_ip: Option<String>,
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateTokenRequest {
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    /// 有效天数，缺省为90天
+    #[serde(default)]
+    pub expires_in_days: Option<u32>,
+}
+
+/// 令牌信息，不含令牌明文
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApiTokenResponse {
+    pub id: u64,
+    pub service_account_id: u64,
+    pub name: String,
+    pub token_prefix: String,
+    pub scopes: Vec<TokenScope>,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_used_ip: Option<String>,
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<ApiTokenRecord> for ApiTokenResponse {
+    fn from(record: ApiTokenRecord) -> Self {
+        Self {
+            id: record.id,
+            service_account_id: record.service_account_id,
+            name: record.name,
+            token_prefix: record.token_prefix,
+            scopes: record.scopes.0,
+            expires_at: record.expires_at,
+            last_used_at: record.last_used_at,
+            last_used_ip: record.last_used_ip,
+            revoked_at: record.revoked_at,
+            created_at: record.created_at,
+        }
+    }
+}
+
+/// 创建令牌的响应，令牌明文只在此处返回一次
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreateTokenResponse {
+    pub token: String,
+    #[serde(flatten)]
+    pub info: ApiTokenResponse,
+}
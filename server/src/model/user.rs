@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+use crate::model::access::Role;
+
+/// 数据库中的用户记录
+#[derive(Debug, FromRow)]
+pub struct UserRecord {
+    pub id: u64,
+    pub username: String,
+    pub password_hash: String,
+    pub identity_id: u64,
+    /// 加密后的TOTP种子
+    pub totp_secret: Option<String>,
+    pub totp_enabled: bool,
+    pub totp_last_step: Option<i64>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateUserRequest {
+    pub username: String,
+    pub password: String,
+    pub role: Role,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UserResponse {
+    pub id: u64,
+    pub username: String,
+    pub identity_id: u64,
+    pub role: Role,
+    pub totp_enabled: bool,
+}
+
+/// 登录请求，启用TOTP后需提供totp_code或一次性恢复码
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct LoginRequest {
+    pub username: String,
+    pub password: String,
+    #[serde(default)]
+    pub totp_code: Option<String>,
+    #[serde(default)]
+    pub recovery_code: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LoginResponse {
+    /// 会话令牌，作为Bearer凭据使用
+    pub token: String,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 登录结果
+#[derive(Debug)]
+pub enum LoginOutcome {
+    Success(LoginResponse),
+    InvalidCredentials,
+    /// 已启用TOTP但未提供第二因子
+    SecondFactorRequired,
+    InvalidSecondFactor,
+}
+
+/// TOTP登记信息，种子只在此处返回一次
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TotpEnrollment {
+    pub secret: String,
+    pub provisioning_uri: String,
+    /// 编码provisioning_uri的SVG二维码
+    pub qr_svg: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TotpConfirmRequest {
+    pub code: String,
+}
+
+/// 一次性恢复码，明文只在此处返回一次
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+use shared::crypto::vault::{Ciphertext, KdfParams};
+use shared::secret::SecretKind;
+use sqlx::FromRow;
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+/// 数据库中的保险库记录
+#[derive(Debug, FromRow)]
+pub struct VaultRecord {
+    pub id: u64,
+    pub name: String,
+    pub owner_identity_id: u64,
+    pub kdf_params: sqlx::types::Json<KdfParams>,
+    pub wrapped_key: String,
+    pub wrapped_key_nonce: String,
+    pub version: u32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 保险库信息，客户端据此派生包装密钥并解开保险库密钥
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VaultResponse {
+    pub id: u64,
+    pub name: String,
+    pub owner_identity_id: u64,
+    pub kdf: KdfParams,
+    pub wrapped_key: Ciphertext,
+    pub version: u32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<VaultRecord> for VaultResponse {
+    fn from(record: VaultRecord) -> Self {
+        Self {
+            id: record.id,
+            name: record.name,
+            owner_identity_id: record.owner_identity_id,
+            kdf: record.kdf_params.0,
+            wrapped_key: Ciphertext {
+                nonce: record.wrapped_key_nonce,
+                ciphertext: record.wrapped_key,
+            },
+            version: record.version,
+            created_at: record.created_at,
+            updated_at: record.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateVaultRequest {
+    pub name: String,
+    pub kdf: KdfParams,
+    /// 由主密码派生的密钥包装后的保险库密钥
+    pub wrapped_key: Ciphertext,
+}
+
+/// 修改主密码：用新KDF参数派生的密钥重新包装同一个保险库密钥
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RewrapVaultRequest {
+    pub kdf: KdfParams,
+    pub wrapped_key: Ciphertext,
+}
+
+/// 在保险库中创建密钥，密钥值由客户端加密
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateVaultKeyRequest {
+    pub name: String,
+    pub encrypted_data: String,
+    pub nonce: String,
+    /// 客户端声明的密钥类型，服务端无法校验密文内容
+    #[serde(default)]
+    pub kind: Option<SecretKind>,
+    #[serde(default)]
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    #[serde(default)]
+    pub metadata: Option<serde_json::Value>,
+}
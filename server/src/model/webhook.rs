@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::fmt;
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+/// Webhook事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum WebhookEvent {
+    #[serde(rename = "key.created")]
+    KeyCreated,
+    #[serde(rename = "key.deleted")]
+    KeyDeleted,
+    #[serde(rename = "certificate.expiring")]
+    CertificateExpiring,
+}
+
+impl WebhookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::KeyCreated => "key.created",
+            WebhookEvent::KeyDeleted => "key.deleted",
+            WebhookEvent::CertificateExpiring => "certificate.expiring",
+        }
+    }
+}
+
+impl fmt::Display for WebhookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for WebhookEvent {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "key.created" => Ok(WebhookEvent::KeyCreated),
+            "key.deleted" => Ok(WebhookEvent::KeyDeleted),
+            "certificate.expiring" => Ok(WebhookEvent::CertificateExpiring),
+            other => Err(format!("Unknown webhook event: {}", other)),
+        }
+    }
+}
+
+/// 数据库中的Webhook记录，events为逗号分隔的事件类型
+#[derive(Debug, Clone, FromRow)]
+pub struct WebhookRecord {
+    pub id: u64,
+    /// 所属团队，只投递该团队密钥的事件
+    pub team_id: u64,
+    pub url: String,
+    pub encrypted_secret: String,
+    pub events: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WebhookResponse {
+    pub id: u64,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 创建Webhook的响应，签名密钥只在此处返回一次
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreateWebhookResponse {
+    #[serde(flatten)]
+    pub webhook: WebhookResponse,
+    pub secret: String,
+}
+
+/// 发送给接收方的事件体
+#[derive(Debug, Serialize)]
+pub struct WebhookPayload {
+    pub id: String,
+    pub event: WebhookEvent,
+    pub occurred_at: chrono::DateTime<chrono::Utc>,
+    pub data: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct DeadLetter {
+    pub id: u64,
+    pub webhook_id: u64,
+    pub event_type: String,
+    pub payload: String,
+    pub attempts: u32,
+    pub last_error: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 即将过期的密钥
+#[derive(Debug, FromRow)]
+pub struct ExpiringKey {
+    pub id: u64,
+    pub team_id: u64,
+    pub name: String,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
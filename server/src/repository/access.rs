@@ -0,0 +1,134 @@
+use crate::model::access::{IdentityRecord, KeyAcl};
+use crate::repository::team as team_repository;
+use sqlx::{MySqlConnection, MySqlPool, Result};
+
+/// 在同一事务中创建身份并加入团队
+pub async fn create_identity(
+    pool: &MySqlPool,
+    name: &str,
+    role: &str,
+    credential_hash: &str,
+    team_id: u64,
+) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+
+    let id = sqlx::query!(
+        r#"
+        INSERT INTO identities (name, role, credential_hash, created_at)
+        VALUES (?, ?, ?, NOW())
+        "#,
+        name,
+        role,
+        credential_hash
+    )
+    .execute(&mut *tx)
+    .await?
+    .last_insert_id();
+
+    team_repository::add_member(&mut *tx, team_id, id).await?;
+
+    tx.commit().await?;
+    Ok(id)
+}
+
+/// 根据凭据哈希查找身份
+pub async fn get_identity_by_credential_hash(
+    pool: &MySqlPool,
+    credential_hash: &str,
+) -> Result<Option<IdentityRecord>> {
+    let identity = sqlx::query_as!(IdentityRecord,
+        r#"
+        SELECT id, name, role, credential_hash, created_at
+        FROM identities
+        WHERE credential_hash = ?
+        "#,
+        credential_hash
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(identity)
+}
+
+pub async fn get_identity_by_id(pool: &MySqlPool, id: u64) -> Result<Option<IdentityRecord>> {
+    let identity = sqlx::query_as!(IdentityRecord,
+        r#"
+        SELECT id, name, role, credential_hash, created_at
+        FROM identities
+        WHERE id = ?
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(identity)
+}
+
+/// 统计身份数量，用于首次启动时的管理员引导
+pub async fn count_identities(pool: &MySqlPool) -> Result<i64> {
+    let count = sqlx::query_scalar!("SELECT COUNT(*) FROM identities")
+        .fetch_one(pool)
+        .await?;
+
+    Ok(count)
+}
+
+/// 新增ACL授权
+pub async fn create_grant(
+    conn: &mut MySqlConnection,
+    key_id: u64,
+    subject_type: &str,
+    subject: &str,
+    permission: &str,
+) -> Result<u64> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO key_acls (key_id, subject_type, subject, permission, created_at)
+        VALUES (?, ?, ?, ?, NOW())
+        ON DUPLICATE KEY UPDATE id = LAST_INSERT_ID(id)
+        "#,
+        key_id,
+        subject_type,
+        subject,
+        permission
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(result.last_insert_id())
+}
+
+/// 列出某个密钥的全部ACL
+/// 在给定连接上列出密钥的ACL授权，事务内调用时能读到本事务的修改
+pub async fn list_grants(conn: &mut MySqlConnection, key_id: u64) -> Result<Vec<KeyAcl>> {
+    let grants = sqlx::query_as!(KeyAcl,
+        r#"
+        SELECT id, key_id, subject_type, subject, permission, created_at
+        FROM key_acls
+        WHERE key_id = ?
+        ORDER BY id
+        "#,
+        key_id
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(grants)
+}
+
+/// 删除ACL授权
+pub async fn delete_grant(pool: &MySqlPool, key_id: u64, acl_id: u64) -> Result<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM key_acls
+        WHERE id = ? AND key_id = ?
+        "#,
+        acl_id,
+        key_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use crate::model::audit::{AuditEvent, AuditHead, AuditQuery};
+use sqlx::{MySql, MySqlPool, QueryBuilder, Result, Transaction};
+
+/// 锁定并读取链尾锚点，跨进程串行化哈希链的追加
+pub async fn lock_head(tx: &mut Transaction<'_, MySql>) -> Result<AuditHead> {
+    let head = sqlx::query_as!(AuditHead,
+        r#"
+        SELECT last_id, last_hash, entries, mac
+        FROM audit_head
+        WHERE id = 1
+        FOR UPDATE
+        "#
+    )
+    .fetch_one(&mut **tx)
+    .await?;
+
+    Ok(head)
+}
+
+pub async fn get_head(pool: &MySqlPool) -> Result<Option<AuditHead>> {
+    let head = sqlx::query_as!(AuditHead,
+        r#"
+        SELECT last_id, last_hash, entries, mac
+        FROM audit_head
+        WHERE id = 1
+        "#
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(head)
+}
+
+pub async fn update_head(tx: &mut Transaction<'_, MySql>, head: &AuditHead) -> Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE audit_head
+        SET last_id = ?, last_hash = ?, entries = ?, mac = ?, updated_at = NOW(6)
+        WHERE id = 1
+        "#,
+        head.last_id,
+        head.last_hash,
+        head.entries,
+        head.mac
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn insert_event(tx: &mut Transaction<'_, MySql>, event: &AuditEvent) -> Result<u64> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO audit_events
+            (actor_id, actor_name, action, key_id, outcome, status_code, source_ip, request_id, created_at, prev_hash, hash)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+        event.actor_id,
+        event.actor_name,
+        event.action,
+        event.key_id,
+        event.outcome,
+        event.status_code,
+        event.source_ip,
+        event.request_id,
+        event.created_at,
+        event.prev_hash,
+        event.hash
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(result.last_insert_id())
+}
+
+/// 按条件查询审计事件（按时间倒序）
+pub async fn query_events(pool: &MySqlPool, query: &AuditQuery, limit: u32) -> Result<Vec<AuditEvent>> {
+    let mut builder = QueryBuilder::<MySql>::new(
+        "SELECT id, actor_id, actor_name, action, key_id, outcome, status_code, source_ip, request_id, created_at, prev_hash, hash \
+         FROM audit_events WHERE 1 = 1",
+    );
+
+    if let Some(actor_id) = query.actor_id {
+        builder.push(" AND actor_id = ").push_bind(actor_id);
+    }
+    if let Some(action) = &query.action {
+        builder.push(" AND action = ").push_bind(action);
+    }
+    if let Some(key_id) = query.key_id {
+        builder.push(" AND key_id = ").push_bind(key_id);
+    }
+    if let Some(outcome) = &query.outcome {
+        builder.push(" AND outcome = ").push_bind(outcome);
+    }
+    if let Some(from) = query.from {
+        builder.push(" AND created_at >= ").push_bind(from);
+    }
+    if let Some(to) = query.to {
+        builder.push(" AND created_at < ").push_bind(to);
+    }
+    builder.push(" ORDER BY id DESC LIMIT ").push_bind(limit);
+
+    builder.build_query_as::<AuditEvent>().fetch_all(pool).await
+}
+
+/// 按id顺序分批读取审计事件，用于哈希链校验
+pub async fn list_events_after(pool: &MySqlPool, after_id: u64, limit: u32) -> Result<Vec<AuditEvent>> {
+    let events = sqlx::query_as!(AuditEvent,
+        r#"
+        SELECT id, actor_id, actor_name, action, key_id, outcome, status_code, source_ip, request_id, created_at, prev_hash, hash
+        FROM audit_events
+        WHERE id > ?
+        ORDER BY id
+        LIMIT ?
+        "#,
+        after_id,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(events)
+}
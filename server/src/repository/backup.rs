@@ -0,0 +1,261 @@
+use crate::model::backup::{BackupAcl, BackupKey, BackupKeyVersion, BackupTeam, BackupVault};
+use crate::model::key::Key;
+use sqlx::types::Json;
+use sqlx::{MySql, MySqlPool, Result, Transaction};
+
+/// 按id顺序分批读取密钥，`team_id`为空时读取整张表
+pub async fn list_keys_after(pool: &MySqlPool, team_id: Option<u64>, after_id: u64, limit: u32) -> Result<Vec<Key>> {
+    let keys = sqlx::query_as!(Key,
+        r#"
+        SELECT id, name, team_id, encrypted_data, kind, expires_at, description, metadata, version, vault_id, nonce, created_at, updated_at
+        FROM keys
+        WHERE (? IS NULL OR team_id = ?) AND id > ?
+        ORDER BY id
+        LIMIT ?
+        "#,
+        team_id,
+        team_id,
+        after_id,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(keys)
+}
+
+pub async fn list_key_versions(pool: &MySqlPool, key_id: u64) -> Result<Vec<BackupKeyVersion>> {
+    let versions = sqlx::query_as!(BackupKeyVersion,
+        r#"
+        SELECT version, encrypted_data, trigger_type, rotated_at
+        FROM key_versions
+        WHERE key_id = ?
+        ORDER BY version
+        "#,
+        key_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(versions)
+}
+
+pub async fn get_key_id_by_name(pool: &MySqlPool, team_id: u64, name: &str) -> Result<Option<u64>> {
+    let id = sqlx::query_scalar!(
+        r#"
+        SELECT id
+        FROM keys
+        WHERE team_id = ? AND name = ?
+        "#,
+        team_id,
+        name
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(id)
+}
+
+pub async fn delete_key_by_name(tx: &mut Transaction<'_, MySql>, team_id: u64, name: &str) -> Result<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM keys
+        WHERE team_id = ? AND name = ?
+        "#,
+        team_id,
+        name
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// 插入恢复的密钥，保留原始时间戳
+pub async fn insert_key(
+    tx: &mut Transaction<'_, MySql>,
+    team_id: u64,
+    name: &str,
+    encrypted_data: &str,
+    vault_id: Option<u64>,
+    key: &BackupKey,
+) -> Result<u64> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO keys (name, team_id, encrypted_data, kind, expires_at, description, metadata, vault_id, nonce, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+        name,
+        team_id,
+        encrypted_data,
+        key.kind,
+        key.expires_at,
+        key.description,
+        key.metadata,
+        vault_id,
+        key.nonce,
+        key.created_at,
+        key.updated_at
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(result.last_insert_id())
+}
+
+/// 按组织和团队名称查找团队
+pub async fn find_team(pool: &MySqlPool, team: &BackupTeam) -> Result<Option<u64>> {
+    let id = sqlx::query_scalar!(
+        r#"
+        SELECT t.id
+        FROM teams t
+        JOIN organizations o ON o.id = t.organization_id
+        WHERE o.name = ? AND t.name = ?
+        "#,
+        team.organization,
+        team.name
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(id)
+}
+
+/// 按名称查找团队，组织或团队不存在时重建
+pub async fn get_or_create_team(tx: &mut Transaction<'_, MySql>, team: &BackupTeam) -> Result<u64> {
+    sqlx::query!(
+        r#"
+        INSERT IGNORE INTO organizations (name, created_at)
+        VALUES (?, NOW())
+        "#,
+        team.organization
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        INSERT IGNORE INTO teams (organization_id, name, created_at)
+        SELECT id, ?, NOW()
+        FROM organizations
+        WHERE name = ?
+        "#,
+        team.name,
+        team.organization
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    let id = sqlx::query_scalar!(
+        r#"
+        SELECT t.id
+        FROM teams t
+        JOIN organizations o ON o.id = t.organization_id
+        WHERE o.name = ? AND t.name = ?
+        "#,
+        team.organization,
+        team.name
+    )
+    .fetch_one(&mut **tx)
+    .await?;
+
+    Ok(id)
+}
+
+/// 按名称查找保险库，不存在时以备份中的包装密钥重建
+pub async fn get_or_create_vault(tx: &mut Transaction<'_, MySql>, vault: &BackupVault) -> Result<u64> {
+    let existing = sqlx::query_scalar!(
+        r#"
+        SELECT id
+        FROM vaults
+        WHERE name = ?
+        "#,
+        vault.name
+    )
+    .fetch_optional(&mut **tx)
+    .await?;
+    if let Some(id) = existing {
+        return Ok(id);
+    }
+
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO vaults (name, owner_identity_id, kdf_params, wrapped_key, wrapped_key_nonce, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, NOW(), NOW())
+        "#,
+        vault.name,
+        vault.owner_identity_id,
+        Json(&vault.kdf),
+        vault.wrapped_key.ciphertext,
+        vault.wrapped_key.nonce
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(result.last_insert_id())
+}
+
+pub async fn insert_key_version(
+    tx: &mut Transaction<'_, MySql>,
+    key_id: u64,
+    version: &BackupKeyVersion,
+    encrypted_data: &str,
+) -> Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO key_versions (key_id, version, encrypted_data, trigger_type, rotated_at)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+        key_id,
+        version.version,
+        encrypted_data,
+        version.trigger_type,
+        version.rotated_at
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn insert_rotation_policy(
+    tx: &mut Transaction<'_, MySql>,
+    key_id: u64,
+    key: &BackupKey,
+) -> Result<()> {
+    let Some(policy) = &key.rotation_policy else {
+        return Ok(());
+    };
+
+    sqlx::query!(
+        r#"
+        INSERT INTO key_rotation_policies (key_id, interval_days, last_rotated_at, next_rotation_at, created_at, updated_at)
+        VALUES (?, ?, ?, ?, NOW(), NOW())
+        "#,
+        key_id,
+        policy.interval_days,
+        policy.last_rotated_at,
+        policy.next_rotation_at
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn insert_acl(tx: &mut Transaction<'_, MySql>, key_id: u64, acl: &BackupAcl) -> Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT IGNORE INTO key_acls (key_id, subject_type, subject, permission, created_at)
+        VALUES (?, ?, ?, ?, NOW())
+        "#,
+        key_id,
+        acl.subject_type,
+        acl.subject,
+        acl.permission
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
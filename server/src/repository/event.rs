@@ -0,0 +1,75 @@
+use crate::model::event::KeyEventRecord;
+use sqlx::{MySqlPool, Result};
+
+pub async fn insert_event(
+    pool: &MySqlPool,
+    event_type: &str,
+    key_id: u64,
+    key_name: &str,
+    version: Option<u32>,
+    audience: Option<&str>,
+) -> Result<u64> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO key_events (event_type, key_id, key_name, version, audience, created_at)
+        VALUES (?, ?, ?, ?, ?, NOW())
+        "#,
+        event_type,
+        key_id,
+        key_name,
+        version,
+        audience
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.last_insert_id())
+}
+
+pub async fn get_event(pool: &MySqlPool, id: u64) -> Result<Option<KeyEventRecord>> {
+    let event = sqlx::query_as!(KeyEventRecord,
+        r#"
+        SELECT id, event_type, key_id, key_name, version, audience, created_at
+        FROM key_events
+        WHERE id = ?
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(event)
+}
+
+/// 按游标顺序读取cursor之后的事件
+pub async fn list_after(pool: &MySqlPool, cursor: u64, limit: u32) -> Result<Vec<KeyEventRecord>> {
+    let events = sqlx::query_as!(KeyEventRecord,
+        r#"
+        SELECT id, event_type, key_id, key_name, version, audience, created_at
+        FROM key_events
+        WHERE id > ?
+        ORDER BY id
+        LIMIT ?
+        "#,
+        cursor,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(events)
+}
+
+/// 最新事件的游标，没有事件时为0
+pub async fn latest_cursor(pool: &MySqlPool) -> Result<u64> {
+    let cursor = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(MAX(id), 0) AS "cursor!: u64"
+        FROM key_events
+        "#
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(cursor)
+}
@@ -0,0 +1,23 @@
+use sqlx::{MySqlPool, Result};
+
+/// 执行一次最简单的查询以确认数据库可用
+pub async fn ping(pool: &MySqlPool) -> Result<()> {
+    sqlx::query!("SELECT 1 AS ok").fetch_one(pool).await?;
+    Ok(())
+}
+
+/// 当前库中是否存在指定的表
+pub async fn table_exists(pool: &MySqlPool, table_name: &str) -> Result<bool> {
+    let count = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*)
+        FROM information_schema.tables
+        WHERE table_schema = DATABASE() AND table_name = ?
+        "#,
+        table_name
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count > 0)
+}
@@ -0,0 +1,90 @@
+use crate::model::idempotency::IdempotencyRecord;
+use sqlx::{MySqlPool, Result};
+
+/// 占用幂等键，返回是否为首次插入
+pub async fn try_insert(pool: &MySqlPool, identity_id: u64, idempotency_key: &str, request_hash: &str) -> Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        INSERT IGNORE INTO idempotency_keys (identity_id, idempotency_key, request_hash, status_code, created_at)
+        VALUES (?, ?, ?, 0, NOW())
+        "#,
+        identity_id,
+        idempotency_key,
+        request_hash
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+pub async fn get(pool: &MySqlPool, identity_id: u64, idempotency_key: &str) -> Result<Option<IdempotencyRecord>> {
+    let record = sqlx::query_as!(IdempotencyRecord,
+        r#"
+        SELECT request_hash, status_code, response_body, created_at
+        FROM idempotency_keys
+        WHERE identity_id = ? AND idempotency_key = ?
+        "#,
+        identity_id,
+        idempotency_key
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(record)
+}
+
+/// 保存完成的响应
+pub async fn complete(
+    pool: &MySqlPool,
+    identity_id: u64,
+    idempotency_key: &str,
+    status_code: u16,
+    response_body: &str,
+) -> Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE idempotency_keys
+        SET status_code = ?, response_body = ?
+        WHERE identity_id = ? AND idempotency_key = ?
+        "#,
+        status_code,
+        response_body,
+        identity_id,
+        idempotency_key
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn delete(pool: &MySqlPool, identity_id: u64, idempotency_key: &str) -> Result<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM idempotency_keys
+        WHERE identity_id = ? AND idempotency_key = ?
+        "#,
+        identity_id,
+        idempotency_key
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// 清理超出重放窗口的记录
+pub async fn purge_expired(pool: &MySqlPool, ttl_secs: u64) -> Result<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM idempotency_keys
+        WHERE created_at < DATE_SUB(NOW(), INTERVAL ? SECOND)
+        "#,
+        ttl_secs
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use crate::model::kmip::KmipObjectRecord;
+use sqlx::{MySqlConnection, MySqlPool, Result};
+
+pub async fn insert_object(
+    conn: &mut MySqlConnection,
+    key_id: u64,
+    state: &str,
+    cryptographic_algorithm: u32,
+    cryptographic_length: i32,
+    cryptographic_usage_mask: Option<i32>,
+    activation_date: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO kmip_objects (key_id, state, cryptographic_algorithm, cryptographic_length, cryptographic_usage_mask, activation_date, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, NOW(), NOW())
+        "#,
+        key_id,
+        state,
+        cryptographic_algorithm,
+        cryptographic_length,
+        cryptographic_usage_mask,
+        activation_date
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_object(pool: &MySqlPool, key_id: u64) -> Result<Option<KmipObjectRecord>> {
+    let object = sqlx::query_as!(KmipObjectRecord,
+        r#"
+        SELECT o.key_id, k.name, o.state, o.cryptographic_algorithm, o.cryptographic_length, o.cryptographic_usage_mask,
+               o.activation_date, o.deactivation_date, o.compromise_date, o.revocation_reason, o.revocation_message,
+               o.created_at, o.updated_at
+        FROM kmip_objects o
+        JOIN keys k ON k.id = o.key_id
+        WHERE o.key_id = ?
+        "#,
+        key_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(object)
+}
+
+/// 在事务中读取并锁定对象，直到事务结束
+pub async fn lock_object(conn: &mut MySqlConnection, key_id: u64) -> Result<Option<KmipObjectRecord>> {
+    let object = sqlx::query_as!(KmipObjectRecord,
+        r#"
+        SELECT o.key_id, k.name, o.state, o.cryptographic_algorithm, o.cryptographic_length, o.cryptographic_usage_mask,
+               o.activation_date, o.deactivation_date, o.compromise_date, o.revocation_reason, o.revocation_message,
+               o.created_at, o.updated_at
+        FROM kmip_objects o
+        JOIN keys k ON k.id = o.key_id
+        WHERE o.key_id = ?
+        FOR UPDATE
+        "#,
+        key_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    Ok(object)
+}
+
+/// 激活对象
+pub async fn activate(conn: &mut MySqlConnection, key_id: u64, activation_date: chrono::DateTime<chrono::Utc>) -> Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE kmip_objects
+        SET state = 'active', activation_date = ?, updated_at = NOW()
+        WHERE key_id = ?
+        "#,
+        activation_date,
+        key_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// 吊销对象，`compromise_date`给出时同时记录泄露时间
+pub async fn revoke(
+    conn: &mut MySqlConnection,
+    key_id: u64,
+    state: &str,
+    compromise_date: Option<chrono::DateTime<chrono::Utc>>,
+    revocation_reason: u32,
+    revocation_message: Option<&str>,
+) -> Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE kmip_objects
+        SET state = ?,
+            deactivation_date = COALESCE(deactivation_date, NOW()),
+            compromise_date = COALESCE(?, compromise_date),
+            revocation_reason = ?,
+            revocation_message = ?,
+            updated_at = NOW()
+        WHERE key_id = ?
+        "#,
+        state,
+        compromise_date,
+        revocation_reason,
+        revocation_message,
+        key_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// 在团队内按名称、算法和状态查找对象，条件为空时不过滤
+pub async fn locate(
+    pool: &MySqlPool,
+    team_id: u64,
+    name: Option<&str>,
+    cryptographic_algorithm: Option<u32>,
+    state: Option<&str>,
+    limit: u32,
+) -> Result<Vec<u64>> {
+    let ids = sqlx::query_scalar!(
+        r#"
+        SELECT o.key_id
+        FROM kmip_objects o
+        JOIN keys k ON k.id = o.key_id
+        WHERE k.team_id = ?
+          AND (? IS NULL OR k.name = ?)
+          AND (? IS NULL OR o.cryptographic_algorithm = ?)
+          AND (? IS NULL OR o.state = ?)
+        ORDER BY o.key_id
+        LIMIT ?
+        "#,
+        team_id,
+        name,
+        name,
+        cryptographic_algorithm,
+        cryptographic_algorithm,
+        state,
+        state,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(ids)
+}
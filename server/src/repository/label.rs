@@ -0,0 +1,96 @@
+use crate::model::key::{Key, KeySearchRequest};
+use sqlx::{MySql, MySqlConnection, MySqlPool, QueryBuilder, Result};
+use std::collections::BTreeMap;
+
+/// 写入密钥标签
+pub async fn insert_labels(conn: &mut MySqlConnection, key_id: u64, labels: &BTreeMap<String, String>) -> Result<()> {
+    for (label_key, label_value) in labels {
+        sqlx::query!(
+            r#"
+            INSERT INTO key_labels (key_id, label_key, label_value)
+            VALUES (?, ?, ?)
+            ON DUPLICATE KEY UPDATE label_value = VALUES(label_value)
+            "#,
+            key_id,
+            label_key,
+            label_value
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// 删除密钥的全部标签
+pub async fn delete_labels(conn: &mut MySqlConnection, key_id: u64) -> Result<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM key_labels
+        WHERE key_id = ?
+        "#,
+        key_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_labels(pool: &MySqlPool, key_id: u64) -> Result<BTreeMap<String, String>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT label_key, label_value
+        FROM key_labels
+        WHERE key_id = ?
+        "#,
+        key_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|row| (row.label_key, row.label_value)).collect())
+}
+
+// 转义LIKE通配符
+fn escape_like(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+/// 在团队内按名称前缀、描述全文、标签和元数据组合检索密钥
+pub async fn search_keys(pool: &MySqlPool, team_id: u64, request: &KeySearchRequest, limit: u32) -> Result<Vec<Key>> {
+    let mut builder = QueryBuilder::<MySql>::new(
+        "SELECT k.id, k.name, k.team_id, k.encrypted_data, k.kind, k.expires_at, k.description, k.metadata, k.version, k.vault_id, k.nonce, k.created_at, k.updated_at \
+         FROM keys k WHERE k.team_id = ",
+    );
+    builder.push_bind(team_id);
+    builder.push(" AND k.id > ").push_bind(request.after_id.unwrap_or(0));
+
+    if let Some(prefix) = &request.name_prefix {
+        builder.push(" AND k.name LIKE ").push_bind(format!("{}%", escape_like(prefix)));
+    }
+    if let Some(description) = &request.description {
+        builder
+            .push(" AND MATCH(k.description) AGAINST (")
+            .push_bind(description)
+            .push(" IN NATURAL LANGUAGE MODE)");
+    }
+    // 每个标签条件对应一次(label_key, label_value)索引查找
+    for (label_key, label_value) in &request.labels {
+        builder
+            .push(" AND EXISTS (SELECT 1 FROM key_labels l WHERE l.key_id = k.id AND l.label_key = ")
+            .push_bind(label_key)
+            .push(" AND l.label_value = ")
+            .push_bind(label_value)
+            .push(")");
+    }
+    if let Some(metadata) = &request.metadata {
+        builder
+            .push(" AND JSON_CONTAINS(k.metadata, ")
+            .push_bind(metadata.to_string())
+            .push(")");
+    }
+    builder.push(" ORDER BY k.id LIMIT ").push_bind(limit);
+
+    builder.build_query_as::<Key>().fetch_all(pool).await
+}
@@ -0,0 +1,210 @@
+use crate::model::lease::{LeaseEndReason, LeaseHook, LeaseHookKind, LeaseRecord, NewLease};
+use sqlx::{MySqlConnection, MySqlPool, Result};
+
+pub async fn insert_hook(
+    pool: &MySqlPool,
+    name: &str,
+    kind: LeaseHookKind,
+    target: &str,
+    created_by: u64,
+) -> Result<u64> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO lease_hooks (name, kind, target, created_by, created_at)
+        VALUES (?, ?, ?, ?, NOW())
+        "#,
+        name,
+        kind.as_str(),
+        target,
+        created_by
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.last_insert_id())
+}
+
+pub async fn get_hook(pool: &MySqlPool, id: u64) -> Result<Option<LeaseHook>> {
+    let hook = sqlx::query_as!(LeaseHook,
+        r#"
+        SELECT id, name, kind, target, created_by, created_at
+        FROM lease_hooks
+        WHERE id = ?
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(hook)
+}
+
+pub async fn list_hooks(pool: &MySqlPool) -> Result<Vec<LeaseHook>> {
+    let hooks = sqlx::query_as!(LeaseHook,
+        r#"
+        SELECT id, name, kind, target, created_by, created_at
+        FROM lease_hooks
+        ORDER BY id
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(hooks)
+}
+
+pub async fn delete_hook(pool: &MySqlPool, id: u64) -> Result<bool> {
+    let result = sqlx::query!("DELETE FROM lease_hooks WHERE id = ?", id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// 锁定密钥行，防止并发为同一密钥创建多个租约
+pub async fn lock_key(conn: &mut MySqlConnection, key_id: u64) -> Result<bool> {
+    let id = sqlx::query_scalar!(
+        r#"
+        SELECT id
+        FROM keys
+        WHERE id = ?
+        FOR UPDATE
+        "#,
+        key_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    Ok(id.is_some())
+}
+
+pub async fn has_active_lease(conn: &mut MySqlConnection, key_id: u64) -> Result<bool> {
+    let count = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*)
+        FROM leases
+        WHERE key_id = ? AND ended_at IS NULL
+        "#,
+        key_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(count > 0)
+}
+
+pub async fn insert_lease(conn: &mut MySqlConnection, lease: &NewLease) -> Result<u64> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO leases (owner_id, kind, key_id, hook_id, ttl_secs, expires_at, max_expires_at, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, NOW())
+        "#,
+        lease.owner_id,
+        lease.kind.as_str(),
+        lease.key_id,
+        lease.hook_id,
+        lease.ttl_secs,
+        lease.expires_at,
+        lease.max_expires_at
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(result.last_insert_id())
+}
+
+pub async fn get_lease(pool: &MySqlPool, id: u64) -> Result<Option<LeaseRecord>> {
+    let lease = sqlx::query_as!(LeaseRecord,
+        r#"
+        SELECT id, owner_id, kind, key_id, hook_id, ttl_secs, expires_at, max_expires_at, renewed_at,
+               ended_at, end_reason, revocation_error, created_at
+        FROM leases
+        WHERE id = ?
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(lease)
+}
+
+/// 列出调用方的租约，按id排序
+pub async fn list_leases(pool: &MySqlPool, owner_id: u64, include_ended: bool) -> Result<Vec<LeaseRecord>> {
+    let leases = sqlx::query_as!(LeaseRecord,
+        r#"
+        SELECT id, owner_id, kind, key_id, hook_id, ttl_secs, expires_at, max_expires_at, renewed_at,
+               ended_at, end_reason, revocation_error, created_at
+        FROM leases
+        WHERE owner_id = ? AND (? OR ended_at IS NULL)
+        ORDER BY id
+        "#,
+        owner_id,
+        include_ended
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(leases)
+}
+
+/// 已到期但尚未结束的租约
+pub async fn list_expired(pool: &MySqlPool, limit: u32) -> Result<Vec<LeaseRecord>> {
+    let leases = sqlx::query_as!(LeaseRecord,
+        r#"
+        SELECT id, owner_id, kind, key_id, hook_id, ttl_secs, expires_at, max_expires_at, renewed_at,
+               ended_at, end_reason, revocation_error, created_at
+        FROM leases
+        WHERE ended_at IS NULL AND expires_at <= NOW()
+        ORDER BY expires_at
+        LIMIT ?
+        "#,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(leases)
+}
+
+/// 租约已结束或已到期时不更新，返回是否续期成功
+pub async fn renew_lease(pool: &MySqlPool, id: u64, expires_at: chrono::DateTime<chrono::Utc>) -> Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE leases
+        SET expires_at = ?, renewed_at = NOW()
+        WHERE id = ? AND ended_at IS NULL AND expires_at > NOW()
+        "#,
+        expires_at,
+        id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// 标记租约结束，返回是否由本次调用结束；并发的撤销和回收只有一方成功
+pub async fn end_lease(pool: &MySqlPool, id: u64, reason: LeaseEndReason) -> Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE leases
+        SET ended_at = NOW(), end_reason = ?
+        WHERE id = ? AND ended_at IS NULL
+        "#,
+        reason.as_str(),
+        id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn set_revocation_error(pool: &MySqlPool, id: u64, error: &str) -> Result<()> {
+    sqlx::query!("UPDATE leases SET revocation_error = ? WHERE id = ?", error, id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
@@ -1,16 +1,43 @@
+pub mod access;
+pub mod share;
+pub mod audit;
+pub mod rotation;
+pub mod webhook;
+pub mod backup;
+pub mod label;
+pub mod idempotency;
+pub mod user;
+pub mod token;
+pub mod vault;
+pub mod health;
+pub mod event;
+pub mod kmip;
+pub mod pki;
+pub mod ssh;
+pub mod lease;
+pub mod quota;
+pub mod team;
+
 use crate::model::key::Key;
-use sqlx::{MySqlPool, Result};
+use sqlx::{MySql, MySqlConnection, MySqlPool, Result, Transaction};
 
-pub async fn create_key(pool: &MySqlPool, key: &Key) -> Result<u64> {
+pub async fn create_key(conn: &mut MySqlConnection, key: &Key) -> Result<u64> {
     let result = sqlx::query!(
         r#"
-        INSERT INTO keys (name, encrypted_data, created_at, updated_at)
-        VALUES (?, ?, NOW(), NOW())
+        INSERT INTO keys (name, team_id, encrypted_data, kind, expires_at, description, metadata, vault_id, nonce, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, NOW(), NOW())
         "#,
         key.name,
-        key.encrypted_data
+        key.team_id,
+        key.encrypted_data,
+        key.kind,
+        key.expires_at,
+        key.description,
+        key.metadata,
+        key.vault_id,
+        key.nonce
     )
-    .execute(pool)
+    .execute(&mut *conn)
     .await?;
     
     Ok(result.last_insert_id())
@@ -19,7 +46,7 @@ pub async fn create_key(pool: &MySqlPool, key: &Key) -> Result<u64> {
 pub async fn get_key_by_id(pool: &MySqlPool, id: u64) -> Result<Option<Key>> {
     let key = sqlx::query_as!(Key,
         r#"
-        SELECT id, name, encrypted_data, created_at, updated_at
+        SELECT id, name, team_id, encrypted_data, kind, expires_at, description, metadata, version, vault_id, nonce, created_at, updated_at
         FROM keys
         WHERE id = ?
         "#,
@@ -31,17 +58,87 @@ pub async fn get_key_by_id(pool: &MySqlPool, id: u64) -> Result<Option<Key>> {
     Ok(key)
 }
 
-/// 删除密钥
-pub async fn delete_key(pool: &MySqlPool, id: u64) -> Result<()> {
-    sqlx::query!(
+/// 在团队内按id读取密钥，其他团队的密钥视为不存在
+pub async fn get_team_key(pool: &MySqlPool, team_id: u64, id: u64) -> Result<Option<Key>> {
+    let key = sqlx::query_as!(Key,
+        r#"
+        SELECT id, name, team_id, encrypted_data, kind, expires_at, description, metadata, version, vault_id, nonce, created_at, updated_at
+        FROM keys
+        WHERE id = ? AND team_id = ?
+        "#,
+        id,
+        team_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(key)
+}
+
+/// 在给定连接上读取密钥所属的团队，密钥不存在时为空
+pub async fn get_key_team(conn: &mut MySqlConnection, id: u64) -> Result<Option<u64>> {
+    let team_id = sqlx::query_scalar!("SELECT team_id FROM keys WHERE id = ?", id)
+        .fetch_optional(conn)
+        .await?;
+
+    Ok(team_id)
+}
+
+/// 在事务中读取并锁定团队内的密钥，直到事务结束
+pub async fn lock_key(conn: &mut MySqlConnection, team_id: u64, id: u64) -> Result<Option<Key>> {
+    let key = sqlx::query_as!(Key,
+        r#"
+        SELECT id, name, team_id, encrypted_data, kind, expires_at, description, metadata, version, vault_id, nonce, created_at, updated_at
+        FROM keys
+        WHERE id = ? AND team_id = ?
+        FOR UPDATE
+        "#,
+        id,
+        team_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    Ok(key)
+}
+
+/// 按版本条件修改密钥，版本匹配时递增版本号，返回是否修改成功
+pub async fn update_key(tx: &mut Transaction<'_, MySql>, key: &Key, expected_version: u32) -> Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE keys
+        SET encrypted_data = ?, kind = ?, nonce = ?, expires_at = ?, description = ?, metadata = ?,
+            version = version + 1, updated_at = NOW()
+        WHERE id = ? AND version = ?
+        "#,
+        key.encrypted_data,
+        key.kind,
+        key.nonce,
+        key.expires_at,
+        key.description,
+        key.metadata,
+        key.id,
+        expected_version
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+/// 删除密钥，给出版本时只在版本匹配时删除，返回是否删除成功
+pub async fn delete_key(conn: &mut MySqlConnection, id: u64, expected_version: Option<u32>) -> Result<bool> {
+    let result = sqlx::query!(
         r#"
         DELETE FROM keys
-        WHERE id = ?
+        WHERE id = ? AND (? IS NULL OR version = ?)
         "#,
-        id
+        id,
+        expected_version,
+        expected_version
     )
-    .execute(pool)
+    .execute(&mut *conn)
     .await?;
     
-    Ok(())
+    Ok(result.rows_affected() == 1)
 }
\ No newline at end of file
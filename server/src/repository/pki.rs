@@ -0,0 +1,184 @@
+use crate::model::pki::{CertificateAuthority, IssuedCertificate, NewCertificateAuthority, NewIssuedCertificate};
+use sqlx::{MySqlConnection, MySqlPool, Result};
+
+pub async fn insert_authority(conn: &mut MySqlConnection, authority: &NewCertificateAuthority) -> Result<u64> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO certificate_authorities (name, key_id, parent_id, subject, certificate, chain, path_length, not_after, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, NOW())
+        "#,
+        authority.name,
+        authority.key_id,
+        authority.parent_id,
+        authority.subject,
+        authority.certificate,
+        authority.chain,
+        authority.path_length,
+        authority.not_after
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(result.last_insert_id())
+}
+
+pub async fn get_authority(pool: &MySqlPool, id: u64) -> Result<Option<CertificateAuthority>> {
+    let authority = sqlx::query_as!(CertificateAuthority,
+        r#"
+        SELECT id, name, key_id, parent_id, subject, certificate, chain, path_length, crl_number, not_after, created_at
+        FROM certificate_authorities
+        WHERE id = ?
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(authority)
+}
+
+pub async fn list_authorities(pool: &MySqlPool) -> Result<Vec<CertificateAuthority>> {
+    let authorities = sqlx::query_as!(CertificateAuthority,
+        r#"
+        SELECT id, name, key_id, parent_id, subject, certificate, chain, path_length, crl_number, not_after, created_at
+        FROM certificate_authorities
+        ORDER BY id
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(authorities)
+}
+
+/// 取出本次CRL使用的序号并递增，需在事务中调用以保证序号单调
+pub async fn next_crl_number(conn: &mut MySqlConnection, id: u64) -> Result<Option<u64>> {
+    let crl_number = sqlx::query_scalar!(
+        r#"
+        SELECT crl_number
+        FROM certificate_authorities
+        WHERE id = ?
+        FOR UPDATE
+        "#,
+        id
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    if crl_number.is_some() {
+        sqlx::query!(
+            r#"
+            UPDATE certificate_authorities
+            SET crl_number = crl_number + 1
+            WHERE id = ?
+            "#,
+            id
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(crl_number)
+}
+
+pub async fn insert_certificate(conn: &mut MySqlConnection, certificate: &NewIssuedCertificate) -> Result<u64> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO issued_certificates (ca_id, serial_number, subject, profile, certificate, key_id, not_before, not_after, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, NOW())
+        "#,
+        certificate.ca_id,
+        certificate.serial_number,
+        certificate.subject,
+        certificate.profile.as_str(),
+        certificate.certificate,
+        certificate.key_id,
+        certificate.not_before,
+        certificate.not_after
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(result.last_insert_id())
+}
+
+pub async fn get_certificate(pool: &MySqlPool, ca_id: u64, serial_number: &str) -> Result<Option<IssuedCertificate>> {
+    let certificate = sqlx::query_as!(IssuedCertificate,
+        r#"
+        SELECT id, ca_id, serial_number, subject, profile, certificate, key_id, not_before, not_after,
+               revoked_at, revocation_reason, created_at
+        FROM issued_certificates
+        WHERE ca_id = ? AND serial_number = ?
+        "#,
+        ca_id,
+        serial_number
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(certificate)
+}
+
+/// 按id分页列出CA签发的证书，`revoked`为true时只返回已吊销的证书
+pub async fn list_certificates(
+    pool: &MySqlPool,
+    ca_id: u64,
+    after_id: u64,
+    limit: u32,
+    revoked: bool,
+) -> Result<Vec<IssuedCertificate>> {
+    let certificates = sqlx::query_as!(IssuedCertificate,
+        r#"
+        SELECT id, ca_id, serial_number, subject, profile, certificate, key_id, not_before, not_after,
+               revoked_at, revocation_reason, created_at
+        FROM issued_certificates
+        WHERE ca_id = ? AND id > ? AND (? = FALSE OR revoked_at IS NOT NULL)
+        ORDER BY id
+        LIMIT ?
+        "#,
+        ca_id,
+        after_id,
+        revoked,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(certificates)
+}
+
+/// 吊销证书，已吊销的证书不受影响；返回是否有记录被更新
+pub async fn revoke_certificate(pool: &MySqlPool, ca_id: u64, serial_number: &str, reason: &str) -> Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE issued_certificates
+        SET revoked_at = NOW(), revocation_reason = ?
+        WHERE ca_id = ? AND serial_number = ? AND revoked_at IS NULL
+        "#,
+        reason,
+        ca_id,
+        serial_number
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// 列出尚未过期的已吊销证书，过期证书不再出现在CRL中
+pub async fn list_revoked(pool: &MySqlPool, ca_id: u64) -> Result<Vec<IssuedCertificate>> {
+    let certificates = sqlx::query_as!(IssuedCertificate,
+        r#"
+        SELECT id, ca_id, serial_number, subject, profile, certificate, key_id, not_before, not_after,
+               revoked_at, revocation_reason, created_at
+        FROM issued_certificates
+        WHERE ca_id = ? AND revoked_at IS NOT NULL AND not_after > NOW()
+        ORDER BY id
+        "#,
+        ca_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(certificates)
+}
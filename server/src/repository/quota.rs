@@ -0,0 +1,136 @@
+use crate::model::quota::{QuotaLimits, QuotaRecord, QuotaSubjectType};
+use sqlx::{MySqlConnection, MySqlPool, Result};
+
+pub async fn upsert_quota(
+    pool: &MySqlPool,
+    subject_type: QuotaSubjectType,
+    subject: &str,
+    limits: &QuotaLimits,
+) -> Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO quotas (subject_type, subject, max_keys, max_data_bytes, requests_per_minute, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, NOW(), NOW())
+        ON DUPLICATE KEY UPDATE max_keys = VALUES(max_keys), max_data_bytes = VALUES(max_data_bytes),
+                                requests_per_minute = VALUES(requests_per_minute), updated_at = NOW()
+        "#,
+        subject_type.as_str(),
+        subject,
+        limits.max_keys,
+        limits.max_data_bytes,
+        limits.requests_per_minute
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_quota(
+    conn: &mut MySqlConnection,
+    subject_type: QuotaSubjectType,
+    subject: &str,
+) -> Result<Option<QuotaRecord>> {
+    let quota = sqlx::query_as!(QuotaRecord,
+        r#"
+        SELECT id, subject_type, subject, max_keys, max_data_bytes, requests_per_minute, created_at, updated_at
+        FROM quotas
+        WHERE subject_type = ? AND subject = ?
+        "#,
+        subject_type.as_str(),
+        subject
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    Ok(quota)
+}
+
+pub async fn list_quotas(pool: &MySqlPool) -> Result<Vec<QuotaRecord>> {
+    let quotas = sqlx::query_as!(QuotaRecord,
+        r#"
+        SELECT id, subject_type, subject, max_keys, max_data_bytes, requests_per_minute, created_at, updated_at
+        FROM quotas
+        ORDER BY subject_type, subject
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(quotas)
+}
+
+pub async fn delete_quota(pool: &MySqlPool, subject_type: QuotaSubjectType, subject: &str) -> Result<bool> {
+    let result = sqlx::query!(
+        "DELETE FROM quotas WHERE subject_type = ? AND subject = ?",
+        subject_type.as_str(),
+        subject
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// 记录密钥的创建者，须与创建密钥处于同一事务
+pub async fn insert_creator(
+    conn: &mut MySqlConnection,
+    key_id: u64,
+    identity_id: u64,
+    source_ip: Option<&str>,
+) -> Result<()> {
+    sqlx::query!(
+        "INSERT INTO key_creators (key_id, identity_id, source_ip) VALUES (?, ?, ?)",
+        key_id,
+        identity_id,
+        source_ip
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// 锁定配额对象直到事务结束：身份锁定其身份行，来源IP锁定其配额行
+///
+/// 同一配额对象并发创建密钥时依次计数，须在记录创建者之前调用。
+pub async fn lock_subject(conn: &mut MySqlConnection, subject_type: QuotaSubjectType, subject: &str) -> Result<()> {
+    match subject_type {
+        QuotaSubjectType::Identity => {
+            sqlx::query_scalar!("SELECT id FROM identities WHERE id = ? FOR UPDATE", subject)
+                .fetch_optional(&mut *conn)
+                .await?;
+        }
+        QuotaSubjectType::Ip => {
+            sqlx::query_scalar!(
+                "SELECT id FROM quotas WHERE subject_type = ? AND subject = ? FOR UPDATE",
+                subject_type.as_str(),
+                subject
+            )
+            .fetch_optional(&mut *conn)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 配额对象创建且仍存在的密钥数
+///
+/// 使用加锁读，事务内读到的是最新提交的数据而不是事务开始时的快照。
+pub async fn count_keys(conn: &mut MySqlConnection, subject_type: QuotaSubjectType, subject: &str) -> Result<u64> {
+    let count = match subject_type {
+        QuotaSubjectType::Identity => {
+            sqlx::query_scalar!("SELECT COUNT(*) FROM key_creators WHERE identity_id = ? LOCK IN SHARE MODE", subject)
+                .fetch_one(&mut *conn)
+                .await?
+        }
+        QuotaSubjectType::Ip => {
+            sqlx::query_scalar!("SELECT COUNT(*) FROM key_creators WHERE source_ip = ? LOCK IN SHARE MODE", subject)
+                .fetch_one(&mut *conn)
+                .await?
+        }
+    };
+
+    Ok(count as u64)
+}
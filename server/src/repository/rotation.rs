@@ -0,0 +1,178 @@
+use crate::model::rotation::{DueRotation, KeyVersion, KeyVersionRecord, RotationPolicy};
+use sqlx::{MySql, MySqlPool, Result, Transaction};
+
+/// 设置或更新轮换策略，下次轮换时间从当前时间起算
+pub async fn upsert_policy(pool: &MySqlPool, key_id: u64, interval_days: u32) -> Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO key_rotation_policies (key_id, interval_days, next_rotation_at, created_at, updated_at)
+        VALUES (?, ?, DATE_ADD(NOW(), INTERVAL ? DAY), NOW(), NOW())
+        ON DUPLICATE KEY UPDATE
+            interval_days = VALUES(interval_days),
+            next_rotation_at = DATE_ADD(COALESCE(last_rotated_at, NOW()), INTERVAL VALUES(interval_days) DAY)
+        "#,
+        key_id,
+        interval_days,
+        interval_days
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn delete_policy(pool: &MySqlPool, key_id: u64) -> Result<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM key_rotation_policies
+        WHERE key_id = ?
+        "#,
+        key_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_policy(pool: &MySqlPool, key_id: u64) -> Result<Option<RotationPolicy>> {
+    let policy = sqlx::query_as!(RotationPolicy,
+        r#"
+        SELECT key_id, interval_days, last_rotated_at, next_rotation_at
+        FROM key_rotation_policies
+        WHERE key_id = ?
+        "#,
+        key_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(policy)
+}
+
+/// 列出在指定天数内到期（含已逾期）的密钥，`team_id`为空时包括所有团队
+pub async fn list_due(pool: &MySqlPool, team_id: Option<u64>, within_days: u32) -> Result<Vec<DueRotation>> {
+    let due = sqlx::query_as!(DueRotation,
+        r#"
+        SELECT p.key_id, k.name, p.interval_days, p.last_rotated_at, p.next_rotation_at
+        FROM key_rotation_policies p
+        JOIN keys k ON k.id = p.key_id
+        WHERE p.next_rotation_at <= DATE_ADD(NOW(), INTERVAL ? DAY)
+          AND (? IS NULL OR k.team_id = ?)
+        ORDER BY p.next_rotation_at
+        "#,
+        within_days,
+        team_id,
+        team_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(due)
+}
+
+/// 锁定密钥行并读取当前加密数据及其版本号
+pub async fn lock_key_data(tx: &mut Transaction<'_, MySql>, key_id: u64) -> Result<Option<(String, u32)>> {
+    let row = sqlx::query!(
+        r#"
+        SELECT encrypted_data, version
+        FROM keys
+        WHERE id = ?
+        FOR UPDATE
+        "#,
+        key_id
+    )
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    Ok(row.map(|row| (row.encrypted_data, row.version)))
+}
+
+/// 保存被轮换下来的旧值
+pub async fn insert_version(
+    tx: &mut Transaction<'_, MySql>,
+    key_id: u64,
+    version: u32,
+    encrypted_data: &str,
+    trigger_type: &str,
+) -> Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO key_versions (key_id, version, encrypted_data, trigger_type, rotated_at)
+        VALUES (?, ?, ?, ?, NOW())
+        "#,
+        key_id,
+        version,
+        encrypted_data,
+        trigger_type
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn update_key_data(tx: &mut Transaction<'_, MySql>, key_id: u64, encrypted_data: &str) -> Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE keys
+        SET encrypted_data = ?, version = version + 1, updated_at = NOW()
+        WHERE id = ?
+        "#,
+        encrypted_data,
+        key_id
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// 记录轮换完成并推算下次轮换时间
+pub async fn mark_rotated(tx: &mut Transaction<'_, MySql>, key_id: u64) -> Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE key_rotation_policies
+        SET last_rotated_at = NOW(),
+            next_rotation_at = DATE_ADD(NOW(), INTERVAL interval_days DAY)
+        WHERE key_id = ?
+        "#,
+        key_id
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn list_versions(pool: &MySqlPool, key_id: u64) -> Result<Vec<KeyVersion>> {
+    let versions = sqlx::query_as!(KeyVersion,
+        r#"
+        SELECT key_id, version, trigger_type, rotated_at
+        FROM key_versions
+        WHERE key_id = ?
+        ORDER BY version
+        "#,
+        key_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(versions)
+}
+
+pub async fn get_version(pool: &MySqlPool, key_id: u64, version: u32) -> Result<Option<KeyVersionRecord>> {
+    let record = sqlx::query_as!(KeyVersionRecord,
+        r#"
+        SELECT key_id, version, encrypted_data
+        FROM key_versions
+        WHERE key_id = ? AND version = ?
+        "#,
+        key_id,
+        version
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(record)
+}
@@ -0,0 +1,144 @@
+use crate::model::share::ShareResponse;
+use sqlx::{MySqlPool, Result};
+
+/// 设置身份的公钥
+pub async fn set_public_key(pool: &MySqlPool, identity_id: u64, public_key: &str) -> Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE identities
+        SET public_key = ?
+        WHERE id = ?
+        "#,
+        public_key,
+        identity_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_public_key(pool: &MySqlPool, identity_id: u64) -> Result<Option<String>> {
+    let public_key = sqlx::query_scalar!(
+        r#"
+        SELECT public_key
+        FROM identities
+        WHERE id = ?
+        "#,
+        identity_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(public_key.flatten())
+}
+
+pub async fn create_share(
+    pool: &MySqlPool,
+    key_id: u64,
+    sharer_id: u64,
+    recipient_id: u64,
+    sealed_data: &str,
+    expires_at: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<u64> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO key_shares (key_id, sharer_id, recipient_id, sealed_data, expires_at, created_at)
+        VALUES (?, ?, ?, ?, ?, NOW())
+        "#,
+        key_id,
+        sharer_id,
+        recipient_id,
+        sealed_data,
+        expires_at
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.last_insert_id())
+}
+
+pub async fn get_share_by_id(pool: &MySqlPool, id: u64) -> Result<Option<ShareResponse>> {
+    let share = sqlx::query_as!(ShareResponse,
+        r#"
+        SELECT s.id, s.key_id, k.name AS key_name,
+               s.sharer_id, sharer.name AS sharer_name,
+               s.recipient_id, recipient.name AS recipient_name,
+               s.sealed_data, s.expires_at, s.revoked_at, s.created_at
+        FROM key_shares s
+        JOIN keys k ON k.id = s.key_id
+        JOIN identities sharer ON sharer.id = s.sharer_id
+        JOIN identities recipient ON recipient.id = s.recipient_id
+        WHERE s.id = ?
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(share)
+}
+
+/// 列出共享给某个身份且仍然有效的密钥
+pub async fn list_received_shares(pool: &MySqlPool, recipient_id: u64) -> Result<Vec<ShareResponse>> {
+    let shares = sqlx::query_as!(ShareResponse,
+        r#"
+        SELECT s.id, s.key_id, k.name AS key_name,
+               s.sharer_id, sharer.name AS sharer_name,
+               s.recipient_id, recipient.name AS recipient_name,
+               s.sealed_data, s.expires_at, s.revoked_at, s.created_at
+        FROM key_shares s
+        JOIN keys k ON k.id = s.key_id
+        JOIN identities sharer ON sharer.id = s.sharer_id
+        JOIN identities recipient ON recipient.id = s.recipient_id
+        WHERE s.recipient_id = ?
+          AND s.revoked_at IS NULL
+          AND (s.expires_at IS NULL OR s.expires_at > NOW())
+        ORDER BY s.id
+        "#,
+        recipient_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(shares)
+}
+
+/// 列出某个身份发出的全部共享
+pub async fn list_sent_shares(pool: &MySqlPool, sharer_id: u64) -> Result<Vec<ShareResponse>> {
+    let shares = sqlx::query_as!(ShareResponse,
+        r#"
+        SELECT s.id, s.key_id, k.name AS key_name,
+               s.sharer_id, sharer.name AS sharer_name,
+               s.recipient_id, recipient.name AS recipient_name,
+               s.sealed_data, s.expires_at, s.revoked_at, s.created_at
+        FROM key_shares s
+        JOIN keys k ON k.id = s.key_id
+        JOIN identities sharer ON sharer.id = s.sharer_id
+        JOIN identities recipient ON recipient.id = s.recipient_id
+        WHERE s.sharer_id = ?
+        ORDER BY s.id
+        "#,
+        sharer_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(shares)
+}
+
+/// 撤销共享
+pub async fn revoke_share(pool: &MySqlPool, id: u64) -> Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE key_shares
+        SET revoked_at = NOW()
+        WHERE id = ? AND revoked_at IS NULL
+        "#,
+        id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
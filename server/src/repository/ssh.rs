@@ -0,0 +1,127 @@
+use crate::model::ssh::{NewSshCertificate, NewSshCertificateAuthority, SshCertificateAuthority, SshCertificateRecord};
+use sqlx::types::Json;
+use sqlx::{MySqlConnection, MySqlPool, Result};
+
+pub async fn insert_authority(conn: &mut MySqlConnection, authority: &NewSshCertificateAuthority) -> Result<u64> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO ssh_certificate_authorities (name, key_id, public_key, allow_user_certificates, allow_host_certificates,
+                                                 max_validity_secs, allowed_principals, allowed_extensions, allowed_critical_options, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, NOW())
+        "#,
+        authority.name,
+        authority.key_id,
+        authority.public_key,
+        authority.allow_user_certificates,
+        authority.allow_host_certificates,
+        authority.max_validity_secs,
+        authority.allowed_principals.as_ref().map(Json),
+        authority.allowed_extensions.as_ref().map(Json),
+        authority.allowed_critical_options.as_ref().map(Json)
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(result.last_insert_id())
+}
+
+pub async fn get_authority(pool: &MySqlPool, id: u64) -> Result<Option<SshCertificateAuthority>> {
+    let authority = sqlx::query_as!(SshCertificateAuthority,
+        r#"
+        SELECT id, name, key_id, public_key, allow_user_certificates as `allow_user_certificates: bool`,
+               allow_host_certificates as `allow_host_certificates: bool`, max_validity_secs,
+               allowed_principals as `allowed_principals: Json<Vec<String>>`,
+               allowed_extensions as `allowed_extensions: Json<Vec<String>>`,
+               allowed_critical_options as `allowed_critical_options: Json<Vec<String>>`,
+               created_at
+        FROM ssh_certificate_authorities
+        WHERE id = ?
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(authority)
+}
+
+pub async fn list_authorities(pool: &MySqlPool) -> Result<Vec<SshCertificateAuthority>> {
+    let authorities = sqlx::query_as!(SshCertificateAuthority,
+        r#"
+        SELECT id, name, key_id, public_key, allow_user_certificates as `allow_user_certificates: bool`,
+               allow_host_certificates as `allow_host_certificates: bool`, max_validity_secs,
+               allowed_principals as `allowed_principals: Json<Vec<String>>`,
+               allowed_extensions as `allowed_extensions: Json<Vec<String>>`,
+               allowed_critical_options as `allowed_critical_options: Json<Vec<String>>`,
+               created_at
+        FROM ssh_certificate_authorities
+        ORDER BY id
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(authorities)
+}
+
+pub async fn insert_certificate(pool: &MySqlPool, certificate: &NewSshCertificate) -> Result<u64> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO ssh_certificates (ca_id, serial, certificate_type, key_identifier, principals, public_key_fingerprint,
+                                      certificate, valid_after, valid_before, issued_by, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, NOW())
+        "#,
+        certificate.ca_id,
+        certificate.serial,
+        certificate.certificate_type.as_str(),
+        certificate.key_identifier,
+        Json(&certificate.principals),
+        certificate.public_key_fingerprint,
+        certificate.certificate,
+        certificate.valid_after,
+        certificate.valid_before,
+        certificate.issued_by
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.last_insert_id())
+}
+
+pub async fn get_certificate(pool: &MySqlPool, ca_id: u64, serial: u64) -> Result<Option<SshCertificateRecord>> {
+    let certificate = sqlx::query_as!(SshCertificateRecord,
+        r#"
+        SELECT id, ca_id, serial, certificate_type, key_identifier, principals as `principals: Json<Vec<String>>`,
+               public_key_fingerprint, certificate, valid_after, valid_before, issued_by, created_at
+        FROM ssh_certificates
+        WHERE ca_id = ? AND serial = ?
+        "#,
+        ca_id,
+        serial
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(certificate)
+}
+
+/// 按id分页列出CA签发的证书
+pub async fn list_certificates(pool: &MySqlPool, ca_id: u64, after_id: u64, limit: u32) -> Result<Vec<SshCertificateRecord>> {
+    let certificates = sqlx::query_as!(SshCertificateRecord,
+        r#"
+        SELECT id, ca_id, serial, certificate_type, key_identifier, principals as `principals: Json<Vec<String>>`,
+               public_key_fingerprint, certificate, valid_after, valid_before, issued_by, created_at
+        FROM ssh_certificates
+        WHERE ca_id = ? AND id > ?
+        ORDER BY id
+        LIMIT ?
+        "#,
+        ca_id,
+        after_id,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(certificates)
+}
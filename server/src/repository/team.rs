@@ -0,0 +1,214 @@
+use crate::model::team::{Organization, Team, TeamMember};
+use sqlx::{MySqlConnection, MySqlPool, Result};
+
+pub async fn create_organization(pool: &MySqlPool, name: &str) -> Result<u64> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO organizations (name, created_at)
+        VALUES (?, NOW())
+        "#,
+        name
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.last_insert_id())
+}
+
+pub async fn get_organization(pool: &MySqlPool, id: u64) -> Result<Option<Organization>> {
+    let organization = sqlx::query_as!(Organization,
+        r#"
+        SELECT id, name, created_at
+        FROM organizations
+        WHERE id = ?
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(organization)
+}
+
+pub async fn list_organizations(pool: &MySqlPool) -> Result<Vec<Organization>> {
+    let organizations = sqlx::query_as!(Organization,
+        r#"
+        SELECT id, name, created_at
+        FROM organizations
+        ORDER BY id
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(organizations)
+}
+
+pub async fn create_team(pool: &MySqlPool, organization_id: u64, name: &str) -> Result<u64> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO teams (organization_id, name, created_at)
+        VALUES (?, ?, NOW())
+        "#,
+        organization_id,
+        name
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.last_insert_id())
+}
+
+pub async fn get_team(pool: &MySqlPool, id: u64) -> Result<Option<Team>> {
+    let team = sqlx::query_as!(Team,
+        r#"
+        SELECT t.id, t.organization_id, o.name AS organization_name, t.name, t.created_at
+        FROM teams t
+        JOIN organizations o ON o.id = t.organization_id
+        WHERE t.id = ?
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(team)
+}
+
+pub async fn list_teams(pool: &MySqlPool, organization_id: u64) -> Result<Vec<Team>> {
+    let teams = sqlx::query_as!(Team,
+        r#"
+        SELECT t.id, t.organization_id, o.name AS organization_name, t.name, t.created_at
+        FROM teams t
+        JOIN organizations o ON o.id = t.organization_id
+        WHERE t.organization_id = ?
+        ORDER BY t.id
+        "#,
+        organization_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(teams)
+}
+
+/// 身份所属的全部团队
+pub async fn list_identity_teams(pool: &MySqlPool, identity_id: u64) -> Result<Vec<Team>> {
+    let teams = sqlx::query_as!(Team,
+        r#"
+        SELECT t.id, t.organization_id, o.name AS organization_name, t.name, t.created_at
+        FROM team_members m
+        JOIN teams t ON t.id = m.team_id
+        JOIN organizations o ON o.id = t.organization_id
+        WHERE m.identity_id = ?
+        ORDER BY t.id
+        "#,
+        identity_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(teams)
+}
+
+pub async fn delete_team(pool: &MySqlPool, id: u64) -> Result<bool> {
+    let result = sqlx::query!("DELETE FROM teams WHERE id = ?", id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// 团队拥有的密钥数
+pub async fn count_keys(pool: &MySqlPool, team_id: u64) -> Result<u64> {
+    let count = sqlx::query_scalar!("SELECT COUNT(*) FROM keys WHERE team_id = ?", team_id)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(count as u64)
+}
+
+/// 在给定连接上加入团队，已是成员时不做修改；新建身份时与创建身份处于同一事务
+pub async fn add_member(conn: &mut MySqlConnection, team_id: u64, identity_id: u64) -> Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT IGNORE INTO team_members (team_id, identity_id, created_at)
+        VALUES (?, ?, NOW())
+        "#,
+        team_id,
+        identity_id
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// 迁移时创建的默认组织下的默认团队
+pub async fn find_default_team(pool: &MySqlPool) -> Result<Option<u64>> {
+    let team_id = sqlx::query_scalar!(
+        r#"
+        SELECT t.id
+        FROM teams t
+        JOIN organizations o ON o.id = t.organization_id
+        WHERE o.name = 'default' AND t.name = 'default'
+        "#
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(team_id)
+}
+
+pub async fn remove_member(pool: &MySqlPool, team_id: u64, identity_id: u64) -> Result<bool> {
+    let result = sqlx::query!(
+        "DELETE FROM team_members WHERE team_id = ? AND identity_id = ?",
+        team_id,
+        identity_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn list_members(pool: &MySqlPool, team_id: u64) -> Result<Vec<TeamMember>> {
+    let members = sqlx::query_as!(TeamMember,
+        r#"
+        SELECT i.id AS identity_id, i.name, i.role, m.created_at
+        FROM team_members m
+        JOIN identities i ON i.id = m.identity_id
+        WHERE m.team_id = ?
+        ORDER BY i.id
+        "#,
+        team_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(members)
+}
+
+pub async fn is_member(pool: &MySqlPool, team_id: u64, identity_id: u64) -> Result<bool> {
+    let member = sqlx::query_scalar!(
+        "SELECT 1 FROM team_members WHERE team_id = ? AND identity_id = ?",
+        team_id,
+        identity_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(member.is_some())
+}
+
+/// 未指定团队时使用的默认团队：身份加入的id最小的团队
+pub async fn default_team(pool: &MySqlPool, identity_id: u64) -> Result<Option<u64>> {
+    let team_id = sqlx::query_scalar!(
+        "SELECT team_id FROM team_members WHERE identity_id = ? ORDER BY team_id LIMIT 1",
+        identity_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(team_id)
+}
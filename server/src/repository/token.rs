@@ -0,0 +1,186 @@
+use crate::model::token::{ApiTokenRecord, ServiceAccount, TokenScope};
+use crate::repository::team as team_repository;
+use sqlx::types::Json;
+use sqlx::{MySqlPool, Result};
+
+/// 在同一事务中创建服务账号及其app角色身份，身份加入给定团队
+pub async fn create_service_account(
+    pool: &MySqlPool,
+    name: &str,
+    credential_hash: &str,
+    description: Option<&str>,
+    team_id: u64,
+) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+
+    let identity_id = sqlx::query!(
+        r#"
+        INSERT INTO identities (name, role, credential_hash, created_at)
+        VALUES (?, 'app', ?, NOW())
+        "#,
+        name,
+        credential_hash
+    )
+    .execute(&mut *tx)
+    .await?
+    .last_insert_id();
+
+    team_repository::add_member(&mut *tx, team_id, identity_id).await?;
+
+    let id = sqlx::query!(
+        r#"
+        INSERT INTO service_accounts (identity_id, description, created_at)
+        VALUES (?, ?, NOW())
+        "#,
+        identity_id,
+        description
+    )
+    .execute(&mut *tx)
+    .await?
+    .last_insert_id();
+
+    tx.commit().await?;
+    Ok(id)
+}
+
+pub async fn get_service_account(pool: &MySqlPool, id: u64) -> Result<Option<ServiceAccount>> {
+    let account = sqlx::query_as!(ServiceAccount,
+        r#"
+        SELECT s.id, s.identity_id, i.name, s.description, s.created_at
+        FROM service_accounts s
+        JOIN identities i ON i.id = s.identity_id
+        WHERE s.id = ?
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(account)
+}
+
+pub async fn list_service_accounts(pool: &MySqlPool) -> Result<Vec<ServiceAccount>> {
+    let accounts = sqlx::query_as!(ServiceAccount,
+        r#"
+        SELECT s.id, s.identity_id, i.name, s.description, s.created_at
+        FROM service_accounts s
+        JOIN identities i ON i.id = s.identity_id
+        ORDER BY s.id
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(accounts)
+}
+
+pub async fn create_token(
+    pool: &MySqlPool,
+    service_account_id: u64,
+    name: &str,
+    token_prefix: &str,
+    token_hash: &str,
+    scopes: &[TokenScope],
+    expires_at: chrono::DateTime<chrono::Utc>,
+) -> Result<u64> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO api_tokens (service_account_id, name, token_prefix, token_hash, scopes, expires_at, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, NOW())
+        "#,
+        service_account_id,
+        name,
+        token_prefix,
+        token_hash,
+        Json(scopes),
+        expires_at
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.last_insert_id())
+}
+
+pub async fn get_token(pool: &MySqlPool, id: u64) -> Result<Option<ApiTokenRecord>> {
+    let token = sqlx::query_as!(ApiTokenRecord,
+        r#"
+        SELECT id, service_account_id, name, token_prefix, scopes as `scopes: Json<Vec<TokenScope>>`,
+               expires_at, last_used_at, last_used_ip, revoked_at, created_at
+        FROM api_tokens
+        WHERE id = ?
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(token)
+}
+
+pub async fn list_tokens(pool: &MySqlPool, service_account_id: u64) -> Result<Vec<ApiTokenRecord>> {
+    let tokens = sqlx::query_as!(ApiTokenRecord,
+        r#"
+        SELECT id, service_account_id, name, token_prefix, scopes as `scopes: Json<Vec<TokenScope>>`,
+               expires_at, last_used_at, last_used_ip, revoked_at, created_at
+        FROM api_tokens
+        WHERE service_account_id = ?
+        ORDER BY id
+        "#,
+        service_account_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(tokens)
+}
+
+/// 查找未吊销且未过期的令牌
+pub async fn get_active_token_by_hash(pool: &MySqlPool, token_hash: &str) -> Result<Option<ApiTokenRecord>> {
+    let token = sqlx::query_as!(ApiTokenRecord,
+        r#"
+        SELECT id, service_account_id, name, token_prefix, scopes as `scopes: Json<Vec<TokenScope>>`,
+               expires_at, last_used_at, last_used_ip, revoked_at, created_at
+        FROM api_tokens
+        WHERE token_hash = ? AND revoked_at IS NULL AND expires_at > NOW()
+        "#,
+        token_hash
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(token)
+}
+
+/// 吊销令牌，返回是否有记录被修改
+pub async fn revoke_token(pool: &MySqlPool, service_account_id: u64, token_id: u64) -> Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE api_tokens
+        SET revoked_at = NOW()
+        WHERE id = ? AND service_account_id = ? AND revoked_at IS NULL
+        "#,
+        token_id,
+        service_account_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+/// 更新最近使用信息，同一令牌每分钟最多写入一次
+pub async fn touch_token(pool: &MySqlPool, id: u64, ip: Option<&str>) -> Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE api_tokens
+        SET last_used_at = NOW(), last_used_ip = ?
+        WHERE id = ? AND (last_used_at IS NULL OR last_used_at < NOW() - INTERVAL 1 MINUTE)
+        "#,
+        ip,
+        id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
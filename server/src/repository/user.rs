@@ -0,0 +1,213 @@
+use crate::model::access::IdentityRecord;
+use crate::model::user::UserRecord;
+use crate::repository::team as team_repository;
+use sqlx::{MySqlPool, Result};
+
+/// 在同一事务中创建用户及其对应的身份，身份加入给定团队
+pub async fn create_user(
+    pool: &MySqlPool,
+    username: &str,
+    role: &str,
+    credential_hash: &str,
+    password_hash: &str,
+    team_id: u64,
+) -> Result<(u64, u64)> {
+    let mut tx = pool.begin().await?;
+
+    let identity_id = sqlx::query!(
+        r#"
+        INSERT INTO identities (name, role, credential_hash, created_at)
+        VALUES (?, ?, ?, NOW())
+        "#,
+        username,
+        role,
+        credential_hash
+    )
+    .execute(&mut *tx)
+    .await?
+    .last_insert_id();
+
+    team_repository::add_member(&mut *tx, team_id, identity_id).await?;
+
+    let user_id = sqlx::query!(
+        r#"
+        INSERT INTO users (username, password_hash, identity_id, created_at)
+        VALUES (?, ?, ?, NOW())
+        "#,
+        username,
+        password_hash,
+        identity_id
+    )
+    .execute(&mut *tx)
+    .await?
+    .last_insert_id();
+
+    tx.commit().await?;
+    Ok((user_id, identity_id))
+}
+
+pub async fn get_user_by_username(pool: &MySqlPool, username: &str) -> Result<Option<UserRecord>> {
+    let user = sqlx::query_as!(UserRecord,
+        r#"
+        SELECT id, username, password_hash, identity_id, totp_secret, totp_enabled as `totp_enabled: bool`,
+               totp_last_step, created_at
+        FROM users
+        WHERE username = ?
+        "#,
+        username
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(user)
+}
+
+pub async fn get_user_by_identity(pool: &MySqlPool, identity_id: u64) -> Result<Option<UserRecord>> {
+    let user = sqlx::query_as!(UserRecord,
+        r#"
+        SELECT id, username, password_hash, identity_id, totp_secret, totp_enabled as `totp_enabled: bool`,
+               totp_last_step, created_at
+        FROM users
+        WHERE identity_id = ?
+        "#,
+        identity_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(user)
+}
+
+/// 保存待确认的TOTP种子，确认前不启用
+pub async fn set_pending_totp(pool: &MySqlPool, user_id: u64, encrypted_secret: &str) -> Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE users
+        SET totp_secret = ?, totp_enabled = FALSE, totp_last_step = NULL
+        WHERE id = ?
+        "#,
+        encrypted_secret,
+        user_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// 启用TOTP并替换全部恢复码
+pub async fn enable_totp(pool: &MySqlPool, user_id: u64, step: i64, recovery_code_hashes: &[String]) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query!(
+        r#"
+        UPDATE users
+        SET totp_enabled = TRUE, totp_last_step = ?
+        WHERE id = ?
+        "#,
+        step,
+        user_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        DELETE FROM user_recovery_codes
+        WHERE user_id = ?
+        "#,
+        user_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    for code_hash in recovery_code_hashes {
+        sqlx::query!(
+            r#"
+            INSERT INTO user_recovery_codes (user_id, code_hash)
+            VALUES (?, ?)
+            "#,
+            user_id,
+            code_hash
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// 记录已使用的时间步，只在新时间步大于旧值时成功
+pub async fn advance_totp_step(pool: &MySqlPool, user_id: u64, step: i64) -> Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE users
+        SET totp_last_step = ?
+        WHERE id = ? AND (totp_last_step IS NULL OR totp_last_step < ?)
+        "#,
+        step,
+        user_id,
+        step
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+/// 消耗一个恢复码，返回是否存在且未被使用
+pub async fn consume_recovery_code(pool: &MySqlPool, user_id: u64, code_hash: &str) -> Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE user_recovery_codes
+        SET used_at = NOW()
+        WHERE user_id = ? AND code_hash = ? AND used_at IS NULL
+        "#,
+        user_id,
+        code_hash
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+pub async fn create_session(
+    pool: &MySqlPool,
+    user_id: u64,
+    token_hash: &str,
+    expires_at: chrono::DateTime<chrono::Utc>,
+) -> Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO user_sessions (user_id, token_hash, expires_at, created_at)
+        VALUES (?, ?, ?, NOW())
+        "#,
+        user_id,
+        token_hash,
+        expires_at
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// 根据未过期的会话令牌哈希查找用户身份
+pub async fn get_identity_by_session_hash(pool: &MySqlPool, token_hash: &str) -> Result<Option<IdentityRecord>> {
+    let identity = sqlx::query_as!(IdentityRecord,
+        r#"
+        SELECT i.id, i.name, i.role, i.credential_hash, i.created_at
+        FROM user_sessions s
+        JOIN users u ON u.id = s.user_id
+        JOIN identities i ON i.id = u.identity_id
+        WHERE s.token_hash = ? AND s.expires_at > NOW()
+        "#,
+        token_hash
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(identity)
+}
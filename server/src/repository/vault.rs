@@ -0,0 +1,86 @@
+use crate::model::vault::VaultRecord;
+use shared::crypto::vault::{Ciphertext, KdfParams};
+use sqlx::types::Json;
+use sqlx::{MySqlPool, Result};
+
+pub async fn create_vault(
+    pool: &MySqlPool,
+    name: &str,
+    owner_identity_id: u64,
+    kdf: &KdfParams,
+    wrapped_key: &Ciphertext,
+) -> Result<u64> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO vaults (name, owner_identity_id, kdf_params, wrapped_key, wrapped_key_nonce, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, NOW(), NOW())
+        "#,
+        name,
+        owner_identity_id,
+        Json(kdf),
+        wrapped_key.ciphertext,
+        wrapped_key.nonce
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.last_insert_id())
+}
+
+pub async fn get_vault(pool: &MySqlPool, id: u64) -> Result<Option<VaultRecord>> {
+    let vault = sqlx::query_as!(VaultRecord,
+        r#"
+        SELECT id, name, owner_identity_id, kdf_params as `kdf_params: Json<KdfParams>`,
+               wrapped_key, wrapped_key_nonce, version, created_at, updated_at
+        FROM vaults
+        WHERE id = ?
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(vault)
+}
+
+pub async fn get_vault_by_name(pool: &MySqlPool, name: &str) -> Result<Option<VaultRecord>> {
+    let vault = sqlx::query_as!(VaultRecord,
+        r#"
+        SELECT id, name, owner_identity_id, kdf_params as `kdf_params: Json<KdfParams>`,
+               wrapped_key, wrapped_key_nonce, version, created_at, updated_at
+        FROM vaults
+        WHERE name = ?
+        "#,
+        name
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(vault)
+}
+
+/// 按版本条件替换被包装的保险库密钥，返回是否修改成功
+pub async fn rewrap(
+    pool: &MySqlPool,
+    id: u64,
+    kdf: &KdfParams,
+    wrapped_key: &Ciphertext,
+    expected_version: u32,
+) -> Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE vaults
+        SET kdf_params = ?, wrapped_key = ?, wrapped_key_nonce = ?, version = version + 1, updated_at = NOW()
+        WHERE id = ? AND version = ?
+        "#,
+        Json(kdf),
+        wrapped_key.ciphertext,
+        wrapped_key.nonce,
+        id,
+        expected_version
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() == 1)
+}
@@ -0,0 +1,197 @@
+use crate::model::webhook::{DeadLetter, ExpiringKey, WebhookRecord};
+use sqlx::{MySqlPool, Result};
+
+pub async fn create_webhook(
+    pool: &MySqlPool,
+    team_id: u64,
+    url: &str,
+    encrypted_secret: &str,
+    events: &str,
+    created_by: u64,
+) -> Result<u64> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO webhooks (team_id, url, encrypted_secret, events, created_by, created_at)
+        VALUES (?, ?, ?, ?, ?, NOW())
+        "#,
+        team_id,
+        url,
+        encrypted_secret,
+        events,
+        created_by
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.last_insert_id())
+}
+
+pub async fn get_webhook(pool: &MySqlPool, id: u64) -> Result<Option<WebhookRecord>> {
+    let webhook = sqlx::query_as!(WebhookRecord,
+        r#"
+        SELECT id, team_id, url, encrypted_secret, events, created_at
+        FROM webhooks
+        WHERE id = ?
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(webhook)
+}
+
+pub async fn list_webhooks(pool: &MySqlPool, team_id: u64) -> Result<Vec<WebhookRecord>> {
+    let webhooks = sqlx::query_as!(WebhookRecord,
+        r#"
+        SELECT id, team_id, url, encrypted_secret, events, created_at
+        FROM webhooks
+        WHERE team_id = ?
+        ORDER BY id
+        "#,
+        team_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(webhooks)
+}
+
+/// 列出团队内订阅了指定事件的Webhook
+pub async fn list_subscribed(pool: &MySqlPool, team_id: u64, event: &str) -> Result<Vec<WebhookRecord>> {
+    let webhooks = sqlx::query_as!(WebhookRecord,
+        r#"
+        SELECT id, team_id, url, encrypted_secret, events, created_at
+        FROM webhooks
+        WHERE team_id = ? AND FIND_IN_SET(?, events) > 0
+        "#,
+        team_id,
+        event
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(webhooks)
+}
+
+pub async fn delete_webhook(pool: &MySqlPool, team_id: u64, id: u64) -> Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM webhooks
+        WHERE id = ? AND team_id = ?
+        "#,
+        id,
+        team_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn create_dead_letter(
+    pool: &MySqlPool,
+    webhook_id: u64,
+    event_type: &str,
+    payload: &str,
+    attempts: u32,
+    last_error: &str,
+) -> Result<u64> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO webhook_dead_letters (webhook_id, event_type, payload, attempts, last_error, created_at)
+        VALUES (?, ?, ?, ?, ?, NOW())
+        "#,
+        webhook_id,
+        event_type,
+        payload,
+        attempts,
+        last_error
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.last_insert_id())
+}
+
+pub async fn list_dead_letters(pool: &MySqlPool, team_id: u64) -> Result<Vec<DeadLetter>> {
+    let dead_letters = sqlx::query_as!(DeadLetter,
+        r#"
+        SELECT d.id, d.webhook_id, d.event_type, d.payload, d.attempts, d.last_error, d.created_at
+        FROM webhook_dead_letters d
+        JOIN webhooks w ON w.id = d.webhook_id
+        WHERE w.team_id = ?
+        ORDER BY d.id DESC
+        "#,
+        team_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(dead_letters)
+}
+
+pub async fn get_dead_letter(pool: &MySqlPool, team_id: u64, id: u64) -> Result<Option<DeadLetter>> {
+    let dead_letter = sqlx::query_as!(DeadLetter,
+        r#"
+        SELECT d.id, d.webhook_id, d.event_type, d.payload, d.attempts, d.last_error, d.created_at
+        FROM webhook_dead_letters d
+        JOIN webhooks w ON w.id = d.webhook_id
+        WHERE d.id = ? AND w.team_id = ?
+        "#,
+        id,
+        team_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(dead_letter)
+}
+
+pub async fn delete_dead_letter(pool: &MySqlPool, id: u64) -> Result<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM webhook_dead_letters
+        WHERE id = ?
+        "#,
+        id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// 列出在指定天数内过期且尚未通知的密钥
+pub async fn list_expiring_keys(pool: &MySqlPool, within_days: u32) -> Result<Vec<ExpiringKey>> {
+    let keys = sqlx::query_as!(ExpiringKey,
+        r#"
+        SELECT id, team_id, name, expires_at as `expires_at!`
+        FROM keys
+        WHERE expires_at IS NOT NULL
+          AND expiry_notified_at IS NULL
+          AND expires_at <= DATE_ADD(NOW(), INTERVAL ? DAY)
+        ORDER BY expires_at
+        "#,
+        within_days
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(keys)
+}
+
+pub async fn mark_expiry_notified(pool: &MySqlPool, key_id: u64) -> Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE keys
+        SET expiry_notified_at = NOW()
+        WHERE id = ?
+        "#,
+        key_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
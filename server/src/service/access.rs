@@ -0,0 +1,226 @@
+use crate::model::access::{
+    CreateIdentityRequest, CreateIdentityResponse, Decision, GrantRequest, Identity, KeyAcl,
+    Permission, Role, SubjectType,
+};
+use crate::repository::{self, access as access_repository, team as team_repository, user as user_repository};
+use sha2::{Digest, Sha256};
+use sqlx::{MySqlConnection, MySqlPool};
+use std::error::Error;
+
+// 凭据随机部分的字节数
+const CREDENTIAL_SIZE: usize = 32;
+
+/// 生成新的随机凭据
+pub fn generate_credential() -> Result<String, Box<dyn Error>> {
+    let mut bytes = [0u8; CREDENTIAL_SIZE];
+    getrandom::getrandom(&mut bytes)?;
+    Ok(format!("ek_{}", hex::encode(bytes)))
+}
+
+/// 计算凭据的SHA-256哈希，数据库中只保存哈希值
+pub fn hash_credential(credential: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(credential.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// 根据凭据认证调用方，凭据可以是身份凭据或用户登录得到的会话令牌
+pub async fn authenticate(
+    pool: &MySqlPool,
+    credential: &str,
+) -> Result<Option<Identity>, Box<dyn Error>> {
+    let credential_hash = hash_credential(credential);
+    let record = match access_repository::get_identity_by_credential_hash(pool, &credential_hash).await? {
+        Some(record) => Some(record),
+        None => user_repository::get_identity_by_session_hash(pool, &credential_hash).await?,
+    };
+
+    match record {
+        Some(record) => Ok(Some(Identity {
+            id: record.id,
+            name: record.name,
+            role: record.role.parse()?,
+            source_ip: None,
+            team_id: None,
+        })),
+        None => Ok(None),
+    }
+}
+
+/// 首次启动时根据BOOTSTRAP_ADMIN_TOKEN创建初始管理员，初始管理员加入默认团队
+pub async fn bootstrap_admin(pool: &MySqlPool) -> Result<(), Box<dyn Error>> {
+    let Ok(token) = std::env::var("BOOTSTRAP_ADMIN_TOKEN") else {
+        return Ok(());
+    };
+
+    if access_repository::count_identities(pool).await? == 0 {
+        let team_id = team_repository::find_default_team(pool)
+            .await?
+            .ok_or("Default team not found; run the database migrations first")?;
+        access_repository::create_identity(pool, "admin", Role::Admin.as_str(), &hash_credential(&token), team_id)
+            .await?;
+        tracing::info!("Bootstrap admin identity created");
+    }
+
+    Ok(())
+}
+
+/// 创建身份并返回一次性可见的凭据，新身份加入创建者当前的团队
+pub async fn create_identity(
+    pool: &MySqlPool,
+    request: CreateIdentityRequest,
+    team_id: u64,
+) -> Result<CreateIdentityResponse, Box<dyn Error>> {
+    let credential = generate_credential()?;
+    let id = access_repository::create_identity(
+        pool,
+        &request.name,
+        request.role.as_str(),
+        &hash_credential(&credential),
+        team_id,
+    )
+    .await?;
+
+    Ok(CreateIdentityResponse {
+        id,
+        name: request.name,
+        role: request.role,
+        credential,
+    })
+}
+
+/// 在给定连接上授予密钥创建者全部权限，须与创建密钥处于同一事务
+pub async fn insert_owner_grants(conn: &mut MySqlConnection, key_id: u64, owner: &Identity) -> Result<(), Box<dyn Error>> {
+    for permission in Permission::ALL {
+        access_repository::create_grant(
+            &mut *conn,
+            key_id,
+            SubjectType::Identity.as_str(),
+            &owner.id.to_string(),
+            permission.as_str(),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+pub async fn grant(pool: &MySqlPool, key_id: u64, request: GrantRequest) -> Result<u64, Box<dyn Error>> {
+    // 校验授权对象
+    match request.subject_type {
+        SubjectType::Identity => {
+            request.subject.parse::<u64>()?;
+        }
+        SubjectType::Role => {
+            request.subject.parse::<Role>()?;
+        }
+    }
+
+    let acl_id = access_repository::create_grant(
+        &mut *pool.acquire().await?,
+        key_id,
+        request.subject_type.as_str(),
+        &request.subject,
+        request.permission.as_str(),
+    )
+    .await?;
+
+    Ok(acl_id)
+}
+
+pub async fn list_grants(pool: &MySqlPool, key_id: u64) -> Result<Vec<KeyAcl>, Box<dyn Error>> {
+    Ok(access_repository::list_grants(&mut *pool.acquire().await?, key_id).await?)
+}
+
+pub async fn revoke(pool: &MySqlPool, key_id: u64, acl_id: u64) -> Result<(), Box<dyn Error>> {
+    access_repository::delete_grant(pool, key_id, acl_id).await?;
+    Ok(())
+}
+
+/// 判定身份对密钥的操作是否被允许（默认拒绝）
+///
+/// 只能访问当前团队的密钥；团队内管理员可读取元数据、修改和删除，审计员可读取元数据；
+/// 读取密钥内容和由服务端使用密钥始终需要显式授权。
+pub async fn evaluate(
+    conn: &mut MySqlConnection,
+    identity: &Identity,
+    key_id: u64,
+    permission: Permission,
+) -> Result<Decision, Box<dyn Error>> {
+    if let Some(team_id) = repository::get_key_team(&mut *conn, key_id).await? {
+        if identity.team_id != Some(team_id) {
+            return Ok(Decision::deny(format!("key {} does not belong to the current team", key_id)));
+        }
+    }
+
+    if let Some(decision) = implicit_decision(identity, permission) {
+        return Ok(decision);
+    }
+    let grants = access_repository::list_grants(&mut *conn, key_id).await?;
+    Ok(decide(identity, key_id, permission, &grants))
+}
+
+// 角色隐含的权限，无需查询ACL
+fn implicit_decision(identity: &Identity, permission: Permission) -> Option<Decision> {
+    match (identity.role, permission) {
+        (Role::Admin, Permission::ReadMetadata | Permission::Update | Permission::Delete) => {
+            Some(Decision::allow("role admin is implicitly granted this permission", None))
+        }
+        (Role::Auditor, Permission::ReadMetadata) => {
+            Some(Decision::allow("role auditor is implicitly granted read_metadata", None))
+        }
+        _ => None,
+    }
+}
+
+/// 按角色和密钥上的ACL记录判定，只有授予该权限且主体匹配的记录才允许访问
+pub fn decide(identity: &Identity, key_id: u64, permission: Permission, grants: &[KeyAcl]) -> Decision {
+    if let Some(decision) = implicit_decision(identity, permission) {
+        return decision;
+    }
+
+    let identity_subject = identity.id.to_string();
+    for acl in grants.iter().filter(|acl| acl.permission == permission.as_str()) {
+        let matched = match acl.subject_type.as_str() {
+            "identity" => acl.subject == identity_subject,
+            "role" => acl.subject == identity.role.as_str(),
+            _ => false,
+        };
+        if matched {
+            return Decision::allow(
+                format!("granted by acl {} ({} {})", acl.id, acl.subject_type, acl.subject),
+                Some(acl.id),
+            );
+        }
+    }
+
+    Decision::deny(format!(
+        "no acl on key {} grants {} to identity {} or role {}",
+        key_id, permission, identity.id, identity.role
+    ))
+}
+
+/// 为管理员解释某个身份的访问判定，以密钥所属团队作为该身份的团队上下文
+pub async fn explain(
+    pool: &MySqlPool,
+    identity_id: u64,
+    key_id: u64,
+    permission: Permission,
+) -> Result<Decision, Box<dyn Error>> {
+    let Some(record) = access_repository::get_identity_by_id(pool, identity_id).await? else {
+        return Ok(Decision::deny(format!("identity {} does not exist", identity_id)));
+    };
+
+    let team_id = match repository::get_key_team(&mut *pool.acquire().await?, key_id).await? {
+        Some(team_id) if team_repository::is_member(pool, team_id, identity_id).await? => Some(team_id),
+        _ => None,
+    };
+    let identity = Identity {
+        id: record.id,
+        name: record.name,
+        role: record.role.parse()?,
+        source_ip: None,
+        team_id,
+    };
+
+    evaluate(&mut *pool.acquire().await?, &identity, key_id, permission).await
+}
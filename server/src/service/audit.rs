@@ -0,0 +1,263 @@
+use crate::model::audit::{AuditEvent, AuditHead, AuditQuery, ChainVerification, NewAuditEvent};
+use crate::repository::audit as audit_repository;
+use chrono::SubsecRound;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use sqlx::MySqlPool;
+use std::error::Error;
+use tokio::sync::Mutex;
+
+// 链首记录的prev_hash
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+// 单次查询返回的最大记录数
+const MAX_QUERY_LIMIT: u32 = 1000;
+// 校验哈希链时每批读取的记录数
+const VERIFY_BATCH_SIZE: u32 = 500;
+
+// 只串行化本进程内的写入，减少并发请求在audit_head行锁上的等待；
+// 多个实例之间的顺序由record中对audit_head的FOR UPDATE保证
+static AUDIT_LOCK: Mutex<()> = Mutex::const_new(());
+
+fn hmac_key() -> Result<String, Box<dyn Error>> {
+    std::env::var("AUDIT_HMAC_KEY").map_err(|_| "Audit HMAC key not configured".into())
+}
+
+/// 计算审计记录的HMAC-SHA256，覆盖上一条hash及本条全部字段
+pub fn compute_hash(key: &str, event: &AuditEvent) -> Result<String, Box<dyn Error>> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes())?;
+    let fields = [
+        event.prev_hash.clone(),
+        event.actor_id.map(|id| id.to_string()).unwrap_or_default(),
+        event.actor_name.clone().unwrap_or_default(),
+        event.action.clone(),
+        event.key_id.map(|id| id.to_string()).unwrap_or_default(),
+        event.outcome.clone(),
+        event.status_code.to_string(),
+        event.source_ip.clone().unwrap_or_default(),
+        event.request_id.clone(),
+        event.created_at.timestamp_micros().to_string(),
+    ];
+    for field in fields {
+        // 长度前缀避免字段拼接产生歧义
+        mac.update(&(field.len() as u64).to_be_bytes());
+        mac.update(field.as_bytes());
+    }
+    Ok(hex::encode(mac.finalize().into_bytes()))
+}
+
+/// 计算链尾锚点的HMAC，防止截断末尾记录后同步改写锚点
+pub fn compute_head_mac(key: &str, head: &AuditHead) -> Result<String, Box<dyn Error>> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes())?;
+    for field in [head.last_id.to_string(), head.last_hash.clone(), head.entries.to_string()] {
+        mac.update(&(field.len() as u64).to_be_bytes());
+        mac.update(field.as_bytes());
+    }
+    Ok(hex::encode(mac.finalize().into_bytes()))
+}
+
+/// 为迁移时写入的链尾锚点补上HMAC，已签名时不做任何修改
+pub async fn anchor_head(pool: &MySqlPool) -> Result<(), Box<dyn Error>> {
+    let key = hmac_key()?;
+    let _guard = AUDIT_LOCK.lock().await;
+
+    let mut tx = pool.begin().await?;
+    let mut head = audit_repository::lock_head(&mut tx).await?;
+    if head.mac.is_empty() {
+        head.mac = compute_head_mac(&key, &head)?;
+        audit_repository::update_head(&mut tx, &head).await?;
+    }
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// 追加一条审计事件到哈希链末尾
+pub async fn record(pool: &MySqlPool, new_event: NewAuditEvent) -> Result<u64, Box<dyn Error>> {
+    let key = hmac_key()?;
+    let _guard = AUDIT_LOCK.lock().await;
+
+    let mut tx = pool.begin().await?;
+    let head = audit_repository::lock_head(&mut tx).await?;
+
+    let mut event = AuditEvent {
+        id: 0,
+        actor_id: new_event.actor_id,
+        actor_name: new_event.actor_name,
+        action: new_event.action,
+        key_id: new_event.key_id,
+        outcome: new_event.outcome,
+        status_code: new_event.status_code,
+        source_ip: new_event.source_ip,
+        request_id: new_event.request_id,
+        // DATETIME(6)只保留微秒精度
+        created_at: chrono::Utc::now().trunc_subsecs(6),
+        prev_hash: head.last_hash,
+        hash: String::new(),
+    };
+    event.hash = compute_hash(&key, &event)?;
+
+    let id = audit_repository::insert_event(&mut tx, &event).await?;
+
+    let mut head = AuditHead {
+        last_id: id,
+        last_hash: event.hash,
+        entries: head.entries + 1,
+        mac: String::new(),
+    };
+    head.mac = compute_head_mac(&key, &head)?;
+    audit_repository::update_head(&mut tx, &head).await?;
+    tx.commit().await?;
+
+    Ok(id)
+}
+
+pub async fn query(pool: &MySqlPool, query: AuditQuery) -> Result<Vec<AuditEvent>, Box<dyn Error>> {
+    let limit = query.limit.unwrap_or(100).min(MAX_QUERY_LIMIT);
+    Ok(audit_repository::query_events(pool, &query, limit).await?)
+}
+
+/// 从链首开始校验哈希链，发现删除或篡改时返回第一条异常记录；
+/// 链尾与audit_head比对，以发现末尾记录被截断
+pub async fn verify_chain(pool: &MySqlPool) -> Result<ChainVerification, Box<dyn Error>> {
+    let key = hmac_key()?;
+    let invalid = |checked, id, reason: &str| ChainVerification {
+        checked,
+        valid: false,
+        first_invalid_id: id,
+        reason: Some(reason.to_string()),
+    };
+
+    // 先读取锚点，校验期间新追加的记录不在本次范围内
+    let Some(head) = audit_repository::get_head(pool).await? else {
+        return Ok(invalid(0, None, "audit head is missing"));
+    };
+    if head.mac.is_empty() {
+        return Ok(invalid(0, None, "audit head is not signed yet; start the server once to anchor it"));
+    }
+    if compute_head_mac(&key, &head)? != head.mac {
+        return Ok(invalid(0, None, "audit head MAC does not match"));
+    }
+
+    let mut checked = 0;
+    let mut last_id = 0;
+    let mut expected_prev = GENESIS_HASH.to_string();
+
+    'batches: loop {
+        let events = audit_repository::list_events_after(pool, last_id, VERIFY_BATCH_SIZE).await?;
+        if events.is_empty() {
+            break;
+        }
+
+        for event in events {
+            if event.id > head.last_id {
+                break 'batches;
+            }
+
+            let failure = if event.prev_hash != expected_prev {
+                Some("prev_hash does not match the preceding entry")
+            } else if compute_hash(&key, &event)? != event.hash {
+                Some("hash does not match entry contents")
+            } else {
+                None
+            };
+
+            if let Some(reason) = failure {
+                return Ok(invalid(checked, Some(event.id), reason));
+            }
+
+            checked += 1;
+            last_id = event.id;
+            expected_prev = event.hash;
+        }
+    }
+
+    if last_id != head.last_id || expected_prev != head.last_hash || checked != head.entries {
+        return Ok(invalid(
+            checked,
+            Some(head.last_id),
+            "chain ends before the recorded head; newest entries are missing",
+        ));
+    }
+
+    Ok(ChainVerification {
+        checked,
+        valid: true,
+        first_invalid_id: None,
+        reason: None,
+    })
+}
+
+/// 由HTTP方法和路由模板得到审计动作名
+pub fn action_for(method: &str, path: &str) -> String {
+    let action = match (method, path) {
+        ("POST", "/keys") => "key.create",
+        ("POST", "/keys/search") => "key.search",
+        ("POST", "/keys/batch") => "key.batch",
+        ("GET", "/keys/{id}") => "key.read",
+        ("GET", "/keys/{id}/material") => "key.read_material",
+        ("PUT", "/keys/{id}") => "key.update",
+        ("DELETE", "/keys/{id}") => "key.delete",
+        ("POST", "/keys/{id}/shares") => "key.share",
+        ("POST", "/keys/{id}/rotate") => "key.rotate",
+        ("PUT", "/keys/{id}/rotation-policy") => "key.set_rotation_policy",
+        ("GET", "/keys/{id}/versions/{version}/material") => "key.read_version_material",
+        ("POST", "/keys/{id}/encrypt") => "key.encrypt",
+        ("POST", "/keys/{id}/decrypt") => "key.decrypt",
+        ("POST", "/keys/{id}/sign") => "key.sign",
+        ("POST", "/pki/cas") => "ca.create",
+        ("POST", "/pki/cas/{id}/certificates") => "ca.issue",
+        ("POST", "/pki/cas/{id}/certificates/{serial}/revoke") => "ca.revoke",
+        ("POST", "/ssh/keys") => "key.create",
+        ("POST", "/ssh/cas") => "ssh_ca.create",
+        ("POST", "/ssh/cas/{id}/sign") => "ssh_ca.sign",
+        ("POST", "/leases") => "lease.create",
+        ("POST", "/leases/{id}/renew") => "lease.renew",
+        ("DELETE", "/leases/{id}") => "lease.revoke",
+        ("POST", "/leases/hooks") => "lease_hook.create",
+        ("DELETE", "/leases/hooks/{id}") => "lease_hook.delete",
+        ("PUT", "/quotas/{subject_type}/{subject}") => "quota.set",
+        ("DELETE", "/quotas/{subject_type}/{subject}") => "quota.delete",
+        ("POST", "/organizations") => "organization.create",
+        ("POST", "/organizations/{id}/teams") => "team.create",
+        ("DELETE", "/teams/{id}") => "team.delete",
+        ("POST", "/teams/{id}/members") => "team_member.add",
+        ("DELETE", "/teams/{id}/members/{identity_id}") => "team_member.remove",
+        ("DELETE", "/shares/{share_id}") => "share.revoke",
+        ("POST", "/keys/{id}/acl") => "acl.grant",
+        ("DELETE", "/keys/{id}/acl/{acl_id}") => "acl.revoke",
+        ("POST", "/identities") => "identity.create",
+        ("POST", "/users") => "user.create",
+        ("POST", "/auth/login") => "user.login",
+        ("POST", "/service-accounts") => "service_account.create",
+        ("POST", "/vaults") => "vault.create",
+        ("PUT", "/vaults/{id}/wrapped-key") => "vault.rewrap",
+        ("POST", "/vaults/{id}/keys") => "key.create",
+        ("POST", "/service-accounts/{id}/tokens") => "api_token.create",
+        ("DELETE", "/service-accounts/{id}/tokens/{token_id}") => "api_token.revoke",
+        ("POST", "/users/me/totp") => "user.totp_enroll",
+        ("POST", "/users/me/totp/confirm") => "user.totp_confirm",
+        ("PUT", "/identities/me/public-key") => "identity.set_public_key",
+        ("GET", "/access/explain") => "access.explain",
+        ("POST", "/webhooks") => "webhook.create",
+        ("DELETE", "/webhooks/{id}") => "webhook.delete",
+        ("GET", "/backup/export") => "backup.export",
+        ("POST", "/backup/restore") => "backup.restore",
+        ("GET", "/backup/team/export") => "backup.team_export",
+        ("POST", "/backup/team/restore") => "backup.team_restore",
+        ("DELETE", "/lockouts/{ip}") => "lockout.unlock",
+        ("GET", "/audit/events") => "audit.query",
+        ("POST", "/audit/verify-chain") => "audit.verify_chain",
+        _ => return format!("{} {}", method.to_lowercase(), path),
+    };
+    action.to_string()
+}
+
+/// 由响应状态码得到审计结果
+pub fn outcome_for(status: u16) -> &'static str {
+    match status {
+        200..=399 => "success",
+        401 => "unauthenticated",
+        403 => "denied",
+        _ => "failure",
+    }
+}
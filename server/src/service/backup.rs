@@ -0,0 +1,364 @@
+use crate::model::backup::{
+    BackupAcl, BackupChecksum, BackupEntry, BackupKey, BackupManifest, BackupRotationPolicy, BackupScope, BackupTeam,
+    BackupVault, ConflictStrategy, RestoreQuery, RestoreReport, BACKUP_FORMAT_VERSION,
+};
+use crate::repository::{
+    access as access_repository, backup as backup_repository, label as label_repository,
+    rotation as rotation_repository, team as team_repository, vault as vault_repository,
+};
+use crate::utils::archive::{read_archive, ArchiveWriter};
+use crate::utils::encryption::{decrypt_data, encrypt_data};
+use sha2::{Digest, Sha256};
+use shared::crypto::vault::Ciphertext;
+use sqlx::MySqlPool;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use tokio::io::AsyncWrite;
+
+// 导出时每批读取的密钥数
+const EXPORT_BATCH_SIZE: u32 = 100;
+// 旧备份没有团队信息，恢复整个存储时放入迁移创建的默认团队
+const DEFAULT_ORGANIZATION: &str = "default";
+const DEFAULT_TEAM: &str = "default";
+
+/// 读取备份密钥，必须与ENCRYPTION_KEY不同
+pub fn backup_key() -> Result<String, Box<dyn Error>> {
+    let backup_key = std::env::var("BACKUP_KEY").map_err(|_| "Backup key not configured")?;
+    if std::env::var("ENCRYPTION_KEY").is_ok_and(|encryption_key| encryption_key == backup_key) {
+        return Err("BACKUP_KEY must differ from ENCRYPTION_KEY".into());
+    }
+    Ok(backup_key)
+}
+
+/// 将范围内的全部密钥及元数据流式导出为加密备份文件
+pub async fn export<W: AsyncWrite + Unpin>(
+    pool: &MySqlPool,
+    scope: BackupScope,
+    writer: W,
+    backup_key: &str,
+) -> Result<BackupManifest, Box<dyn Error>> {
+    let mut archive = ArchiveWriter::new(writer, backup_key).await?;
+    let mut archive_hasher = Sha256::new();
+    let mut checksums = Vec::new();
+    let mut teams = HashMap::new();
+    let mut last_id = 0;
+
+    loop {
+        let keys = backup_repository::list_keys_after(pool, scope.team_id(), last_id, EXPORT_BATCH_SIZE).await?;
+        if keys.is_empty() {
+            break;
+        }
+
+        for key in keys {
+            let key_id = key.id.ok_or("Key row without id")?;
+            last_id = key_id;
+
+            let rotation_policy = rotation_repository::get_policy(pool, key_id)
+                .await?
+                .map(|policy| BackupRotationPolicy {
+                    interval_days: policy.interval_days,
+                    last_rotated_at: policy.last_rotated_at,
+                    next_rotation_at: policy.next_rotation_at,
+                });
+            let acls = access_repository::list_grants(&mut *pool.acquire().await?, key_id)
+                .await?
+                .into_iter()
+                .map(|acl| BackupAcl {
+                    subject_type: acl.subject_type,
+                    subject: acl.subject,
+                    permission: acl.permission,
+                })
+                .collect();
+            let vault = match key.vault_id {
+                Some(vault_id) => vault_repository::get_vault(pool, vault_id).await?.map(|vault| BackupVault {
+                    name: vault.name,
+                    owner_identity_id: vault.owner_identity_id,
+                    kdf: vault.kdf_params.0,
+                    wrapped_key: Ciphertext {
+                        nonce: vault.wrapped_key_nonce,
+                        ciphertext: vault.wrapped_key,
+                    },
+                }),
+                None => None,
+            };
+            let team = match key.team_id {
+                Some(team_id) => backup_team(pool, &mut teams, team_id).await?,
+                None => None,
+            };
+
+            let entry = BackupEntry::Key(BackupKey {
+                id: key_id,
+                name: key.name.clone(),
+                encrypted_data: key.encrypted_data,
+                kind: key.kind,
+                expires_at: key.expires_at,
+                description: key.description,
+                labels: label_repository::get_labels(pool, key_id).await?,
+                metadata: key.metadata,
+                nonce: key.nonce,
+                vault,
+                team,
+                created_at: key.created_at.ok_or("Key row without created_at")?,
+                updated_at: key.updated_at.ok_or("Key row without updated_at")?,
+                rotation_policy,
+                versions: backup_repository::list_key_versions(pool, key_id).await?,
+                acls,
+            });
+
+            let plaintext = serde_json::to_vec(&entry)?;
+            archive_hasher.update(&plaintext);
+            checksums.push(BackupChecksum {
+                name: key.name,
+                sha256: hex::encode(Sha256::digest(&plaintext)),
+            });
+            archive.write_chunk(&plaintext, false).await?;
+        }
+    }
+
+    let manifest = BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        created_at: chrono::Utc::now(),
+        key_count: checksums.len() as u64,
+        checksums,
+        archive_checksum: hex::encode(archive_hasher.finalize()),
+    };
+    archive
+        .write_chunk(&serde_json::to_vec(&BackupEntry::Manifest(manifest.clone()))?, true)
+        .await?;
+
+    Ok(manifest)
+}
+
+// 按团队id查找团队名称，同一团队只查询一次
+async fn backup_team(
+    pool: &MySqlPool,
+    teams: &mut HashMap<u64, Option<BackupTeam>>,
+    team_id: u64,
+) -> Result<Option<BackupTeam>, Box<dyn Error>> {
+    if let Some(team) = teams.get(&team_id) {
+        return Ok(team.clone());
+    }
+    let team = team_repository::get_team(pool, team_id).await?.map(|team| BackupTeam {
+        organization: team.organization_name,
+        name: team.name,
+    });
+    teams.insert(team_id, team.clone());
+    Ok(team)
+}
+
+/// 解密并校验备份文件，返回密钥条目和清单
+fn parse_archive(data: &[u8], backup_key: &str) -> Result<(Vec<BackupKey>, BackupManifest), Box<dyn Error>> {
+    let mut chunks = read_archive(data, backup_key)?;
+    let manifest_chunk = chunks.pop().ok_or("Archive contains no manifest")?;
+    let BackupEntry::Manifest(manifest) = serde_json::from_slice(&manifest_chunk)? else {
+        return Err("Final chunk is not a manifest".into());
+    };
+
+    if manifest.format_version != BACKUP_FORMAT_VERSION {
+        return Err(format!("Unsupported backup format version {}", manifest.format_version).into());
+    }
+    if manifest.key_count != chunks.len() as u64 || manifest.checksums.len() != chunks.len() {
+        return Err("Manifest key count does not match archive contents".into());
+    }
+
+    let mut archive_hasher = Sha256::new();
+    let mut keys = Vec::with_capacity(chunks.len());
+    for (chunk, checksum) in chunks.iter().zip(&manifest.checksums) {
+        archive_hasher.update(chunk);
+        if hex::encode(Sha256::digest(chunk)) != checksum.sha256 {
+            return Err(format!("Checksum mismatch for key {}", checksum.name).into());
+        }
+        let BackupEntry::Key(key) = serde_json::from_slice(chunk)? else {
+            return Err("Unexpected manifest before end of archive".into());
+        };
+        keys.push(key);
+    }
+    if hex::encode(archive_hasher.finalize()) != manifest.archive_checksum {
+        return Err("Archive checksum mismatch".into());
+    }
+
+    Ok((keys, manifest))
+}
+
+// 用源密钥解密并用目标密钥重新加密，两者相同时只做解密校验
+fn reencrypt(encrypted_data: &str, source_key: &str, target_key: &str) -> Result<String, Box<dyn Error>> {
+    let plaintext = decrypt_data(encrypted_data, source_key)?;
+    if source_key == target_key {
+        Ok(encrypted_data.to_string())
+    } else {
+        encrypt_data(&plaintext, target_key)
+    }
+}
+
+// 恢复的目标团队：已存在的团队，或写入时按名称重建的团队
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum TargetTeam {
+    Existing(u64),
+    Missing(BackupTeam),
+}
+
+impl TargetTeam {
+    async fn resolve(pool: &MySqlPool, scope: BackupScope, key: &BackupKey) -> Result<Self, Box<dyn Error>> {
+        let team = match scope {
+            BackupScope::Team(team_id) => return Ok(TargetTeam::Existing(team_id)),
+            BackupScope::All => key.team.clone().unwrap_or_else(|| BackupTeam {
+                organization: DEFAULT_ORGANIZATION.to_string(),
+                name: DEFAULT_TEAM.to_string(),
+            }),
+        };
+        Ok(match backup_repository::find_team(pool, &team).await? {
+            Some(team_id) => TargetTeam::Existing(team_id),
+            None => TargetTeam::Missing(team),
+        })
+    }
+
+    // 团队中是否已有同名密钥，尚未创建的团队中没有任何密钥
+    async fn has_key(&self, pool: &MySqlPool, name: &str) -> Result<bool, Box<dyn Error>> {
+        match self {
+            TargetTeam::Existing(team_id) => {
+                Ok(backup_repository::get_key_id_by_name(pool, *team_id, name).await?.is_some())
+            }
+            TargetTeam::Missing(_) => Ok(false),
+        }
+    }
+}
+
+// 为冲突的密钥生成团队内未被占用的新名称
+async fn available_name(
+    pool: &MySqlPool,
+    team: &TargetTeam,
+    name: &str,
+    taken: &HashSet<(TargetTeam, String)>,
+) -> Result<String, Box<dyn Error>> {
+    for suffix in 1.. {
+        let candidate = if suffix == 1 {
+            format!("{}-restored", name)
+        } else {
+            format!("{}-restored-{}", name, suffix)
+        };
+        if !taken.contains(&(team.clone(), candidate.clone())) && !team.has_key(pool, &candidate).await? {
+            return Ok(candidate);
+        }
+    }
+    unreachable!()
+}
+
+/// 从备份文件恢复密钥
+///
+/// 恢复整个存储时密钥回到备份中记录的团队，不存在的团队按名称重建（重建的团队没有成员）；
+/// 恢复到单个团队时全部密钥进入该团队。
+/// `source_key`为导出时使用的ENCRYPTION_KEY，`target_key`为当前服务的ENCRYPTION_KEY，
+/// 两者不同时密钥值会被重新加密。dry_run只做校验并报告冲突，不写入数据库。
+pub async fn restore(
+    pool: &MySqlPool,
+    scope: BackupScope,
+    data: &[u8],
+    backup_key: &str,
+    source_key: &str,
+    target_key: &str,
+    query: RestoreQuery,
+) -> Result<RestoreReport, Box<dyn Error>> {
+    let (keys, manifest) = parse_archive(data, backup_key)?;
+    let mut report = RestoreReport {
+        dry_run: query.dry_run,
+        manifest_created_at: Some(manifest.created_at),
+        total: keys.len() as u64,
+        ..Default::default()
+    };
+
+    // 校验所有密钥值都能用源密钥解密，并在需要时重新加密
+    let mut prepared = Vec::with_capacity(keys.len());
+    for key in keys {
+        // 保险库中的密钥为客户端密文，原样恢复
+        if key.vault.is_some() {
+            let encrypted_data = key.encrypted_data.clone();
+            prepared.push((key, encrypted_data, Vec::new()));
+            continue;
+        }
+        let encrypted_data = match reencrypt(&key.encrypted_data, source_key, target_key) {
+            Ok(data) => data,
+            Err(e) => {
+                report.errors.push(format!("{}: {}", key.name, e));
+                continue;
+            }
+        };
+        let mut versions = Vec::with_capacity(key.versions.len());
+        for version in &key.versions {
+            match reencrypt(&version.encrypted_data, source_key, target_key) {
+                Ok(data) => versions.push(data),
+                Err(e) => report.errors.push(format!("{} v{}: {}", key.name, version.version, e)),
+            }
+        }
+        if versions.len() == key.versions.len() {
+            prepared.push((key, encrypted_data, versions));
+        }
+    }
+
+    // 检查名称冲突
+    let mut taken = HashSet::new();
+    let mut plan = Vec::with_capacity(prepared.len());
+    for (key, encrypted_data, versions) in prepared {
+        let team = TargetTeam::resolve(pool, scope, &key).await?;
+        let exists = team.has_key(pool, &key.name).await?;
+        let target_name = if exists {
+            report.conflicts.push(key.name.clone());
+            match query.conflict {
+                ConflictStrategy::Skip => {
+                    report.skipped += 1;
+                    continue;
+                }
+                ConflictStrategy::Overwrite => {
+                    report.overwritten += 1;
+                    key.name.clone()
+                }
+                ConflictStrategy::Rename => {
+                    let name = available_name(pool, &team, &key.name, &taken).await?;
+                    report.renamed.push(format!("{} -> {}", key.name, name));
+                    name
+                }
+                ConflictStrategy::Fail => key.name.clone(),
+            }
+        } else {
+            key.name.clone()
+        };
+        taken.insert((team.clone(), target_name.clone()));
+        plan.push((key, team, target_name, exists, encrypted_data, versions));
+    }
+
+    if query.conflict == ConflictStrategy::Fail && !report.conflicts.is_empty() {
+        report.errors.push("Restore aborted: conflicting key names exist".to_string());
+    }
+    if query.dry_run || !report.errors.is_empty() {
+        report.restored = 0;
+        return Ok(report);
+    }
+
+    let mut tx = pool.begin().await?;
+    for (key, team, target_name, exists, encrypted_data, versions) in plan {
+        let team_id = match &team {
+            TargetTeam::Existing(team_id) => *team_id,
+            TargetTeam::Missing(team) => backup_repository::get_or_create_team(&mut tx, team).await?,
+        };
+        if exists && query.conflict == ConflictStrategy::Overwrite {
+            backup_repository::delete_key_by_name(&mut tx, team_id, &target_name).await?;
+        }
+
+        let vault_id = match &key.vault {
+            Some(vault) => Some(backup_repository::get_or_create_vault(&mut tx, vault).await?),
+            None => None,
+        };
+        let key_id = backup_repository::insert_key(&mut tx, team_id, &target_name, &encrypted_data, vault_id, &key).await?;
+        for (version, data) in key.versions.iter().zip(&versions) {
+            backup_repository::insert_key_version(&mut tx, key_id, version, data).await?;
+        }
+        backup_repository::insert_rotation_policy(&mut tx, key_id, &key).await?;
+        label_repository::insert_labels(&mut *tx, key_id, &key.labels).await?;
+        for acl in &key.acls {
+            backup_repository::insert_acl(&mut tx, key_id, acl).await?;
+        }
+        report.restored += 1;
+    }
+    tx.commit().await?;
+
+    Ok(report)
+}
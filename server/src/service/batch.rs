@@ -0,0 +1,234 @@
+use crate::model::access::{Identity, Permission};
+use crate::model::batch::{BatchItemResult, BatchMode, BatchOperation, BatchRequest, BatchResponse};
+use crate::model::key::{CreateKeyRequest, KeyResponse};
+use crate::model::quota::QuotaExceeded;
+use crate::model::token::{TokenGrant, TokenOperation};
+use crate::repository::{self, label as label_repository};
+use crate::service::{access as access_service, event as event_service, quota as quota_service};
+use sqlx::{Acquire, MySqlConnection, MySqlPool};
+use std::error::Error;
+use std::fmt::Display;
+
+use super::{announce_created, announce_deleted, prepare_key};
+
+// 全部回滚模式下未生效的操作使用的状态码
+const STATUS_NOT_APPLIED: u16 = 424;
+
+// 已在事务中生效、提交后才通知webhook和事件订阅方的操作
+enum Applied {
+    Created { key_id: u64 },
+    Deleted { key_id: u64, team_id: u64, name: String, audience: Vec<String> },
+}
+
+impl Applied {
+    fn key_id(&self) -> u64 {
+        match self {
+            Applied::Created { key_id } | Applied::Deleted { key_id, .. } => *key_id,
+        }
+    }
+}
+
+// 单个操作失败的状态码和原因
+struct ItemError {
+    status: u16,
+    message: String,
+}
+
+impl ItemError {
+    fn new(status: u16, message: impl Into<String>) -> Self {
+        ItemError { status, message: message.into() }
+    }
+
+    fn denied() -> Self {
+        ItemError::new(403, "Access denied")
+    }
+
+    fn internal(e: impl Display) -> Self {
+        ItemError::new(500, format!("Failed to apply operation: {}", e))
+    }
+
+    // 超出配额为429，与POST /keys一致
+    fn from_service(e: Box<dyn Error>) -> Self {
+        match e.downcast_ref::<QuotaExceeded>() {
+            Some(exceeded) => ItemError::new(429, exceeded.to_string()),
+            None => ItemError::internal(e),
+        }
+    }
+}
+
+/// 在一个数据库事务中执行批量创建和删除
+///
+/// 每个操作在独立的保存点中执行：尽力模式下只撤销失败的操作，全部回滚模式下首个失败后回滚整个事务。
+pub async fn execute(
+    pool: &MySqlPool,
+    identity: &Identity,
+    grant: Option<&TokenGrant>,
+    request: BatchRequest,
+    encryption_key: &str,
+) -> Result<BatchResponse, Box<dyn Error>> {
+    let mode = request.mode;
+    let mut outcomes = Vec::with_capacity(request.operations.len());
+    let mut aborted = false;
+
+    let mut tx = pool.begin().await?;
+    for operation in request.operations {
+        if aborted {
+            outcomes.push(Err(ItemError::new(STATUS_NOT_APPLIED, "Not applied: an earlier operation failed")));
+            continue;
+        }
+
+        let mut savepoint = tx.begin().await?;
+        match apply(pool, &mut savepoint, identity, grant, operation, encryption_key).await {
+            Ok(applied) => {
+                savepoint.commit().await?;
+                outcomes.push(Ok(applied));
+            }
+            Err(e) => {
+                savepoint.rollback().await?;
+                aborted = mode == BatchMode::AllOrNothing;
+                outcomes.push(Err(e));
+            }
+        }
+    }
+
+    let committed = !aborted && outcomes.iter().any(Result::is_ok);
+    if committed {
+        tx.commit().await?;
+    } else {
+        tx.rollback().await?;
+    }
+
+    let mut results = Vec::with_capacity(outcomes.len());
+    for (index, outcome) in outcomes.into_iter().enumerate() {
+        let result = match outcome {
+            Ok(applied) if !committed => BatchItemResult {
+                index,
+                status: STATUS_NOT_APPLIED,
+                key_id: Some(applied.key_id()),
+                key: None,
+                error: Some("Rolled back: another operation failed".to_string()),
+            },
+            Ok(Applied::Created { key_id }) => {
+                let key = repository::get_key_by_id(pool, key_id).await?.ok_or("Failed to retrieve created key")?;
+                created(index, announce_created(pool, key).await?)
+            }
+            Ok(Applied::Deleted { key_id, team_id, name, audience }) => {
+                announce_deleted(pool, team_id, key_id, &name, &audience).await;
+                BatchItemResult { index, status: 200, key_id: Some(key_id), key: None, error: None }
+            }
+            Err(e) => BatchItemResult { index, status: e.status, key_id: None, key: None, error: Some(e.message) },
+        };
+        results.push(result);
+    }
+
+    let succeeded = results.iter().filter(|result| result.error.is_none()).count();
+    Ok(BatchResponse {
+        mode,
+        committed,
+        succeeded,
+        failed: results.len() - succeeded,
+        results,
+    })
+}
+
+fn created(index: usize, key: KeyResponse) -> BatchItemResult {
+    BatchItemResult {
+        index,
+        status: 201,
+        key_id: Some(key.id),
+        key: Some(key),
+        error: None,
+    }
+}
+
+async fn apply(
+    pool: &MySqlPool,
+    conn: &mut MySqlConnection,
+    identity: &Identity,
+    grant: Option<&TokenGrant>,
+    operation: BatchOperation,
+    encryption_key: &str,
+) -> Result<Applied, ItemError> {
+    match operation {
+        BatchOperation::Create(request) => create(pool, conn, identity, grant, request, encryption_key).await,
+        BatchOperation::Delete { id, expected_version } => {
+            delete(conn, identity, grant, id, expected_version).await
+        }
+    }
+}
+
+// 与POST /keys相同的权限检查、校验和配额，创建者获得全部权限
+async fn create(
+    pool: &MySqlPool,
+    conn: &mut MySqlConnection,
+    identity: &Identity,
+    grant: Option<&TokenGrant>,
+    request: CreateKeyRequest,
+    encryption_key: &str,
+) -> Result<Applied, ItemError> {
+    if !identity.role.can_create_keys() {
+        tracing::warn!(identity_id = identity.id, role = %identity.role, "Access denied: batch create key");
+        return Err(ItemError::denied());
+    }
+    if grant.is_some_and(|grant| !grant.allows(&request.name, TokenOperation::Create)) {
+        tracing::warn!(identity_id = identity.id, "Access denied: batch create key outside token scope");
+        return Err(ItemError::denied());
+    }
+
+    let size = quota_service::data_size(request.data.as_deref(), request.payload.as_ref()).map_err(ItemError::internal)?;
+    quota_service::check_data_size(pool, identity, size).await.map_err(ItemError::from_service)?;
+    let (key, labels) = prepare_key(request, identity, encryption_key).map_err(|e| ItemError::new(400, e.to_string()))?;
+    let key_id = match repository::create_key(&mut *conn, &key).await {
+        Ok(key_id) => key_id,
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            return Err(ItemError::new(409, format!("Key name already exists: {}", key.name)));
+        }
+        Err(e) => return Err(ItemError::internal(e)),
+    };
+    label_repository::insert_labels(&mut *conn, key_id, &labels).await.map_err(ItemError::internal)?;
+    access_service::insert_owner_grants(&mut *conn, key_id, identity).await.map_err(ItemError::internal)?;
+    quota_service::claim_key(&mut *conn, key_id, identity).await.map_err(ItemError::from_service)?;
+
+    Ok(Applied::Created { key_id })
+}
+
+// 与DELETE /keys/{id}相同的ACL及令牌作用范围检查，版本不一致时返回412
+async fn delete(
+    conn: &mut MySqlConnection,
+    identity: &Identity,
+    grant: Option<&TokenGrant>,
+    id: u64,
+    expected_version: Option<u32>,
+) -> Result<Applied, ItemError> {
+    // 与authorize_key一致，其他团队的密钥按不存在处理
+    let team_id = identity.team_id.ok_or_else(|| ItemError::new(404, "Key not found"))?;
+    let Some(key) = repository::lock_key(&mut *conn, team_id, id).await.map_err(ItemError::internal)? else {
+        return Err(ItemError::new(404, "Key not found"));
+    };
+
+    let decision = access_service::evaluate(&mut *conn, identity, id, Permission::Delete)
+        .await
+        .map_err(ItemError::internal)?;
+    if !decision.allowed {
+        tracing::warn!(identity_id = identity.id, key_id = id, reason = %decision.reason, "Access denied: batch delete key");
+        return Err(ItemError::denied());
+    }
+    if grant.is_some_and(|grant| !grant.allows(&key.name, TokenOperation::Delete)) {
+        tracing::warn!(identity_id = identity.id, key_id = id, "Access denied: batch delete key outside token scope");
+        return Err(ItemError::denied());
+    }
+    let current = key.version.ok_or_else(|| ItemError::internal("Key row without version"))?;
+    if expected_version.is_some_and(|expected| expected != current) {
+        return Err(ItemError::new(
+            412,
+            format!("Resource was modified concurrently, current version is \"{}\"", current),
+        ));
+    }
+
+    let audience = event_service::deletion_audience(&mut *conn, id).await.map_err(ItemError::internal)?;
+    if !repository::delete_key(&mut *conn, id, expected_version).await.map_err(ItemError::internal)? {
+        return Err(ItemError::new(404, "Key not found"));
+    }
+
+    Ok(Applied::Deleted { key_id: id, team_id, name: key.name, audience })
+}
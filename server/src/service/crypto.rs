@@ -0,0 +1,251 @@
+use aes_gcm::aead::consts::U12;
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::aes::Aes192;
+use aes_gcm::{Aes128Gcm, Aes256Gcm, AesGcm, KeyInit, Nonce};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use shared::secret::{BlobEncoding, SecretPayload};
+use sqlx::MySqlPool;
+use std::error::Error;
+
+use crate::model::crypto::{
+    DecryptRequest, DecryptResponse, EncryptRequest, EncryptResponse, KeyOperation, KeyType, PublicKeyResponse,
+    SignRequest, SignResponse,
+};
+use crate::repository;
+use crate::utils::encryption::decrypt_data;
+use crate::utils::keypair::{certificate_der, PrivateKey};
+
+type Aes192Gcm = AesGcm<Aes192, U12>;
+
+// AES-GCM的IV大小（12字节）
+const IV_SIZE: usize = 12;
+
+/// 可由服务端使用的密钥：Base64 Blob中的AES密钥，或证书、SSH密钥中的PEM私钥
+enum UsableKey {
+    Aes(Vec<u8>),
+    Private {
+        key: PrivateKey,
+        certificate: Option<String>,
+    },
+}
+
+/// 密钥不存在或不能由服务端使用
+enum Unusable {
+    NotFound,
+    Unsupported(String),
+}
+
+impl Unusable {
+    fn into_operation<T>(self) -> KeyOperation<T> {
+        match self {
+            Unusable::NotFound => KeyOperation::NotFound,
+            Unusable::Unsupported(reason) => KeyOperation::Unsupported(reason),
+        }
+    }
+}
+
+/// 使用AES密钥执行AES-GCM加密
+pub async fn encrypt(
+    pool: &MySqlPool,
+    key_id: u64,
+    request: EncryptRequest,
+    encryption_key: &str,
+) -> Result<KeyOperation<EncryptResponse>, Box<dyn Error>> {
+    let aes_key = match load_key(pool, key_id, encryption_key).await? {
+        Ok((_, UsableKey::Aes(aes_key))) => aes_key,
+        Ok(_) => return Ok(KeyOperation::Unsupported("Key is not an AES key".to_string())),
+        Err(unusable) => return Ok(unusable.into_operation()),
+    };
+
+    let aad = request.aad.as_deref().map_or(Some(Vec::new()), decode);
+    let (Some(plaintext), Some(aad)) = (decode(&request.plaintext), aad) else {
+        return Ok(KeyOperation::Rejected("plaintext and aad must be Base64 encoded".to_string()));
+    };
+    let iv = match request.iv.as_deref() {
+        Some(iv) => match decode(iv) {
+            Some(iv) if iv.len() == IV_SIZE => iv,
+            _ => return Ok(KeyOperation::Rejected("iv must be 12 Base64 encoded bytes".to_string())),
+        },
+        None => {
+            let mut iv = vec![0u8; IV_SIZE];
+            getrandom::getrandom(&mut iv)?;
+            iv
+        }
+    };
+
+    let ciphertext = aes_gcm(&aes_key, &iv, Payload { msg: &plaintext, aad: &aad }, true)
+        .map_err(|_| "AES-GCM encryption failed")?;
+    Ok(KeyOperation::Done(EncryptResponse {
+        key_id,
+        iv: STANDARD.encode(iv),
+        ciphertext: STANDARD.encode(ciphertext),
+    }))
+}
+
+/// 使用AES密钥执行AES-GCM解密，认证失败时返回Rejected
+pub async fn decrypt(
+    pool: &MySqlPool,
+    key_id: u64,
+    request: DecryptRequest,
+    encryption_key: &str,
+) -> Result<KeyOperation<DecryptResponse>, Box<dyn Error>> {
+    let aes_key = match load_key(pool, key_id, encryption_key).await? {
+        Ok((_, UsableKey::Aes(aes_key))) => aes_key,
+        Ok(_) => return Ok(KeyOperation::Unsupported("Key is not an AES key".to_string())),
+        Err(unusable) => return Ok(unusable.into_operation()),
+    };
+
+    let aad = request.aad.as_deref().map_or(Some(Vec::new()), decode);
+    let (Some(ciphertext), Some(iv), Some(aad)) = (decode(&request.ciphertext), decode(&request.iv), aad) else {
+        return Ok(KeyOperation::Rejected("ciphertext, iv and aad must be Base64 encoded".to_string()));
+    };
+    if iv.len() != IV_SIZE {
+        return Ok(KeyOperation::Rejected("iv must be 12 bytes".to_string()));
+    }
+
+    match aes_gcm(&aes_key, &iv, Payload { msg: &ciphertext, aad: &aad }, false) {
+        Ok(plaintext) => Ok(KeyOperation::Done(DecryptResponse {
+            key_id,
+            plaintext: STANDARD.encode(plaintext),
+        })),
+        Err(_) => Ok(KeyOperation::Rejected("Decryption failed".to_string())),
+    }
+}
+
+/// 使用私钥签名，私钥不离开服务端
+pub async fn sign(
+    pool: &MySqlPool,
+    key_id: u64,
+    request: SignRequest,
+    encryption_key: &str,
+) -> Result<KeyOperation<SignResponse>, Box<dyn Error>> {
+    let private_key = match load_key(pool, key_id, encryption_key).await? {
+        Ok((_, UsableKey::Private { key, .. })) => key,
+        Ok(_) => return Ok(KeyOperation::Unsupported("Key is not a private key".to_string())),
+        Err(unusable) => return Ok(unusable.into_operation()),
+    };
+    if private_key.key_type() != request.algorithm.key_type() {
+        return Ok(KeyOperation::Unsupported(format!(
+            "Algorithm {:?} cannot be used with a {:?} key",
+            request.algorithm,
+            private_key.key_type()
+        )));
+    }
+
+    let Some(data) = decode(&request.data) else {
+        return Ok(KeyOperation::Rejected("data must be Base64 encoded".to_string()));
+    };
+    // 密钥与算法匹配时，签名失败只可能由输入引起
+    match private_key.sign(request.algorithm, &data, request.prehashed) {
+        Ok(signature) => Ok(KeyOperation::Done(SignResponse {
+            key_id,
+            algorithm: request.algorithm,
+            signature: STANDARD.encode(signature),
+        })),
+        Err(e) => Ok(KeyOperation::Rejected(e.to_string())),
+    }
+}
+
+/// 读取密钥的公开信息，供PKCS#11等客户端构造公钥和证书对象
+pub async fn get_public_key(
+    pool: &MySqlPool,
+    key_id: u64,
+    encryption_key: &str,
+) -> Result<KeyOperation<PublicKeyResponse>, Box<dyn Error>> {
+    let (name, key) = match load_key(pool, key_id, encryption_key).await? {
+        Ok(loaded) => loaded,
+        Err(unusable) => return Ok(unusable.into_operation()),
+    };
+
+    let response = match key {
+        UsableKey::Aes(aes_key) => PublicKeyResponse {
+            key_id,
+            name,
+            key_type: KeyType::Aes,
+            bits: (aes_key.len() * 8) as u32,
+            public_key: None,
+            modulus: None,
+            public_exponent: None,
+            ec_point: None,
+            certificate: None,
+        },
+        UsableKey::Private { key, certificate } => {
+            let info = key.public_key_info()?;
+            PublicKeyResponse {
+                key_id,
+                name,
+                key_type: info.key_type,
+                bits: info.bits,
+                public_key: Some(STANDARD.encode(info.spki)),
+                modulus: info.modulus.map(|modulus| STANDARD.encode(modulus)),
+                public_exponent: info.public_exponent.map(|exponent| STANDARD.encode(exponent)),
+                ec_point: info.ec_point.map(|point| STANDARD.encode(point)),
+                certificate: certificate
+                    .as_deref()
+                    .and_then(certificate_der)
+                    .map(|der| STANDARD.encode(der)),
+            }
+        }
+    };
+    Ok(KeyOperation::Done(response))
+}
+
+// 读取并解析密钥，返回密钥名称和可用的密钥内容
+async fn load_key(
+    pool: &MySqlPool,
+    key_id: u64,
+    encryption_key: &str,
+) -> Result<Result<(String, UsableKey), Unusable>, Box<dyn Error>> {
+    let Some(key) = repository::get_key_by_id(pool, key_id).await? else {
+        return Ok(Err(Unusable::NotFound));
+    };
+    if key.vault_id.is_some() {
+        return Ok(Err(Unusable::Unsupported(
+            "Vault keys are encrypted client-side and cannot be used by the server".to_string(),
+        )));
+    }
+
+    let plaintext = decrypt_data(&key.encrypted_data, encryption_key)?;
+    let payload = match key.kind {
+        Some(_) => serde_json::from_str::<SecretPayload>(&plaintext)?,
+        None => SecretPayload::from_legacy(plaintext),
+    };
+
+    let usable = match payload {
+        SecretPayload::Blob { data, encoding: BlobEncoding::Base64, .. } => match STANDARD.decode(data) {
+            Ok(bytes) if matches!(bytes.len(), 16 | 24 | 32) => UsableKey::Aes(bytes),
+            _ => return Ok(Err(Unusable::Unsupported("Blob is not a 128, 192 or 256 bit AES key".to_string()))),
+        },
+        SecretPayload::Certificate { certificate, private_key, .. } => match PrivateKey::from_pem(&private_key) {
+            Ok(key) => UsableKey::Private { key, certificate: Some(certificate) },
+            Err(e) => return Ok(Err(Unusable::Unsupported(format!("Private key cannot be used: {}", e)))),
+        },
+        SecretPayload::SshKey { private_key, passphrase: None, .. } => match PrivateKey::from_pem(&private_key) {
+            Ok(key) => UsableKey::Private { key, certificate: None },
+            Err(e) => return Ok(Err(Unusable::Unsupported(format!("Private key cannot be used: {}", e)))),
+        },
+        _ => {
+            return Ok(Err(Unusable::Unsupported(
+                "Key kind does not support server-side operations".to_string(),
+            )))
+        }
+    };
+    Ok(Ok((key.name, usable)))
+}
+
+// 按密钥长度选择AES-128/192/256-GCM
+fn aes_gcm(key: &[u8], iv: &[u8], payload: Payload, encrypt: bool) -> Result<Vec<u8>, aes_gcm::Error> {
+    let nonce = Nonce::<U12>::from_slice(iv);
+    match (key.len(), encrypt) {
+        (16, true) => Aes128Gcm::new_from_slice(key).map_err(|_| aes_gcm::Error)?.encrypt(nonce, payload),
+        (16, false) => Aes128Gcm::new_from_slice(key).map_err(|_| aes_gcm::Error)?.decrypt(nonce, payload),
+        (24, true) => Aes192Gcm::new_from_slice(key).map_err(|_| aes_gcm::Error)?.encrypt(nonce, payload),
+        (24, false) => Aes192Gcm::new_from_slice(key).map_err(|_| aes_gcm::Error)?.decrypt(nonce, payload),
+        (_, true) => Aes256Gcm::new_from_slice(key).map_err(|_| aes_gcm::Error)?.encrypt(nonce, payload),
+        (_, false) => Aes256Gcm::new_from_slice(key).map_err(|_| aes_gcm::Error)?.decrypt(nonce, payload),
+    }
+}
+
+fn decode(value: &str) -> Option<Vec<u8>> {
+    STANDARD.decode(value).ok()
+}
@@ -0,0 +1,160 @@
+use crate::model::access::{Identity, Permission, Role};
+use crate::model::event::{KeyEvent, KeyEventType};
+use crate::repository::{self, access as access_repository, event as event_repository};
+use crate::service::access as access_service;
+use once_cell::sync::Lazy;
+use sqlx::{MySqlConnection, MySqlPool};
+use std::collections::VecDeque;
+use std::error::Error;
+use tokio::sync::{broadcast, Mutex};
+
+// 事件总线容量，订阅方落后超过此数量时改为从数据库补读
+const BUS_CAPACITY: usize = 1024;
+// 单次从数据库补读的事件数
+const CATCH_UP_BATCH: u32 = 500;
+
+/// 进程内事件总线，事件先写入数据库再广播
+static BUS: Lazy<broadcast::Sender<KeyEvent>> = Lazy::new(|| broadcast::channel(BUS_CAPACITY).0);
+
+// 串行化写入和广播，保证广播顺序与游标顺序一致
+static PUBLISH_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// 记录并广播一条密钥变更事件；失败只记录日志，不影响已完成的密钥操作
+pub async fn publish(
+    pool: &MySqlPool,
+    event_type: KeyEventType,
+    key_id: u64,
+    key_name: &str,
+    version: Option<u32>,
+    audience: &[String],
+) {
+    let _guard = PUBLISH_LOCK.lock().await;
+    let audience = (!audience.is_empty()).then(|| audience.join(","));
+
+    let result = record_event(pool, event_type, key_id, key_name, version, audience.as_deref()).await;
+
+    match result {
+        // 没有订阅方时发送失败，属正常情况
+        Ok(event) => {
+            let _ = BUS.send(event);
+        }
+        Err(e) => tracing::error!(error = %e, key_id, event = %event_type, "Failed to publish key event"),
+    }
+}
+
+async fn record_event(
+    pool: &MySqlPool,
+    event_type: KeyEventType,
+    key_id: u64,
+    key_name: &str,
+    version: Option<u32>,
+    audience: Option<&str>,
+) -> Result<KeyEvent, Box<dyn Error>> {
+    let id = event_repository::insert_event(pool, event_type.as_str(), key_id, key_name, version, audience).await?;
+    let record = event_repository::get_event(pool, id).await?.ok_or("Failed to retrieve key event")?;
+    Ok(KeyEvent::try_from(record)?)
+}
+
+/// 删除前记录可读取该密钥元数据的ACL主体及密钥所属团队，删除后ACL随密钥一并删除
+pub async fn deletion_audience(conn: &mut MySqlConnection, key_id: u64) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut audience: Vec<String> = access_repository::list_grants(&mut *conn, key_id)
+        .await?
+        .into_iter()
+        .filter(|acl| acl.permission == Permission::ReadMetadata.as_str())
+        .map(|acl| format!("{}:{}", acl.subject_type, acl.subject))
+        .collect();
+    if let Some(team_id) = repository::get_key_team(&mut *conn, key_id).await? {
+        audience.push(format!("team:{}", team_id));
+    }
+    Ok(audience)
+}
+
+/// 订阅事件流；给出cursor时先补发该游标之后的事件，否则只推送订阅之后的新事件
+pub async fn subscribe(pool: &MySqlPool, identity: Identity, cursor: Option<u64>) -> Result<Subscription, Box<dyn Error>> {
+    // 先订阅再确定起点，避免两者之间的事件丢失
+    let receiver = BUS.subscribe();
+    let (cursor, backlog) = match cursor {
+        Some(cursor) => (cursor, true),
+        None => (event_repository::latest_cursor(pool).await?, false),
+    };
+
+    Ok(Subscription {
+        pool: pool.clone(),
+        identity,
+        receiver,
+        cursor,
+        pending: VecDeque::new(),
+        backlog,
+    })
+}
+
+/// 单个客户端的事件订阅，只返回调用方有权读取元数据的事件
+pub struct Subscription {
+    pool: MySqlPool,
+    identity: Identity,
+    receiver: broadcast::Receiver<KeyEvent>,
+    cursor: u64,
+    pending: VecDeque<KeyEvent>,
+    // 数据库中可能还有未补读的事件
+    backlog: bool,
+}
+
+impl Subscription {
+    /// 等待下一条可见事件
+    pub async fn next(&mut self) -> Result<KeyEvent, Box<dyn Error>> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                self.cursor = event.cursor;
+                if self.visible(&event).await? {
+                    return Ok(event);
+                }
+                continue;
+            }
+            if self.backlog {
+                self.catch_up().await?;
+                continue;
+            }
+
+            match self.receiver.recv().await {
+                // 已在补读中发送过
+                Ok(event) if event.cursor <= self.cursor => {}
+                Ok(event) if event.cursor == self.cursor + 1 => self.pending.push_back(event),
+                // 游标不连续或订阅方落后时，以数据库为准补读
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => self.backlog = true,
+                Err(broadcast::error::RecvError::Closed) => return Err("Key event bus closed".into()),
+            }
+        }
+    }
+
+    async fn catch_up(&mut self) -> Result<(), Box<dyn Error>> {
+        let records = event_repository::list_after(&self.pool, self.cursor, CATCH_UP_BATCH).await?;
+        self.backlog = records.len() as u32 == CATCH_UP_BATCH;
+        for record in records {
+            self.pending.push_back(KeyEvent::try_from(record)?);
+        }
+        Ok(())
+    }
+
+    // 删除事件按删除时的团队和ACL判定，其余事件按当前团队和ACL判定；已删除密钥的旧事件不再可见
+    async fn visible(&self, event: &KeyEvent) -> Result<bool, Box<dyn Error>> {
+        if event.event != KeyEventType::Deleted {
+            let mut conn = self.pool.acquire().await?;
+            let key_team = repository::get_key_team(&mut conn, event.key_id).await?;
+            if key_team.is_none() || key_team != self.identity.team_id {
+                return Ok(false);
+            }
+            let decision = access_service::evaluate(&mut conn, &self.identity, event.key_id, Permission::ReadMetadata).await?;
+            return Ok(decision.allowed);
+        }
+        let Some(team_id) = self.identity.team_id else {
+            return Ok(false);
+        };
+        if !event.audience.contains(&format!("team:{}", team_id)) {
+            return Ok(false);
+        }
+
+        Ok(matches!(self.identity.role, Role::Admin | Role::Auditor)
+            || event.audience.contains(&format!("identity:{}", self.identity.id))
+            || event.audience.contains(&format!("role:{}", self.identity.role.as_str())))
+    }
+}
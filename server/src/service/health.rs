@@ -0,0 +1,38 @@
+use crate::model::health::{ReadinessCheck, ReadinessReport};
+use crate::repository::health as health_repository;
+use sqlx::MySqlPool;
+
+fn check(name: &'static str, result: Result<(), String>) -> ReadinessCheck {
+    ReadinessCheck {
+        name,
+        ok: result.is_ok(),
+        error: result.err(),
+    }
+}
+
+/// 就绪检查：数据库可连接、keys表存在、ENCRYPTION_KEY已配置
+pub async fn readiness(pool: &MySqlPool) -> ReadinessReport {
+    let database = health_repository::ping(pool).await.map_err(|e| e.to_string());
+    let keys_table = match &database {
+        Ok(()) => match health_repository::table_exists(pool, "keys").await {
+            Ok(true) => Ok(()),
+            Ok(false) => Err("Table `keys` does not exist; run migrations".to_string()),
+            Err(e) => Err(e.to_string()),
+        },
+        Err(_) => Err("Database unavailable".to_string()),
+    };
+    let encryption_key = match std::env::var("ENCRYPTION_KEY") {
+        Ok(value) if !value.is_empty() => Ok(()),
+        _ => Err("ENCRYPTION_KEY is not configured".to_string()),
+    };
+
+    let checks = vec![
+        check("database", database),
+        check("keys_table", keys_table),
+        check("encryption_key", encryption_key),
+    ];
+    ReadinessReport {
+        ready: checks.iter().all(|check| check.ok),
+        checks,
+    }
+}
@@ -0,0 +1,77 @@
+use crate::model::idempotency::IdempotencyState;
+use crate::repository::idempotency as idempotency_repository;
+use sha2::{Digest, Sha256};
+use sqlx::MySqlPool;
+use std::error::Error;
+
+// 默认重放窗口（秒）
+const DEFAULT_TTL_SECS: u64 = 24 * 60 * 60;
+// 幂等键最大长度
+pub const MAX_KEY_LENGTH: usize = 255;
+
+fn ttl_secs() -> u64 {
+    std::env::var("IDEMPOTENCY_TTL_SECS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_TTL_SECS)
+}
+
+/// 计算请求指纹：方法、路径和请求体
+pub fn request_hash(method: &str, path: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_bytes());
+    hasher.update(b" ");
+    hasher.update(path.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(body);
+    hex::encode(hasher.finalize())
+}
+
+/// 查询或占用幂等键
+pub async fn begin(
+    pool: &MySqlPool,
+    identity_id: u64,
+    idempotency_key: &str,
+    request_hash: &str,
+) -> Result<IdempotencyState, Box<dyn Error>> {
+    let ttl = ttl_secs();
+    idempotency_repository::purge_expired(pool, ttl).await?;
+
+    if idempotency_repository::try_insert(pool, identity_id, idempotency_key, request_hash).await? {
+        return Ok(IdempotencyState::New);
+    }
+
+    let Some(record) = idempotency_repository::get(pool, identity_id, idempotency_key).await? else {
+        // 记录恰好被清理，重新占位
+        return if idempotency_repository::try_insert(pool, identity_id, idempotency_key, request_hash).await? {
+            Ok(IdempotencyState::New)
+        } else {
+            Ok(IdempotencyState::InProgress)
+        };
+    };
+
+    if record.request_hash != request_hash {
+        return Ok(IdempotencyState::Mismatch);
+    }
+    match (record.status_code, record.response_body) {
+        (0, _) | (_, None) => Ok(IdempotencyState::InProgress),
+        (status_code, Some(body)) => Ok(IdempotencyState::Replay { status_code, body }),
+    }
+}
+
+pub async fn complete(
+    pool: &MySqlPool,
+    identity_id: u64,
+    idempotency_key: &str,
+    status_code: u16,
+    body: &str,
+) -> Result<(), Box<dyn Error>> {
+    idempotency_repository::complete(pool, identity_id, idempotency_key, status_code, body).await?;
+    Ok(())
+}
+
+/// 请求失败时释放幂等键，允许客户端重试
+pub async fn release(pool: &MySqlPool, identity_id: u64, idempotency_key: &str) -> Result<(), Box<dyn Error>> {
+    idempotency_repository::delete(pool, identity_id, idempotency_key).await?;
+    Ok(())
+}
@@ -0,0 +1,191 @@
+use crate::model::access::{Identity, Permission};
+use crate::model::key::CreateKeyRequest;
+use crate::model::kmip::{CreateObject, KmipObjectRecord, LocateFilter, NewKmipObject, StateChange, KMIP_CONTENT_TYPE};
+use crate::repository::{self, kmip as kmip_repository};
+use crate::service::{access as access_service, event as event_service, quota as quota_service};
+use crate::utils::encryption::decrypt_data;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use shared::kmip::{State, REVOCATION_REASON_KEY_COMPROMISE};
+use shared::secret::{BlobEncoding, SecretPayload};
+use sqlx::MySqlPool;
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use super::{announce_created, announce_deleted, prepare_key};
+
+// 单次Locate返回的最大对象数
+const MAX_LOCATE_ITEMS: u32 = 1000;
+
+/// 新建对称密钥：密钥材料以Base64 Blob保存在keys表，生命周期状态保存在kmip_objects表
+pub async fn create_object(
+    pool: &MySqlPool,
+    owner: &Identity,
+    object: NewKmipObject,
+    encryption_key: &str,
+) -> Result<CreateObject, Box<dyn Error>> {
+    let request = CreateKeyRequest {
+        name: object.name,
+        data: None,
+        payload: Some(SecretPayload::Blob {
+            data: STANDARD.encode(&object.material),
+            encoding: BlobEncoding::Base64,
+            content_type: Some(KMIP_CONTENT_TYPE.to_string()),
+        }),
+        expires_at: None,
+        description: None,
+        labels: BTreeMap::new(),
+        metadata: None,
+    };
+    let size = quota_service::data_size(None, request.payload.as_ref())?;
+    quota_service::check_data_size(pool, owner, size).await?;
+    let (key, _) = prepare_key(request, owner, encryption_key)?;
+    let state = match object.activation_date {
+        Some(date) if date <= chrono::Utc::now() => State::Active,
+        _ => State::PreActive,
+    };
+
+    let mut tx = pool.begin().await?;
+    let key_id = match repository::create_key(&mut *tx, &key).await {
+        Ok(key_id) => key_id,
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => return Ok(CreateObject::NameTaken),
+        Err(e) => return Err(e.into()),
+    };
+    access_service::insert_owner_grants(&mut *tx, key_id, owner).await?;
+    quota_service::claim_key(&mut *tx, key_id, owner).await?;
+    kmip_repository::insert_object(
+        &mut *tx,
+        key_id,
+        state.as_str(),
+        object.cryptographic_algorithm,
+        object.cryptographic_length,
+        object.cryptographic_usage_mask,
+        object.activation_date,
+    )
+    .await?;
+    tx.commit().await?;
+
+    let created_key = repository::get_key_by_id(pool, key_id).await?.ok_or("Failed to retrieve created key")?;
+    announce_created(pool, created_key).await?;
+
+    Ok(CreateObject::Created(key_id))
+}
+
+pub async fn get_object(pool: &MySqlPool, key_id: u64) -> Result<Option<KmipObjectRecord>, Box<dyn Error>> {
+    Ok(kmip_repository::get_object(pool, key_id).await?)
+}
+
+/// 读取并解密密钥材料
+pub async fn get_material(pool: &MySqlPool, key_id: u64, encryption_key: &str) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+    let Some(key) = repository::get_key_by_id(pool, key_id).await? else {
+        return Ok(None);
+    };
+
+    let plaintext = decrypt_data(&key.encrypted_data, encryption_key)?;
+    match SecretPayload::from_json(&plaintext) {
+        Some(SecretPayload::Blob { data, encoding: BlobEncoding::Base64, .. }) => Ok(Some(STANDARD.decode(data)?)),
+        _ => Err("KMIP object is not stored as a Base64 blob".into()),
+    }
+}
+
+/// 激活：只有PreActive状态的对象可以激活
+pub async fn activate(pool: &MySqlPool, key_id: u64) -> Result<StateChange, Box<dyn Error>> {
+    let mut tx = pool.begin().await?;
+    let Some(object) = kmip_repository::lock_object(&mut *tx, key_id).await? else {
+        return Ok(StateChange::NotFound);
+    };
+    let state = object.state()?;
+    if state != State::PreActive {
+        return Ok(StateChange::IllegalState(state));
+    }
+
+    kmip_repository::activate(&mut *tx, key_id, chrono::Utc::now()).await?;
+    tx.commit().await?;
+    Ok(StateChange::Done)
+}
+
+/// 吊销：因密钥泄露吊销时进入Compromised状态，否则进入Deactivated状态
+pub async fn revoke(
+    pool: &MySqlPool,
+    key_id: u64,
+    reason: u32,
+    message: Option<&str>,
+    compromise_occurrence_date: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<StateChange, Box<dyn Error>> {
+    let mut tx = pool.begin().await?;
+    let Some(object) = kmip_repository::lock_object(&mut *tx, key_id).await? else {
+        return Ok(StateChange::NotFound);
+    };
+    let state = object.state()?;
+
+    if reason == REVOCATION_REASON_KEY_COMPROMISE {
+        // 已停用的密钥仍可被标记为泄露
+        if state == State::Compromised {
+            return Ok(StateChange::IllegalState(state));
+        }
+        let compromise_date = compromise_occurrence_date.unwrap_or_else(chrono::Utc::now);
+        kmip_repository::revoke(&mut *tx, key_id, State::Compromised.as_str(), Some(compromise_date), reason, message)
+            .await?;
+    } else {
+        if !matches!(state, State::PreActive | State::Active) {
+            return Ok(StateChange::IllegalState(state));
+        }
+        kmip_repository::revoke(&mut *tx, key_id, State::Deactivated.as_str(), None, reason, message).await?;
+    }
+
+    tx.commit().await?;
+    Ok(StateChange::Done)
+}
+
+/// 销毁：Active状态的对象必须先吊销；销毁后密钥记录一并删除
+pub async fn destroy(pool: &MySqlPool, key_id: u64) -> Result<StateChange, Box<dyn Error>> {
+    let Some(team_id) = repository::get_key_team(&mut *pool.acquire().await?, key_id).await? else {
+        return Ok(StateChange::NotFound);
+    };
+    let audience = event_service::deletion_audience(&mut *pool.acquire().await?, key_id).await?;
+
+    let mut tx = pool.begin().await?;
+    let Some(object) = kmip_repository::lock_object(&mut *tx, key_id).await? else {
+        return Ok(StateChange::NotFound);
+    };
+    let state = object.state()?;
+    if state == State::Active {
+        return Ok(StateChange::IllegalState(state));
+    }
+
+    if !repository::delete_key(&mut *tx, key_id, None).await? {
+        return Ok(StateChange::NotFound);
+    }
+    tx.commit().await?;
+
+    announce_deleted(pool, team_id, key_id, &object.name, &audience).await;
+    Ok(StateChange::Done)
+}
+
+/// 在调用方当前团队内查找有权读取元数据的对象
+pub async fn locate(pool: &MySqlPool, identity: &Identity, filter: LocateFilter) -> Result<Vec<u64>, Box<dyn Error>> {
+    let Some(team_id) = identity.team_id else {
+        return Ok(Vec::new());
+    };
+    let limit = filter.maximum_items.unwrap_or(MAX_LOCATE_ITEMS).clamp(1, MAX_LOCATE_ITEMS);
+    let candidates = kmip_repository::locate(
+        pool,
+        team_id,
+        filter.name.as_deref(),
+        filter.cryptographic_algorithm,
+        filter.state.map(State::as_str),
+        MAX_LOCATE_ITEMS,
+    )
+    .await?;
+
+    let mut conn = pool.acquire().await?;
+    let mut located = Vec::new();
+    for key_id in candidates {
+        if located.len() as u32 >= limit {
+            break;
+        }
+        if access_service::evaluate(&mut conn, identity, key_id, Permission::ReadMetadata).await?.allowed {
+            located.push(key_id);
+        }
+    }
+    Ok(located)
+}
@@ -0,0 +1,486 @@
+use crate::model::access::Identity;
+use crate::model::audit::NewAuditEvent;
+use crate::model::key::CreateKeyRequest;
+use crate::model::lease::{
+    CreateLeaseHookRequest, CreateLeaseRequest, CreateLeaseResponse, GenerateCredentialRequest, LeaseEndReason,
+    LeaseHook, LeaseHookKind, LeaseHookResponse, LeaseKind, LeaseListQuery, LeaseOperation, LeaseRecord,
+    LeaseResponse, NewLease, RenewLeaseRequest,
+};
+use crate::repository::{self, label as label_repository, lease as lease_repository};
+use crate::service::{access as access_service, audit as audit_service, quota as quota_service};
+use once_cell::sync::Lazy;
+use rand_core::{OsRng, RngCore};
+use serde_json::json;
+use shared::secret::SecretPayload;
+use sqlx::MySqlPool;
+use std::error::Error;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+
+use super::{announce_created, prepare_key};
+
+// 未指定时的租约时长（秒）
+const DEFAULT_TTL_SECS: u64 = 3600;
+// 未指定最长时长时为ttl_secs的倍数
+const DEFAULT_MAX_TTL_FACTOR: u64 = 24;
+// 服务端允许的最长租约时长（秒），可由LEASE_MAX_TTL_SECS覆盖
+const DEFAULT_MAX_TTL_LIMIT_SECS: u64 = 30 * 24 * 3600;
+// 默认回收检查间隔（秒）
+const DEFAULT_REAP_INTERVAL_SECS: u64 = 60;
+// 每轮最多回收的租约数
+const REAP_BATCH_SIZE: u32 = 100;
+// 钩子执行超时（秒）
+const HOOK_TIMEOUT_SECS: u64 = 30;
+// 钩子失败时保留的输出长度
+const MAX_HOOK_OUTPUT_LENGTH: usize = 512;
+// 随机凭据的默认、最短和最长长度
+const DEFAULT_CREDENTIAL_LENGTH: usize = 32;
+const MIN_CREDENTIAL_LENGTH: usize = 16;
+const MAX_CREDENTIAL_LENGTH: usize = 256;
+const CREDENTIAL_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+// 钩子只允许访问本机，不跟随重定向
+static HOOK_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(HOOK_TIMEOUT_SECS))
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .expect("Failed to build lease hook HTTP client")
+});
+
+/// 注册撤销钩子
+pub async fn create_hook(
+    pool: &MySqlPool,
+    identity: &Identity,
+    request: CreateLeaseHookRequest,
+) -> Result<LeaseOperation<LeaseHookResponse>, Box<dyn Error>> {
+    if request.name.trim().is_empty() {
+        return Ok(LeaseOperation::Invalid("Hook name must not be empty".to_string()));
+    }
+    let target = match request.kind {
+        LeaseHookKind::Command => command_path(&request.target).map(|path| path.to_string_lossy().into_owned()),
+        LeaseHookKind::Webhook => webhook_url(&request.target).map(|url| url.to_string()),
+    };
+    let target = match target {
+        Ok(target) => target,
+        Err(reason) => return Ok(LeaseOperation::Invalid(reason)),
+    };
+
+    let id = match lease_repository::insert_hook(pool, &request.name, request.kind, &target, identity.id).await {
+        Ok(id) => id,
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            return Ok(LeaseOperation::Conflict("Hook name already exists".to_string()));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let hook = lease_repository::get_hook(pool, id).await?.ok_or("Failed to retrieve created hook")?;
+
+    Ok(LeaseOperation::Done(hook.try_into()?))
+}
+
+pub async fn list_hooks(pool: &MySqlPool) -> Result<Vec<LeaseHookResponse>, Box<dyn Error>> {
+    let hooks = lease_repository::list_hooks(pool).await?;
+    Ok(hooks.into_iter().map(LeaseHookResponse::try_from).collect::<Result<_, _>>()?)
+}
+
+pub async fn delete_hook(pool: &MySqlPool, id: u64) -> Result<bool, Box<dyn Error>> {
+    Ok(lease_repository::delete_hook(pool, id).await?)
+}
+
+/// 为已有密钥或新生成的随机凭据创建租约
+///
+/// 调用方须已通过对已有密钥的delete权限检查。
+pub async fn create_lease(
+    pool: &MySqlPool,
+    owner: &Identity,
+    request: CreateLeaseRequest,
+    encryption_key: &str,
+) -> Result<LeaseOperation<CreateLeaseResponse>, Box<dyn Error>> {
+    let limit_secs = std::env::var("LEASE_MAX_TTL_SECS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_MAX_TTL_LIMIT_SECS);
+    let ttl_secs = request.ttl_secs.unwrap_or(DEFAULT_TTL_SECS);
+    if ttl_secs == 0 || ttl_secs > limit_secs {
+        return Ok(LeaseOperation::Invalid(format!("TTL must be between 1 and {} seconds", limit_secs)));
+    }
+    let max_ttl_secs = match request.max_ttl_secs {
+        Some(max_ttl_secs) if max_ttl_secs < ttl_secs || max_ttl_secs > limit_secs => {
+            return Ok(LeaseOperation::Invalid(format!(
+                "Maximum TTL must be between the TTL and {} seconds",
+                limit_secs
+            )));
+        }
+        Some(max_ttl_secs) => max_ttl_secs,
+        None => ttl_secs.saturating_mul(DEFAULT_MAX_TTL_FACTOR).min(limit_secs),
+    };
+    if let Some(hook_id) = request.hook_id {
+        if lease_repository::get_hook(pool, hook_id).await?.is_none() {
+            return Ok(LeaseOperation::Invalid("Lease hook not found".to_string()));
+        }
+    }
+
+    let now = chrono::Utc::now();
+    let mut lease = NewLease {
+        owner_id: owner.id,
+        kind: LeaseKind::Key,
+        key_id: 0,
+        hook_id: request.hook_id,
+        ttl_secs,
+        expires_at: now + chrono::Duration::seconds(ttl_secs as i64),
+        max_expires_at: now + chrono::Duration::seconds(max_ttl_secs as i64),
+    };
+
+    let (lease_id, credential) = match (request.key_id, request.generate) {
+        (Some(key_id), None) => {
+            let mut tx = pool.begin().await?;
+            if !lease_repository::lock_key(&mut *tx, key_id).await? {
+                return Ok(LeaseOperation::NotFound);
+            }
+            if lease_repository::has_active_lease(&mut *tx, key_id).await? {
+                return Ok(LeaseOperation::Conflict("Key already has an active lease".to_string()));
+            }
+            lease.key_id = key_id;
+            let lease_id = lease_repository::insert_lease(&mut *tx, &lease).await?;
+            tx.commit().await?;
+            (lease_id, None)
+        }
+        (None, Some(generate)) => {
+            lease.kind = LeaseKind::Generated;
+            match create_generated(pool, owner, generate, lease, encryption_key).await? {
+                LeaseOperation::Done(created) => created,
+                LeaseOperation::NotFound => return Ok(LeaseOperation::NotFound),
+                LeaseOperation::Invalid(reason) => return Ok(LeaseOperation::Invalid(reason)),
+                LeaseOperation::Conflict(reason) => return Ok(LeaseOperation::Conflict(reason)),
+            }
+        }
+        _ => return Ok(LeaseOperation::Invalid("Specify exactly one of key_id or generate".to_string())),
+    };
+
+    let record = lease_repository::get_lease(pool, lease_id).await?.ok_or("Failed to retrieve created lease")?;
+    Ok(LeaseOperation::Done(CreateLeaseResponse {
+        lease: record.try_into()?,
+        credential,
+    }))
+}
+
+// 生成随机凭据并与租约在同一事务中写入，密钥过期时间为租约的最长到期时间
+async fn create_generated(
+    pool: &MySqlPool,
+    owner: &Identity,
+    request: GenerateCredentialRequest,
+    mut lease: NewLease,
+    encryption_key: &str,
+) -> Result<LeaseOperation<(u64, Option<String>)>, Box<dyn Error>> {
+    let length = request.length.unwrap_or(DEFAULT_CREDENTIAL_LENGTH);
+    if !(MIN_CREDENTIAL_LENGTH..=MAX_CREDENTIAL_LENGTH).contains(&length) {
+        return Ok(LeaseOperation::Invalid(format!(
+            "Credential length must be between {} and {}",
+            MIN_CREDENTIAL_LENGTH, MAX_CREDENTIAL_LENGTH
+        )));
+    }
+    let credential = generate_credential(length);
+    let name = request.name.unwrap_or_else(|| {
+        let mut suffix = [0u8; 8];
+        OsRng.fill_bytes(&mut suffix);
+        format!("lease/{}", hex::encode(suffix))
+    });
+
+    let (data, payload) = match request.username {
+        Some(username) => (None, Some(SecretPayload::Password { username, password: credential.clone(), url: None })),
+        None => (Some(credential.clone()), None),
+    };
+    let key_request = CreateKeyRequest {
+        name,
+        data,
+        payload,
+        expires_at: Some(lease.max_expires_at),
+        description: request.description,
+        labels: request.labels,
+        metadata: None,
+    };
+    let (key, labels) = match prepare_key(key_request, owner, encryption_key) {
+        Ok(prepared) => prepared,
+        Err(e) => return Ok(LeaseOperation::Invalid(e.to_string())),
+    };
+
+    let mut tx = pool.begin().await?;
+    let key_id = match repository::create_key(&mut *tx, &key).await {
+        Ok(key_id) => key_id,
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            return Ok(LeaseOperation::Conflict("Key name already exists".to_string()));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    label_repository::insert_labels(&mut *tx, key_id, &labels).await?;
+    access_service::insert_owner_grants(&mut *tx, key_id, owner).await?;
+    quota_service::claim_key(&mut *tx, key_id, owner).await?;
+    lease.key_id = key_id;
+    let lease_id = lease_repository::insert_lease(&mut *tx, &lease).await?;
+    tx.commit().await?;
+
+    let created_key = repository::get_key_by_id(pool, key_id).await?.ok_or("Failed to retrieve created key")?;
+    announce_created(pool, created_key).await?;
+
+    Ok(LeaseOperation::Done((lease_id, Some(credential))))
+}
+
+pub async fn get_lease(pool: &MySqlPool, id: u64) -> Result<Option<LeaseRecord>, Box<dyn Error>> {
+    Ok(lease_repository::get_lease(pool, id).await?)
+}
+
+/// 列出调用方自己的租约
+pub async fn list_leases(
+    pool: &MySqlPool,
+    owner: &Identity,
+    query: LeaseListQuery,
+) -> Result<Vec<LeaseResponse>, Box<dyn Error>> {
+    let leases = lease_repository::list_leases(pool, owner.id, query.include_ended).await?;
+    Ok(leases.into_iter().map(LeaseResponse::try_from).collect::<Result<_, _>>()?)
+}
+
+/// 续期租约，不超过申请时确定的最长到期时间
+pub async fn renew_lease(
+    pool: &MySqlPool,
+    id: u64,
+    request: RenewLeaseRequest,
+) -> Result<LeaseOperation<LeaseResponse>, Box<dyn Error>> {
+    let Some(lease) = lease_repository::get_lease(pool, id).await? else {
+        return Ok(LeaseOperation::NotFound);
+    };
+    let now = chrono::Utc::now();
+    if lease.ended_at.is_some() || lease.expires_at <= now {
+        return Ok(LeaseOperation::Conflict("Lease has ended".to_string()));
+    }
+    let increment_secs = request.increment_secs.unwrap_or(lease.ttl_secs);
+    if increment_secs == 0 {
+        return Ok(LeaseOperation::Invalid("Increment must be at least one second".to_string()));
+    }
+
+    let remaining_secs = (lease.max_expires_at - now).num_seconds().max(0) as u64;
+    let expires_at = now + chrono::Duration::seconds(increment_secs.min(remaining_secs) as i64);
+    if !lease_repository::renew_lease(pool, id, expires_at).await? {
+        return Ok(LeaseOperation::Conflict("Lease has ended".to_string()));
+    }
+
+    let lease = lease_repository::get_lease(pool, id).await?.ok_or("Lease not found")?;
+    Ok(LeaseOperation::Done(lease.try_into()?))
+}
+
+/// 撤销租约：删除密钥并执行钩子
+pub async fn revoke_lease(pool: &MySqlPool, id: u64) -> Result<LeaseOperation<LeaseResponse>, Box<dyn Error>> {
+    let Some(lease) = lease_repository::get_lease(pool, id).await? else {
+        return Ok(LeaseOperation::NotFound);
+    };
+    if !end_lease(pool, &lease, LeaseEndReason::Revoked).await? {
+        return Ok(LeaseOperation::Conflict("Lease has already ended".to_string()));
+    }
+
+    let lease = lease_repository::get_lease(pool, id).await?.ok_or("Lease not found")?;
+    Ok(LeaseOperation::Done(lease.try_into()?))
+}
+
+/// 启动后台租约回收任务
+pub fn spawn_reaper(pool: &'static MySqlPool) {
+    let interval_secs = std::env::var("LEASE_REAP_INTERVAL_SECS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_REAP_INTERVAL_SECS);
+    if interval_secs == 0 {
+        tracing::warn!("LEASE_REAP_INTERVAL_SECS must be positive, reaping every second");
+    }
+    let interval_secs = interval_secs.max(1);
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            if let Err(e) = reap_expired(pool).await {
+                tracing::error!(error = %e, "Lease reaping failed");
+            }
+        }
+    });
+}
+
+/// 结束所有已到期的租约
+pub async fn reap_expired(pool: &MySqlPool) -> Result<(), Box<dyn Error>> {
+    for lease in lease_repository::list_expired(pool, REAP_BATCH_SIZE).await? {
+        let result = end_lease(pool, &lease, LeaseEndReason::Expired).await.map_err(|e| e.to_string());
+
+        let (outcome, status_code) = match &result {
+            // 已被并发的撤销请求结束
+            Ok(false) => continue,
+            Ok(true) => {
+                tracing::info!(lease_id = lease.id, key_id = ?lease.key_id, "Lease expired");
+                ("success", 200)
+            }
+            Err(e) => {
+                tracing::error!(lease_id = lease.id, error = %e, "Failed to expire lease");
+                ("failure", 500)
+            }
+        };
+
+        let event = NewAuditEvent {
+            actor_id: None,
+            actor_name: Some("lease-reaper".to_string()),
+            action: "lease.expire".to_string(),
+            key_id: lease.key_id,
+            outcome: outcome.to_string(),
+            status_code,
+            source_ip: None,
+            request_id: format!("lease-{}-{}", lease.id, chrono::Utc::now().timestamp()),
+        };
+        if let Err(e) = audit_service::record(pool, event).await {
+            tracing::error!(error = %e, "Failed to record audit event");
+        }
+    }
+
+    Ok(())
+}
+
+// 标记租约结束后删除密钥并执行钩子，返回是否由本次调用结束
+//
+// 删除或钩子失败不回滚租约状态，失败原因记录在revocation_error中。
+async fn end_lease(pool: &MySqlPool, lease: &LeaseRecord, reason: LeaseEndReason) -> Result<bool, Box<dyn Error>> {
+    if !lease_repository::end_lease(pool, lease.id, reason).await? {
+        return Ok(false);
+    }
+
+    let mut errors = Vec::new();
+    let mut key_name = None;
+    if let Some(key_id) = lease.key_id {
+        key_name = repository::get_key_by_id(pool, key_id).await?.map(|key| key.name);
+        if let Err(e) = super::delete_key_unscoped(pool, key_id).await {
+            errors.push(format!("Failed to delete key: {}", e));
+        }
+    }
+    if let Some(hook_id) = lease.hook_id {
+        if let Some(hook) = lease_repository::get_hook(pool, hook_id).await? {
+            if let Err(e) = run_hook(&hook, lease, key_name.as_deref(), reason).await {
+                errors.push(format!("Hook {} failed: {}", hook.name, e));
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        let error = errors.join("; ");
+        tracing::error!(lease_id = lease.id, error = %error, "Lease revocation incomplete");
+        lease_repository::set_revocation_error(pool, lease.id, &error).await?;
+    }
+    Ok(true)
+}
+
+// 命令通过环境变量接收租约信息，Webhook接收相同字段的JSON
+async fn run_hook(hook: &LeaseHook, lease: &LeaseRecord, key_name: Option<&str>, reason: LeaseEndReason) -> Result<(), String> {
+    let kind: LeaseHookKind = hook.kind.parse()?;
+    let key_id = lease.key_id.map(|key_id| key_id.to_string()).unwrap_or_default();
+
+    match kind {
+        LeaseHookKind::Command => {
+            // 注册后目录配置可能已变更，执行前重新检查
+            let path = command_path(&hook.target)?;
+            let mut command = tokio::process::Command::new(path);
+            command
+                .env("ECIPHER_LEASE_ID", lease.id.to_string())
+                .env("ECIPHER_LEASE_KIND", &lease.kind)
+                .env("ECIPHER_LEASE_KEY_ID", key_id)
+                .env("ECIPHER_LEASE_KEY_NAME", key_name.unwrap_or_default())
+                .env("ECIPHER_LEASE_REASON", reason.as_str())
+                .stdin(Stdio::null())
+                .kill_on_drop(true);
+
+            let output = tokio::time::timeout(Duration::from_secs(HOOK_TIMEOUT_SECS), command.output())
+                .await
+                .map_err(|_| "Command timed out".to_string())?
+                .map_err(|e| e.to_string())?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let stderr: String = stderr.trim().chars().take(MAX_HOOK_OUTPUT_LENGTH).collect();
+                return Err(format!("Command exited with {}: {}", output.status, stderr));
+            }
+        }
+        LeaseHookKind::Webhook => {
+            let url = webhook_url(&hook.target)?;
+            let response = HOOK_CLIENT
+                .post(url)
+                .header("X-Ecipher-Event", format!("lease.{}", reason))
+                .json(&json!({
+                    "lease_id": lease.id,
+                    "kind": lease.kind,
+                    "key_id": lease.key_id,
+                    "key_name": key_name,
+                    "reason": reason,
+                }))
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+            if !response.status().is_success() {
+                return Err(format!("Webhook responded with {}", response.status()));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// 命令钩子须位于LEASE_HOOK_DIR下，未配置该目录时禁用命令钩子
+fn command_path(target: &str) -> Result<PathBuf, String> {
+    let directory = std::env::var("LEASE_HOOK_DIR")
+        .ok()
+        .filter(|directory| !directory.is_empty())
+        .ok_or("Command hooks are disabled; set LEASE_HOOK_DIR")?;
+    let directory = std::fs::canonicalize(&directory).map_err(|e| format!("Invalid LEASE_HOOK_DIR: {}", e))?;
+
+    let path = PathBuf::from(target);
+    if !path.is_absolute() {
+        return Err("Command hook must be an absolute path".to_string());
+    }
+    // 解析符号链接后再比较，防止通过链接逃出目录
+    let path = std::fs::canonicalize(&path).map_err(|e| format!("Command hook not found: {}", e))?;
+    if !path.starts_with(&directory) || !path.is_file() {
+        return Err(format!("Command hook must be a file under {}", directory.display()));
+    }
+
+    Ok(path)
+}
+
+// Webhook钩子只能指向回环地址
+fn webhook_url(target: &str) -> Result<reqwest::Url, String> {
+    let url = reqwest::Url::parse(target).map_err(|e| format!("Invalid webhook URL: {}", e))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err("Webhook URL must use http or https".to_string());
+    }
+
+    let host = url.host_str().unwrap_or_default();
+    let loopback = host.eq_ignore_ascii_case("localhost")
+        || host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback());
+    if !loopback {
+        return Err("Webhook hook must target a loopback address".to_string());
+    }
+
+    Ok(url)
+}
+
+// 按字母表均匀取值，丢弃会造成偏差的字节
+fn generate_credential(length: usize) -> String {
+    let limit = 256 - 256 % CREDENTIAL_ALPHABET.len();
+    let mut credential = String::with_capacity(length);
+    let mut buffer = [0u8; 64];
+
+    while credential.len() < length {
+        OsRng.fill_bytes(&mut buffer);
+        for byte in buffer {
+            if (byte as usize) < limit && credential.len() < length {
+                credential.push(CREDENTIAL_ALPHABET[byte as usize % CREDENTIAL_ALPHABET.len()] as char);
+            }
+        }
+    }
+
+    credential
+}
@@ -1,61 +1,440 @@
-use crate::model::key::{CreateKeyRequest, Key, KeyResponse};
-use crate::repository;
-use crate::utils::encryption::encrypt_data;
+pub mod access;
+pub mod share;
+pub mod audit;
+pub mod rotation;
+pub mod webhook;
+pub mod backup;
+pub mod idempotency;
+pub mod user;
+pub mod token;
+pub mod vault;
+pub mod health;
+pub mod event;
+pub mod batch;
+pub mod kmip;
+pub mod crypto;
+pub mod pki;
+pub mod ssh;
+pub mod lease;
+pub mod quota;
+pub mod team;
+
+use crate::model::access::{Identity, Permission};
+use crate::model::token::{TokenGrant, TokenOperation};
+use crate::model::key::{
+    ConditionalWrite, CreateKeyRequest, Key, KeyMaterialResponse, KeyResponse, KeySearchRequest, KeySearchResponse,
+    UpdateKeyRequest,
+};
+use crate::model::event::KeyEventType;
+use crate::model::team::NoTeamContext;
+use crate::model::webhook::WebhookEvent;
+use crate::repository::{self, label as label_repository};
+use crate::service::{access as access_service, event as event_service, quota as quota_service, webhook as webhook_service};
+use crate::utils::certificate::{certificate_not_after, validate_certificate_bundle};
+use crate::utils::encryption::{decrypt_data, encrypt_data};
+use serde_json::json;
+use shared::secret::{SecretKind, SecretPayload};
 use sqlx::MySqlPool;
+use std::collections::BTreeMap;
 use std::error::Error;
 
+// 标签键最大长度
+const MAX_LABEL_KEY_LENGTH: usize = 128;
+// 标签值最大长度
+const MAX_LABEL_VALUE_LENGTH: usize = 255;
+// 单次搜索返回的最大记录数
+const MAX_SEARCH_LIMIT: u32 = 200;
+
+/// 校验标签：键由字母、数字及 . _ - / 组成
+fn validate_labels(labels: &BTreeMap<String, String>) -> Result<(), Box<dyn Error>> {
+    for (label_key, label_value) in labels {
+        if label_key.is_empty()
+            || label_key.len() > MAX_LABEL_KEY_LENGTH
+            || !label_key.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '/'))
+        {
+            return Err(format!("Invalid label key: {}", label_key).into());
+        }
+        if label_value.len() > MAX_LABEL_VALUE_LENGTH {
+            return Err(format!("Label value too long for key: {}", label_key).into());
+        }
+    }
+    Ok(())
+}
+
+/// 保险库中的密钥由客户端加密，服务端无法解密、轮换或共享
+fn ensure_server_encrypted(key: &Key) -> Result<(), Box<dyn Error>> {
+    if key.vault_id.is_some() {
+        return Err("Operation not supported for client-encrypted vault keys".into());
+    }
+    Ok(())
+}
+
+/// 轮换生成的随机值会破坏结构化密钥的字段结构
+fn ensure_untyped(key: &Key) -> Result<(), Box<dyn Error>> {
+    if key.kind.is_some() {
+        return Err("Automatic rotation is only supported for untyped keys".into());
+    }
+    Ok(())
+}
+
+/// 校验结构化密钥内容，证书类型会解析证书并返回其到期时间
+fn validate_payload(payload: &SecretPayload) -> Result<Option<chrono::DateTime<chrono::Utc>>, Box<dyn Error>> {
+    payload.validate()?;
+    if let SecretPayload::Certificate { certificate, private_key, chain } = payload {
+        return Ok(Some(validate_certificate_bundle(certificate, private_key, chain.as_deref())?));
+    }
+    Ok(None)
+}
+
+// 由数据库记录构造响应，附带标签
+async fn to_response(pool: &MySqlPool, key: Key) -> Result<KeyResponse, Box<dyn Error>> {
+    let id = key.id.ok_or("Key row without id")?;
+
+    Ok(KeyResponse {
+        id,
+        name: key.name,
+        expires_at: key.expires_at,
+        description: key.description,
+        labels: label_repository::get_labels(pool, id).await?,
+        metadata: key.metadata,
+        version: key.version.ok_or("Key row without version")?,
+        kind: key.kind.as_deref().map(str::parse::<SecretKind>).transpose()?,
+        vault_id: key.vault_id,
+        created_at: key.created_at.ok_or("Key row without created_at")?,
+    })
+}
+
+/// 创建密钥，创建者获得全部权限并计入其配额
 pub async fn create_key(
     pool: &MySqlPool,
+    owner: &Identity,
     request: CreateKeyRequest,
     encryption_key: &str,
 ) -> Result<KeyResponse, Box<dyn Error>> {
+    let size = quota_service::data_size(request.data.as_deref(), request.payload.as_ref())?;
+    quota_service::check_data_size(pool, owner, size).await?;
+    let (key, labels) = prepare_key(request, owner, encryption_key)?;
+
+    let mut tx = pool.begin().await?;
+    let key_id = repository::create_key(&mut *tx, &key).await?;
+    label_repository::insert_labels(&mut *tx, key_id, &labels).await?;
+    access_service::insert_owner_grants(&mut *tx, key_id, owner).await?;
+    quota_service::claim_key(&mut *tx, key_id, owner).await?;
+    tx.commit().await?;
+
+    // 获取创建的密钥
+    let created_key = repository::get_key_by_id(pool, key_id).await?
+        .ok_or("Failed to retrieve created key")?;
+    
+    announce_created(pool, created_key).await
+}
+
+/// 校验创建请求并加密数据，返回待写入的密钥记录及其标签；密钥属于创建者的当前团队
+fn prepare_key(
+    request: CreateKeyRequest,
+    owner: &Identity,
+    encryption_key: &str,
+) -> Result<(Key, BTreeMap<String, String>), Box<dyn Error>> {
+    let team_id = owner.team_id.ok_or(NoTeamContext)?;
+    validate_labels(&request.labels)?;
+    if request.metadata.as_ref().is_some_and(|metadata| !metadata.is_object()) {
+        return Err("Metadata must be a JSON object".into());
+    }
+
+    // 未显式指定过期时间时，尝试从PEM证书中读取
+    let (data, kind, expires_at) = match (request.data, request.payload) {
+        (Some(data), None) => {
+            let expires_at = request.expires_at.or_else(|| certificate_not_after(&data));
+            (data, None, expires_at)
+        }
+        (None, Some(payload)) => {
+            let not_after = validate_payload(&payload)?;
+            (serde_json::to_string(&payload)?, Some(payload.kind().to_string()), request.expires_at.or(not_after))
+        }
+        _ => return Err("Exactly one of data or payload must be given".into()),
+    };
+
     // 加密数据
-    let encrypted_data = encrypt_data(&request.data, encryption_key)?;
+    let encrypted_data = encrypt_data(&data, encryption_key)?;
     
     // 创建密钥记录
     let key = Key {
         id: None,
         name: request.name,
+        team_id: Some(team_id),
         encrypted_data,
+        kind,
+        expires_at,
+        description: request.description,
+        metadata: request.metadata,
+        version: None,
+        vault_id: None,
+        nonce: None,
         created_at: None,
         updated_at: None,
     };
-    
-    let key_id = repository::create_key(pool, &key).await?;
-    
-    // 获取创建的密钥
-    let created_key = repository::get_key_by_id(pool, key_id).await?
-        .ok_or("Failed to retrieve created key")?;
-    
-    Ok(KeyResponse {
-        id: created_key.id.unwrap(),
-        name: created_key.name,
-        created_at: created_key.created_at.unwrap(),
-    })
+
+    Ok((key, request.labels))
+}
+
+// 密钥创建成功后通知所属团队的webhook和事件订阅方，返回密钥的响应
+async fn announce_created(pool: &MySqlPool, key: Key) -> Result<KeyResponse, Box<dyn Error>> {
+    let team_id = key.team_id.ok_or("Key row without team")?;
+    let response = to_response(pool, key).await?;
+    webhook_service::dispatch(pool, team_id, WebhookEvent::KeyCreated, json!({
+        "key_id": response.id,
+        "name": response.name,
+        "expires_at": response.expires_at,
+        "kind": response.kind,
+    }));
+    event_service::publish(pool, KeyEventType::Created, response.id, &response.name, Some(response.version), &[]).await;
+    Ok(response)
+}
+
+/// 在调用方当前团队内按id读取密钥，其他团队的密钥视为不存在
+pub async fn find_team_key(pool: &MySqlPool, caller: &Identity, id: u64) -> Result<Option<Key>, Box<dyn Error>> {
+    match caller.team_id {
+        Some(team_id) => Ok(repository::get_team_key(pool, team_id, id).await?),
+        None => Ok(None),
+    }
 }
 
 /// 获取密钥信息
 pub async fn get_key(
     pool: &MySqlPool,
+    caller: &Identity,
     id: u64,
 ) -> Result<Option<KeyResponse>, Box<dyn Error>> {
-    let key = repository::get_key_by_id(pool, id).await?;
+    let key = find_team_key(pool, caller, id).await?;
     
     match key {
-        Some(key) => Ok(Some(KeyResponse {
+        Some(key) => Ok(Some(to_response(pool, key).await?)),
+        None => Ok(None),
+    }
+}
+
+/// 在调用方当前团队内检索密钥，只返回有权读取元数据且在令牌作用范围内的结果
+pub async fn search_keys(
+    pool: &MySqlPool,
+    identity: &Identity,
+    grant: Option<&TokenGrant>,
+    request: KeySearchRequest,
+) -> Result<KeySearchResponse, Box<dyn Error>> {
+    let Some(team_id) = identity.team_id else {
+        return Ok(KeySearchResponse {
+            keys: Vec::new(),
+            next_after_id: None,
+        });
+    };
+    let limit = request.limit.unwrap_or(50).clamp(1, MAX_SEARCH_LIMIT);
+    let keys = label_repository::search_keys(pool, team_id, &request, limit).await?;
+
+    // 游标基于数据库返回的最后一条，而非过滤后的结果
+    let next_after_id = if keys.len() as u32 == limit {
+        keys.last().and_then(|key| key.id)
+    } else {
+        None
+    };
+
+    let mut conn = pool.acquire().await?;
+    let mut results = Vec::with_capacity(keys.len());
+    for key in keys {
+        let key_id = key.id.ok_or("Key row without id")?;
+        if grant.is_some_and(|grant| !grant.allows(&key.name, TokenOperation::ReadMetadata)) {
+            continue;
+        }
+        if access_service::evaluate(&mut conn, identity, key_id, Permission::ReadMetadata).await?.allowed {
+            results.push(to_response(pool, key).await?);
+        }
+    }
+
+    Ok(KeySearchResponse {
+        keys: results,
+        next_after_id,
+    })
+}
+
+/// 获取解密后的密钥内容
+pub async fn get_key_material(
+    pool: &MySqlPool,
+    caller: &Identity,
+    id: u64,
+    encryption_key: &str,
+) -> Result<Option<KeyMaterialResponse>, Box<dyn Error>> {
+    let key = find_team_key(pool, caller, id).await?;
+
+    match key {
+        // 保险库密钥原样返回客户端密文
+        Some(key) if key.vault_id.is_some() => Ok(Some(KeyMaterialResponse {
             id: key.id.unwrap(),
+            data: Some(key.encrypted_data),
+            payload: None,
             name: key.name,
-            created_at: key.created_at.unwrap(),
+            vault_id: key.vault_id,
+            nonce: key.nonce,
         })),
+        Some(key) => {
+            let plaintext = decrypt_data(&key.encrypted_data, encryption_key)?;
+            // 结构化密钥以payload返回，未声明类型的密钥保持原样
+            let (data, payload) = match key.kind {
+                Some(_) => (None, Some(serde_json::from_str::<SecretPayload>(&plaintext)?)),
+                None => (Some(plaintext), None),
+            };
+            Ok(Some(KeyMaterialResponse {
+                id: key.id.unwrap(),
+                data,
+                payload,
+                name: key.name,
+                vault_id: None,
+                nonce: None,
+            }))
+        }
         None => Ok(None),
     }
 }
 
-/// 删除密钥
+/// 修改密钥
+///
+/// 给出`expected_version`时只在版本一致时写入；未给出时以读取到的当前版本为准。
+pub async fn update_key(
+    pool: &MySqlPool,
+    caller: &Identity,
+    id: u64,
+    request: UpdateKeyRequest,
+    expected_version: Option<u32>,
+    encryption_key: &str,
+) -> Result<ConditionalWrite<KeyResponse>, Box<dyn Error>> {
+    if let Some(labels) = &request.labels {
+        validate_labels(labels)?;
+    }
+    if request.metadata.as_ref().is_some_and(|metadata| !metadata.is_object()) {
+        return Err("Metadata must be a JSON object".into());
+    }
+    let size = quota_service::data_size(request.data.as_deref(), request.payload.as_ref())?
+        + request.encrypted_data.as_ref().map_or(0, String::len);
+    quota_service::check_data_size(pool, caller, size).await?;
+
+    let Some(mut key) = find_team_key(pool, caller, id).await? else {
+        return Ok(ConditionalWrite::NotFound);
+    };
+    let current = key.version.ok_or("Key row without version")?;
+    let expected = expected_version.unwrap_or(current);
+    if expected != current {
+        return Ok(ConditionalWrite::VersionMismatch { current });
+    }
+
+    if key.vault_id.is_some() {
+        if request.data.is_some() || request.payload.is_some() {
+            return Err("Vault keys must be updated with client-encrypted data".into());
+        }
+        match (request.encrypted_data, request.nonce) {
+            (Some(encrypted_data), Some(nonce)) => {
+                vault::validate_ciphertext(&encrypted_data, &nonce)?;
+                key.encrypted_data = encrypted_data;
+                key.nonce = Some(nonce);
+            }
+            (None, None) => {}
+            _ => return Err("encrypted_data and nonce must be given together".into()),
+        }
+    } else if request.encrypted_data.is_some() || request.nonce.is_some() {
+        return Err("Only vault keys accept client-encrypted data".into());
+    } else if request.data.is_some() && request.payload.is_some() {
+        return Err("data and payload must not be given together".into());
+    } else if let Some(payload) = &request.payload {
+        let not_after = validate_payload(payload)?;
+        key.encrypted_data = encrypt_data(&serde_json::to_string(payload)?, encryption_key)?;
+        key.kind = Some(payload.kind().to_string());
+        if let Some(expires_at) = not_after {
+            key.expires_at = Some(expires_at);
+        }
+    } else if let Some(data) = &request.data {
+        if key.kind.is_some() {
+            return Err("Typed keys must be updated with a payload".into());
+        }
+        key.encrypted_data = encrypt_data(data, encryption_key)?;
+        if let Some(expires_at) = certificate_not_after(data) {
+            key.expires_at = Some(expires_at);
+        }
+    }
+    if request.description.is_some() {
+        key.description = request.description;
+    }
+    if request.metadata.is_some() {
+        key.metadata = request.metadata;
+    }
+
+    let mut tx = pool.begin().await?;
+    // 读取与写入之间被并发修改时，条件更新不会命中任何行
+    if !repository::update_key(&mut tx, &key, expected).await? {
+        tx.rollback().await?;
+        let current = repository::get_key_by_id(pool, id).await?.and_then(|key| key.version);
+        return Ok(match current {
+            Some(current) => ConditionalWrite::VersionMismatch { current },
+            None => ConditionalWrite::NotFound,
+        });
+    }
+    if let Some(labels) = &request.labels {
+        label_repository::delete_labels(&mut *tx, id).await?;
+        label_repository::insert_labels(&mut *tx, id, labels).await?;
+    }
+    tx.commit().await?;
+
+    let updated_key = repository::get_key_by_id(pool, id).await?
+        .ok_or("Failed to retrieve updated key")?;
+    let response = to_response(pool, updated_key).await?;
+    event_service::publish(pool, KeyEventType::Updated, response.id, &response.name, Some(response.version), &[]).await;
+
+    Ok(ConditionalWrite::Done(response))
+}
+
+/// 删除密钥，给出`expected_version`时只在版本一致时删除
 pub async fn delete_key(
     pool: &MySqlPool,
+    caller: &Identity,
     id: u64,
-) -> Result<(), Box<dyn Error>> {
-    repository::delete_key(pool, id).await?;
-    Ok(())
+    expected_version: Option<u32>,
+) -> Result<ConditionalWrite<()>, Box<dyn Error>> {
+    let Some(key) = find_team_key(pool, caller, id).await? else {
+        return Ok(ConditionalWrite::NotFound);
+    };
+    let team_id = key.team_id.ok_or("Key row without team")?;
+    let audience = event_service::deletion_audience(&mut *pool.acquire().await?, id).await?;
+
+    if !repository::delete_key(&mut *pool.acquire().await?, id, expected_version).await? {
+        let current = repository::get_key_by_id(pool, id).await?.and_then(|key| key.version);
+        return Ok(match current {
+            Some(current) => ConditionalWrite::VersionMismatch { current },
+            None => ConditionalWrite::NotFound,
+        });
+    }
+
+    announce_deleted(pool, team_id, id, &key.name, &audience).await;
+    Ok(ConditionalWrite::Done(()))
+}
+
+/// 不经团队检查直接删除密钥，只供租约回收等没有调用方身份的内部任务使用
+///
+/// 返回密钥是否存在并已删除。
+pub async fn delete_key_unscoped(pool: &MySqlPool, id: u64) -> Result<bool, Box<dyn Error>> {
+    let Some(key) = repository::get_key_by_id(pool, id).await? else {
+        return Ok(false);
+    };
+    let team_id = key.team_id.ok_or("Key row without team")?;
+    let audience = event_service::deletion_audience(&mut *pool.acquire().await?, id).await?;
+
+    if !repository::delete_key(&mut *pool.acquire().await?, id, None).await? {
+        return Ok(false);
+    }
+
+    announce_deleted(pool, team_id, id, &key.name, &audience).await;
+    Ok(true)
+}
+
+// 密钥删除成功后通知所属团队的webhook和事件订阅方，`audience`为删除前可读取元数据的ACL主体
+async fn announce_deleted(pool: &MySqlPool, team_id: u64, id: u64, name: &str, audience: &[String]) {
+    webhook_service::dispatch(pool, team_id, WebhookEvent::KeyDeleted, json!({
+        "key_id": id,
+        "name": name,
+    }));
+    event_service::publish(pool, KeyEventType::Deleted, id, name, None, audience).await;
 }
\ No newline at end of file
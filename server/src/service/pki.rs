@@ -0,0 +1,543 @@
+use crate::model::access::Identity;
+use crate::model::key::CreateKeyRequest;
+use crate::model::pki::{
+    CaOperation, CaResponse, CertificateAuthority, CertificateListQuery, CertificateProfile, CertificateStatus,
+    CreateCaRequest, IssueCertificateRequest, IssuedCertificate, IssuedCertificateResponse, NewCertificateAuthority,
+    NewIssuedCertificate, RevocationReason, RevokedCertificate,
+};
+use crate::repository::{self, label as label_repository, pki as pki_repository};
+use crate::service::{access as access_service, quota as quota_service};
+use crate::utils::encryption::decrypt_data;
+use crate::utils::x509::{self, Issuer, IssuerUrls, OcspResponseStatus};
+use rcgen::{CertificateParams, CertificateSigningRequestParams, DnType, KeyPair, SanType};
+use shared::secret::SecretPayload;
+use sqlx::MySqlPool;
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use super::{announce_created, prepare_key};
+
+// 根CA和中间CA的默认有效期（天）
+const ROOT_VALIDITY_DAYS: u32 = 3650;
+const INTERMEDIATE_VALIDITY_DAYS: u32 = 1825;
+// 证书生效时间提前量，容忍依赖方的时钟偏差
+const BACKDATE_MINUTES: i64 = 5;
+// CRL和OCSP响应的有效时长
+const CRL_VALIDITY_HOURS: i64 = 24;
+const OCSP_VALIDITY_HOURS: i64 = 1;
+// 单次列出的最大证书数
+const MAX_LIST_LIMIT: u32 = 200;
+// 保存CA私钥的密钥名称前缀及标签
+const CA_KEY_PREFIX: &str = "pki/";
+const CA_LABEL: &str = "pki.ca";
+// 未配置PKI_BASE_URL时证书中使用的服务地址
+const DEFAULT_BASE_URL: &str = "http://127.0.0.1:3000";
+
+/// 创建根CA或由已有CA签发的中间CA，CA私钥以证书类型的密钥保存并归创建者所有
+pub async fn create_ca(
+    pool: &MySqlPool,
+    owner: &Identity,
+    request: CreateCaRequest,
+    encryption_key: &str,
+) -> Result<CaOperation<CaResponse>, Box<dyn Error>> {
+    if request.name.trim().is_empty() || request.common_name.trim().is_empty() {
+        return Ok(CaOperation::Invalid("Name and common_name are required".to_string()));
+    }
+
+    let now = chrono::Utc::now();
+    let (parent, path_length, not_after) = match request.parent_id {
+        Some(parent_id) => {
+            let Some(parent) = pki_repository::get_authority(pool, parent_id).await? else {
+                return Ok(CaOperation::Invalid("Parent CA not found".to_string()));
+            };
+            // 上级CA的路径长度限制了下级CA还能签发的层数
+            let path_length = match parent.path_length {
+                Some(0) => return Ok(CaOperation::Invalid("Parent CA cannot issue intermediate CAs".to_string())),
+                Some(limit) => Some(request.path_length.map_or(limit - 1, |length| length.min(limit - 1))),
+                None => request.path_length,
+            };
+            let days = request.validity_days.unwrap_or(INTERMEDIATE_VALIDITY_DAYS);
+            let not_after = (now + chrono::Duration::days(days.into())).min(parent.not_after);
+            let issuer = load_issuer(pool, &parent, encryption_key).await?;
+            (Some((parent, issuer)), path_length, not_after)
+        }
+        None => {
+            let days = request.validity_days.unwrap_or(ROOT_VALIDITY_DAYS);
+            (None, request.path_length, now + chrono::Duration::days(days.into()))
+        }
+    };
+    if request.validity_days == Some(0) || request.validity_days.unwrap_or(0) > CertificateProfile::Ca.max_validity_days() {
+        return Ok(CaOperation::Invalid("Invalid validity_days".to_string()));
+    }
+    if not_after <= now {
+        return Ok(CaOperation::Invalid("Parent CA has expired".to_string()));
+    }
+
+    let key_pair = x509::generate_key(request.key_algorithm)?;
+    let serial = x509::random_serial();
+    let mut params = CertificateParams::default();
+    params.distinguished_name = x509::distinguished_name(
+        &request.common_name,
+        request.organization.as_deref(),
+        request.country.as_deref(),
+    );
+    let urls = parent.as_ref().map(|(parent, _)| issuer_urls(parent.id));
+    x509::apply_profile(
+        &mut params,
+        CertificateProfile::Ca,
+        &serial,
+        now - chrono::Duration::minutes(BACKDATE_MINUTES),
+        not_after,
+        path_length,
+        urls.as_ref(),
+    )?;
+    let certificate = match &parent {
+        Some((_, issuer)) => issuer.issue(params, &key_pair)?,
+        None => params.self_signed(&key_pair)?,
+    };
+    let certificate_pem = certificate.pem();
+    let subject = x509::subject_of(certificate.der())?;
+    let chain = parent.as_ref().map(|(parent, _)| chain_of(parent));
+
+    let key_request = CreateKeyRequest {
+        name: format!("{}{}", CA_KEY_PREFIX, request.name),
+        data: None,
+        payload: Some(SecretPayload::Certificate {
+            certificate: certificate_pem.clone(),
+            private_key: key_pair.serialize_pem(),
+            chain: chain.clone(),
+        }),
+        expires_at: None,
+        description: Some(format!("Private key of CA {}", request.name)),
+        labels: BTreeMap::from([(CA_LABEL.to_string(), request.name.clone())]),
+        metadata: None,
+    };
+    let (key, labels) = prepare_key(key_request, owner, encryption_key)?;
+
+    let mut tx = pool.begin().await?;
+    let key_id = match repository::create_key(&mut *tx, &key).await {
+        Ok(key_id) => key_id,
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            return Ok(CaOperation::Conflict("CA key name already exists".to_string()));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    label_repository::insert_labels(&mut *tx, key_id, &labels).await?;
+    access_service::insert_owner_grants(&mut *tx, key_id, owner).await?;
+    quota_service::claim_key(&mut *tx, key_id, owner).await?;
+    let authority = NewCertificateAuthority {
+        name: request.name,
+        key_id,
+        parent_id: request.parent_id,
+        subject: subject.clone(),
+        certificate: certificate_pem.clone(),
+        chain,
+        path_length,
+        not_after,
+    };
+    let ca_id = match pki_repository::insert_authority(&mut *tx, &authority).await {
+        Ok(ca_id) => ca_id,
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            return Ok(CaOperation::Conflict("CA name already exists".to_string()));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    // 中间CA证书同时记录为上级CA签发的证书，以便吊销
+    if let Some((parent, _)) = &parent {
+        let issued = NewIssuedCertificate {
+            ca_id: parent.id,
+            serial_number: x509::serial_hex(&serial),
+            subject,
+            profile: CertificateProfile::Ca,
+            certificate: certificate_pem,
+            key_id: Some(key_id),
+            not_before: now - chrono::Duration::minutes(BACKDATE_MINUTES),
+            not_after,
+        };
+        pki_repository::insert_certificate(&mut *tx, &issued).await?;
+    }
+    tx.commit().await?;
+
+    let created_key = repository::get_key_by_id(pool, key_id).await?.ok_or("Failed to retrieve created key")?;
+    announce_created(pool, created_key).await?;
+
+    let authority = pki_repository::get_authority(pool, ca_id).await?.ok_or("Failed to retrieve created CA")?;
+    Ok(CaOperation::Done(authority.into()))
+}
+
+pub async fn get_ca(pool: &MySqlPool, ca_id: u64) -> Result<Option<CertificateAuthority>, Box<dyn Error>> {
+    Ok(pki_repository::get_authority(pool, ca_id).await?)
+}
+
+pub async fn list_cas(pool: &MySqlPool) -> Result<Vec<CaResponse>, Box<dyn Error>> {
+    Ok(pki_repository::list_authorities(pool).await?.into_iter().map(CaResponse::from).collect())
+}
+
+/// CA证书及其上级证书链，供依赖方下载
+pub async fn ca_certificate_chain(pool: &MySqlPool, ca_id: u64) -> Result<Option<String>, Box<dyn Error>> {
+    let authority = pki_repository::get_authority(pool, ca_id).await?;
+    Ok(authority.as_ref().map(chain_of))
+}
+
+/// 按模板签发证书；未给出CSR时生成私钥，并将证书和私钥保存为归调用方所有的密钥
+pub async fn issue_certificate(
+    pool: &MySqlPool,
+    owner: &Identity,
+    ca_id: u64,
+    request: IssueCertificateRequest,
+    encryption_key: &str,
+) -> Result<CaOperation<IssuedCertificateResponse>, Box<dyn Error>> {
+    let Some(authority) = pki_repository::get_authority(pool, ca_id).await? else {
+        return Ok(CaOperation::NotFound);
+    };
+    let profile = request.profile;
+    if profile == CertificateProfile::Ca {
+        return Ok(CaOperation::Invalid("Intermediate CAs are created through POST /pki/cas".to_string()));
+    }
+
+    let days = request.validity_days.unwrap_or(profile.default_validity_days());
+    if days == 0 || days > profile.max_validity_days() {
+        return Ok(CaOperation::Invalid(format!(
+            "validity_days must be between 1 and {} for {} certificates",
+            profile.max_validity_days(),
+            profile
+        )));
+    }
+    let now = chrono::Utc::now();
+    let not_before = now - chrono::Duration::minutes(BACKDATE_MINUTES);
+    let not_after = (now + chrono::Duration::days(days.into())).min(authority.not_after);
+    if not_after <= now {
+        return Ok(CaOperation::Invalid("CA has expired".to_string()));
+    }
+
+    // 公钥来自CSR，或由服务端生成
+    let (mut params, subject_key) = match &request.csr {
+        Some(csr) => match CertificateSigningRequestParams::from_pem(csr) {
+            Ok(csr) => (csr.params, SubjectKey::Requested(csr.public_key)),
+            Err(_) => return Ok(CaOperation::Invalid("CSR could not be parsed or verified".to_string())),
+        },
+        None => {
+            if request.common_name.is_none() {
+                return Ok(CaOperation::Invalid("common_name is required without a CSR".to_string()));
+            }
+            (CertificateParams::default(), SubjectKey::Generated(x509::generate_key(request.key_algorithm)?))
+        }
+    };
+    if let Some(common_name) = &request.common_name {
+        params.distinguished_name = x509::distinguished_name(common_name, None, None);
+    }
+    if let Some(organization) = &request.organization {
+        params.distinguished_name.push(DnType::OrganizationName, organization.as_str());
+    }
+    match subject_alt_names(&request) {
+        Ok(names) if !names.is_empty() => params.subject_alt_names = names,
+        Ok(_) => {}
+        Err(message) => return Ok(CaOperation::Invalid(message)),
+    }
+    if profile == CertificateProfile::Server && params.subject_alt_names.is_empty() {
+        return Ok(CaOperation::Invalid("Server certificates require a DNS name or IP address".to_string()));
+    }
+
+    let serial = x509::random_serial();
+    let serial_number = x509::serial_hex(&serial);
+    x509::apply_profile(&mut params, profile, &serial, not_before, not_after, None, Some(&issuer_urls(authority.id)))?;
+    let issuer = load_issuer(pool, &authority, encryption_key).await?;
+    let certificate = match &subject_key {
+        SubjectKey::Generated(key_pair) => issuer.issue(params, key_pair)?,
+        SubjectKey::Requested(public_key) => issuer.issue(params, public_key)?,
+    };
+    let certificate_pem = certificate.pem();
+    let mut issued = NewIssuedCertificate {
+        ca_id,
+        serial_number: serial_number.clone(),
+        subject: x509::subject_of(certificate.der())?,
+        profile,
+        certificate: certificate_pem.clone(),
+        key_id: None,
+        not_before,
+        not_after,
+    };
+
+    let mut tx = pool.begin().await?;
+    let created_key_id = match &subject_key {
+        SubjectKey::Generated(key_pair) => {
+            let key_request = CreateKeyRequest {
+                name: request.key_name.unwrap_or_else(|| format!("{}/{}", authority.name, serial_number)),
+                data: None,
+                payload: Some(SecretPayload::Certificate {
+                    certificate: certificate_pem,
+                    private_key: key_pair.serialize_pem(),
+                    chain: Some(chain_of(&authority)),
+                }),
+                expires_at: None,
+                description: None,
+                labels: BTreeMap::new(),
+                metadata: None,
+            };
+            let (key, _) = prepare_key(key_request, owner, encryption_key)?;
+            let key_id = match repository::create_key(&mut *tx, &key).await {
+                Ok(key_id) => key_id,
+                Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+                    return Ok(CaOperation::Conflict("Key name already exists".to_string()));
+                }
+                Err(e) => return Err(e.into()),
+            };
+            access_service::insert_owner_grants(&mut *tx, key_id, owner).await?;
+            quota_service::claim_key(&mut *tx, key_id, owner).await?;
+            issued.key_id = Some(key_id);
+            Some(key_id)
+        }
+        SubjectKey::Requested(_) => None,
+    };
+    pki_repository::insert_certificate(&mut *tx, &issued).await?;
+    tx.commit().await?;
+
+    if let Some(key_id) = created_key_id {
+        let created_key = repository::get_key_by_id(pool, key_id).await?.ok_or("Failed to retrieve created key")?;
+        announce_created(pool, created_key).await?;
+    }
+
+    let certificate = pki_repository::get_certificate(pool, ca_id, &serial_number)
+        .await?
+        .ok_or("Failed to retrieve issued certificate")?;
+    Ok(CaOperation::Done(certificate.try_into()?))
+}
+
+/// 列出CA签发的证书，CA不存在时返回None
+pub async fn list_certificates(
+    pool: &MySqlPool,
+    ca_id: u64,
+    query: CertificateListQuery,
+) -> Result<Option<Vec<IssuedCertificateResponse>>, Box<dyn Error>> {
+    if pki_repository::get_authority(pool, ca_id).await?.is_none() {
+        return Ok(None);
+    }
+
+    let limit = query.limit.unwrap_or(50).clamp(1, MAX_LIST_LIMIT);
+    let certificates =
+        pki_repository::list_certificates(pool, ca_id, query.after_id.unwrap_or(0), limit, query.revoked).await?;
+    let responses = certificates
+        .into_iter()
+        .map(IssuedCertificateResponse::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Some(responses))
+}
+
+pub async fn get_certificate(
+    pool: &MySqlPool,
+    ca_id: u64,
+    serial_number: &str,
+) -> Result<Option<IssuedCertificateResponse>, Box<dyn Error>> {
+    let certificate = pki_repository::get_certificate(pool, ca_id, &serial_number.to_ascii_lowercase()).await?;
+    Ok(certificate.map(IssuedCertificateResponse::try_from).transpose()?)
+}
+
+/// 吊销证书，吊销后出现在之后生成的CRL和OCSP响应中
+pub async fn revoke_certificate(
+    pool: &MySqlPool,
+    ca_id: u64,
+    serial_number: &str,
+    reason: RevocationReason,
+) -> Result<CaOperation<IssuedCertificateResponse>, Box<dyn Error>> {
+    let serial_number = serial_number.to_ascii_lowercase();
+    let Some(certificate) = pki_repository::get_certificate(pool, ca_id, &serial_number).await? else {
+        return Ok(CaOperation::NotFound);
+    };
+    if certificate.revoked_at.is_some()
+        || !pki_repository::revoke_certificate(pool, ca_id, &serial_number, reason.as_str()).await?
+    {
+        return Ok(CaOperation::Conflict("Certificate already revoked".to_string()));
+    }
+
+    let certificate = pki_repository::get_certificate(pool, ca_id, &serial_number)
+        .await?
+        .ok_or("Failed to retrieve revoked certificate")?;
+    Ok(CaOperation::Done(certificate.try_into()?))
+}
+
+/// 生成DER格式的CRL，每次生成使用新的CRL序号；CA不存在时返回None
+pub async fn generate_crl(pool: &MySqlPool, ca_id: u64, encryption_key: &str) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+    let Some(authority) = pki_repository::get_authority(pool, ca_id).await? else {
+        return Ok(None);
+    };
+
+    let mut tx = pool.begin().await?;
+    let crl_number = pki_repository::next_crl_number(&mut *tx, ca_id).await?.ok_or("CA disappeared")?;
+    tx.commit().await?;
+
+    let revoked = pki_repository::list_revoked(pool, ca_id)
+        .await?
+        .into_iter()
+        .map(revoked_entry)
+        .collect::<Result<Vec<_>, _>>()?;
+    let now = chrono::Utc::now();
+    let issuer = load_issuer(pool, &authority, encryption_key).await?;
+    Ok(Some(issuer.crl(crl_number, now, now + chrono::Duration::hours(CRL_VALIDITY_HOURS), &revoked)?))
+}
+
+/// 应答DER格式的OCSP请求；无法解析的请求返回malformedRequest，CA不存在时返回None
+pub async fn ocsp_response(
+    pool: &MySqlPool,
+    ca_id: u64,
+    request: &[u8],
+    encryption_key: &str,
+) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+    let Some(authority) = pki_repository::get_authority(pool, ca_id).await? else {
+        return Ok(None);
+    };
+    let Some(request) = x509::parse_ocsp_request(request) else {
+        return Ok(Some(x509::ocsp_error(OcspResponseStatus::MalformedRequest)));
+    };
+
+    let issuer = load_issuer(pool, &authority, encryption_key).await?;
+    let mut statuses = Vec::with_capacity(request.certificates.len());
+    for id in &request.certificates {
+        // 其它CA签发的证书和未记录的序号均为unknown
+        let certificate = match issuer.matches(id) {
+            true => pki_repository::get_certificate(pool, ca_id, &id.serial_number).await?,
+            false => None,
+        };
+        statuses.push(certificate_status(certificate)?);
+    }
+
+    let now = chrono::Utc::now();
+    Ok(Some(issuer.ocsp_response(&request, &statuses, now, now + chrono::Duration::hours(OCSP_VALIDITY_HOURS))?))
+}
+
+// 被签发证书的公钥来源
+enum SubjectKey {
+    Generated(KeyPair),
+    Requested(rcgen::PublicKey),
+}
+
+// 读取CA私钥并构造签发者
+async fn load_issuer(pool: &MySqlPool, authority: &CertificateAuthority, encryption_key: &str) -> Result<Issuer, Box<dyn Error>> {
+    let key = repository::get_key_by_id(pool, authority.key_id).await?.ok_or("CA key not found")?;
+    let plaintext = decrypt_data(&key.encrypted_data, encryption_key)?;
+    match SecretPayload::from_json(&plaintext) {
+        Some(SecretPayload::Certificate { private_key, .. }) => Issuer::from_pem(&authority.certificate, &private_key),
+        _ => Err("CA key is not stored as a certificate".into()),
+    }
+}
+
+// 签发的证书中CRL、OCSP和CA证书的地址
+fn issuer_urls(ca_id: u64) -> IssuerUrls {
+    let base_url = std::env::var("PKI_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+    let base_url = base_url.trim_end_matches('/');
+    IssuerUrls {
+        crl: format!("{}/pki/cas/{}/crl", base_url, ca_id),
+        ocsp: format!("{}/pki/cas/{}/ocsp", base_url, ca_id),
+        ca_issuers: format!("{}/pki/cas/{}/certificate", base_url, ca_id),
+    }
+}
+
+// CA证书后接其上级证书链
+fn chain_of(authority: &CertificateAuthority) -> String {
+    match &authority.chain {
+        Some(chain) => format!("{}{}", authority.certificate, chain),
+        None => authority.certificate.clone(),
+    }
+}
+
+fn subject_alt_names(request: &IssueCertificateRequest) -> Result<Vec<SanType>, String> {
+    let mut names = Vec::new();
+    for name in &request.dns_names {
+        names.push(SanType::DnsName(name.as_str().try_into().map_err(|_| format!("Invalid DNS name: {}", name))?));
+    }
+    names.extend(request.ip_addresses.iter().copied().map(SanType::IpAddress));
+    for address in &request.email_addresses {
+        names.push(SanType::Rfc822Name(
+            address.as_str().try_into().map_err(|_| format!("Invalid email address: {}", address))?,
+        ));
+    }
+    Ok(names)
+}
+
+// OCSP应答中证书记录对应的状态
+fn certificate_status(certificate: Option<IssuedCertificate>) -> Result<CertificateStatus, Box<dyn Error>> {
+    Ok(match certificate {
+        Some(certificate) if certificate.revoked_at.is_some() => CertificateStatus::Revoked(revoked_entry(certificate)?),
+        Some(_) => CertificateStatus::Good,
+        None => CertificateStatus::Unknown,
+    })
+}
+
+fn revoked_entry(certificate: IssuedCertificate) -> Result<RevokedCertificate, Box<dyn Error>> {
+    Ok(RevokedCertificate {
+        serial_number: certificate.serial_number,
+        revoked_at: certificate.revoked_at.ok_or("Certificate is not revoked")?,
+        reason: certificate.revocation_reason.as_deref().unwrap_or("unspecified").parse()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issued(revoked_at: Option<chrono::DateTime<chrono::Utc>>, reason: Option<&str>) -> IssuedCertificate {
+        let now = chrono::Utc::now();
+        IssuedCertificate {
+            id: 1,
+            ca_id: 1,
+            serial_number: "1f2e3d".to_string(),
+            subject: "CN=service.example.com".to_string(),
+            profile: "server".to_string(),
+            certificate: String::new(),
+            key_id: None,
+            not_before: now,
+            not_after: now + chrono::Duration::days(1),
+            revoked_at,
+            revocation_reason: reason.map(str::to_string),
+            created_at: now,
+        }
+    }
+
+    fn request(dns_names: &[&str], email_addresses: &[&str]) -> IssueCertificateRequest {
+        IssueCertificateRequest {
+            profile: CertificateProfile::Server,
+            csr: None,
+            common_name: Some("service.example.com".to_string()),
+            organization: None,
+            dns_names: dns_names.iter().map(|name| name.to_string()).collect(),
+            ip_addresses: vec!["127.0.0.1".parse().unwrap()],
+            email_addresses: email_addresses.iter().map(|address| address.to_string()).collect(),
+            validity_days: None,
+            key_algorithm: Default::default(),
+            key_name: None,
+        }
+    }
+
+    #[test]
+    fn ocsp_status_follows_the_certificate_record() {
+        assert!(matches!(certificate_status(None), Ok(CertificateStatus::Unknown)));
+        assert!(matches!(certificate_status(Some(issued(None, None))), Ok(CertificateStatus::Good)));
+
+        let revoked_at = chrono::Utc::now();
+        match certificate_status(Some(issued(Some(revoked_at), Some("key_compromise")))) {
+            Ok(CertificateStatus::Revoked(revoked)) => {
+                assert_eq!(revoked.serial_number, "1f2e3d");
+                assert_eq!(revoked.revoked_at, revoked_at);
+                assert_eq!(revoked.reason, RevocationReason::KeyCompromise);
+            }
+            other => panic!("expected a revoked status, got {:?}", other.map_err(|e| e.to_string())),
+        }
+
+        // 未记录原因时按unspecified处理，无法识别的原因视为数据错误
+        match certificate_status(Some(issued(Some(revoked_at), None))) {
+            Ok(CertificateStatus::Revoked(revoked)) => assert_eq!(revoked.reason, RevocationReason::Unspecified),
+            other => panic!("expected a revoked status, got {:?}", other.map_err(|e| e.to_string())),
+        }
+        assert!(certificate_status(Some(issued(Some(revoked_at), Some("bogus")))).is_err());
+    }
+
+    #[test]
+    fn subject_alt_names_validate_each_name() {
+        let names = subject_alt_names(&request(&["service.example.com"], &["ops@example.com"])).unwrap();
+        assert_eq!(names.len(), 3);
+
+        let error = subject_alt_names(&request(&["例子.example.com"], &[])).unwrap_err();
+        assert_eq!(error, "Invalid DNS name: 例子.example.com");
+        let error = subject_alt_names(&request(&[], &["运维@example.com"])).unwrap_err();
+        assert_eq!(error, "Invalid email address: 运维@example.com");
+    }
+}
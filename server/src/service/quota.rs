@@ -0,0 +1,212 @@
+use crate::model::access::Identity;
+use crate::model::quota::{
+    CallerUsageResponse, QuotaExceeded, QuotaKind, QuotaLimits, QuotaResponse, QuotaSubjectType, QuotaUsage,
+};
+use crate::repository::quota as quota_repository;
+use crate::utils::metrics::METRICS;
+use crate::utils::rate_limit::REQUEST_COUNTER;
+use shared::secret::SecretPayload;
+use sqlx::{MySqlConnection, MySqlPool};
+use std::error::Error;
+use std::net::IpAddr;
+
+// 调用方适用的配额对象及各自生效的限制
+type Subjects = Vec<(QuotaSubjectType, String, QuotaLimits)>;
+
+// 服务端默认配额，只作用于没有单独配置的身份；未设置或为0时不限制
+fn default_limits() -> QuotaLimits {
+    fn limit<T: std::str::FromStr + Default + PartialEq>(name: &str) -> Option<T> {
+        std::env::var(name)
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .filter(|value| *value != T::default())
+    }
+
+    QuotaLimits {
+        max_keys: limit("QUOTA_DEFAULT_MAX_KEYS"),
+        max_data_bytes: limit("QUOTA_DEFAULT_MAX_DATA_BYTES"),
+        requests_per_minute: limit("QUOTA_DEFAULT_REQUESTS_PER_MINUTE"),
+    }
+}
+
+/// 校验配额对象：身份为数字id，IP统一为标准格式
+pub fn parse_subject(subject_type: QuotaSubjectType, subject: &str) -> Result<String, String> {
+    match subject_type {
+        QuotaSubjectType::Identity => subject
+            .parse::<u64>()
+            .map(|id| id.to_string())
+            .map_err(|_| "Identity quota subject must be an identity id".to_string()),
+        QuotaSubjectType::Ip => subject
+            .parse::<IpAddr>()
+            .map(|ip| ip.to_string())
+            .map_err(|_| "IP quota subject must be an IP address".to_string()),
+    }
+}
+
+// 身份总是适用（无单独配置时使用默认值）；来源IP只在单独配置时适用
+async fn subjects(conn: &mut MySqlConnection, caller: &Identity) -> Result<Subjects, sqlx::Error> {
+    let identity = caller.id.to_string();
+    let identity_limits = quota_repository::get_quota(&mut *conn, QuotaSubjectType::Identity, &identity)
+        .await?
+        .map_or_else(default_limits, |record| QuotaLimits::from(&record));
+    let mut subjects = vec![(QuotaSubjectType::Identity, identity, identity_limits)];
+
+    if let Some(ip) = caller.source_ip.map(|ip| ip.to_string()) {
+        if let Some(record) = quota_repository::get_quota(&mut *conn, QuotaSubjectType::Ip, &ip).await? {
+            subjects.push((QuotaSubjectType::Ip, ip, QuotaLimits::from(&record)));
+        }
+    }
+
+    Ok(subjects)
+}
+
+fn exceeded(kind: QuotaKind, subject_type: QuotaSubjectType, subject: &str, limit: u64) -> QuotaExceeded {
+    METRICS.record_quota_exceeded(kind);
+    tracing::warn!(quota = %kind, %subject_type, subject, limit, "Quota exceeded");
+
+    QuotaExceeded {
+        kind,
+        subject_type,
+        subject: subject.to_string(),
+        limit,
+        retry_after_secs: None,
+    }
+}
+
+/// 计入一次请求并检查每分钟请求数，认证成功后对所有接口调用
+pub async fn check_request(pool: &MySqlPool, caller: &Identity) -> Result<(), Box<dyn Error>> {
+    let subjects = subjects(&mut *pool.acquire().await?, caller).await?;
+
+    for (subject_type, subject, limits) in subjects {
+        let (count, remaining) = REQUEST_COUNTER.record(subject_type, &subject);
+        if let Some(limit) = limits.requests_per_minute.filter(|limit| count > *limit) {
+            let mut error = exceeded(QuotaKind::RequestsPerMinute, subject_type, &subject, limit.into());
+            error.retry_after_secs = Some(remaining.as_secs().max(1));
+            return Err(error.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// 记录新密钥的创建者并检查密钥数配额，须在创建密钥的事务中调用；超出时调用方回滚事务
+///
+/// 先锁定有密钥数限制的配额对象再计数，同一对象的并发创建不会同时通过检查。
+pub async fn claim_key(conn: &mut MySqlConnection, key_id: u64, owner: &Identity) -> Result<(), Box<dyn Error>> {
+    let subjects = subjects(&mut *conn, owner).await?;
+    for (subject_type, subject, limits) in &subjects {
+        if limits.max_keys.is_some() {
+            quota_repository::lock_subject(&mut *conn, *subject_type, subject).await?;
+        }
+    }
+
+    let source_ip = owner.source_ip.map(|ip| ip.to_string());
+    quota_repository::insert_creator(&mut *conn, key_id, owner.id, source_ip.as_deref()).await?;
+
+    for (subject_type, subject, limits) in subjects {
+        let Some(limit) = limits.max_keys else {
+            continue;
+        };
+        if quota_repository::count_keys(&mut *conn, subject_type, &subject).await? > limit {
+            return Err(exceeded(QuotaKind::MaxKeys, subject_type, &subject, limit).into());
+        }
+    }
+
+    Ok(())
+}
+
+/// 检查写入的data或payload大小
+pub async fn check_data_size(pool: &MySqlPool, caller: &Identity, size: usize) -> Result<(), Box<dyn Error>> {
+    let subjects = subjects(&mut *pool.acquire().await?, caller).await?;
+
+    for (subject_type, subject, limits) in subjects {
+        if let Some(limit) = limits.max_data_bytes.filter(|limit| size as u64 > *limit) {
+            return Err(exceeded(QuotaKind::MaxDataBytes, subject_type, &subject, limit).into());
+        }
+    }
+
+    Ok(())
+}
+
+/// 计入配额的数据大小：data的字节数或payload序列化后的字节数
+pub fn data_size(data: Option<&str>, payload: Option<&SecretPayload>) -> Result<usize, Box<dyn Error>> {
+    let mut size = data.map_or(0, str::len);
+    if let Some(payload) = payload {
+        size += serde_json::to_string(payload)?.len();
+    }
+    Ok(size)
+}
+
+async fn usage(
+    conn: &mut MySqlConnection,
+    subject_type: QuotaSubjectType,
+    subject: String,
+    limits: QuotaLimits,
+) -> Result<QuotaUsage, sqlx::Error> {
+    Ok(QuotaUsage {
+        keys: quota_repository::count_keys(&mut *conn, subject_type, &subject).await?,
+        requests_this_minute: REQUEST_COUNTER.current(subject_type, &subject),
+        subject_type,
+        subject,
+        limits,
+    })
+}
+
+/// 调用方自身及其来源IP的配额用量
+pub async fn caller_usage(pool: &MySqlPool, caller: &Identity) -> Result<CallerUsageResponse, Box<dyn Error>> {
+    let mut conn = pool.acquire().await?;
+    let mut identity = None;
+    let mut ip = None;
+
+    for (subject_type, subject, limits) in subjects(&mut conn, caller).await? {
+        let subject_usage = usage(&mut conn, subject_type, subject, limits).await?;
+        match subject_type {
+            QuotaSubjectType::Identity => identity = Some(subject_usage),
+            QuotaSubjectType::Ip => ip = Some(subject_usage),
+        }
+    }
+
+    Ok(CallerUsageResponse {
+        identity: identity.ok_or("Identity quota missing")?,
+        ip,
+    })
+}
+
+/// 任意配额对象的用量，身份没有单独配置时显示默认限制
+pub async fn subject_usage(
+    pool: &MySqlPool,
+    subject_type: QuotaSubjectType,
+    subject: &str,
+) -> Result<QuotaUsage, Box<dyn Error>> {
+    let mut conn = pool.acquire().await?;
+    let limits = match quota_repository::get_quota(&mut conn, subject_type, subject).await? {
+        Some(record) => QuotaLimits::from(&record),
+        None if subject_type == QuotaSubjectType::Identity => default_limits(),
+        None => QuotaLimits::default(),
+    };
+
+    Ok(usage(&mut conn, subject_type, subject.to_string(), limits).await?)
+}
+
+pub async fn set_quota(
+    pool: &MySqlPool,
+    subject_type: QuotaSubjectType,
+    subject: &str,
+    limits: QuotaLimits,
+) -> Result<QuotaResponse, Box<dyn Error>> {
+    quota_repository::upsert_quota(pool, subject_type, subject, &limits).await?;
+    let record = quota_repository::get_quota(&mut *pool.acquire().await?, subject_type, subject)
+        .await?
+        .ok_or("Failed to retrieve quota")?;
+
+    Ok(record.try_into()?)
+}
+
+pub async fn list_quotas(pool: &MySqlPool) -> Result<Vec<QuotaResponse>, Box<dyn Error>> {
+    let quotas = quota_repository::list_quotas(pool).await?;
+    Ok(quotas.into_iter().map(QuotaResponse::try_from).collect::<Result<_, _>>()?)
+}
+
+pub async fn delete_quota(pool: &MySqlPool, subject_type: QuotaSubjectType, subject: &str) -> Result<bool, Box<dyn Error>> {
+    Ok(quota_repository::delete_quota(pool, subject_type, subject).await?)
+}
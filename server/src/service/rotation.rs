@@ -0,0 +1,167 @@
+use crate::model::audit::NewAuditEvent;
+use crate::model::event::KeyEventType;
+use crate::model::key::KeyMaterialResponse;
+use crate::model::rotation::{DueRotation, KeyVersion, RotationPolicy, RotationTrigger};
+use crate::repository::{self, rotation as rotation_repository};
+use crate::service::{audit as audit_service, ensure_server_encrypted, ensure_untyped, event as event_service};
+use crate::utils::encryption::{decrypt_data, encrypt_data};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64_ENGINE};
+use sqlx::MySqlPool;
+use std::error::Error;
+use std::time::Duration;
+
+// 自动生成的新密钥值字节数
+const GENERATED_KEY_SIZE: usize = 32;
+// 默认调度检查间隔（秒）
+const DEFAULT_CHECK_INTERVAL_SECS: u64 = 3600;
+
+/// 设置或取消密钥的轮换策略
+pub async fn set_policy(
+    pool: &MySqlPool,
+    key_id: u64,
+    interval_days: Option<u32>,
+) -> Result<Option<RotationPolicy>, Box<dyn Error>> {
+    match interval_days {
+        Some(0) => Err("Rotation interval must be at least one day".into()),
+        Some(days) => {
+            let key = repository::get_key_by_id(pool, key_id).await?.ok_or("Key not found")?;
+            ensure_server_encrypted(&key)?;
+            ensure_untyped(&key)?;
+
+            rotation_repository::upsert_policy(pool, key_id, days).await?;
+            Ok(rotation_repository::get_policy(pool, key_id).await?)
+        }
+        None => {
+            rotation_repository::delete_policy(pool, key_id).await?;
+            Ok(None)
+        }
+    }
+}
+
+pub async fn get_policy(pool: &MySqlPool, key_id: u64) -> Result<Option<RotationPolicy>, Box<dyn Error>> {
+    Ok(rotation_repository::get_policy(pool, key_id).await?)
+}
+
+/// 列出团队内即将到期的轮换
+pub async fn list_due(pool: &MySqlPool, team_id: u64, within_days: u32) -> Result<Vec<DueRotation>, Box<dyn Error>> {
+    Ok(rotation_repository::list_due(pool, Some(team_id), within_days).await?)
+}
+
+/// 轮换密钥：生成新值，旧值作为历史版本保留仅供解密
+///
+/// 历史版本沿用keys.version（即ETag）计数：旧值以轮换前的版本号保存，新值的版本号加一。
+/// 返回被保留的旧版本号。
+pub async fn rotate_key(
+    pool: &MySqlPool,
+    key_id: u64,
+    trigger: RotationTrigger,
+    encryption_key: &str,
+) -> Result<u32, Box<dyn Error>> {
+    let key = repository::get_key_by_id(pool, key_id).await?.ok_or("Key not found")?;
+    ensure_server_encrypted(&key)?;
+    ensure_untyped(&key)?;
+
+    let mut bytes = [0u8; GENERATED_KEY_SIZE];
+    getrandom::getrandom(&mut bytes)?;
+    let new_encrypted_data = encrypt_data(&BASE64_ENGINE.encode(bytes), encryption_key)?;
+
+    let mut tx = pool.begin().await?;
+    let (old_encrypted_data, version) = rotation_repository::lock_key_data(&mut tx, key_id)
+        .await?
+        .ok_or("Key not found")?;
+
+    rotation_repository::insert_version(&mut tx, key_id, version, &old_encrypted_data, trigger.as_str()).await?;
+    rotation_repository::update_key_data(&mut tx, key_id, &new_encrypted_data).await?;
+    rotation_repository::mark_rotated(&mut tx, key_id).await?;
+    tx.commit().await?;
+
+    let current_version = repository::get_key_by_id(pool, key_id).await?.and_then(|key| key.version);
+    event_service::publish(pool, KeyEventType::Updated, key_id, &key.name, current_version, &[]).await;
+
+    Ok(version)
+}
+
+pub async fn list_versions(pool: &MySqlPool, key_id: u64) -> Result<Vec<KeyVersion>, Box<dyn Error>> {
+    Ok(rotation_repository::list_versions(pool, key_id).await?)
+}
+
+/// 解密某个历史版本的密钥值
+pub async fn get_version_material(
+    pool: &MySqlPool,
+    key_id: u64,
+    version: u32,
+    encryption_key: &str,
+) -> Result<Option<KeyMaterialResponse>, Box<dyn Error>> {
+    let Some(record) = rotation_repository::get_version(pool, key_id, version).await? else {
+        return Ok(None);
+    };
+    let key = repository::get_key_by_id(pool, key_id).await?.ok_or("Key not found")?;
+
+    Ok(Some(KeyMaterialResponse {
+        id: record.key_id,
+        name: key.name,
+        data: Some(decrypt_data(&record.encrypted_data, encryption_key)?),
+        payload: None,
+        vault_id: None,
+        nonce: None,
+    }))
+}
+
+/// 启动后台轮换调度任务
+pub fn spawn_scheduler(pool: &'static MySqlPool) {
+    let interval_secs = std::env::var("ROTATION_CHECK_INTERVAL_SECS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_CHECK_INTERVAL_SECS);
+    // tokio的interval不接受0周期
+    if interval_secs == 0 {
+        tracing::warn!("ROTATION_CHECK_INTERVAL_SECS is 0, checking every second instead");
+    }
+    let interval_secs = interval_secs.max(1);
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            if let Err(e) = run_due_rotations(pool).await {
+                tracing::error!(error = %e, "Scheduled key rotation failed");
+            }
+        }
+    });
+}
+
+/// 轮换所有已到期的密钥
+pub async fn run_due_rotations(pool: &MySqlPool) -> Result<(), Box<dyn Error>> {
+    let encryption_key = std::env::var("ENCRYPTION_KEY").map_err(|_| "Encryption key not configured")?;
+
+    for due in rotation_repository::list_due(pool, None, 0).await? {
+        let result = rotate_key(pool, due.key_id, RotationTrigger::Scheduled, &encryption_key).await;
+
+        let (outcome, status_code) = match &result {
+            Ok(version) => {
+                tracing::info!(key_id = due.key_id, retired_version = version, "Key rotated");
+                ("success", 200)
+            }
+            Err(e) => {
+                tracing::error!(key_id = due.key_id, error = %e, "Failed to rotate key");
+                ("failure", 500)
+            }
+        };
+
+        let event = NewAuditEvent {
+            actor_id: None,
+            actor_name: Some("rotation-scheduler".to_string()),
+            action: "key.rotate".to_string(),
+            key_id: Some(due.key_id),
+            outcome: outcome.to_string(),
+            status_code,
+            source_ip: None,
+            request_id: format!("rotation-{}-{}", due.key_id, chrono::Utc::now().timestamp()),
+        };
+        if let Err(e) = audit_service::record(pool, event).await {
+            tracing::error!(error = %e, "Failed to record audit event");
+        }
+    }
+
+    Ok(())
+}
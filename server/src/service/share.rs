@@ -0,0 +1,114 @@
+use crate::model::access::{Identity, Role};
+use crate::model::share::{CreateShareRequest, PublicKeyResponse, ShareResponse};
+use crate::repository::{self, share as share_repository, team as team_repository};
+use crate::service::ensure_server_encrypted;
+use crate::utils::encryption::decrypt_data;
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64_ENGINE};
+use shared::crypto::sealed::{self, PUBLIC_KEY_SIZE};
+use sqlx::MySqlPool;
+use std::error::Error;
+
+/// 设置调用方的X25519公钥
+pub async fn set_public_key(
+    pool: &MySqlPool,
+    identity: &Identity,
+    public_key: &str,
+) -> Result<PublicKeyResponse, Box<dyn Error>> {
+    // 校验公钥格式
+    decode_public_key(public_key)?;
+
+    share_repository::set_public_key(pool, identity.id, public_key).await?;
+
+    Ok(PublicKeyResponse {
+        identity_id: identity.id,
+        public_key: public_key.to_string(),
+    })
+}
+
+pub async fn get_public_key(
+    pool: &MySqlPool,
+    identity_id: u64,
+) -> Result<Option<PublicKeyResponse>, Box<dyn Error>> {
+    let public_key = share_repository::get_public_key(pool, identity_id).await?;
+
+    Ok(public_key.map(|public_key| PublicKeyResponse { identity_id, public_key }))
+}
+
+/// 将密钥内容用接收方公钥重新封装后共享
+pub async fn share_key(
+    pool: &MySqlPool,
+    sharer: &Identity,
+    key_id: u64,
+    request: CreateShareRequest,
+    encryption_key: &str,
+) -> Result<ShareResponse, Box<dyn Error>> {
+    if request.expires_at.is_some_and(|expires_at| expires_at <= chrono::Utc::now()) {
+        return Err("Share expiry must be in the future".into());
+    }
+
+    let key = repository::get_key_by_id(pool, key_id)
+        .await?
+        .ok_or("Key not found")?;
+    ensure_server_encrypted(&key)?;
+
+    // 只能分享给密钥所属团队的成员，其他团队的身份按不存在处理
+    let team_id = key.team_id.ok_or("Key row without team")?;
+    if !team_repository::is_member(pool, team_id, request.recipient_id).await? {
+        return Err("Recipient is not a member of the key's team".into());
+    }
+
+    let public_key = share_repository::get_public_key(pool, request.recipient_id)
+        .await?
+        .ok_or("Recipient has no public key registered")?;
+    let public_key = decode_public_key(&public_key)?;
+
+    // 解密后立即用接收方公钥封装，服务端不保存可被自身解开的副本
+    let data = decrypt_data(&key.encrypted_data, encryption_key)?;
+    let sealed = sealed::seal(&public_key, data.as_bytes()).ok_or("Failed to seal key for recipient")?;
+
+    let share_id = share_repository::create_share(
+        pool,
+        key_id,
+        sharer.id,
+        request.recipient_id,
+        &BASE64_ENGINE.encode(sealed.to_bytes()),
+        request.expires_at,
+    )
+    .await?;
+
+    let share = share_repository::get_share_by_id(pool, share_id)
+        .await?
+        .ok_or("Failed to retrieve created share")?;
+
+    Ok(share)
+}
+
+pub async fn get_share(pool: &MySqlPool, id: u64) -> Result<Option<ShareResponse>, Box<dyn Error>> {
+    Ok(share_repository::get_share_by_id(pool, id).await?)
+}
+
+pub async fn list_received(pool: &MySqlPool, identity: &Identity) -> Result<Vec<ShareResponse>, Box<dyn Error>> {
+    Ok(share_repository::list_received_shares(pool, identity.id).await?)
+}
+
+pub async fn list_sent(pool: &MySqlPool, identity: &Identity) -> Result<Vec<ShareResponse>, Box<dyn Error>> {
+    Ok(share_repository::list_sent_shares(pool, identity.id).await?)
+}
+
+/// 只有共享发起人或管理员可以撤销共享
+pub fn can_revoke(identity: &Identity, share: &ShareResponse) -> bool {
+    identity.role == Role::Admin || share.sharer_id == identity.id
+}
+
+pub async fn revoke(pool: &MySqlPool, id: u64) -> Result<(), Box<dyn Error>> {
+    share_repository::revoke_share(pool, id).await?;
+    Ok(())
+}
+
+fn decode_public_key(public_key: &str) -> Result<[u8; PUBLIC_KEY_SIZE], Box<dyn Error>> {
+    let bytes = BASE64_ENGINE.decode(public_key)?;
+    let bytes: [u8; PUBLIC_KEY_SIZE] = bytes
+        .try_into()
+        .map_err(|_| "Public key must be 32 bytes")?;
+    Ok(bytes)
+}
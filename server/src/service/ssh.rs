@@ -0,0 +1,338 @@
+use crate::model::access::Identity;
+use crate::model::key::CreateKeyRequest;
+use crate::model::pki::CaOperation;
+use crate::model::ssh::{
+    CreateSshCaRequest, GenerateSshKeyRequest, GeneratedSshKeyResponse, NewSshCertificate, NewSshCertificateAuthority,
+    SignSshKeyRequest, SshCaResponse, SshCertificateAuthority, SshCertificateListQuery, SshCertificateResponse,
+    SshCertificateType,
+};
+use crate::repository::{self, label as label_repository, ssh as ssh_repository};
+use crate::service::{access as access_service, quota as quota_service};
+use crate::utils::encryption::decrypt_data;
+use crate::utils::keypair::PrivateKey;
+use crate::utils::ssh::{self, CertificateSpec, SshPublicKey, CRITICAL_OPTIONS, DEFAULT_USER_EXTENSIONS};
+use rand_core::{OsRng, RngCore};
+use shared::secret::SecretPayload;
+use sqlx::MySqlPool;
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+
+use super::{announce_created, prepare_key};
+
+// CA默认和最长的单张证书有效期（秒）
+const DEFAULT_MAX_VALIDITY_SECS: u64 = 24 * 3600;
+const MAX_VALIDITY_SECS: u64 = 366 * 24 * 3600;
+// 未指定时签发证书的有效期（秒）
+const DEFAULT_VALIDITY_SECS: u64 = 3600;
+// 证书生效时间提前量，容忍服务器的时钟偏差
+const BACKDATE_SECS: i64 = 300;
+// 单张证书的最大principal数
+const MAX_PRINCIPALS: usize = 256;
+// 单次列出的最大证书数
+const MAX_LIST_LIMIT: u32 = 200;
+// 保存CA私钥的密钥名称前缀及标签
+const CA_KEY_PREFIX: &str = "ssh/";
+const CA_LABEL: &str = "ssh.ca";
+
+/// 创建SSH CA，CA私钥以ssh_key类型的密钥保存并归创建者所有
+pub async fn create_ca(
+    pool: &MySqlPool,
+    owner: &Identity,
+    request: CreateSshCaRequest,
+    encryption_key: &str,
+) -> Result<CaOperation<SshCaResponse>, Box<dyn Error>> {
+    if request.name.trim().is_empty() {
+        return Ok(CaOperation::Invalid("Name is required".to_string()));
+    }
+    let max_validity_secs = request.max_validity_secs.unwrap_or(DEFAULT_MAX_VALIDITY_SECS);
+    if max_validity_secs == 0 || max_validity_secs > MAX_VALIDITY_SECS {
+        return Ok(CaOperation::Invalid(format!("max_validity_secs must be between 1 and {}", MAX_VALIDITY_SECS)));
+    }
+    if let Some(option) = request
+        .allowed_critical_options
+        .iter()
+        .flatten()
+        .find(|option| !CRITICAL_OPTIONS.contains(&option.as_str()))
+    {
+        return Ok(CaOperation::Invalid(format!("Unknown critical option: {}", option)));
+    }
+
+    let private_key_pem = ssh::generate_key(request.key_algorithm)?;
+    let private_key = PrivateKey::from_pem(&private_key_pem)?;
+    let public_key = ssh::public_key(&private_key, Some(format!("ecipher-ssh-ca-{}", request.name)))?.to_line();
+
+    let key_request = CreateKeyRequest {
+        name: format!("{}{}", CA_KEY_PREFIX, request.name),
+        data: None,
+        payload: Some(SecretPayload::SshKey {
+            private_key: private_key_pem,
+            public_key: public_key.clone(),
+            passphrase: None,
+        }),
+        expires_at: None,
+        description: Some(format!("Private key of SSH CA {}", request.name)),
+        labels: BTreeMap::from([(CA_LABEL.to_string(), request.name.clone())]),
+        metadata: None,
+    };
+    let (key, labels) = prepare_key(key_request, owner, encryption_key)?;
+
+    let mut tx = pool.begin().await?;
+    let key_id = match repository::create_key(&mut *tx, &key).await {
+        Ok(key_id) => key_id,
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            return Ok(CaOperation::Conflict("SSH CA key name already exists".to_string()));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    label_repository::insert_labels(&mut *tx, key_id, &labels).await?;
+    access_service::insert_owner_grants(&mut *tx, key_id, owner).await?;
+    quota_service::claim_key(&mut *tx, key_id, owner).await?;
+    let authority = NewSshCertificateAuthority {
+        name: request.name,
+        key_id,
+        public_key,
+        allow_user_certificates: request.allow_user_certificates.unwrap_or(true),
+        allow_host_certificates: request.allow_host_certificates.unwrap_or(false),
+        max_validity_secs,
+        allowed_principals: request.allowed_principals,
+        allowed_extensions: request.allowed_extensions,
+        allowed_critical_options: request.allowed_critical_options,
+    };
+    let ca_id = match ssh_repository::insert_authority(&mut *tx, &authority).await {
+        Ok(ca_id) => ca_id,
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            return Ok(CaOperation::Conflict("SSH CA name already exists".to_string()));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    tx.commit().await?;
+
+    let created_key = repository::get_key_by_id(pool, key_id).await?.ok_or("Failed to retrieve created key")?;
+    announce_created(pool, created_key).await?;
+
+    let authority = ssh_repository::get_authority(pool, ca_id).await?.ok_or("Failed to retrieve created SSH CA")?;
+    Ok(CaOperation::Done(authority.into()))
+}
+
+pub async fn get_ca(pool: &MySqlPool, ca_id: u64) -> Result<Option<SshCertificateAuthority>, Box<dyn Error>> {
+    Ok(ssh_repository::get_authority(pool, ca_id).await?)
+}
+
+pub async fn list_cas(pool: &MySqlPool) -> Result<Vec<SshCaResponse>, Box<dyn Error>> {
+    Ok(ssh_repository::list_authorities(pool).await?.into_iter().map(SshCaResponse::from).collect())
+}
+
+/// 将公钥签名为SSH证书，证书内容须满足CA的限制
+pub async fn sign_key(
+    pool: &MySqlPool,
+    identity: &Identity,
+    ca_id: u64,
+    request: SignSshKeyRequest,
+    encryption_key: &str,
+) -> Result<CaOperation<SshCertificateResponse>, Box<dyn Error>> {
+    let Some(authority) = ssh_repository::get_authority(pool, ca_id).await? else {
+        return Ok(CaOperation::NotFound);
+    };
+    let subject = match SshPublicKey::parse(&request.public_key) {
+        Ok(subject) => subject,
+        Err(message) => return Ok(CaOperation::Invalid(message)),
+    };
+
+    let certificate_type = request.certificate_type;
+    let allowed = match certificate_type {
+        SshCertificateType::User => authority.allow_user_certificates,
+        SshCertificateType::Host => authority.allow_host_certificates,
+    };
+    if !allowed {
+        return Ok(CaOperation::Invalid(format!("CA does not issue {} certificates", certificate_type)));
+    }
+
+    // 空principal列表表示任意用户或主机，必须显式给出
+    if request.principals.is_empty() || request.principals.len() > MAX_PRINCIPALS {
+        return Ok(CaOperation::Invalid(format!("Between 1 and {} principals are required", MAX_PRINCIPALS)));
+    }
+    if let Some(patterns) = &authority.allowed_principals {
+        if let Some(principal) = request.principals.iter().find(|principal| !principal_allowed(&patterns.0, principal)) {
+            return Ok(CaOperation::Invalid(format!("Principal not allowed by CA: {}", principal)));
+        }
+    }
+
+    let validity_secs = request.validity_secs.unwrap_or(DEFAULT_VALIDITY_SECS.min(authority.max_validity_secs));
+    if validity_secs == 0 || validity_secs > authority.max_validity_secs {
+        return Ok(CaOperation::Invalid(format!(
+            "validity_secs must be between 1 and {}",
+            authority.max_validity_secs
+        )));
+    }
+    let now = chrono::Utc::now();
+    let start = request.valid_after.unwrap_or(now);
+    if start < now - chrono::Duration::seconds(BACKDATE_SECS) {
+        return Ok(CaOperation::Invalid("valid_after must not be in the past".to_string()));
+    }
+    let valid_after = start - chrono::Duration::seconds(BACKDATE_SECS);
+    let valid_before = start + chrono::Duration::seconds(validity_secs as i64);
+
+    // 主机证书不使用关键选项和扩展
+    let (critical_options, extensions) = match certificate_type {
+        SshCertificateType::Host => {
+            if !request.critical_options.is_empty() || request.extensions.as_ref().is_some_and(|e| !e.is_empty()) {
+                return Ok(CaOperation::Invalid("Host certificates take no critical options or extensions".to_string()));
+            }
+            (BTreeMap::new(), BTreeSet::new())
+        }
+        SshCertificateType::User => {
+            let extensions: BTreeSet<String> = match request.extensions {
+                Some(extensions) => extensions.into_iter().collect(),
+                None => DEFAULT_USER_EXTENSIONS.iter().map(|extension| extension.to_string()).collect(),
+            };
+            if let Some(name) = request.critical_options.keys().find(|name| {
+                !CRITICAL_OPTIONS.contains(&name.as_str())
+                    || authority.allowed_critical_options.as_ref().is_some_and(|allowed| !allowed.0.contains(name))
+            }) {
+                return Ok(CaOperation::Invalid(format!("Critical option not allowed: {}", name)));
+            }
+            if let Some(name) = extensions.iter().find(|name| {
+                authority.allowed_extensions.as_ref().is_some_and(|allowed| !allowed.0.contains(name))
+            }) {
+                return Ok(CaOperation::Invalid(format!("Extension not allowed: {}", name)));
+            }
+            (request.critical_options, extensions)
+        }
+    };
+
+    // 序列号随机生成，0保留给未指定序列号的证书
+    let serial = OsRng.next_u64().max(1);
+    let key_identifier = request.key_identifier.unwrap_or_else(|| format!("{}/{}", authority.name, serial));
+    let ca_key = load_ca_key(pool, &authority, encryption_key).await?;
+    let spec = CertificateSpec {
+        serial,
+        certificate_type,
+        key_identifier: &key_identifier,
+        principals: &request.principals,
+        valid_after,
+        valid_before,
+        critical_options: &critical_options,
+        extensions: &extensions,
+    };
+    let certificate = ssh::sign_certificate(&ca_key, &subject, &spec)?;
+
+    let record = NewSshCertificate {
+        ca_id,
+        serial,
+        certificate_type,
+        key_identifier,
+        principals: request.principals,
+        public_key_fingerprint: subject.fingerprint(),
+        certificate,
+        valid_after,
+        valid_before,
+        issued_by: identity.id,
+    };
+    ssh_repository::insert_certificate(pool, &record).await?;
+
+    let certificate = ssh_repository::get_certificate(pool, ca_id, serial)
+        .await?
+        .ok_or("Failed to retrieve issued SSH certificate")?;
+    Ok(CaOperation::Done(certificate.try_into()?))
+}
+
+/// 列出CA签发的证书，CA不存在时返回None
+pub async fn list_certificates(
+    pool: &MySqlPool,
+    ca_id: u64,
+    query: SshCertificateListQuery,
+) -> Result<Option<Vec<SshCertificateResponse>>, Box<dyn Error>> {
+    if ssh_repository::get_authority(pool, ca_id).await?.is_none() {
+        return Ok(None);
+    }
+
+    let limit = query.limit.unwrap_or(50).clamp(1, MAX_LIST_LIMIT);
+    let certificates = ssh_repository::list_certificates(pool, ca_id, query.after_id.unwrap_or(0), limit).await?;
+    let responses = certificates
+        .into_iter()
+        .map(SshCertificateResponse::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Some(responses))
+}
+
+pub async fn get_certificate(
+    pool: &MySqlPool,
+    ca_id: u64,
+    serial: u64,
+) -> Result<Option<SshCertificateResponse>, Box<dyn Error>> {
+    let certificate = ssh_repository::get_certificate(pool, ca_id, serial).await?;
+    Ok(certificate.map(SshCertificateResponse::try_from).transpose()?)
+}
+
+/// 生成SSH密钥对，保存为归调用方所有的ssh_key类型密钥
+pub async fn generate_key(
+    pool: &MySqlPool,
+    owner: &Identity,
+    request: GenerateSshKeyRequest,
+    encryption_key: &str,
+) -> Result<CaOperation<GeneratedSshKeyResponse>, Box<dyn Error>> {
+    let private_key_pem = ssh::generate_key(request.algorithm)?;
+    let private_key = PrivateKey::from_pem(&private_key_pem)?;
+    let comment = request.comment.unwrap_or_else(|| request.name.clone());
+    let public_key = ssh::public_key(&private_key, Some(comment))?;
+
+    let key_request = CreateKeyRequest {
+        name: request.name.clone(),
+        data: None,
+        payload: Some(SecretPayload::SshKey {
+            private_key: private_key_pem,
+            public_key: public_key.to_line(),
+            passphrase: None,
+        }),
+        expires_at: None,
+        description: request.description,
+        labels: request.labels,
+        metadata: None,
+    };
+    let (key, labels) = match prepare_key(key_request, owner, encryption_key) {
+        Ok(prepared) => prepared,
+        Err(e) => return Ok(CaOperation::Invalid(e.to_string())),
+    };
+
+    let mut tx = pool.begin().await?;
+    let key_id = match repository::create_key(&mut *tx, &key).await {
+        Ok(key_id) => key_id,
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            return Ok(CaOperation::Conflict("Key name already exists".to_string()));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    label_repository::insert_labels(&mut *tx, key_id, &labels).await?;
+    access_service::insert_owner_grants(&mut *tx, key_id, owner).await?;
+    quota_service::claim_key(&mut *tx, key_id, owner).await?;
+    tx.commit().await?;
+
+    let created_key = repository::get_key_by_id(pool, key_id).await?.ok_or("Failed to retrieve created key")?;
+    announce_created(pool, created_key).await?;
+
+    Ok(CaOperation::Done(GeneratedSshKeyResponse {
+        key_id,
+        name: request.name,
+        public_key: public_key.to_line(),
+        fingerprint: public_key.fingerprint(),
+    }))
+}
+
+// 读取CA私钥
+async fn load_ca_key(pool: &MySqlPool, authority: &SshCertificateAuthority, encryption_key: &str) -> Result<PrivateKey, Box<dyn Error>> {
+    let key = repository::get_key_by_id(pool, authority.key_id).await?.ok_or("SSH CA key not found")?;
+    let plaintext = decrypt_data(&key.encrypted_data, encryption_key)?;
+    match SecretPayload::from_json(&plaintext) {
+        Some(SecretPayload::SshKey { private_key, .. }) => PrivateKey::from_pem(&private_key),
+        _ => Err("SSH CA key is not stored as an SSH key".into()),
+    }
+}
+
+// `*`匹配任意principal，`*.example.com`匹配该域名的任意子域，其它模式须完全相同
+fn principal_allowed(patterns: &[String], principal: &str) -> bool {
+    patterns.iter().any(|pattern| match pattern.strip_prefix('*') {
+        Some("") => true,
+        Some(suffix) if suffix.starts_with('.') => principal.len() > suffix.len() && principal.ends_with(suffix),
+        _ => pattern == principal,
+    })
+}
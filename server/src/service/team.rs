@@ -0,0 +1,138 @@
+use crate::model::access::Identity;
+use crate::model::team::{
+    AddMemberRequest, CallerTeamsResponse, CreateOrganizationRequest, CreateTeamRequest, Organization, Team,
+    TeamMember, TeamOperation,
+};
+use crate::repository::{access as access_repository, team as team_repository};
+use sqlx::MySqlPool;
+use std::error::Error;
+
+// 组织和团队名称的最大长度
+const MAX_NAME_LENGTH: usize = 255;
+
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Name must not be empty".to_string());
+    }
+    if name.len() > MAX_NAME_LENGTH {
+        return Err(format!("Name must be at most {} bytes", MAX_NAME_LENGTH));
+    }
+    Ok(())
+}
+
+pub async fn is_member(pool: &MySqlPool, team_id: u64, identity_id: u64) -> Result<bool, Box<dyn Error>> {
+    Ok(team_repository::is_member(pool, team_id, identity_id).await?)
+}
+
+/// 请求未指定团队时使用的团队，身份不属于任何团队时为空
+pub async fn default_team(pool: &MySqlPool, identity_id: u64) -> Result<Option<u64>, Box<dyn Error>> {
+    Ok(team_repository::default_team(pool, identity_id).await?)
+}
+
+pub async fn create_organization(
+    pool: &MySqlPool,
+    request: CreateOrganizationRequest,
+) -> Result<TeamOperation<Organization>, Box<dyn Error>> {
+    if let Err(reason) = validate_name(&request.name) {
+        return Ok(TeamOperation::Invalid(reason));
+    }
+
+    let id = match team_repository::create_organization(pool, &request.name).await {
+        Ok(id) => id,
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            return Ok(TeamOperation::Conflict("Organization name already exists".to_string()));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let organization = team_repository::get_organization(pool, id)
+        .await?
+        .ok_or("Failed to retrieve created organization")?;
+
+    Ok(TeamOperation::Done(organization))
+}
+
+pub async fn list_organizations(pool: &MySqlPool) -> Result<Vec<Organization>, Box<dyn Error>> {
+    Ok(team_repository::list_organizations(pool).await?)
+}
+
+pub async fn create_team(
+    pool: &MySqlPool,
+    organization_id: u64,
+    request: CreateTeamRequest,
+) -> Result<TeamOperation<Team>, Box<dyn Error>> {
+    if let Err(reason) = validate_name(&request.name) {
+        return Ok(TeamOperation::Invalid(reason));
+    }
+    if team_repository::get_organization(pool, organization_id).await?.is_none() {
+        return Ok(TeamOperation::NotFound);
+    }
+
+    let id = match team_repository::create_team(pool, organization_id, &request.name).await {
+        Ok(id) => id,
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            return Ok(TeamOperation::Conflict("Team name already exists in this organization".to_string()));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let team = team_repository::get_team(pool, id).await?.ok_or("Failed to retrieve created team")?;
+
+    Ok(TeamOperation::Done(team))
+}
+
+pub async fn list_teams(pool: &MySqlPool, organization_id: u64) -> Result<TeamOperation<Vec<Team>>, Box<dyn Error>> {
+    if team_repository::get_organization(pool, organization_id).await?.is_none() {
+        return Ok(TeamOperation::NotFound);
+    }
+
+    Ok(TeamOperation::Done(team_repository::list_teams(pool, organization_id).await?))
+}
+
+/// 删除团队，团队仍拥有密钥时拒绝删除
+pub async fn delete_team(pool: &MySqlPool, team_id: u64) -> Result<TeamOperation<()>, Box<dyn Error>> {
+    if team_repository::get_team(pool, team_id).await?.is_none() {
+        return Ok(TeamOperation::NotFound);
+    }
+    if team_repository::count_keys(pool, team_id).await? > 0 {
+        return Ok(TeamOperation::Conflict("Team still owns keys".to_string()));
+    }
+
+    team_repository::delete_team(pool, team_id).await?;
+    Ok(TeamOperation::Done(()))
+}
+
+pub async fn list_members(pool: &MySqlPool, team_id: u64) -> Result<TeamOperation<Vec<TeamMember>>, Box<dyn Error>> {
+    if team_repository::get_team(pool, team_id).await?.is_none() {
+        return Ok(TeamOperation::NotFound);
+    }
+
+    Ok(TeamOperation::Done(team_repository::list_members(pool, team_id).await?))
+}
+
+/// 将身份加入团队，已是成员时不做修改
+pub async fn add_member(
+    pool: &MySqlPool,
+    team_id: u64,
+    request: AddMemberRequest,
+) -> Result<TeamOperation<Vec<TeamMember>>, Box<dyn Error>> {
+    if team_repository::get_team(pool, team_id).await?.is_none() {
+        return Ok(TeamOperation::NotFound);
+    }
+    if access_repository::get_identity_by_id(pool, request.identity_id).await?.is_none() {
+        return Ok(TeamOperation::Invalid(format!("Identity {} does not exist", request.identity_id)));
+    }
+
+    team_repository::add_member(&mut *pool.acquire().await?, team_id, request.identity_id).await?;
+    Ok(TeamOperation::Done(team_repository::list_members(pool, team_id).await?))
+}
+
+pub async fn remove_member(pool: &MySqlPool, team_id: u64, identity_id: u64) -> Result<bool, Box<dyn Error>> {
+    Ok(team_repository::remove_member(pool, team_id, identity_id).await?)
+}
+
+/// 调用方所属的团队及当前请求使用的团队
+pub async fn caller_teams(pool: &MySqlPool, caller: &Identity) -> Result<CallerTeamsResponse, Box<dyn Error>> {
+    Ok(CallerTeamsResponse {
+        current_team_id: caller.team_id,
+        teams: team_repository::list_identity_teams(pool, caller.id).await?,
+    })
+}
@@ -0,0 +1,149 @@
+use crate::model::access::Identity;
+use crate::model::token::{
+    ApiTokenResponse, CreateServiceAccountRequest, CreateTokenRequest, CreateTokenResponse, ServiceAccount,
+    TokenGrant,
+};
+use crate::repository::{access as access_repository, token as token_repository};
+use crate::service::access as access_service;
+use sqlx::MySqlPool;
+use std::error::Error;
+
+/// API令牌前缀，用于与身份凭据和会话令牌区分
+pub const TOKEN_PREFIX: &str = "ekt_";
+// 列表和日志中展示的令牌前缀长度
+const DISPLAY_PREFIX_LENGTH: usize = 12;
+// 默认有效天数
+const DEFAULT_TTL_DAYS: u32 = 90;
+// 默认最长有效天数
+const DEFAULT_MAX_TTL_DAYS: u32 = 365;
+
+fn max_ttl_days() -> u32 {
+    std::env::var("API_TOKEN_MAX_TTL_DAYS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_MAX_TTL_DAYS)
+}
+
+/// 凭据是否为API令牌
+pub fn is_api_token(credential: &str) -> bool {
+    credential.starts_with(TOKEN_PREFIX)
+}
+
+/// 创建服务账号，其身份角色固定为app，凭据只能通过API令牌获得
+pub async fn create_service_account(
+    pool: &MySqlPool,
+    request: CreateServiceAccountRequest,
+    team_id: u64,
+) -> Result<ServiceAccount, Box<dyn Error>> {
+    if request.name.trim().is_empty() {
+        return Err("Service account name must not be empty".into());
+    }
+
+    let credential_hash = access_service::hash_credential(&access_service::generate_credential()?);
+    let id = token_repository::create_service_account(
+        pool,
+        &request.name,
+        &credential_hash,
+        request.description.as_deref(),
+        team_id,
+    )
+    .await?;
+
+    Ok(token_repository::get_service_account(pool, id)
+        .await?
+        .ok_or("Failed to retrieve created service account")?)
+}
+
+pub async fn list_service_accounts(pool: &MySqlPool) -> Result<Vec<ServiceAccount>, Box<dyn Error>> {
+    Ok(token_repository::list_service_accounts(pool).await?)
+}
+
+/// 为服务账号签发令牌；服务账号不存在时返回None
+pub async fn create_token(
+    pool: &MySqlPool,
+    service_account_id: u64,
+    request: CreateTokenRequest,
+) -> Result<Option<CreateTokenResponse>, Box<dyn Error>> {
+    if request.scopes.is_empty()
+        || request
+            .scopes
+            .iter()
+            .any(|scope| scope.key_names.is_empty() || scope.operations.is_empty())
+    {
+        return Err("Each token scope must list at least one key name and one operation".into());
+    }
+    let ttl_days = request.expires_in_days.unwrap_or(DEFAULT_TTL_DAYS);
+    if ttl_days == 0 || ttl_days > max_ttl_days() {
+        return Err(format!("expires_in_days must be between 1 and {}", max_ttl_days()).into());
+    }
+
+    if token_repository::get_service_account(pool, service_account_id).await?.is_none() {
+        return Ok(None);
+    }
+
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes)?;
+    let token = format!("{}{}", TOKEN_PREFIX, hex::encode(bytes));
+    let expires_at = chrono::Utc::now() + chrono::Duration::days(i64::from(ttl_days));
+
+    let id = token_repository::create_token(
+        pool,
+        service_account_id,
+        &request.name,
+        &token[..DISPLAY_PREFIX_LENGTH],
+        &access_service::hash_credential(&token),
+        &request.scopes,
+        expires_at,
+    )
+    .await?;
+    let record = token_repository::get_token(pool, id)
+        .await?
+        .ok_or("Failed to retrieve created token")?;
+
+    Ok(Some(CreateTokenResponse {
+        token,
+        info: record.into(),
+    }))
+}
+
+pub async fn list_tokens(pool: &MySqlPool, service_account_id: u64) -> Result<Vec<ApiTokenResponse>, Box<dyn Error>> {
+    let tokens = token_repository::list_tokens(pool, service_account_id).await?;
+    Ok(tokens.into_iter().map(ApiTokenResponse::from).collect())
+}
+
+pub async fn revoke_token(pool: &MySqlPool, service_account_id: u64, token_id: u64) -> Result<bool, Box<dyn Error>> {
+    Ok(token_repository::revoke_token(pool, service_account_id, token_id).await?)
+}
+
+/// 认证API令牌，返回服务账号身份及令牌作用范围，并记录最近使用信息
+pub async fn authenticate(
+    pool: &MySqlPool,
+    token: &str,
+    source_ip: Option<&str>,
+) -> Result<Option<(Identity, TokenGrant)>, Box<dyn Error>> {
+    let Some(record) = token_repository::get_active_token_by_hash(pool, &access_service::hash_credential(token)).await? else {
+        return Ok(None);
+    };
+    let Some(account) = token_repository::get_service_account(pool, record.service_account_id).await? else {
+        return Ok(None);
+    };
+    let Some(identity) = access_repository::get_identity_by_id(pool, account.identity_id).await? else {
+        return Ok(None);
+    };
+
+    token_repository::touch_token(pool, record.id, source_ip).await?;
+
+    Ok(Some((
+        Identity {
+            id: identity.id,
+            name: identity.name,
+            role: identity.role.parse()?,
+            source_ip: None,
+            team_id: None,
+        },
+        TokenGrant {
+            token_id: record.id,
+            scopes: record.scopes.0,
+        },
+    )))
+}
@@ -0,0 +1,206 @@
+use crate::model::access::Identity;
+use crate::model::user::{
+    CreateUserRequest, LoginOutcome, LoginRequest, LoginResponse, RecoveryCodesResponse, TotpEnrollment,
+    UserRecord, UserResponse,
+};
+use crate::repository::user as user_repository;
+use crate::service::access as access_service;
+use crate::utils::encryption::{decrypt_data, encrypt_data};
+use crate::utils::totp;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use sqlx::MySqlPool;
+use std::error::Error;
+
+// 密码最小长度
+const MIN_PASSWORD_LENGTH: usize = 12;
+// 一次生成的恢复码数量
+const RECOVERY_CODE_COUNT: usize = 10;
+// 默认会话有效期（秒）
+const DEFAULT_SESSION_TTL_SECS: i64 = 12 * 60 * 60;
+
+fn session_ttl_secs() -> i64 {
+    std::env::var("SESSION_TTL_SECS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_SESSION_TTL_SECS)
+}
+
+fn totp_issuer() -> String {
+    std::env::var("TOTP_ISSUER").unwrap_or_else(|_| "ecipher".to_string())
+}
+
+/// 使用Argon2id计算密码哈希
+fn hash_password(password: &str) -> Result<String, Box<dyn Error>> {
+    let mut salt = [0u8; 16];
+    getrandom::getrandom(&mut salt)?;
+    let salt = SaltString::encode_b64(&salt).map_err(|e| format!("Invalid salt: {}", e))?;
+
+    Ok(Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map_err(|e| format!("Failed to hash password: {}", e))?
+        .to_string())
+}
+
+fn verify_password(password: &str, password_hash: &str) -> Result<bool, Box<dyn Error>> {
+    let parsed = PasswordHash::new(password_hash).map_err(|e| format!("Invalid password hash: {}", e))?;
+    Ok(Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok())
+}
+
+// 恢复码忽略大小写和分隔符
+fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    access_service::hash_credential(&normalized)
+}
+
+// 生成形如 a1b2c-d3e4f 的恢复码
+fn generate_recovery_code() -> Result<String, Box<dyn Error>> {
+    let mut bytes = [0u8; 5];
+    getrandom::getrandom(&mut bytes)?;
+    let code = hex::encode(bytes);
+    Ok(format!("{}-{}", &code[..5], &code[5..]))
+}
+
+/// 创建用户及其身份；身份的机器凭据不会返回，用户只能通过登录获得会话
+pub async fn create_user(
+    pool: &MySqlPool,
+    request: CreateUserRequest,
+    team_id: u64,
+) -> Result<UserResponse, Box<dyn Error>> {
+    if request.username.trim().is_empty() {
+        return Err("Username must not be empty".into());
+    }
+    if request.password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(format!("Password must be at least {} characters", MIN_PASSWORD_LENGTH).into());
+    }
+
+    let credential_hash = access_service::hash_credential(&access_service::generate_credential()?);
+    let password_hash = hash_password(&request.password)?;
+    let (id, identity_id) = user_repository::create_user(
+        pool,
+        &request.username,
+        request.role.as_str(),
+        &credential_hash,
+        &password_hash,
+        team_id,
+    )
+    .await?;
+
+    Ok(UserResponse {
+        id,
+        username: request.username,
+        identity_id,
+        role: request.role,
+        totp_enabled: false,
+    })
+}
+
+// 校验TOTP验证码或恢复码
+async fn verify_second_factor(
+    pool: &MySqlPool,
+    user: &UserRecord,
+    request: &LoginRequest,
+    encryption_key: &str,
+) -> Result<bool, Box<dyn Error>> {
+    if let Some(code) = &request.totp_code {
+        let secret = decrypt_data(user.totp_secret.as_deref().ok_or("TOTP enabled without secret")?, encryption_key)?;
+        return match totp::verify(&secret, code, chrono::Utc::now().timestamp())? {
+            Some(step) => Ok(user_repository::advance_totp_step(pool, user.id, step).await?),
+            None => Ok(false),
+        };
+    }
+    if let Some(code) = &request.recovery_code {
+        return Ok(user_repository::consume_recovery_code(pool, user.id, &hash_recovery_code(code)).await?);
+    }
+    Ok(false)
+}
+
+/// 用户名密码登录，启用TOTP的用户还需通过第二因子
+pub async fn login(
+    pool: &MySqlPool,
+    request: LoginRequest,
+    encryption_key: &str,
+) -> Result<LoginOutcome, Box<dyn Error>> {
+    let Some(user) = user_repository::get_user_by_username(pool, &request.username).await? else {
+        return Ok(LoginOutcome::InvalidCredentials);
+    };
+    if !verify_password(&request.password, &user.password_hash)? {
+        return Ok(LoginOutcome::InvalidCredentials);
+    }
+
+    if user.totp_enabled {
+        if request.totp_code.is_none() && request.recovery_code.is_none() {
+            return Ok(LoginOutcome::SecondFactorRequired);
+        }
+        if !verify_second_factor(pool, &user, &request, encryption_key).await? {
+            tracing::warn!(user_id = user.id, "Login rejected: invalid second factor");
+            return Ok(LoginOutcome::InvalidSecondFactor);
+        }
+    }
+
+    let token = access_service::generate_credential()?;
+    let expires_at = chrono::Utc::now() + chrono::Duration::seconds(session_ttl_secs());
+    user_repository::create_session(pool, user.id, &access_service::hash_credential(&token), expires_at).await?;
+
+    Ok(LoginOutcome::Success(LoginResponse { token, expires_at }))
+}
+
+/// 开始TOTP登记，生成新种子；已启用时返回None
+pub async fn enroll_totp(
+    pool: &MySqlPool,
+    identity: &Identity,
+    encryption_key: &str,
+) -> Result<Option<TotpEnrollment>, Box<dyn Error>> {
+    let user = user_repository::get_user_by_identity(pool, identity.id)
+        .await?
+        .ok_or("Identity is not a user account")?;
+    if user.totp_enabled {
+        return Ok(None);
+    }
+
+    let secret = totp::generate_secret()?;
+    user_repository::set_pending_totp(pool, user.id, &encrypt_data(&secret, encryption_key)?).await?;
+
+    let provisioning_uri = totp::provisioning_uri(&totp_issuer(), &user.username, &secret);
+    let qr_svg = totp::qr_svg(&provisioning_uri)?;
+
+    Ok(Some(TotpEnrollment {
+        secret,
+        provisioning_uri,
+        qr_svg,
+    }))
+}
+
+/// 用首个验证码确认登记，启用TOTP并返回恢复码；验证码错误时返回None
+pub async fn confirm_totp(
+    pool: &MySqlPool,
+    identity: &Identity,
+    code: &str,
+    encryption_key: &str,
+) -> Result<Option<RecoveryCodesResponse>, Box<dyn Error>> {
+    let user = user_repository::get_user_by_identity(pool, identity.id)
+        .await?
+        .ok_or("Identity is not a user account")?;
+    if user.totp_enabled {
+        return Err("TOTP is already enabled".into());
+    }
+    let encrypted_secret = user.totp_secret.ok_or("TOTP enrollment has not been started")?;
+    let secret = decrypt_data(&encrypted_secret, encryption_key)?;
+
+    let Some(step) = totp::verify(&secret, code, chrono::Utc::now().timestamp())? else {
+        return Ok(None);
+    };
+
+    let mut recovery_codes = Vec::with_capacity(RECOVERY_CODE_COUNT);
+    for _ in 0..RECOVERY_CODE_COUNT {
+        recovery_codes.push(generate_recovery_code()?);
+    }
+    let hashes: Vec<String> = recovery_codes.iter().map(|code| hash_recovery_code(code)).collect();
+    user_repository::enable_totp(pool, user.id, step, &hashes).await?;
+
+    Ok(Some(RecoveryCodesResponse { recovery_codes }))
+}
@@ -0,0 +1,135 @@
+use crate::model::access::Identity;
+use crate::model::event::KeyEventType;
+use crate::model::key::{ConditionalWrite, Key, KeyResponse};
+use crate::model::team::NoTeamContext;
+use crate::model::vault::{CreateVaultKeyRequest, CreateVaultRequest, RewrapVaultRequest, VaultResponse};
+use crate::model::webhook::WebhookEvent;
+use crate::repository::{self, label as label_repository, vault as vault_repository};
+use crate::service::{
+    access as access_service, event as event_service, quota as quota_service, to_response, validate_labels,
+    webhook as webhook_service,
+};
+use serde_json::json;
+use shared::crypto::vault::{Ciphertext, KdfParams, WRAPPED_KEY_SIZE};
+use sqlx::MySqlPool;
+use std::error::Error;
+
+/// 校验客户端密文的编码格式，服务端不解密
+pub fn validate_ciphertext(encrypted_data: &str, nonce: &str) -> Result<(), Box<dyn Error>> {
+    Ciphertext {
+        nonce: nonce.to_string(),
+        ciphertext: encrypted_data.to_string(),
+    }
+    .validate()?;
+    Ok(())
+}
+
+fn validate_wrapping(kdf: &KdfParams, wrapped_key: &Ciphertext) -> Result<(), Box<dyn Error>> {
+    kdf.validate()?;
+    if wrapped_key.validate()? != WRAPPED_KEY_SIZE {
+        return Err("Wrapped vault key has an unexpected length".into());
+    }
+    Ok(())
+}
+
+/// 创建保险库，调用方成为所有者
+pub async fn create_vault(
+    pool: &MySqlPool,
+    owner: &Identity,
+    request: CreateVaultRequest,
+) -> Result<VaultResponse, Box<dyn Error>> {
+    validate_wrapping(&request.kdf, &request.wrapped_key)?;
+
+    let id = vault_repository::create_vault(pool, &request.name, owner.id, &request.kdf, &request.wrapped_key).await?;
+
+    Ok(vault_repository::get_vault(pool, id)
+        .await?
+        .ok_or("Failed to retrieve created vault")?
+        .into())
+}
+
+pub async fn get_vault(pool: &MySqlPool, id: u64) -> Result<Option<VaultResponse>, Box<dyn Error>> {
+    Ok(vault_repository::get_vault(pool, id).await?.map(VaultResponse::from))
+}
+
+/// 修改主密码后重新包装保险库密钥，保险库中的密文无需重新上传
+pub async fn rewrap(
+    pool: &MySqlPool,
+    id: u64,
+    request: RewrapVaultRequest,
+    expected_version: Option<u32>,
+) -> Result<ConditionalWrite<VaultResponse>, Box<dyn Error>> {
+    validate_wrapping(&request.kdf, &request.wrapped_key)?;
+
+    let Some(vault) = vault_repository::get_vault(pool, id).await? else {
+        return Ok(ConditionalWrite::NotFound);
+    };
+    let expected = expected_version.unwrap_or(vault.version);
+
+    if !vault_repository::rewrap(pool, id, &request.kdf, &request.wrapped_key, expected).await? {
+        return Ok(match vault_repository::get_vault(pool, id).await? {
+            Some(vault) => ConditionalWrite::VersionMismatch { current: vault.version },
+            None => ConditionalWrite::NotFound,
+        });
+    }
+
+    Ok(ConditionalWrite::Done(
+        vault_repository::get_vault(pool, id)
+            .await?
+            .ok_or("Failed to retrieve updated vault")?
+            .into(),
+    ))
+}
+
+/// 在保险库中创建密钥，原样保存客户端密文；创建者获得全部权限并计入其配额
+pub async fn create_key(
+    pool: &MySqlPool,
+    owner: &Identity,
+    vault_id: u64,
+    request: CreateVaultKeyRequest,
+) -> Result<KeyResponse, Box<dyn Error>> {
+    validate_labels(&request.labels)?;
+    if request.metadata.as_ref().is_some_and(|metadata| !metadata.is_object()) {
+        return Err("Metadata must be a JSON object".into());
+    }
+    validate_ciphertext(&request.encrypted_data, &request.nonce)?;
+    quota_service::check_data_size(pool, owner, request.encrypted_data.len()).await?;
+
+    let key = Key {
+        id: None,
+        name: request.name,
+        team_id: Some(owner.team_id.ok_or(NoTeamContext)?),
+        encrypted_data: request.encrypted_data,
+        kind: request.kind.map(|kind| kind.to_string()),
+        expires_at: request.expires_at,
+        description: request.description,
+        metadata: request.metadata,
+        version: None,
+        vault_id: Some(vault_id),
+        nonce: Some(request.nonce),
+        created_at: None,
+        updated_at: None,
+    };
+
+    let mut tx = pool.begin().await?;
+    let key_id = repository::create_key(&mut *tx, &key).await?;
+    label_repository::insert_labels(&mut *tx, key_id, &request.labels).await?;
+    access_service::insert_owner_grants(&mut *tx, key_id, owner).await?;
+    quota_service::claim_key(&mut *tx, key_id, owner).await?;
+    tx.commit().await?;
+
+    let created_key = repository::get_key_by_id(pool, key_id).await?
+        .ok_or("Failed to retrieve created key")?;
+    let team_id = created_key.team_id.ok_or("Key row without team")?;
+    let response = to_response(pool, created_key).await?;
+
+    webhook_service::dispatch(pool, team_id, WebhookEvent::KeyCreated, json!({
+        "key_id": response.id,
+        "name": response.name,
+        "expires_at": response.expires_at,
+        "vault_id": vault_id,
+    }));
+    event_service::publish(pool, KeyEventType::Created, response.id, &response.name, Some(response.version), &[]).await;
+
+    Ok(response)
+}
@@ -0,0 +1,432 @@
+use crate::model::access::Identity;
+use crate::model::webhook::{
+    CreateWebhookRequest, CreateWebhookResponse, DeadLetter, WebhookEvent, WebhookPayload,
+    WebhookRecord, WebhookResponse,
+};
+use crate::repository::webhook as webhook_repository;
+use crate::utils::encryption::{decrypt_data, encrypt_data};
+use hmac::{Hmac, Mac};
+use once_cell::sync::Lazy;
+use serde_json::json;
+use sha2::Sha256;
+use sqlx::MySqlPool;
+use std::error::Error;
+use std::time::Duration;
+
+// 默认最大投递次数
+const DEFAULT_MAX_ATTEMPTS: u32 = 5;
+// 首次重试前的等待时间（毫秒），之后每次翻倍
+const INITIAL_BACKOFF_MS: u64 = 1000;
+// 单次重试最长等待时间（毫秒）
+const MAX_BACKOFF_MS: u64 = 60_000;
+// 单次请求超时（秒）
+const DELIVERY_TIMEOUT_SECS: u64 = 10;
+// 默认过期检查间隔（秒）
+const DEFAULT_EXPIRY_CHECK_INTERVAL_SECS: u64 = 3600;
+// 默认提前通知天数
+const DEFAULT_EXPIRY_WARNING_DAYS: u32 = 30;
+
+static HTTP_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(DELIVERY_TIMEOUT_SECS))
+        .build()
+        .expect("Failed to build webhook HTTP client")
+});
+
+fn to_response(record: &WebhookRecord) -> WebhookResponse {
+    WebhookResponse {
+        id: record.id,
+        url: record.url.clone(),
+        events: record.events.split(',').filter_map(|event| event.parse().ok()).collect(),
+        created_at: record.created_at,
+    }
+}
+
+/// 在团队内注册Webhook并生成签名密钥
+pub async fn create(
+    pool: &MySqlPool,
+    identity: &Identity,
+    team_id: u64,
+    request: CreateWebhookRequest,
+    encryption_key: &str,
+) -> Result<CreateWebhookResponse, Box<dyn Error>> {
+    let url = reqwest::Url::parse(&request.url)?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err("Webhook URL must use http or https".into());
+    }
+    if request.events.is_empty() {
+        return Err("At least one event must be subscribed".into());
+    }
+
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes)?;
+    let secret = format!("whsec_{}", hex::encode(bytes));
+
+    let events = request.events.iter().map(|event| event.as_str()).collect::<Vec<_>>().join(",");
+    let id = webhook_repository::create_webhook(
+        pool,
+        team_id,
+        url.as_str(),
+        &encrypt_data(&secret, encryption_key)?,
+        &events,
+        identity.id,
+    )
+    .await?;
+
+    let record = webhook_repository::get_webhook(pool, id)
+        .await?
+        .ok_or("Failed to retrieve created webhook")?;
+
+    Ok(CreateWebhookResponse {
+        webhook: to_response(&record),
+        secret,
+    })
+}
+
+pub async fn list(pool: &MySqlPool, team_id: u64) -> Result<Vec<WebhookResponse>, Box<dyn Error>> {
+    let webhooks = webhook_repository::list_webhooks(pool, team_id).await?;
+    Ok(webhooks.iter().map(to_response).collect())
+}
+
+/// 删除团队内的Webhook，不存在时返回false
+pub async fn delete(pool: &MySqlPool, team_id: u64, id: u64) -> Result<bool, Box<dyn Error>> {
+    Ok(webhook_repository::delete_webhook(pool, team_id, id).await?)
+}
+
+pub async fn list_dead_letters(pool: &MySqlPool, team_id: u64) -> Result<Vec<DeadLetter>, Box<dyn Error>> {
+    Ok(webhook_repository::list_dead_letters(pool, team_id).await?)
+}
+
+/// 重新投递团队内的死信，成功后删除死信记录；死信不存在时返回None
+pub async fn retry_dead_letter(pool: &MySqlPool, team_id: u64, id: u64) -> Result<Option<bool>, Box<dyn Error>> {
+    let Some(dead_letter) = webhook_repository::get_dead_letter(pool, team_id, id).await? else {
+        return Ok(None);
+    };
+    let webhook = webhook_repository::get_webhook(pool, dead_letter.webhook_id)
+        .await?
+        .ok_or("Webhook not found")?;
+
+    let delivered = deliver_with_retry(pool, &webhook, &dead_letter.event_type, &dead_letter.payload).await;
+    if delivered {
+        webhook_repository::delete_dead_letter(pool, id).await?;
+    }
+    Ok(Some(delivered))
+}
+
+/// 计算签名：HMAC-SHA256("{timestamp}.{body}")
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> Result<String, Box<dyn Error>> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())?;
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    Ok(format!("sha256={}", hex::encode(mac.finalize().into_bytes())))
+}
+
+/// 异步分发事件给`team_id`团队内所有订阅的Webhook，不阻塞调用方
+pub fn dispatch(pool: &MySqlPool, team_id: u64, event: WebhookEvent, data: serde_json::Value) {
+    let pool = pool.clone();
+
+    tokio::spawn(async move {
+        let webhooks = match webhook_repository::list_subscribed(&pool, team_id, event.as_str()).await {
+            Ok(webhooks) => webhooks,
+            Err(e) => {
+                tracing::error!(error = %e, event = %event, "Failed to load webhooks");
+                return;
+            }
+        };
+
+        let mut bytes = [0u8; 16];
+        let _ = getrandom::getrandom(&mut bytes);
+        let payload = WebhookPayload {
+            id: hex::encode(bytes),
+            event,
+            occurred_at: chrono::Utc::now(),
+            data,
+        };
+        let body = match serde_json::to_string(&payload) {
+            Ok(body) => body,
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to serialize webhook payload");
+                return;
+            }
+        };
+
+        for webhook in webhooks {
+            let pool = pool.clone();
+            let body = body.clone();
+            tokio::spawn(async move {
+                deliver_with_retry(&pool, &webhook, event.as_str(), &body).await;
+            });
+        }
+    });
+}
+
+// 投递的重试次数和退避时间
+#[derive(Debug, Clone, Copy)]
+struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    fn from_env() -> Self {
+        let max_attempts = std::env::var("WEBHOOK_MAX_ATTEMPTS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(DEFAULT_MAX_ATTEMPTS)
+            .max(1);
+        RetryPolicy {
+            max_attempts,
+            initial_backoff: Duration::from_millis(INITIAL_BACKOFF_MS),
+            max_backoff: Duration::from_millis(MAX_BACKOFF_MS),
+        }
+    }
+}
+
+// 一次投递的最终结果
+#[derive(Debug, PartialEq, Eq)]
+enum Delivery {
+    /// 第`attempt`次尝试成功
+    Delivered { attempt: u32 },
+    /// 全部尝试失败，需要写入死信表
+    DeadLetter { attempts: u32, last_error: String },
+}
+
+/// 按指数退避重试投递，全部失败后写入死信表
+async fn deliver_with_retry(pool: &MySqlPool, webhook: &WebhookRecord, event_type: &str, body: &str) -> bool {
+    let secret = match webhook_secret(webhook) {
+        Ok(secret) => secret,
+        Err(e) => {
+            tracing::error!(webhook_id = webhook.id, error = %e, "Failed to decrypt webhook secret");
+            return false;
+        }
+    };
+
+    match deliver_with_backoff(webhook, &secret, event_type, body, RetryPolicy::from_env()).await {
+        Delivery::Delivered { .. } => true,
+        Delivery::DeadLetter { attempts, last_error } => {
+            if let Err(e) = webhook_repository::create_dead_letter(pool, webhook.id, event_type, body, attempts, &last_error).await {
+                tracing::error!(webhook_id = webhook.id, error = %e, "Failed to record webhook dead letter");
+            }
+            false
+        }
+    }
+}
+
+fn webhook_secret(webhook: &WebhookRecord) -> Result<String, Box<dyn Error>> {
+    let encryption_key = std::env::var("ENCRYPTION_KEY").map_err(|_| "Encryption key not configured")?;
+    decrypt_data(&webhook.encrypted_secret, &encryption_key)
+}
+
+async fn deliver_with_backoff(
+    webhook: &WebhookRecord,
+    secret: &str,
+    event_type: &str,
+    body: &str,
+    policy: RetryPolicy,
+) -> Delivery {
+    let mut backoff = policy.initial_backoff;
+    let mut last_error = String::new();
+
+    for attempt in 1..=policy.max_attempts {
+        match deliver(webhook, secret, event_type, body).await {
+            Ok(()) => {
+                tracing::info!(webhook_id = webhook.id, event = event_type, attempt, "Webhook delivered");
+                return Delivery::Delivered { attempt };
+            }
+            Err(e) => {
+                last_error = e.to_string();
+                tracing::warn!(webhook_id = webhook.id, event = event_type, attempt, error = %last_error, "Webhook delivery failed");
+            }
+        }
+
+        if attempt < policy.max_attempts {
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(policy.max_backoff);
+        }
+    }
+
+    Delivery::DeadLetter {
+        attempts: policy.max_attempts,
+        last_error,
+    }
+}
+
+async fn deliver(webhook: &WebhookRecord, secret: &str, event_type: &str, body: &str) -> Result<(), Box<dyn Error>> {
+    let timestamp = chrono::Utc::now().timestamp();
+
+    let response = HTTP_CLIENT
+        .post(&webhook.url)
+        .header("Content-Type", "application/json")
+        .header("X-Ecipher-Event", event_type)
+        .header("X-Ecipher-Timestamp", timestamp.to_string())
+        .header("X-Ecipher-Signature", sign(secret, timestamp, body)?)
+        .body(body.to_string())
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(format!("Receiver responded with {}", response.status()).into());
+    }
+    Ok(())
+}
+
+/// 启动后台任务，定期检查即将过期的证书并发出通知
+pub fn spawn_expiry_monitor(pool: &'static MySqlPool) {
+    let interval_secs = std::env::var("EXPIRY_CHECK_INTERVAL_SECS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_EXPIRY_CHECK_INTERVAL_SECS);
+    // 周期为0时interval会panic，按1秒处理
+    if interval_secs == 0 {
+        tracing::warn!("EXPIRY_CHECK_INTERVAL_SECS is 0, using 1 second");
+    }
+    let interval_secs = interval_secs.max(1);
+    let warning_days = std::env::var("EXPIRY_WARNING_DAYS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_EXPIRY_WARNING_DAYS);
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            if let Err(e) = notify_expiring(pool, warning_days).await {
+                tracing::error!(error = %e, "Expiry check failed");
+            }
+        }
+    });
+}
+
+pub async fn notify_expiring(pool: &MySqlPool, warning_days: u32) -> Result<(), Box<dyn Error>> {
+    for key in webhook_repository::list_expiring_keys(pool, warning_days).await? {
+        dispatch(pool, key.team_id, WebhookEvent::CertificateExpiring, json!({
+            "key_id": key.id,
+            "name": key.name,
+            "expires_at": key.expires_at,
+        }));
+        webhook_repository::mark_expiry_notified(pool, key.id).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        extract::State,
+        http::{HeaderMap, StatusCode},
+        routing::post,
+        Router,
+    };
+    use std::sync::{Arc, Mutex};
+    use tokio::net::TcpListener;
+    use tokio::time::Instant;
+
+    const SECRET: &str = "whsec_test";
+    const BODY: &str = r#"{"event":"key.created"}"#;
+
+    // 本地接收方收到的一次请求
+    struct Received {
+        at: Instant,
+        headers: HeaderMap,
+        body: String,
+    }
+
+    // 本地接收方：前`failures`次请求返回503，之后返回200
+    #[derive(Clone)]
+    struct Receiver {
+        failures: usize,
+        received: Arc<Mutex<Vec<Received>>>,
+    }
+
+    async fn handle_receive(State(receiver): State<Receiver>, headers: HeaderMap, body: String) -> StatusCode {
+        let mut received = receiver.received.lock().unwrap();
+        received.push(Received { at: Instant::now(), headers, body });
+        if received.len() <= receiver.failures {
+            StatusCode::SERVICE_UNAVAILABLE
+        } else {
+            StatusCode::OK
+        }
+    }
+
+    // 在127.0.0.1的随机端口启动接收方，返回指向它的Webhook
+    async fn spawn_receiver(failures: usize) -> (WebhookRecord, Arc<Mutex<Vec<Received>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let app = Router::new()
+            .route("/hook", post(handle_receive))
+            .with_state(Receiver { failures, received: received.clone() });
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let webhook = WebhookRecord {
+            id: 1,
+            team_id: 1,
+            url: format!("http://{}/hook", addr),
+            encrypted_secret: String::new(),
+            events: "key.created".to_string(),
+            created_at: chrono::Utc::now(),
+        };
+        (webhook, received)
+    }
+
+    fn policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_millis(1000),
+        }
+    }
+
+    #[tokio::test]
+    async fn signs_the_timestamp_and_body() {
+        let (webhook, received) = spawn_receiver(0).await;
+
+        let delivery = deliver_with_backoff(&webhook, SECRET, "key.created", BODY, policy(1)).await;
+        assert_eq!(delivery, Delivery::Delivered { attempt: 1 });
+
+        let received = received.lock().unwrap();
+        let request = &received[0];
+        assert_eq!(request.body, BODY);
+        assert_eq!(request.headers["x-ecipher-event"], "key.created");
+        let timestamp = request.headers["x-ecipher-timestamp"].to_str().unwrap();
+        let signature = request.headers["x-ecipher-signature"].to_str().unwrap();
+
+        // 按接收方的方式独立验证签名
+        let mut mac = Hmac::<Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+        mac.update(format!("{}.{}", timestamp, BODY).as_bytes());
+        let digest = hex::decode(signature.strip_prefix("sha256=").unwrap()).unwrap();
+        assert!(mac.verify_slice(&digest).is_ok());
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors_with_backoff() {
+        let (webhook, received) = spawn_receiver(2).await;
+
+        let delivery = deliver_with_backoff(&webhook, SECRET, "key.created", BODY, policy(5)).await;
+        assert_eq!(delivery, Delivery::Delivered { attempt: 3 });
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 3);
+        // 等待时间每次翻倍
+        assert!(received[1].at - received[0].at >= Duration::from_millis(50));
+        assert!(received[2].at - received[1].at >= Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn dead_letters_after_the_last_attempt() {
+        let (webhook, received) = spawn_receiver(usize::MAX).await;
+
+        let delivery = deliver_with_backoff(&webhook, SECRET, "key.created", BODY, policy(3)).await;
+        match delivery {
+            Delivery::DeadLetter { attempts, last_error } => {
+                assert_eq!(attempts, 3);
+                assert!(last_error.contains("503"), "{}", last_error);
+            }
+            other => panic!("expected a dead letter, got {:?}", other),
+        }
+        assert_eq!(received.lock().unwrap().len(), 3);
+    }
+}
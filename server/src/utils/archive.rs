@@ -0,0 +1,145 @@
+//! 分块认证加密的备份文件格式
+//!
+//! 文件头为魔数与随机盐值，之后是若干数据块：
+//! `[u32 密文长度][12字节nonce][密文]`。
+//! 每块的附加认证数据包含文件头、块序号和是否为最后一块，
+//! 因此块被删除、重排或文件被截断都会导致解密失败。
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use hkdf::Hkdf;
+use sha2::Sha256;
+use std::error::Error;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+const MAGIC: &[u8; 10] = b"ECIPHERBK\x01";
+const SALT_SIZE: usize = 16;
+const NONCE_SIZE: usize = 12;
+const HEADER_SIZE: usize = MAGIC.len() + SALT_SIZE;
+// 单块密文最大长度，防止恶意文件导致过量内存分配
+const MAX_CHUNK_SIZE: usize = 16 * 1024 * 1024;
+const HKDF_INFO: &[u8] = b"ecipher-backup-v1";
+
+// 由备份密钥和盐值派生本文件的AES-256-GCM密钥
+fn derive_cipher(backup_key: &str, salt: &[u8]) -> Result<Aes256Gcm, Box<dyn Error>> {
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(salt), backup_key.as_bytes())
+        .expand(HKDF_INFO, &mut key)
+        .map_err(|e| format!("Key derivation error: {:?}", e))?;
+    Ok(Aes256Gcm::new_from_slice(&key)?)
+}
+
+fn chunk_aad(header: &[u8], index: u64, is_final: bool) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(header.len() + 9);
+    aad.extend_from_slice(header);
+    aad.extend_from_slice(&index.to_be_bytes());
+    aad.push(is_final as u8);
+    aad
+}
+
+/// 备份文件写入器
+pub struct ArchiveWriter<W> {
+    writer: W,
+    cipher: Aes256Gcm,
+    header: Vec<u8>,
+    index: u64,
+}
+
+impl<W: AsyncWrite + Unpin> ArchiveWriter<W> {
+    /// 写入文件头并返回写入器
+    pub async fn new(mut writer: W, backup_key: &str) -> Result<Self, Box<dyn Error>> {
+        let mut salt = [0u8; SALT_SIZE];
+        getrandom::getrandom(&mut salt)?;
+
+        let mut header = Vec::with_capacity(HEADER_SIZE);
+        header.extend_from_slice(MAGIC);
+        header.extend_from_slice(&salt);
+        writer.write_all(&header).await?;
+
+        Ok(Self {
+            writer,
+            cipher: derive_cipher(backup_key, &salt)?,
+            header,
+            index: 0,
+        })
+    }
+
+    /// 加密并写入一个数据块，最后一块必须设置is_final
+    pub async fn write_chunk(&mut self, plaintext: &[u8], is_final: bool) -> Result<(), Box<dyn Error>> {
+        let mut nonce = [0u8; NONCE_SIZE];
+        getrandom::getrandom(&mut nonce)?;
+
+        let aad = chunk_aad(&self.header, self.index, is_final);
+        let ciphertext = self
+            .cipher
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg: plaintext, aad: &aad })
+            .map_err(|e| format!("Encryption error: {:?}", e))?;
+
+        self.writer.write_all(&(ciphertext.len() as u32).to_be_bytes()).await?;
+        self.writer.write_all(&nonce).await?;
+        self.writer.write_all(&ciphertext).await?;
+        self.index += 1;
+
+        if is_final {
+            self.writer.flush().await?;
+        }
+        Ok(())
+    }
+}
+
+/// 解密并校验整个备份文件，返回各数据块的明文
+pub fn read_archive(data: &[u8], backup_key: &str) -> Result<Vec<Vec<u8>>, Box<dyn Error>> {
+    if data.len() < HEADER_SIZE || &data[..MAGIC.len()] != MAGIC {
+        return Err("Not an ecipher backup archive".into());
+    }
+    let header = &data[..HEADER_SIZE];
+    let cipher = derive_cipher(backup_key, &header[MAGIC.len()..])?;
+
+    let mut chunks = Vec::new();
+    let mut offset = HEADER_SIZE;
+    let mut finished = false;
+
+    while offset < data.len() {
+        if finished {
+            return Err("Unexpected data after final chunk".into());
+        }
+        if data.len() - offset < 4 + NONCE_SIZE {
+            return Err("Truncated chunk header".into());
+        }
+
+        let length = u32::from_be_bytes(data[offset..offset + 4].try_into()?) as usize;
+        if length > MAX_CHUNK_SIZE {
+            return Err("Chunk exceeds maximum size".into());
+        }
+        let nonce = &data[offset + 4..offset + 4 + NONCE_SIZE];
+        let start = offset + 4 + NONCE_SIZE;
+        let ciphertext = data.get(start..start + length).ok_or("Truncated chunk")?;
+        let index = chunks.len() as u64;
+
+        // 先按普通块解密，失败再按最后一块解密
+        let plaintext = match cipher.decrypt(
+            Nonce::from_slice(nonce),
+            Payload { msg: ciphertext, aad: &chunk_aad(header, index, false) },
+        ) {
+            Ok(plaintext) => plaintext,
+            Err(_) => {
+                let plaintext = cipher
+                    .decrypt(
+                        Nonce::from_slice(nonce),
+                        Payload { msg: ciphertext, aad: &chunk_aad(header, index, true) },
+                    )
+                    .map_err(|_| format!("Chunk {} failed authentication", index))?;
+                finished = true;
+                plaintext
+            }
+        };
+
+        chunks.push(plaintext);
+        offset = start + length;
+    }
+
+    if !finished {
+        return Err("Archive is truncated: final chunk missing".into());
+    }
+    Ok(chunks)
+}
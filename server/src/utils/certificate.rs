@@ -0,0 +1,49 @@
+use x509_parser::pem::Pem;
+
+/// 从PEM数据中读取第一张X.509证书的到期时间
+///
+/// 数据不包含证书或解析失败时返回None。
+pub fn certificate_not_after(data: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    if !data.contains("-----BEGIN CERTIFICATE-----") {
+        return None;
+    }
+
+    for pem in Pem::iter_from_buffer(data.as_bytes()) {
+        let pem = pem.ok()?;
+        if pem.label != "CERTIFICATE" {
+            continue;
+        }
+        let certificate = pem.parse_x509().ok()?;
+        return chrono::DateTime::from_timestamp(certificate.validity().not_after.timestamp(), 0);
+    }
+
+    None
+}
+
+/// 校验证书、私钥及中间证书均为可解析的PEM，返回证书到期时间
+pub fn validate_certificate_bundle(
+    certificate: &str,
+    private_key: &str,
+    chain: Option<&str>,
+) -> Result<chrono::DateTime<chrono::Utc>, &'static str> {
+    let not_after = certificate_not_after(certificate).ok_or("Certificate could not be parsed")?;
+
+    let key_pem = Pem::iter_from_buffer(private_key.as_bytes())
+        .next()
+        .and_then(|pem| pem.ok())
+        .ok_or("Private key could not be parsed")?;
+    if !key_pem.label.ends_with("PRIVATE KEY") || key_pem.contents.is_empty() {
+        return Err("Private key could not be parsed");
+    }
+
+    if let Some(chain) = chain {
+        for pem in Pem::iter_from_buffer(chain.as_bytes()) {
+            let pem = pem.map_err(|_| "Certificate chain could not be parsed")?;
+            if pem.label != "CERTIFICATE" || pem.parse_x509().is_err() {
+                return Err("Certificate chain could not be parsed");
+            }
+        }
+    }
+
+    Ok(not_after)
+}
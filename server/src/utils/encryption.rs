@@ -4,6 +4,8 @@ use std::error::Error;
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64_ENGINE};
 use sha2::{Sha256, Digest};
 
+use crate::utils::metrics::METRICS;
+
 
 // AES-256-GCM的nonce大小（12字节）
 const NONCE_SIZE: usize = 12;
@@ -17,6 +19,12 @@ const NONCE_SIZE: usize = 12;
 /// # 返回值
 /// 成功时返回Base64编码的加密数据
 pub fn encrypt_data(data: &str, encryption_key: &str) -> Result<String, Box<dyn Error>> {
+    let result = encrypt(data, encryption_key);
+    METRICS.record_encrypt(result.is_ok());
+    result
+}
+
+fn encrypt(data: &str, encryption_key: &str) -> Result<String, Box<dyn Error>> {
     // 从encryption_key派生256位密钥
     let mut hasher = Sha256::new();
     hasher.update(encryption_key.as_bytes());
@@ -50,6 +58,12 @@ pub fn encrypt_data(data: &str, encryption_key: &str) -> Result<String, Box<dyn
 /// # 返回值
 /// 成功时返回解密后的原始数据
 pub fn decrypt_data(encrypted_data: &str, encryption_key: &str) -> Result<String, Box<dyn Error>> {
+    let result = decrypt(encrypted_data, encryption_key);
+    METRICS.record_decrypt(result.is_ok());
+    result
+}
+
+fn decrypt(encrypted_data: &str, encryption_key: &str) -> Result<String, Box<dyn Error>> {
     // 解码Base64数据
     let combined = BASE64_ENGINE.decode(encrypted_data)?;
     
@@ -0,0 +1,156 @@
+use ed25519_dalek::Signer;
+use p256::ecdsa::signature::hazmat::PrehashSigner;
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use rand_core::OsRng;
+use rsa::pkcs1::DecodeRsaPrivateKey;
+use rsa::pkcs8::{DecodePrivateKey, EncodePublicKey};
+use rsa::traits::PublicKeyParts;
+use rsa::{Pkcs1v15Sign, Pss, RsaPrivateKey};
+use sha2::{Digest, Sha256, Sha384};
+use std::error::Error;
+use x509_parser::pem::Pem;
+
+use crate::model::crypto::{KeyType, SignAlgorithm};
+
+/// 服务端可用于签名的私钥
+pub enum PrivateKey {
+    Rsa(RsaPrivateKey),
+    EcP256(p256::SecretKey),
+    Ed25519(ed25519_dalek::SigningKey),
+}
+
+/// 私钥对应的公开信息
+pub struct PublicKeyInfo {
+    pub key_type: KeyType,
+    pub bits: u32,
+    /// SubjectPublicKeyInfo DER
+    pub spki: Vec<u8>,
+    pub modulus: Option<Vec<u8>>,
+    pub public_exponent: Option<Vec<u8>>,
+    pub ec_point: Option<Vec<u8>>,
+}
+
+impl PrivateKey {
+    /// 解析PKCS#8、PKCS#1（RSA）或SEC1（P-256）格式的PEM私钥，不支持加密私钥和OpenSSH格式
+    pub fn from_pem(pem: &str) -> Result<Self, Box<dyn Error>> {
+        let label = Pem::iter_from_buffer(pem.as_bytes())
+            .next()
+            .and_then(|pem| pem.ok())
+            .map(|pem| pem.label)
+            .ok_or("Private key is not PEM encoded")?;
+
+        match label.as_str() {
+            "RSA PRIVATE KEY" => Ok(PrivateKey::Rsa(RsaPrivateKey::from_pkcs1_pem(pem)?)),
+            "EC PRIVATE KEY" => Ok(PrivateKey::EcP256(p256::SecretKey::from_sec1_pem(pem)?)),
+            "PRIVATE KEY" => {
+                if let Ok(key) = RsaPrivateKey::from_pkcs8_pem(pem) {
+                    return Ok(PrivateKey::Rsa(key));
+                }
+                if let Ok(key) = p256::SecretKey::from_pkcs8_pem(pem) {
+                    return Ok(PrivateKey::EcP256(key));
+                }
+                Ok(PrivateKey::Ed25519(ed25519_dalek::SigningKey::from_pkcs8_pem(pem)?))
+            }
+            other => Err(format!("Unsupported private key format: {}", other).into()),
+        }
+    }
+
+    pub fn key_type(&self) -> KeyType {
+        match self {
+            PrivateKey::Rsa(_) => KeyType::Rsa,
+            PrivateKey::EcP256(_) => KeyType::EcP256,
+            PrivateKey::Ed25519(_) => KeyType::Ed25519,
+        }
+    }
+
+    pub fn public_key_info(&self) -> Result<PublicKeyInfo, Box<dyn Error>> {
+        let info = match self {
+            PrivateKey::Rsa(key) => {
+                let public_key = key.to_public_key();
+                PublicKeyInfo {
+                    key_type: KeyType::Rsa,
+                    bits: (public_key.size() * 8) as u32,
+                    spki: public_key.to_public_key_der()?.as_bytes().to_vec(),
+                    modulus: Some(public_key.n().to_bytes_be()),
+                    public_exponent: Some(public_key.e().to_bytes_be()),
+                    ec_point: None,
+                }
+            }
+            PrivateKey::EcP256(key) => {
+                let public_key = key.public_key();
+                PublicKeyInfo {
+                    key_type: KeyType::EcP256,
+                    bits: 256,
+                    spki: public_key.to_public_key_der()?.as_bytes().to_vec(),
+                    modulus: None,
+                    public_exponent: None,
+                    ec_point: Some(public_key.to_encoded_point(false).as_bytes().to_vec()),
+                }
+            }
+            PrivateKey::Ed25519(key) => {
+                let public_key = key.verifying_key();
+                PublicKeyInfo {
+                    key_type: KeyType::Ed25519,
+                    bits: 256,
+                    spki: public_key.to_public_key_der()?.as_bytes().to_vec(),
+                    modulus: None,
+                    public_exponent: None,
+                    ec_point: Some(public_key.to_bytes().to_vec()),
+                }
+            }
+        };
+        Ok(info)
+    }
+
+    /// 按给定算法签名，算法与密钥类型不符时返回错误
+    pub fn sign(&self, algorithm: SignAlgorithm, data: &[u8], prehashed: bool) -> Result<Vec<u8>, Box<dyn Error>> {
+        match (self, algorithm) {
+            (PrivateKey::Rsa(key), SignAlgorithm::RsaPkcs1Sha256) => {
+                Ok(key.sign(Pkcs1v15Sign::new::<Sha256>(), &digest::<Sha256>(data, prehashed)?)?)
+            }
+            (PrivateKey::Rsa(key), SignAlgorithm::RsaPkcs1Sha384) => {
+                Ok(key.sign(Pkcs1v15Sign::new::<Sha384>(), &digest::<Sha384>(data, prehashed)?)?)
+            }
+            (PrivateKey::Rsa(key), SignAlgorithm::RsaPssSha256) => {
+                Ok(key.sign_with_rng(&mut OsRng, Pss::new::<Sha256>(), &digest::<Sha256>(data, prehashed)?)?)
+            }
+            (PrivateKey::Rsa(key), SignAlgorithm::RsaPssSha384) => {
+                Ok(key.sign_with_rng(&mut OsRng, Pss::new::<Sha384>(), &digest::<Sha384>(data, prehashed)?)?)
+            }
+            (PrivateKey::EcP256(key), SignAlgorithm::EcdsaSha256 | SignAlgorithm::EcdsaSha384) => {
+                // 预先计算的摘要可以是任意长度，按ECDSA规则截断
+                let hashed = match (prehashed, algorithm) {
+                    (true, _) => data.to_vec(),
+                    (false, SignAlgorithm::EcdsaSha256) => Sha256::digest(data).to_vec(),
+                    (false, _) => Sha384::digest(data).to_vec(),
+                };
+                let signature: p256::ecdsa::Signature = p256::ecdsa::SigningKey::from(key).sign_prehash(&hashed)?;
+                Ok(signature.to_bytes().to_vec())
+            }
+            (PrivateKey::Ed25519(_), SignAlgorithm::Ed25519) if prehashed => {
+                Err("Ed25519 does not support prehashed input".into())
+            }
+            (PrivateKey::Ed25519(key), SignAlgorithm::Ed25519) => Ok(key.sign(data).to_bytes().to_vec()),
+            _ => Err(format!("Algorithm {:?} does not match the {:?} key", algorithm, self.key_type()).into()),
+        }
+    }
+}
+
+// 计算摘要；已给出摘要时只校验长度
+fn digest<D: Digest>(data: &[u8], prehashed: bool) -> Result<Vec<u8>, Box<dyn Error>> {
+    if !prehashed {
+        return Ok(D::digest(data).to_vec());
+    }
+    if data.len() != <D as Digest>::output_size() {
+        return Err(format!("Prehashed input must be {} bytes", <D as Digest>::output_size()).into());
+    }
+    Ok(data.to_vec())
+}
+
+/// 读取PEM数据中第一张证书的DER
+pub fn certificate_der(data: &str) -> Option<Vec<u8>> {
+    Pem::iter_from_buffer(data.as_bytes())
+        .filter_map(|pem| pem.ok())
+        .find(|pem| pem.label == "CERTIFICATE")
+        .map(|pem| pem.contents)
+}
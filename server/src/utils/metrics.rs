@@ -0,0 +1,185 @@
+use axum::http::StatusCode;
+use once_cell::sync::Lazy;
+use sqlx::MySqlPool;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::model::quota::QuotaKind;
+use crate::utils::rate_limit::REQUEST_COUNTER;
+
+// 请求耗时直方图的桶上界（秒）
+const LATENCY_BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// 全局指标注册表，以Prometheus文本格式导出
+pub static METRICS: Lazy<Metrics> = Lazy::new(Metrics::default);
+
+#[derive(Default)]
+struct Histogram {
+    // 各桶的累计计数，与LATENCY_BUCKETS一一对应
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        for (bucket, upper) in self.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if value <= upper {
+                *bucket += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+#[derive(Default)]
+struct OperationCounter {
+    success: AtomicU64,
+    failure: AtomicU64,
+}
+
+impl OperationCounter {
+    fn record(&self, ok: bool) {
+        let counter = if ok { &self.success } else { &self.failure };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[derive(Default)]
+pub struct Metrics {
+    // (method, route) -> 耗时直方图
+    latencies: Mutex<BTreeMap<(String, String), Histogram>>,
+    // (route, status) -> 4xx/5xx响应数
+    errors: Mutex<BTreeMap<(String, u16), u64>>,
+    // 失败原因 -> 认证失败次数
+    auth_failures: Mutex<BTreeMap<&'static str, u64>>,
+    // 配额项 -> 超出配额被拒绝的次数
+    quota_exceeded: Mutex<BTreeMap<&'static str, u64>>,
+    encrypt: OperationCounter,
+    decrypt: OperationCounter,
+}
+
+impl Metrics {
+    /// 记录一次请求的耗时和状态码，route为路由模板以控制标签基数
+    pub fn observe_request(&self, method: &str, route: &str, status: StatusCode, elapsed: Duration) {
+        self.latencies
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry((method.to_string(), route.to_string()))
+            .or_default()
+            .observe(elapsed.as_secs_f64());
+
+        if status.is_client_error() || status.is_server_error() {
+            *self
+                .errors
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .entry((route.to_string(), status.as_u16()))
+                .or_default() += 1;
+        }
+    }
+
+    pub fn record_auth_failure(&self, reason: &'static str) {
+        *self
+            .auth_failures
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(reason)
+            .or_default() += 1;
+    }
+
+    pub fn record_quota_exceeded(&self, kind: QuotaKind) {
+        *self
+            .quota_exceeded
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(kind.as_str())
+            .or_default() += 1;
+    }
+
+    pub fn record_encrypt(&self, ok: bool) {
+        self.encrypt.record(ok);
+    }
+
+    pub fn record_decrypt(&self, ok: bool) {
+        self.decrypt.record(ok);
+    }
+
+    /// 以Prometheus文本格式导出全部指标，连接池指标在导出时读取
+    pub fn render(&self, pool: &MySqlPool) -> String {
+        let mut out = String::new();
+
+        out.push_str("# HELP ecipher_http_request_duration_seconds HTTP request latency by route.\n");
+        out.push_str("# TYPE ecipher_http_request_duration_seconds histogram\n");
+        for ((method, route), histogram) in self.latencies.lock().unwrap_or_else(|e| e.into_inner()).iter() {
+            let labels = format!("method=\"{}\",route=\"{}\"", escape(method), escape(route));
+            for (upper, count) in LATENCY_BUCKETS.iter().zip(histogram.buckets) {
+                let _ = writeln!(out, "ecipher_http_request_duration_seconds_bucket{{{},le=\"{}\"}} {}", labels, upper, count);
+            }
+            let _ = writeln!(out, "ecipher_http_request_duration_seconds_bucket{{{},le=\"+Inf\"}} {}", labels, histogram.count);
+            let _ = writeln!(out, "ecipher_http_request_duration_seconds_sum{{{}}} {}", labels, histogram.sum);
+            let _ = writeln!(out, "ecipher_http_request_duration_seconds_count{{{}}} {}", labels, histogram.count);
+        }
+
+        out.push_str("# HELP ecipher_http_errors_total HTTP 4xx and 5xx responses by route and status.\n");
+        out.push_str("# TYPE ecipher_http_errors_total counter\n");
+        for ((route, status), count) in self.errors.lock().unwrap_or_else(|e| e.into_inner()).iter() {
+            let _ = writeln!(out, "ecipher_http_errors_total{{route=\"{}\",status=\"{}\"}} {}", escape(route), status, count);
+        }
+
+        out.push_str("# HELP ecipher_auth_failures_total Failed authentication attempts by reason.\n");
+        out.push_str("# TYPE ecipher_auth_failures_total counter\n");
+        for (reason, count) in self.auth_failures.lock().unwrap_or_else(|e| e.into_inner()).iter() {
+            let _ = writeln!(out, "ecipher_auth_failures_total{{reason=\"{}\"}} {}", reason, count);
+        }
+
+        out.push_str("# HELP ecipher_quota_exceeded_total Requests rejected for exceeding a caller quota.\n");
+        out.push_str("# TYPE ecipher_quota_exceeded_total counter\n");
+        for (quota, count) in self.quota_exceeded.lock().unwrap_or_else(|e| e.into_inner()).iter() {
+            let _ = writeln!(out, "ecipher_quota_exceeded_total{{quota=\"{}\"}} {}", quota, count);
+        }
+
+        out.push_str("# HELP ecipher_quota_requests_current_minute Requests in the current one-minute quota window by caller.\n");
+        out.push_str("# TYPE ecipher_quota_requests_current_minute gauge\n");
+        for (subject_type, subject, count) in REQUEST_COUNTER.snapshot() {
+            let _ = writeln!(
+                out,
+                "ecipher_quota_requests_current_minute{{subject_type=\"{}\",subject=\"{}\"}} {}",
+                subject_type,
+                escape(&subject),
+                count
+            );
+        }
+
+        for (name, help, counter) in [
+            ("ecipher_encrypt_operations_total", "Server-side encryptions by result.", &self.encrypt),
+            ("ecipher_decrypt_operations_total", "Server-side decryptions by result.", &self.decrypt),
+        ] {
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+            let _ = writeln!(out, "# TYPE {} counter", name);
+            let _ = writeln!(out, "{}{{result=\"success\"}} {}", name, counter.success.load(Ordering::Relaxed));
+            let _ = writeln!(out, "{}{{result=\"failure\"}} {}", name, counter.failure.load(Ordering::Relaxed));
+        }
+
+        let size = pool.size();
+        let idle = pool.num_idle() as u32;
+        out.push_str("# HELP ecipher_db_pool_connections Database pool connections by state.\n");
+        out.push_str("# TYPE ecipher_db_pool_connections gauge\n");
+        let _ = writeln!(out, "ecipher_db_pool_connections{{state=\"active\"}} {}", size.saturating_sub(idle));
+        let _ = writeln!(out, "ecipher_db_pool_connections{{state=\"idle\"}} {}", idle);
+        out.push_str("# HELP ecipher_db_pool_max_connections Configured database pool size.\n");
+        out.push_str("# TYPE ecipher_db_pool_max_connections gauge\n");
+        let _ = writeln!(out, "ecipher_db_pool_max_connections {}", pool.options().get_max_connections());
+
+        out
+    }
+}
+
+// 转义标签值中的反斜杠、引号和换行
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
@@ -1 +1,656 @@
-use axum::{http::{header, Request, Response, StatusCode}, middleware::Next};\nuse std::time::Duration;\nuse tower_http::trace::TraceLayer;\nuse tracing::{info_span, Span};\n\n/// CORS中间件，处理跨域请求\npub async fn cors_middleware<B>(mut request: Request<B>, next: Next<B>) -> Response<B> {\n    // 处理OPTIONS预检请求\n    if request.method() == axum::http::Method::OPTIONS {\n        let mut response = Response::new(());\n        *response.status_mut() = StatusCode::NO_CONTENT;\n        set_cors_headers(response.headers_mut());\n        return response.map(|_| unreachable!());\n    }\n\n    // 处理常规请求\n    let response = next.run(request).await;\n    \n    // 为响应添加CORS头\n    let mut response_builder = response.into_parts();\n    set_cors_headers(&mut response_builder.headers);\n    \n    Response::from_parts(response_builder.headers, response_builder.body)\n}\n\n/// 设置CORS响应头\nfn set_cors_headers(headers: &mut axum::http::HeaderMap) {\n    headers.insert(\n        header::ACCESS_CONTROL_ALLOW_ORIGIN,\n        header::HeaderValue::from_static("*"),\n    );\n    headers.insert(\n        header::ACCESS_CONTROL_ALLOW_METHODS,\n        header::HeaderValue::from_static("GET, POST, PUT, DELETE, OPTIONS"),\n    );\n    headers.insert(\n        header::ACCESS_CONTROL_ALLOW_HEADERS,\n        header::HeaderValue::from_static("Content-Type, Authorization"),\n    );\n    headers.insert(\n        header::ACCESS_CONTROL_ALLOW_CREDENTIALS,\n        header::HeaderValue::from_static("true"),\n    );\n}\n\n/// 创建日志中间件\npub fn trace_layer() -> TraceLayer<impl Fn(&Request<()>) -> Span + Clone> {\n    TraceLayer::new_for_http()\n        .make_span_with(|request: &Request<()>| {\n            let matched_path = request\n                .extensions()\n                .get::<axum::extract::MatchedPath>()\n                .map(|mp| mp.as_str())\n                .unwrap_or("/");\n            \n            info_span!("http_request", \n                method = %request.method(), \n                path = %matched_path,\n                remote_addr = tracing::field::Empty\n            )\n        })\n        .on_response(|response: &Response<()>, latency: Duration, span: &Span| {\n            span.record("status", %response.status());\n            tracing::info!("{} {}ms", response.status(), latency.as_millis());\n        })\n}
\ No newline at end of file
+use axum::{body::Body, extract::{ConnectInfo, MatchedPath, Path}, extract::rejection::PathRejection, http::{header, HeaderValue, Method, Request, Response, StatusCode}, middleware::Next, response::IntoResponse, Extension};
+use sqlx::MySqlPool;
+use std::collections::HashMap;
+use std::error::Error;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+use tower_http::classify::{ServerErrorsAsFailures, SharedClassifier};
+use tower_http::trace::{DefaultOnRequest, TraceLayer};
+use tracing::{info_span, Span};
+
+use crate::config::database;
+use crate::model::access::{Identity, Permission};
+use crate::model::audit::{NewAuditEvent, RequestContext};
+use crate::model::idempotency::IdempotencyState;
+use crate::model::quota::QuotaExceeded;
+use crate::model::team::{NoTeamContext, TEAM_HEADER};
+use crate::model::token::TokenGrant;
+use crate::repository;
+use crate::service::access as access_service;
+use crate::service::audit as audit_service;
+use crate::service::idempotency as idempotency_service;
+use crate::service::quota as quota_service;
+use crate::service::team as team_service;
+use crate::service::token as token_service;
+use crate::utils::metrics::METRICS;
+use crate::utils::rate_limit::{LOCKOUTS, RATE_LIMITER};
+
+/// CORS中间件，处理跨域请求
+pub async fn cors_middleware(request: axum::extract::Request, next: Next) -> axum::response::Response {
+    // 处理OPTIONS预检请求
+    if request.method() == Method::OPTIONS {
+        let mut response = StatusCode::NO_CONTENT.into_response();
+        set_cors_headers(response.headers_mut());
+        return response;
+    }
+
+    // 处理常规请求，并为响应添加CORS头
+    let mut response = next.run(request).await;
+    set_cors_headers(response.headers_mut());
+    response
+}
+
+// 无需Bearer凭据即可访问的路径
+const PUBLIC_PATHS: &[&str] = &["/auth/login", "/healthz", "/readyz", "/metrics", "/api/v1/openapi.json"];
+// 无需认证的路由模板：依赖方需要匿名下载CA证书、CRL、SSH CA公钥和查询OCSP
+const PUBLIC_ROUTES: &[&str] = &[
+    "/pki/cas/{id}/certificate",
+    "/pki/cas/{id}/crl",
+    "/pki/cas/{id}/ocsp",
+    "/ssh/cas/{id}/public-key",
+];
+// API令牌可访问的路由模板：api::key中的密钥接口，以及PKCS#11模块使用的服务端加解密、签名和公钥接口
+const TOKEN_PATHS: &[&str] = &[
+    "/keys",
+    "/keys/search",
+    "/keys/batch",
+    "/keys/{id}",
+    "/keys/{id}/material",
+    "/keys/{id}/encrypt",
+    "/keys/{id}/decrypt",
+    "/keys/{id}/sign",
+    "/keys/{id}/public-key",
+];
+
+/// 设置CORS响应头
+fn set_cors_headers(headers: &mut axum::http::HeaderMap) {
+    headers.insert(
+        header::ACCESS_CONTROL_ALLOW_ORIGIN,
+        header::HeaderValue::from_static("*"),
+    );
+    headers.insert(
+        header::ACCESS_CONTROL_ALLOW_METHODS,
+        header::HeaderValue::from_static("GET, POST, PUT, DELETE, OPTIONS"),
+    );
+    headers.insert(
+        header::ACCESS_CONTROL_ALLOW_HEADERS,
+        header::HeaderValue::from_static("Content-Type, Authorization, If-Match, Idempotency-Key, X-Team-Id"),
+    );
+    headers.insert(
+        header::ACCESS_CONTROL_EXPOSE_HEADERS,
+        header::HeaderValue::from_static("ETag, Idempotent-Replayed"),
+    );
+    headers.insert(
+        header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
+        header::HeaderValue::from_static("true"),
+    );
+}
+
+// 按5xx状态码判定请求失败
+type HttpClassifier = SharedClassifier<ServerErrorsAsFailures>;
+type MakeSpan = fn(&Request<Body>) -> Span;
+type OnResponse = fn(&Response<Body>, Duration, &Span);
+
+/// 创建日志中间件
+pub fn trace_layer() -> TraceLayer<HttpClassifier, MakeSpan, DefaultOnRequest, OnResponse> {
+    TraceLayer::new_for_http()
+        .make_span_with(request_span as MakeSpan)
+        .on_response(record_response as OnResponse)
+}
+
+// 按路由模板创建请求的span
+fn request_span(request: &Request<Body>) -> Span {
+    let matched_path = request
+        .extensions()
+        .get::<axum::extract::MatchedPath>()
+        .map(|mp| mp.as_str())
+        .unwrap_or("/");
+    
+    info_span!("http_request", 
+        method = %request.method(), 
+        path = %matched_path,
+        status = tracing::field::Empty,
+        remote_addr = tracing::field::Empty
+    )
+}
+
+fn record_response(response: &Response<Body>, latency: Duration, span: &Span) {
+    span.record("status", tracing::field::display(response.status()));
+    tracing::info!("{} {}ms", response.status(), latency.as_millis());
+}
+
+/// 校验Bearer凭据，API令牌同时返回其授权范围；REST、gRPC和KMIP接口共用
+///
+/// `team`为调用方选择的团队id，调用方必须是该团队成员；未给出时使用其所属的第一个团队。
+/// 认证成功后按调用方的每分钟请求数配额计数，超出时返回429。
+pub async fn authenticate_credential(
+    credential: &str,
+    source_ip: Option<&str>,
+    team: Option<&str>,
+) -> Result<(Identity, Option<TokenGrant>), (StatusCode, String)> {
+    let pool = database::get_pool();
+    let (mut identity, grant) = if token_service::is_api_token(credential) {
+        let (identity, grant) = token_service::authenticate(pool, credential, source_ip)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to authenticate: {:?}", e)))?
+            .ok_or_else(|| {
+                tracing::warn!(source_ip, "API token authentication failed");
+                METRICS.record_auth_failure("invalid_api_token");
+                (StatusCode::UNAUTHORIZED, "Invalid credential".to_string())
+            })?;
+        (identity, Some(grant))
+    } else {
+        let identity = access_service::authenticate(pool, credential)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to authenticate: {:?}", e)))?
+            .ok_or_else(|| {
+                tracing::warn!(source_ip, "Authentication failed");
+                METRICS.record_auth_failure("invalid_credential");
+                (StatusCode::UNAUTHORIZED, "Invalid credential".to_string())
+            })?;
+        (identity, None)
+    };
+
+    identity.source_ip = source_ip.and_then(|ip| ip.parse().ok());
+    identity.team_id = resolve_team(pool, &identity, team).await?;
+    quota_service::check_request(pool, &identity)
+        .await
+        .map_err(service_error("Failed to check quota"))?;
+    Ok((identity, grant))
+}
+
+// 确定请求的团队上下文，选择非所属团队时返回403
+async fn resolve_team(pool: &MySqlPool, identity: &Identity, team: Option<&str>) -> Result<Option<u64>, (StatusCode, String)> {
+    let Some(team) = team else {
+        return team_service::default_team(pool, identity.id)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to resolve team: {:?}", e)));
+    };
+
+    let team_id: u64 = team
+        .parse()
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid team id".to_string()))?;
+    let member = team_service::is_member(pool, team_id, identity.id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to resolve team: {:?}", e)))?;
+    if !member {
+        tracing::warn!(identity_id = identity.id, team_id, "Access denied: not a member of team");
+        return Err((StatusCode::FORBIDDEN, "Not a member of the requested team".to_string()));
+    }
+
+    Ok(Some(team_id))
+}
+
+/// 将服务层错误转换为响应：超出配额为429，正文以"Quota exceeded"开头；没有团队上下文为403；其它错误为500
+pub fn service_error(context: &'static str) -> impl Fn(Box<dyn Error>) -> (StatusCode, String) {
+    move |e| {
+        if let Some(exceeded) = e.downcast_ref::<QuotaExceeded>() {
+            return (StatusCode::TOO_MANY_REQUESTS, exceeded.to_string());
+        }
+        if let Some(no_team) = e.downcast_ref::<NoTeamContext>() {
+            return (StatusCode::FORBIDDEN, no_team.to_string());
+        }
+        (StatusCode::INTERNAL_SERVER_ERROR, format!("{}: {:?}", context, e))
+    }
+}
+
+/// 认证中间件，根据Authorization: Bearer凭据识别调用方身份
+pub async fn auth_middleware(
+    matched_path: Option<MatchedPath>,
+    mut request: axum::extract::Request,
+    next: Next,
+) -> Result<axum::response::Response, (StatusCode, String)> {
+    // 预检请求、登录接口和CA公开接口不需要认证
+    if request.method() == Method::OPTIONS
+        || PUBLIC_PATHS.contains(&request.uri().path())
+        || matched_path.as_ref().is_some_and(|mp| PUBLIC_ROUTES.contains(&mp.as_str()))
+    {
+        return Ok(next.run(request).await);
+    }
+
+    let credential = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or_else(|| {
+            METRICS.record_auth_failure("missing_credential");
+            (StatusCode::UNAUTHORIZED, "Missing bearer credential".to_string())
+        })?
+        .to_string();
+
+    let source_ip = client_ip(&request).map(|ip| ip.to_string());
+    let team = request
+        .headers()
+        .get(TEAM_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let (identity, grant) = authenticate_credential(&credential, source_ip.as_deref(), team.as_deref()).await?;
+
+    if let Some(grant) = grant {
+        // API令牌只能访问密钥接口
+        if !matched_path.as_ref().is_some_and(|mp| TOKEN_PATHS.contains(&mp.as_str())) {
+            tracing::warn!(identity_id = identity.id, token_id = grant.token_id, path = %request.uri().path(), "Access denied: route not available to API tokens");
+            return Err((StatusCode::FORBIDDEN, "API tokens may only access key routes".to_string()));
+        }
+        request.extensions_mut().insert(grant);
+    }
+
+    request.extensions_mut().insert(identity.clone());
+    let mut response = next.run(request).await;
+
+    // 同时写入响应扩展，供外层审计中间件记录调用方
+    response.extensions_mut().insert(identity);
+    Ok(response)
+}
+
+/// 密钥ACL中间件，按请求方法和路由映射到权限并执行默认拒绝判定
+pub async fn key_acl_middleware(
+    Extension(identity): Extension<Identity>,
+    grant: Option<Extension<TokenGrant>>,
+    matched_path: MatchedPath,
+    Path(params): Path<HashMap<String, String>>,
+    request: axum::extract::Request,
+    next: Next,
+) -> Result<axum::response::Response, (StatusCode, String)> {
+    let key_id: u64 = params
+        .get("id")
+        .and_then(|id| id.parse().ok())
+        .ok_or((StatusCode::BAD_REQUEST, "Invalid key id".to_string()))?;
+
+    let permission = key_permission(request.method(), matched_path.as_str());
+
+    let grant = grant.map(|Extension(grant)| grant);
+    authorize_key(&identity, grant.as_ref(), key_id, permission).await?;
+
+    Ok(next.run(request).await)
+}
+
+// 按请求方法和路由模板确定所需的密钥权限，公钥等其余读取接口只需要元数据读取权限
+fn key_permission(method: &Method, path: &str) -> Permission {
+    match (method, path) {
+        (&Method::GET, path) if path.ends_with("/material") => Permission::ReadMaterial,
+        (&Method::PUT, _) => Permission::Update,
+        // 轮换会替换密钥值
+        (&Method::POST, path) if path.ends_with("/rotate") => Permission::Update,
+        // 共享密钥需要能读取密钥内容
+        (&Method::POST, path) if path.ends_with("/shares") => Permission::ReadMaterial,
+        // 服务端加解密和签名只需要使用权限
+        (&Method::POST, path) if ["/encrypt", "/decrypt", "/sign"].iter().any(|suffix| path.ends_with(suffix)) => {
+            Permission::Use
+        }
+        (&Method::DELETE, _) => Permission::Delete,
+        _ => Permission::ReadMetadata,
+    }
+}
+
+/// 对密钥执行默认拒绝的ACL判定，API令牌还需满足令牌自身的作用范围
+///
+/// 不属于调用方当前团队的密钥按不存在处理，返回404。
+pub async fn authorize_key(
+    identity: &Identity,
+    grant: Option<&TokenGrant>,
+    key_id: u64,
+    permission: Permission,
+) -> Result<(), (StatusCode, String)> {
+    let mut conn = database::get_pool()
+        .acquire()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to acquire connection: {:?}", e)))?;
+    let key_team = repository::get_key_team(&mut conn, key_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get key: {:?}", e)))?;
+    if key_team.is_some_and(|team_id| identity.team_id != Some(team_id)) {
+        tracing::warn!(identity_id = identity.id, team_id = identity.team_id, key_id, "Access denied: key outside current team");
+        return Err((StatusCode::NOT_FOUND, "Key not found".to_string()));
+    }
+
+    let decision = access_service::evaluate(&mut conn, identity, key_id, permission)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to evaluate access: {:?}", e)))?;
+
+    if !decision.allowed {
+        tracing::warn!(
+            identity_id = identity.id,
+            role = %identity.role,
+            key_id,
+            permission = %permission,
+            reason = %decision.reason,
+            "Access denied"
+        );
+        return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+    }
+
+    // API令牌还需满足令牌自身的作用范围；密钥不存在时交由处理函数返回404
+    if let Some(grant) = grant {
+        let key = repository::get_key_by_id(database::get_pool(), key_id)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get key: {:?}", e)))?;
+        if key.is_some_and(|key| !grant.allows(&key.name, permission.into())) {
+            tracing::warn!(identity_id = identity.id, token_id = grant.token_id, key_id, permission = %permission, "Access denied: outside token scope");
+            return Err((StatusCode::FORBIDDEN, "Access denied".to_string()));
+        }
+    }
+
+    Ok(())
+}
+
+/// 审计中间件，为每个请求分配request id，并将结果追加到审计哈希链
+pub async fn audit_middleware(
+    matched_path: Option<MatchedPath>,
+    params: Result<Path<HashMap<String, String>>, PathRejection>,
+    mut request: axum::extract::Request,
+    next: Next,
+) -> axum::response::Response {
+    if request.method() == Method::OPTIONS {
+        return next.run(request).await;
+    }
+
+    let request_id = request
+        .headers()
+        .get("x-request-id")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+        .unwrap_or_else(generate_request_id);
+    let source_ip = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string());
+    let method = request.method().to_string();
+    let path = matched_path
+        .as_ref()
+        .map(|mp| mp.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+
+    // 仅/keys路由下的id参数表示密钥id
+    let key_id = match (&params, path.starts_with("/keys/")) {
+        (Ok(Path(params)), true) => params.get("id").and_then(|id| id.parse().ok()),
+        _ => None,
+    };
+
+    request.extensions_mut().insert(RequestContext {
+        request_id: request_id.clone(),
+        source_ip: source_ip.clone(),
+    });
+
+    let mut response = next.run(request).await;
+    let identity = response.extensions().get::<Identity>().cloned();
+    let status = response.status().as_u16();
+
+    let event = NewAuditEvent {
+        actor_id: identity.as_ref().map(|identity| identity.id),
+        actor_name: identity.map(|identity| identity.name),
+        action: audit_service::action_for(&method, &path),
+        key_id,
+        outcome: audit_service::outcome_for(status).to_string(),
+        status_code: status,
+        source_ip,
+        request_id: request_id.clone(),
+    };
+    if let Err(e) = audit_service::record(database::get_pool(), event).await {
+        tracing::error!(error = %e, request_id = %request_id, "Failed to record audit event");
+    }
+
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert("x-request-id", value);
+    }
+    response
+}
+
+/// 生成随机request id
+pub fn generate_request_id() -> String {
+    let mut bytes = [0u8; 16];
+    let _ = getrandom::getrandom(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// 幂等中间件，相同Idempotency-Key的重复请求在重放窗口内返回首次的响应
+pub async fn idempotency_middleware(
+    Extension(identity): Extension<Identity>,
+    request: axum::extract::Request,
+    next: Next,
+) -> Result<axum::response::Response, (StatusCode, String)> {
+    let Some(idempotency_key) = request
+        .headers()
+        .get("idempotency-key")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+    else {
+        return Ok(next.run(request).await);
+    };
+    if idempotency_key.is_empty() || idempotency_key.len() > idempotency_service::MAX_KEY_LENGTH {
+        return Err((StatusCode::BAD_REQUEST, "Invalid Idempotency-Key".to_string()));
+    }
+
+    // 缓存请求体以计算指纹，之后重新组装请求
+    let (parts, body) = request.into_parts();
+    let bytes = axum::body::to_bytes(body, usize::MAX)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Failed to read request body: {:?}", e)))?;
+    let request_hash = idempotency_service::request_hash(parts.method.as_str(), parts.uri.path(), &bytes);
+
+    let pool = database::get_pool();
+    let state = idempotency_service::begin(pool, identity.id, &idempotency_key, &request_hash)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to check idempotency key: {:?}", e)))?;
+
+    match state {
+        IdempotencyState::New => {}
+        IdempotencyState::InProgress => {
+            return Err((StatusCode::CONFLICT, "A request with this Idempotency-Key is in progress".to_string()));
+        }
+        IdempotencyState::Mismatch => {
+            return Err((
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "Idempotency-Key was already used with a different request".to_string(),
+            ));
+        }
+        IdempotencyState::Replay { status_code, body } => {
+            let mut response = axum::response::Response::new(axum::body::Body::from(body));
+            *response.status_mut() = StatusCode::from_u16(status_code).unwrap_or(StatusCode::OK);
+            response.headers_mut().insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+            response.headers_mut().insert("idempotent-replayed", HeaderValue::from_static("true"));
+            return Ok(response);
+        }
+    }
+
+    let response = next
+        .run(axum::extract::Request::from_parts(parts, axum::body::Body::from(bytes)))
+        .await;
+
+    // 服务端错误不保存，允许客户端用同一幂等键重试
+    if response.status().is_server_error() {
+        if let Err(e) = idempotency_service::release(pool, identity.id, &idempotency_key).await {
+            tracing::error!(error = %e, "Failed to release idempotency key");
+        }
+        return Ok(response);
+    }
+
+    let (parts, body) = response.into_parts();
+    let bytes = axum::body::to_bytes(body, usize::MAX)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to read response body: {:?}", e)))?;
+    let stored = idempotency_service::complete(
+        pool,
+        identity.id,
+        &idempotency_key,
+        parts.status.as_u16(),
+        &String::from_utf8_lossy(&bytes),
+    )
+    .await;
+    if let Err(e) = stored {
+        tracing::error!(error = %e, "Failed to store idempotent response");
+    }
+
+    Ok(axum::response::Response::from_parts(parts, axum::body::Body::from(bytes)))
+}
+
+// 从连接信息中取得客户端IP
+fn client_ip(request: &axum::extract::Request) -> Option<std::net::IpAddr> {
+    request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())
+}
+
+// 构造带Retry-After的429响应
+fn too_many_requests(retry_after: Duration, message: &str) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    let secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+    let mut response = (StatusCode::TOO_MANY_REQUESTS, message.to_string()).into_response();
+    response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(secs.max(1)));
+    response
+}
+
+/// 限流中间件，按客户端IP和路由模板使用令牌桶限流
+pub async fn rate_limit_middleware(
+    matched_path: Option<MatchedPath>,
+    request: axum::extract::Request,
+    next: Next,
+) -> axum::response::Response {
+    let Some(ip) = client_ip(&request) else {
+        return next.run(request).await;
+    };
+    let route = matched_path
+        .as_ref()
+        .map(|mp| mp.as_str())
+        .unwrap_or("<unmatched>");
+
+    if let Err(retry_after) = RATE_LIMITER.check(ip, route) {
+        tracing::warn!(%ip, route, "Rate limit exceeded");
+        return too_many_requests(retry_after, "Too many requests");
+    }
+
+    next.run(request).await
+}
+
+/// 锁定中间件，对反复在/keys/{id}上得到401/404或登录失败的客户端按指数退避锁定
+pub async fn lockout_middleware(
+    matched_path: Option<MatchedPath>,
+    request: axum::extract::Request,
+    next: Next,
+) -> axum::response::Response {
+    let Some(ip) = client_ip(&request) else {
+        return next.run(request).await;
+    };
+
+    if let Err(retry_after) = LOCKOUTS.check(ip) {
+        tracing::warn!(%ip, retry_after_secs = retry_after.as_secs(), "Request rejected: client locked out");
+        return too_many_requests(retry_after, "Client temporarily locked out");
+    }
+
+    let watched = matched_path
+        .as_ref()
+        .is_some_and(|mp| mp.as_str().starts_with("/keys/{id}") || mp.as_str() == "/auth/login");
+    let context = request.extensions().get::<RequestContext>().cloned();
+
+    let response = next.run(request).await;
+    if !watched || !matches!(response.status(), StatusCode::UNAUTHORIZED | StatusCode::NOT_FOUND) {
+        return response;
+    }
+
+    let request_id = context.map(|context| context.request_id).unwrap_or_else(generate_request_id);
+    record_enumeration_failure(ip, &request_id).await;
+
+    response
+}
+
+/// 记录一次疑似枚举密钥id的失败，达到阈值时锁定客户端并写入审计日志
+pub async fn record_enumeration_failure(ip: IpAddr, request_id: &str) {
+    if let Some((duration, lockouts)) = LOCKOUTS.record_failure(ip) {
+        tracing::warn!(%ip, lockouts, duration_secs = duration.as_secs(), "Client locked out for key id enumeration");
+
+        let event = NewAuditEvent {
+            actor_id: None,
+            actor_name: None,
+            action: "client.lockout".to_string(),
+            key_id: None,
+            outcome: "denied".to_string(),
+            status_code: StatusCode::TOO_MANY_REQUESTS.as_u16(),
+            source_ip: Some(ip.to_string()),
+            request_id: request_id.to_string(),
+        };
+        if let Err(e) = audit_service::record(database::get_pool(), event).await {
+            tracing::error!(error = %e, "Failed to record lockout audit event");
+        }
+    }
+}
+
+/// 指标中间件，按路由模板记录请求耗时和错误状态码
+pub async fn metrics_middleware(
+    matched_path: Option<MatchedPath>,
+    request: axum::extract::Request,
+    next: Next,
+) -> axum::response::Response {
+    let method = request.method().clone();
+    let route = matched_path
+        .as_ref()
+        .map(|mp| mp.as_str().to_string())
+        .unwrap_or_else(|| "<unmatched>".to_string());
+    let started = std::time::Instant::now();
+
+    let response = next.run(request).await;
+    METRICS.observe_request(method.as_str(), &route, response.status(), started.elapsed());
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::access::{KeyAcl, Role};
+    use crate::model::token::TokenOperation;
+
+    // ecipher-pkcs11以API令牌调用的服务端接口
+    const PKCS11_ROUTES: [(Method, &str); 4] = [
+        (Method::POST, "/keys/{id}/encrypt"),
+        (Method::POST, "/keys/{id}/decrypt"),
+        (Method::POST, "/keys/{id}/sign"),
+        (Method::GET, "/keys/{id}/public-key"),
+    ];
+
+    #[test]
+    fn api_tokens_reach_the_pkcs11_routes() {
+        for (_, path) in &PKCS11_ROUTES {
+            assert!(TOKEN_PATHS.contains(path), "{} is not available to API tokens", path);
+        }
+    }
+
+    #[test]
+    fn server_side_crypto_requires_use() {
+        for (method, path) in &PKCS11_ROUTES[..3] {
+            let permission = key_permission(method, path);
+            assert_eq!(permission, Permission::Use, "{} {}", method, path);
+            assert_eq!(TokenOperation::from(permission), TokenOperation::Use);
+        }
+        assert_eq!(key_permission(&Method::GET, "/keys/{id}/public-key"), Permission::ReadMetadata);
+        assert_eq!(key_permission(&Method::GET, "/keys/{id}/material"), Permission::ReadMaterial);
+        assert_eq!(key_permission(&Method::DELETE, "/keys/{id}"), Permission::Delete);
+    }
+
+    #[test]
+    fn read_only_grant_cannot_rotate() {
+        let operator = Identity {
+            id: 42,
+            name: "operator".to_string(),
+            role: Role::Operator,
+            source_ip: None,
+            team_id: Some(1),
+        };
+        let grants = [KeyAcl {
+            id: 1,
+            key_id: 7,
+            subject_type: "identity".to_string(),
+            subject: "42".to_string(),
+            permission: Permission::ReadMetadata.as_str().to_string(),
+            created_at: chrono::Utc::now(),
+        }];
+
+        let rotate = key_permission(&Method::POST, "/keys/{id}/rotate");
+        assert_eq!(rotate, Permission::Update);
+        assert!(!access_service::decide(&operator, 7, rotate, &grants).allowed);
+
+        let list_versions = key_permission(&Method::GET, "/keys/{id}/versions");
+        assert!(access_service::decide(&operator, 7, list_versions, &grants).allowed);
+    }
+}
@@ -1,2 +1,10 @@
+pub mod archive;
+pub mod certificate;
 pub mod encryption;
-pub mod middleware;
\ No newline at end of file
+pub mod metrics;
+pub mod middleware;
+pub mod rate_limit;
+pub mod totp;
+pub mod keypair;
+pub mod x509;
+pub mod ssh;
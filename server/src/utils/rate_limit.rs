@@ -0,0 +1,282 @@
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+use crate::model::quota::QuotaSubjectType;
+
+// 超过该数量时清理已回满的令牌桶
+const MAX_TRACKED_BUCKETS: usize = 10_000;
+// 超过该数量时清理已过期的锁定记录
+const MAX_TRACKED_LOCKOUTS: usize = 10_000;
+// 请求数配额的计数窗口
+const QUOTA_WINDOW: Duration = Duration::from_secs(60);
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+/// 全局限流器：每个(客户端IP, 路由)一个令牌桶
+pub static RATE_LIMITER: Lazy<RateLimiter> =
+    Lazy::new(|| limiter_from_env().expect("Invalid rate limit configuration"));
+
+fn limiter_from_env() -> Result<RateLimiter, String> {
+    RateLimiter::new(env_or("RATE_LIMIT_BURST", 30.0), env_or("RATE_LIMIT_PER_SEC", 5.0))
+}
+
+/// 启动时校验限流配置，避免到第一个请求才发现配置错误
+pub fn validate_config() -> Result<(), String> {
+    limiter_from_env().map(|_| ())
+}
+
+/// 全局锁定表：按客户端IP记录疑似枚举密钥id的失败请求
+pub static LOCKOUTS: Lazy<LockoutTracker> = Lazy::new(|| {
+    LockoutTracker::new(
+        env_or("LOCKOUT_THRESHOLD", 20),
+        Duration::from_secs(env_or("LOCKOUT_WINDOW_SECS", 60)),
+        Duration::from_secs(env_or("LOCKOUT_BASE_SECS", 60)),
+        Duration::from_secs(env_or("LOCKOUT_MAX_SECS", 24 * 60 * 60)),
+    )
+});
+
+/// 全局请求计数：每个配额对象一个固定的一分钟窗口
+pub static REQUEST_COUNTER: Lazy<RequestCounter> = Lazy::new(RequestCounter::default);
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+pub struct RateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    buckets: Mutex<HashMap<(IpAddr, String), TokenBucket>>,
+}
+
+impl RateLimiter {
+    /// 速率必须为正数，否则计算等待时间时会除以0
+    pub fn new(capacity: f64, refill_per_sec: f64) -> Result<Self, String> {
+        if !(refill_per_sec.is_finite() && refill_per_sec > 0.0) {
+            return Err(format!("RATE_LIMIT_PER_SEC must be a positive number, got {}", refill_per_sec));
+        }
+        if !(capacity.is_finite() && capacity >= 1.0) {
+            return Err(format!("RATE_LIMIT_BURST must be at least 1, got {}", capacity));
+        }
+        Ok(Self {
+            capacity,
+            refill_per_sec,
+            buckets: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// 消耗一个令牌；令牌不足时返回需要等待的时间
+    pub fn check(&self, ip: IpAddr, route: &str) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+
+        if buckets.len() > MAX_TRACKED_BUCKETS {
+            let full_after = Duration::from_secs_f64(self.capacity / self.refill_per_sec);
+            buckets.retain(|_, bucket| now.duration_since(bucket.last_refill) < full_after);
+        }
+
+        let bucket = buckets
+            .entry((ip, route.to_string()))
+            .or_insert(TokenBucket { tokens: self.capacity, last_refill: now });
+
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / self.refill_per_sec))
+        }
+    }
+}
+
+struct LockoutEntry {
+    failures: u32,
+    window_start: Instant,
+    // 累计锁定次数，决定下一次锁定时长
+    lockouts: u32,
+    locked_until: Option<Instant>,
+}
+
+impl LockoutEntry {
+    // 不在锁定期、失败计数窗口已结束，且上次锁定结束已超过最长锁定时长（不再影响下次锁定时长）
+    fn expired(&self, now: Instant, window: Duration, max: Duration) -> bool {
+        if now.duration_since(self.window_start) <= window {
+            return false;
+        }
+        match self.locked_until {
+            Some(until) => until.checked_add(max).is_some_and(|end| end <= now),
+            None => true,
+        }
+    }
+}
+
+/// 当前锁定状态，供管理员查看
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LockoutStatus {
+    #[schema(value_type = String)]
+    pub ip: IpAddr,
+    pub lockouts: u32,
+    pub remaining_secs: u64,
+}
+
+pub struct LockoutTracker {
+    threshold: u32,
+    window: Duration,
+    base: Duration,
+    max: Duration,
+    entries: Mutex<HashMap<IpAddr, LockoutEntry>>,
+}
+
+impl LockoutTracker {
+    pub fn new(threshold: u32, window: Duration, base: Duration, max: Duration) -> Self {
+        Self {
+            threshold,
+            window,
+            base,
+            max,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 客户端处于锁定期时返回剩余时间
+    pub fn check(&self, ip: IpAddr) -> Result<(), Duration> {
+        let now = Instant::now();
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+
+        match entries.get(&ip).and_then(|entry| entry.locked_until) {
+            Some(until) if until > now => Err(until - now),
+            _ => Ok(()),
+        }
+    }
+
+    /// 记录一次失败；达到阈值时开始新的锁定并返回锁定时长
+    ///
+    /// 锁定时长为 base * 2^(n-1)，n为累计锁定次数，上限为max。
+    pub fn record_failure(&self, ip: IpAddr) -> Option<(Duration, u32)> {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+
+        // 轮换来源IP时记录会不断增加，超过上限时清理已过期的记录
+        if entries.len() > MAX_TRACKED_LOCKOUTS {
+            entries.retain(|_, entry| !entry.expired(now, self.window, self.max));
+        }
+
+        let entry = entries.entry(ip).or_insert(LockoutEntry {
+            failures: 0,
+            window_start: now,
+            lockouts: 0,
+            locked_until: None,
+        });
+
+        if now.duration_since(entry.window_start) > self.window {
+            entry.failures = 0;
+            entry.window_start = now;
+        }
+        entry.failures += 1;
+        if entry.failures < self.threshold {
+            return None;
+        }
+
+        entry.lockouts += 1;
+        entry.failures = 0;
+        entry.window_start = now;
+        let duration = self
+            .base
+            .checked_mul(1 << (entry.lockouts - 1).min(16))
+            .unwrap_or(self.max)
+            .min(self.max);
+        entry.locked_until = Some(now + duration);
+
+        Some((duration, entry.lockouts))
+    }
+
+    /// 管理员解除锁定，同时清空累计次数；返回该IP此前是否有记录
+    pub fn unlock(&self, ip: IpAddr) -> bool {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.remove(&ip).is_some()
+    }
+
+    pub fn list(&self) -> Vec<LockoutStatus> {
+        let now = Instant::now();
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+
+        entries
+            .iter()
+            .filter_map(|(ip, entry)| {
+                let until = entry.locked_until.filter(|until| *until > now)?;
+                Some(LockoutStatus {
+                    ip: *ip,
+                    lockouts: entry.lockouts,
+                    remaining_secs: (until - now).as_secs(),
+                })
+            })
+            .collect()
+    }
+}
+
+struct RequestWindow {
+    started: Instant,
+    count: u32,
+}
+
+#[derive(Default)]
+pub struct RequestCounter {
+    windows: Mutex<HashMap<(QuotaSubjectType, String), RequestWindow>>,
+}
+
+impl RequestCounter {
+    /// 计入一次请求，返回当前窗口内的请求数（含本次）及窗口剩余时间
+    pub fn record(&self, subject_type: QuotaSubjectType, subject: &str) -> (u32, Duration) {
+        let now = Instant::now();
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+
+        if windows.len() > MAX_TRACKED_BUCKETS {
+            windows.retain(|_, window| now.duration_since(window.started) < QUOTA_WINDOW);
+        }
+
+        let window = windows
+            .entry((subject_type, subject.to_string()))
+            .or_insert(RequestWindow { started: now, count: 0 });
+        if now.duration_since(window.started) >= QUOTA_WINDOW {
+            window.started = now;
+            window.count = 0;
+        }
+        window.count = window.count.saturating_add(1);
+
+        (window.count, QUOTA_WINDOW - now.duration_since(window.started))
+    }
+
+    /// 当前窗口内的请求数，窗口已结束时为0
+    pub fn current(&self, subject_type: QuotaSubjectType, subject: &str) -> u32 {
+        let windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        windows
+            .get(&(subject_type, subject.to_string()))
+            .filter(|window| window.started.elapsed() < QUOTA_WINDOW)
+            .map_or(0, |window| window.count)
+    }
+
+    /// 所有窗口未结束的配额对象及其请求数，按对象排序
+    pub fn snapshot(&self) -> Vec<(QuotaSubjectType, String, u32)> {
+        let windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        let mut counts: Vec<_> = windows
+            .iter()
+            .filter(|(_, window)| window.started.elapsed() < QUOTA_WINDOW)
+            .map(|((subject_type, subject), window)| (*subject_type, subject.clone(), window.count))
+            .collect();
+        counts.sort();
+        counts
+    }
+}
@@ -0,0 +1,244 @@
+//! OpenSSH公钥和证书的线格式
+//!
+//! 证书按OpenSSH源码中的PROTOCOL.certkeys编码：被签名公钥的字段原样写入证书，
+//! 因此支持任意受支持类型的用户公钥；CA只支持Ed25519和RSA（rsa-sha2-256签名）。
+
+use base64::{
+    engine::general_purpose::{STANDARD, STANDARD_NO_PAD},
+    Engine,
+};
+use rand_core::{OsRng, RngCore};
+use rsa::pkcs8::{EncodePrivateKey, LineEnding};
+use rsa::traits::PublicKeyParts;
+use rsa::RsaPrivateKey;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+
+use crate::model::crypto::SignAlgorithm;
+use crate::model::ssh::{SshCertificateType, SshKeyAlgorithm};
+use crate::utils::keypair::PrivateKey;
+
+// 证书类型名称的后缀，如ssh-ed25519-cert-v01@openssh.com
+const CERTIFICATE_SUFFIX: &str = "-cert-v01@openssh.com";
+// 证书nonce的字节数
+const NONCE_SIZE: usize = 32;
+// 可被签名的公钥类型
+const SUBJECT_KEY_TYPES: &[&str] = &[
+    "ssh-ed25519",
+    "ssh-rsa",
+    "ecdsa-sha2-nistp256",
+    "ecdsa-sha2-nistp384",
+    "ecdsa-sha2-nistp521",
+    "sk-ssh-ed25519@openssh.com",
+    "sk-ecdsa-sha2-nistp256@openssh.com",
+];
+
+/// ssh-keygen签发用户证书时默认包含的扩展
+pub const DEFAULT_USER_EXTENSIONS: &[&str] = &[
+    "permit-X11-forwarding",
+    "permit-agent-forwarding",
+    "permit-port-forwarding",
+    "permit-pty",
+    "permit-user-rc",
+];
+/// OpenSSH支持的关键选项，sshd拒绝包含未知关键选项的证书
+pub const CRITICAL_OPTIONS: &[&str] = &["force-command", "source-address", "verify-required"];
+
+/// authorized_keys格式的SSH公钥
+#[derive(Debug, Clone)]
+pub struct SshPublicKey {
+    pub key_type: String,
+    /// 完整的公钥编码，以类型名称开头
+    pub blob: Vec<u8>,
+    pub comment: Option<String>,
+}
+
+impl SshPublicKey {
+    /// 解析`<类型> <Base64> [注释]`，不接受证书
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let mut fields = line.split_whitespace();
+        let key_type = fields.next().ok_or("Public key is empty")?;
+        if !SUBJECT_KEY_TYPES.contains(&key_type) {
+            return Err(format!("Unsupported SSH public key type: {}", key_type));
+        }
+        let blob = fields
+            .next()
+            .and_then(|encoded| STANDARD.decode(encoded).ok())
+            .ok_or("Public key is not Base64 encoded")?;
+        if read_string(&blob) != Some(key_type.as_bytes()) {
+            return Err("Public key type does not match its encoding".to_string());
+        }
+        let comment = fields.collect::<Vec<_>>().join(" ");
+
+        Ok(Self {
+            key_type: key_type.to_string(),
+            blob,
+            comment: Some(comment).filter(|comment| !comment.is_empty()),
+        })
+    }
+
+    /// 与ssh-keygen -l一致的SHA256指纹
+    pub fn fingerprint(&self) -> String {
+        format!("SHA256:{}", STANDARD_NO_PAD.encode(Sha256::digest(&self.blob)))
+    }
+
+    pub fn to_line(&self) -> String {
+        match &self.comment {
+            Some(comment) => format!("{} {} {}", self.key_type, STANDARD.encode(&self.blob), comment),
+            None => format!("{} {}", self.key_type, STANDARD.encode(&self.blob)),
+        }
+    }
+
+    // 类型名称之后的公钥字段，证书中紧随nonce写入
+    fn key_fields(&self) -> &[u8] {
+        &self.blob[4 + self.key_type.len()..]
+    }
+}
+
+/// 证书内容，签名时按此顺序编码
+pub struct CertificateSpec<'a> {
+    pub serial: u64,
+    pub certificate_type: SshCertificateType,
+    pub key_identifier: &'a str,
+    pub principals: &'a [String],
+    pub valid_after: chrono::DateTime<chrono::Utc>,
+    pub valid_before: chrono::DateTime<chrono::Utc>,
+    pub critical_options: &'a BTreeMap<String, String>,
+    pub extensions: &'a BTreeSet<String>,
+}
+
+/// 生成私钥，返回PKCS#8 PEM
+pub fn generate_key(algorithm: SshKeyAlgorithm) -> Result<String, Box<dyn Error>> {
+    match algorithm {
+        SshKeyAlgorithm::Ed25519 => {
+            let mut seed = [0u8; 32];
+            OsRng.fill_bytes(&mut seed);
+            let key = ed25519_dalek::SigningKey::from_bytes(&seed);
+            Ok(key.to_pkcs8_pem(LineEnding::LF)?.to_string())
+        }
+        SshKeyAlgorithm::Rsa3072 => {
+            let key = RsaPrivateKey::new(&mut OsRng, 3072)?;
+            Ok(key.to_pkcs8_pem(LineEnding::LF)?.to_string())
+        }
+    }
+}
+
+/// 私钥对应的SSH公钥，只支持Ed25519和RSA
+pub fn public_key(private_key: &PrivateKey, comment: Option<String>) -> Result<SshPublicKey, Box<dyn Error>> {
+    let mut blob = Vec::new();
+    let key_type = match private_key {
+        PrivateKey::Ed25519(key) => {
+            put_string(&mut blob, b"ssh-ed25519");
+            put_string(&mut blob, key.verifying_key().as_bytes());
+            "ssh-ed25519"
+        }
+        PrivateKey::Rsa(key) => {
+            put_string(&mut blob, b"ssh-rsa");
+            put_mpint(&mut blob, &key.e().to_bytes_be());
+            put_mpint(&mut blob, &key.n().to_bytes_be());
+            "ssh-rsa"
+        }
+        PrivateKey::EcP256(_) => return Err("SSH keys must be Ed25519 or RSA".into()),
+    };
+
+    Ok(SshPublicKey { key_type: key_type.to_string(), blob, comment })
+}
+
+/// 使用CA私钥签发证书，返回OpenSSH格式的单行证书
+pub fn sign_certificate(
+    ca_key: &PrivateKey,
+    subject: &SshPublicKey,
+    spec: &CertificateSpec,
+) -> Result<String, Box<dyn Error>> {
+    let signature_key = public_key(ca_key, None)?;
+    let certificate_type = format!("{}{}", subject.key_type, CERTIFICATE_SUFFIX);
+    let mut nonce = [0u8; NONCE_SIZE];
+    OsRng.fill_bytes(&mut nonce);
+
+    let mut certificate = Vec::new();
+    put_string(&mut certificate, certificate_type.as_bytes());
+    put_string(&mut certificate, &nonce);
+    certificate.extend_from_slice(subject.key_fields());
+    certificate.extend_from_slice(&spec.serial.to_be_bytes());
+    certificate.extend_from_slice(&spec.certificate_type.code().to_be_bytes());
+    put_string(&mut certificate, spec.key_identifier.as_bytes());
+
+    let mut principals = Vec::new();
+    for principal in spec.principals {
+        put_string(&mut principals, principal.as_bytes());
+    }
+    put_string(&mut certificate, &principals);
+    certificate.extend_from_slice(&timestamp(spec.valid_after).to_be_bytes());
+    certificate.extend_from_slice(&timestamp(spec.valid_before).to_be_bytes());
+
+    // 关键选项和扩展须按名称排序；选项值本身再编码为string，无值时为空
+    let mut critical_options = Vec::new();
+    for (name, value) in spec.critical_options {
+        put_string(&mut critical_options, name.as_bytes());
+        let mut data = Vec::new();
+        if !value.is_empty() {
+            put_string(&mut data, value.as_bytes());
+        }
+        put_string(&mut critical_options, &data);
+    }
+    put_string(&mut certificate, &critical_options);
+
+    let mut extensions = Vec::new();
+    for name in spec.extensions {
+        put_string(&mut extensions, name.as_bytes());
+        put_string(&mut extensions, b"");
+    }
+    put_string(&mut certificate, &extensions);
+
+    // reserved
+    put_string(&mut certificate, b"");
+    put_string(&mut certificate, &signature_key.blob);
+
+    let signature = sign(ca_key, &certificate)?;
+    put_string(&mut certificate, &signature);
+
+    Ok(format!("{} {} {}", certificate_type, STANDARD.encode(certificate), spec.key_identifier))
+}
+
+// SSH签名：string算法名称 + string签名值
+fn sign(private_key: &PrivateKey, data: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    let (algorithm, signature) = match private_key {
+        PrivateKey::Ed25519(_) => ("ssh-ed25519", private_key.sign(SignAlgorithm::Ed25519, data, false)?),
+        PrivateKey::Rsa(_) => ("rsa-sha2-256", private_key.sign(SignAlgorithm::RsaPkcs1Sha256, data, false)?),
+        PrivateKey::EcP256(_) => return Err("SSH CA keys must be Ed25519 or RSA".into()),
+    };
+
+    let mut encoded = Vec::new();
+    put_string(&mut encoded, algorithm.as_bytes());
+    put_string(&mut encoded, &signature);
+    Ok(encoded)
+}
+
+// 早于1970年的时间按0处理
+fn timestamp(time: chrono::DateTime<chrono::Utc>) -> u64 {
+    time.timestamp().max(0) as u64
+}
+
+fn put_string(buffer: &mut Vec<u8>, value: &[u8]) {
+    buffer.extend_from_slice(&(value.len() as u32).to_be_bytes());
+    buffer.extend_from_slice(value);
+}
+
+// 无符号大整数，最高位为1时补一个零字节
+fn put_mpint(buffer: &mut Vec<u8>, value: &[u8]) {
+    let start = value.iter().position(|byte| *byte != 0).unwrap_or(value.len());
+    let value = &value[start..];
+    if value.first().is_some_and(|byte| byte & 0x80 != 0) {
+        buffer.extend_from_slice(&(value.len() as u32 + 1).to_be_bytes());
+        buffer.push(0);
+        buffer.extend_from_slice(value);
+    } else {
+        put_string(buffer, value);
+    }
+}
+
+fn read_string(buffer: &[u8]) -> Option<&[u8]> {
+    let length = u32::from_be_bytes(buffer.get(..4)?.try_into().ok()?) as usize;
+    buffer.get(4..4 + length)
+}
@@ -0,0 +1,86 @@
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use std::error::Error;
+
+// RFC 6238默认参数：30秒时间步、6位数字、HMAC-SHA1
+const STEP_SECS: i64 = 30;
+const DIGITS: u32 = 6;
+// 种子长度（字节），RFC 4226建议至少160位
+const SECRET_SIZE: usize = 20;
+// 允许前后各一个时间步的时钟偏差
+const SKEW_STEPS: i64 = 1;
+
+const BASE32: base32::Alphabet = base32::Alphabet::Rfc4648 { padding: false };
+
+/// 生成新的TOTP种子，返回Base32编码
+pub fn generate_secret() -> Result<String, Box<dyn Error>> {
+    let mut bytes = [0u8; SECRET_SIZE];
+    getrandom::getrandom(&mut bytes)?;
+    Ok(base32::encode(BASE32, &bytes))
+}
+
+/// 计算指定时间步的验证码（RFC 4226动态截断）
+fn code_at(secret: &[u8], step: i64) -> Result<u32, Box<dyn Error>> {
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret)?;
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([digest[offset], digest[offset + 1], digest[offset + 2], digest[offset + 3]]) & 0x7fff_ffff;
+    Ok(binary % 10u32.pow(DIGITS))
+}
+
+/// 校验验证码，成功时返回匹配的时间步
+///
+/// 调用方应保证返回的时间步大于上一次成功的时间步，以防止重放。
+pub fn verify(secret_base32: &str, code: &str, unix_time: i64) -> Result<Option<i64>, Box<dyn Error>> {
+    let code = code.trim();
+    if code.len() != DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return Ok(None);
+    }
+    let code: u32 = code.parse()?;
+    let secret = base32::decode(BASE32, secret_base32).ok_or("Invalid TOTP secret")?;
+
+    let current = unix_time.div_euclid(STEP_SECS);
+    for step in current - SKEW_STEPS..=current + SKEW_STEPS {
+        if code_at(&secret, step)? == code {
+            return Ok(Some(step));
+        }
+    }
+    Ok(None)
+}
+
+// 按RFC 3986对URI组件做百分号编码
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// 生成认证器App使用的otpauth://配置URI
+pub fn provisioning_uri(issuer: &str, account: &str, secret_base32: &str) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        percent_encode(issuer),
+        percent_encode(account),
+        secret_base32,
+        percent_encode(issuer),
+        DIGITS,
+        STEP_SECS
+    )
+}
+
+/// 将配置URI渲染为SVG格式的二维码
+pub fn qr_svg(uri: &str) -> Result<String, Box<dyn Error>> {
+    let code = qrcode::QrCode::new(uri.as_bytes())?;
+    Ok(code
+        .render::<qrcode::render::svg::Color>()
+        .min_dimensions(200, 200)
+        .build())
+}
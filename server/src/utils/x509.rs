@@ -0,0 +1,736 @@
+//! 内置CA的证书、CRL和OCSP编码
+//!
+//! 证书和CRL由rcgen生成；OCSP只实现RFC 6960中由CA直接签名的基本响应，
+//! 请求和响应的DER在此手工编解码。全部操作只依赖CA证书和私钥，不访问数据库和网络。
+
+use rcgen::{
+    BasicConstraints, Certificate, CertificateParams, CertificateRevocationListParams, CrlDistributionPoint,
+    CustomExtension, DistinguishedName, DnType, ExtendedKeyUsagePurpose, IsCa, KeyIdMethod, KeyPair,
+    KeyUsagePurpose, PublicKeyData, RevokedCertParams, SerialNumber,
+};
+use rand_core::{OsRng, RngCore};
+use rsa::pkcs8::{EncodePrivateKey, LineEnding};
+use rsa::RsaPrivateKey;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use time::OffsetDateTime;
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+use crate::model::crypto::{KeyType, SignAlgorithm};
+use crate::model::pki::{CertificateProfile, CertificateStatus, KeyAlgorithm, RevocationReason, RevokedCertificate};
+use crate::utils::keypair::{certificate_der, PrivateKey};
+
+// DER标签
+const TAG_BOOLEAN: u8 = 0x01;
+const TAG_INTEGER: u8 = 0x02;
+const TAG_BIT_STRING: u8 = 0x03;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_OID: u8 = 0x06;
+const TAG_ENUMERATED: u8 = 0x0A;
+const TAG_GENERALIZED_TIME: u8 = 0x18;
+const TAG_SEQUENCE: u8 = 0x30;
+// 上下文标签，[n] EXPLICIT为0xA0 | n
+const TAG_CONTEXT_0: u8 = 0xA0;
+const TAG_CONTEXT_1: u8 = 0xA1;
+const TAG_CONTEXT_2: u8 = 0xA2;
+// GeneralName中的uniformResourceIdentifier [6] IMPLICIT
+const TAG_URI: u8 = 0x86;
+
+// 对象标识符的DER内容
+const OID_SHA1: &[u8] = &[0x2B, 0x0E, 0x03, 0x02, 0x1A];
+const OID_SHA256: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01];
+const OID_OCSP_BASIC: &[u8] = &[0x2B, 0x06, 0x01, 0x05, 0x05, 0x07, 0x30, 0x01, 0x01];
+const OID_OCSP_NONCE: &[u8] = &[0x2B, 0x06, 0x01, 0x05, 0x05, 0x07, 0x30, 0x01, 0x02];
+const OID_AD_OCSP: &[u8] = &[0x2B, 0x06, 0x01, 0x05, 0x05, 0x07, 0x30, 0x01];
+const OID_AD_CA_ISSUERS: &[u8] = &[0x2B, 0x06, 0x01, 0x05, 0x05, 0x07, 0x30, 0x02];
+// authorityInfoAccess扩展
+const OID_AUTHORITY_INFO_ACCESS: &[u64] = &[1, 3, 6, 1, 5, 5, 7, 1, 1];
+
+// OCSP响应签名算法的AlgorithmIdentifier
+const ALGORITHM_SHA256_RSA: &[u8] = &[
+    0x30, 0x0D, 0x06, 0x09, 0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x0B, 0x05, 0x00,
+];
+const ALGORITHM_ECDSA_SHA256: &[u8] = &[0x30, 0x0A, 0x06, 0x08, 0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x04, 0x03, 0x02];
+const ALGORITHM_ED25519: &[u8] = &[0x30, 0x05, 0x06, 0x03, 0x2B, 0x65, 0x70];
+
+// 序列号的字节数
+const SERIAL_SIZE: usize = 16;
+
+/// OCSP响应状态（OCSPResponseStatus）
+#[derive(Debug, Clone, Copy)]
+pub enum OcspResponseStatus {
+    MalformedRequest = 1,
+    InternalError = 2,
+}
+
+/// 签发的证书中指向CA的地址
+pub struct IssuerUrls {
+    pub crl: String,
+    pub ocsp: String,
+    pub ca_issuers: String,
+}
+
+/// OCSP请求中的CertID
+#[derive(Debug, Clone)]
+pub struct OcspCertId {
+    // 原始DER，原样写回响应
+    raw: Vec<u8>,
+    // 不支持的摘要算法为None，此时按未知证书处理
+    hash: Option<OcspHash>,
+    issuer_name_hash: Vec<u8>,
+    issuer_key_hash: Vec<u8>,
+    /// 小写十六进制序列号
+    pub serial_number: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum OcspHash {
+    Sha1,
+    Sha256,
+}
+
+impl OcspHash {
+    fn digest(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            OcspHash::Sha1 => Sha1::digest(data).to_vec(),
+            OcspHash::Sha256 => Sha256::digest(data).to_vec(),
+        }
+    }
+}
+
+/// 解析后的OCSP请求
+#[derive(Debug, Clone)]
+pub struct OcspRequest {
+    pub certificates: Vec<OcspCertId>,
+    // nonce扩展的extnValue内容，原样写回响应
+    nonce: Option<Vec<u8>>,
+}
+
+/// 用于签发证书、CRL和OCSP响应的CA
+pub struct Issuer {
+    certificate: Certificate,
+    key_pair: KeyPair,
+    private_key: PrivateKey,
+    // 主题名称的DER，用于匹配OCSP请求中的issuerNameHash
+    name_der: Vec<u8>,
+    // subjectPublicKey的内容，用于匹配issuerKeyHash
+    public_key_bits: Vec<u8>,
+}
+
+impl Issuer {
+    /// 由PEM格式的CA证书和PKCS#8私钥构造
+    pub fn from_pem(certificate_pem: &str, private_key_pem: &str) -> Result<Self, Box<dyn Error>> {
+        let key_pair = KeyPair::from_pem(private_key_pem)?;
+        // rcgen通过CA证书的参数和私钥重建签发者，主题和密钥标识与原证书一致
+        let certificate = CertificateParams::from_ca_cert_pem(certificate_pem)?.self_signed(&key_pair)?;
+
+        let der = certificate_der(certificate_pem).ok_or("CA certificate is not PEM encoded")?;
+        let (_, parsed) = X509Certificate::from_der(&der).map_err(|_| "CA certificate could not be parsed")?;
+        let name_der = parsed.subject().as_raw().to_vec();
+        let public_key_bits = parsed.public_key().subject_public_key.data.to_vec();
+
+        Ok(Self {
+            certificate,
+            key_pair,
+            private_key: PrivateKey::from_pem(private_key_pem)?,
+            name_der,
+            public_key_bits,
+        })
+    }
+
+    /// 由CA签发证书
+    pub fn issue(&self, params: CertificateParams, public_key: &impl PublicKeyData) -> Result<Certificate, Box<dyn Error>> {
+        Ok(params.signed_by(public_key, &self.certificate, &self.key_pair)?)
+    }
+
+    /// 生成DER格式的CRL
+    pub fn crl(
+        &self,
+        crl_number: u64,
+        this_update: chrono::DateTime<chrono::Utc>,
+        next_update: chrono::DateTime<chrono::Utc>,
+        revoked: &[RevokedCertificate],
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        let revoked_certs = revoked
+            .iter()
+            .map(|certificate| -> Result<RevokedCertParams, Box<dyn Error>> {
+                Ok(RevokedCertParams {
+                    serial_number: SerialNumber::from_slice(&hex::decode(&certificate.serial_number)?),
+                    revocation_time: offset(certificate.revoked_at)?,
+                    reason_code: Some(crl_reason(certificate.reason)),
+                    invalidity_date: None,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let params = CertificateRevocationListParams {
+            this_update: offset(this_update)?,
+            next_update: offset(next_update)?,
+            crl_number: SerialNumber::from(crl_number),
+            issuing_distribution_point: None,
+            revoked_certs,
+            key_identifier_method: KeyIdMethod::Sha256,
+        };
+        Ok(params.signed_by(&self.certificate, &self.key_pair)?.der().to_vec())
+    }
+
+    /// CertID中的签发者摘要是否与该CA一致
+    pub fn matches(&self, id: &OcspCertId) -> bool {
+        id.hash.is_some_and(|hash| {
+            hash.digest(&self.name_der) == id.issuer_name_hash && hash.digest(&self.public_key_bits) == id.issuer_key_hash
+        })
+    }
+
+    /// 生成由CA签名的OCSP成功响应，`statuses`与请求中的证书一一对应
+    pub fn ocsp_response(
+        &self,
+        request: &OcspRequest,
+        statuses: &[CertificateStatus],
+        produced_at: chrono::DateTime<chrono::Utc>,
+        next_update: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut responses = Vec::new();
+        for (id, status) in request.certificates.iter().zip(statuses) {
+            let cert_status = match status {
+                // good [0] IMPLICIT NULL
+                CertificateStatus::Good => vec![0x80, 0x00],
+                CertificateStatus::Revoked(revoked) => tlv(
+                    TAG_CONTEXT_1,
+                    &[
+                        generalized_time(revoked.revoked_at),
+                        tlv(TAG_CONTEXT_0, &tlv(TAG_ENUMERATED, &[revoked.reason.code()])),
+                    ]
+                    .concat(),
+                ),
+                // unknown [2] IMPLICIT NULL
+                CertificateStatus::Unknown => vec![0x82, 0x00],
+            };
+            responses.extend(sequence(&[
+                &id.raw,
+                &cert_status,
+                &generalized_time(produced_at),
+                &tlv(TAG_CONTEXT_0, &generalized_time(next_update)),
+            ]));
+        }
+
+        // responderID使用byKey，即CA公钥的SHA-1
+        let mut response_data = vec![
+            tlv(TAG_CONTEXT_2, &tlv(TAG_OCTET_STRING, &Sha1::digest(&self.public_key_bits))),
+            generalized_time(produced_at),
+            tlv(TAG_SEQUENCE, &responses),
+        ];
+        if let Some(nonce) = &request.nonce {
+            let extension = sequence(&[&tlv(TAG_OID, OID_OCSP_NONCE), &tlv(TAG_OCTET_STRING, nonce)]);
+            response_data.push(tlv(TAG_CONTEXT_1, &tlv(TAG_SEQUENCE, &extension)));
+        }
+        let tbs_response_data = tlv(TAG_SEQUENCE, &response_data.concat());
+
+        let (algorithm, signature) = self.sign(&tbs_response_data)?;
+        let basic_response = sequence(&[&tbs_response_data, algorithm, &bit_string(&signature)]);
+        let response_bytes = sequence(&[&tlv(TAG_OID, OID_OCSP_BASIC), &tlv(TAG_OCTET_STRING, &basic_response)]);
+        Ok(sequence(&[&tlv(TAG_ENUMERATED, &[0]), &tlv(TAG_CONTEXT_0, &response_bytes)]))
+    }
+
+    // 使用CA私钥签名，返回AlgorithmIdentifier和X.509格式的签名值
+    fn sign(&self, data: &[u8]) -> Result<(&'static [u8], Vec<u8>), Box<dyn Error>> {
+        match self.private_key.key_type() {
+            KeyType::Rsa => Ok((ALGORITHM_SHA256_RSA, self.private_key.sign(SignAlgorithm::RsaPkcs1Sha256, data, false)?)),
+            KeyType::EcP256 => {
+                // keypair返回定长r||s，X.509中为DER编码的Ecdsa-Sig-Value
+                let signature = self.private_key.sign(SignAlgorithm::EcdsaSha256, data, false)?;
+                let signature = p256::ecdsa::Signature::from_slice(&signature)?;
+                Ok((ALGORITHM_ECDSA_SHA256, signature.to_der().as_bytes().to_vec()))
+            }
+            KeyType::Ed25519 => Ok((ALGORITHM_ED25519, self.private_key.sign(SignAlgorithm::Ed25519, data, false)?)),
+            KeyType::Aes => Err("CA key must be a private key".into()),
+        }
+    }
+}
+
+/// 生成新的私钥
+pub fn generate_key(algorithm: KeyAlgorithm) -> Result<KeyPair, Box<dyn Error>> {
+    match algorithm {
+        KeyAlgorithm::EcdsaP256 => Ok(KeyPair::generate_for(&rcgen::PKCS_ECDSA_P256_SHA256)?),
+        KeyAlgorithm::Ed25519 => Ok(KeyPair::generate_for(&rcgen::PKCS_ED25519)?),
+        // rcgen不能生成RSA密钥，由rsa生成后以PKCS#8导入
+        KeyAlgorithm::Rsa2048 => {
+            let key = RsaPrivateKey::new(&mut OsRng, 2048)?;
+            Ok(KeyPair::from_pem(&key.to_pkcs8_pem(LineEnding::LF)?)?)
+        }
+    }
+}
+
+/// 生成随机的正序列号，首字节非零以保证DER编码无前导零
+pub fn random_serial() -> Vec<u8> {
+    let mut serial = vec![0u8; SERIAL_SIZE];
+    OsRng.fill_bytes(&mut serial);
+    serial[0] = (serial[0] & 0x7F).max(1);
+    serial
+}
+
+/// 序列号的小写十六进制表示，忽略前导零字节
+pub fn serial_hex(serial: &[u8]) -> String {
+    let start = serial.iter().position(|byte| *byte != 0).unwrap_or(serial.len());
+    hex::encode(&serial[start..])
+}
+
+pub fn distinguished_name(common_name: &str, organization: Option<&str>, country: Option<&str>) -> DistinguishedName {
+    let mut name = DistinguishedName::new();
+    if let Some(country) = country {
+        name.push(DnType::CountryName, country);
+    }
+    if let Some(organization) = organization {
+        name.push(DnType::OrganizationName, organization);
+    }
+    name.push(DnType::CommonName, common_name);
+    name
+}
+
+/// 按模板设置序列号、有效期和扩展；CSR中请求的其它扩展一律丢弃
+///
+/// `urls`为空时表示自签名的根CA证书。
+pub fn apply_profile(
+    params: &mut CertificateParams,
+    profile: CertificateProfile,
+    serial: &[u8],
+    not_before: chrono::DateTime<chrono::Utc>,
+    not_after: chrono::DateTime<chrono::Utc>,
+    path_length: Option<u8>,
+    urls: Option<&IssuerUrls>,
+) -> Result<(), Box<dyn Error>> {
+    params.serial_number = Some(SerialNumber::from_slice(serial));
+    params.not_before = offset(not_before)?;
+    params.not_after = offset(not_after)?;
+    params.custom_extensions.clear();
+
+    let (is_ca, key_usages, extended_key_usages) = match profile {
+        CertificateProfile::Ca => (
+            IsCa::Ca(path_length.map_or(BasicConstraints::Unconstrained, BasicConstraints::Constrained)),
+            vec![KeyUsagePurpose::KeyCertSign, KeyUsagePurpose::CrlSign, KeyUsagePurpose::DigitalSignature],
+            vec![],
+        ),
+        CertificateProfile::Server => (
+            IsCa::ExplicitNoCa,
+            vec![KeyUsagePurpose::DigitalSignature, KeyUsagePurpose::KeyEncipherment],
+            vec![ExtendedKeyUsagePurpose::ServerAuth],
+        ),
+        CertificateProfile::Client => (
+            IsCa::ExplicitNoCa,
+            vec![KeyUsagePurpose::DigitalSignature],
+            vec![ExtendedKeyUsagePurpose::ClientAuth],
+        ),
+        CertificateProfile::CodeSigning => (
+            IsCa::ExplicitNoCa,
+            vec![KeyUsagePurpose::DigitalSignature],
+            vec![ExtendedKeyUsagePurpose::CodeSigning],
+        ),
+    };
+    params.is_ca = is_ca;
+    params.key_usages = key_usages;
+    params.extended_key_usages = extended_key_usages;
+
+    match urls {
+        Some(urls) => {
+            params.use_authority_key_identifier_extension = true;
+            params.crl_distribution_points = vec![CrlDistributionPoint { uris: vec![urls.crl.clone()] }];
+            params.custom_extensions.push(authority_info_access(urls));
+        }
+        None => {
+            params.use_authority_key_identifier_extension = false;
+            params.crl_distribution_points.clear();
+        }
+    }
+    Ok(())
+}
+
+/// 读取DER证书的主题
+pub fn subject_of(der: &[u8]) -> Result<String, Box<dyn Error>> {
+    let (_, certificate) = X509Certificate::from_der(der).map_err(|_| "Certificate could not be parsed")?;
+    Ok(certificate.subject().to_string())
+}
+
+/// 解析DER格式的OCSP请求，忽略请求签名和除nonce外的扩展
+pub fn parse_ocsp_request(der: &[u8]) -> Option<OcspRequest> {
+    let Some((TAG_SEQUENCE, request, _, _)) = read_tlv(der) else {
+        return None;
+    };
+    let Some((TAG_SEQUENCE, tbs_request, _, _)) = read_tlv(request) else {
+        return None;
+    };
+
+    // 跳过可选的version [0]和requestorName [1]
+    let (mut tag, mut request_list, _, mut rest) = read_tlv(tbs_request)?;
+    while tag == TAG_CONTEXT_0 || tag == TAG_CONTEXT_1 {
+        (tag, request_list, _, rest) = read_tlv(rest)?;
+    }
+    if tag != TAG_SEQUENCE {
+        return None;
+    }
+
+    let mut certificates = Vec::new();
+    while !request_list.is_empty() {
+        let Some((TAG_SEQUENCE, single_request, _, next)) = read_tlv(request_list) else {
+            return None;
+        };
+        let Some((TAG_SEQUENCE, cert_id, raw, _)) = read_tlv(single_request) else {
+            return None;
+        };
+        certificates.push(parse_cert_id(cert_id, raw)?);
+        request_list = next;
+    }
+    if certificates.is_empty() {
+        return None;
+    }
+
+    // requestExtensions [2]中只读取nonce
+    let mut nonce = None;
+    if let Some((TAG_CONTEXT_2, extensions, _, _)) = read_tlv(rest) {
+        let (_, mut extensions, _, _) = read_tlv(extensions)?;
+        while !extensions.is_empty() {
+            let (_, extension, _, next) = read_tlv(extensions)?;
+            let (_, oid, _, fields) = read_tlv(extension)?;
+            if oid == OID_OCSP_NONCE {
+                let (mut tag, mut value, _, rest) = read_tlv(fields)?;
+                if tag == TAG_BOOLEAN {
+                    (tag, value, _, _) = read_tlv(rest)?;
+                }
+                if tag == TAG_OCTET_STRING {
+                    nonce = Some(value.to_vec());
+                }
+            }
+            extensions = next;
+        }
+    }
+
+    Some(OcspRequest { certificates, nonce })
+}
+
+/// 只含状态的OCSP错误响应
+pub fn ocsp_error(status: OcspResponseStatus) -> Vec<u8> {
+    sequence(&[&tlv(TAG_ENUMERATED, &[status as u8])])
+}
+
+fn parse_cert_id(cert_id: &[u8], raw: &[u8]) -> Option<OcspCertId> {
+    let Some((TAG_SEQUENCE, algorithm, _, rest)) = read_tlv(cert_id) else {
+        return None;
+    };
+    let Some((TAG_OID, oid, _, _)) = read_tlv(algorithm) else {
+        return None;
+    };
+    let hash = match oid {
+        OID_SHA1 => Some(OcspHash::Sha1),
+        OID_SHA256 => Some(OcspHash::Sha256),
+        _ => None,
+    };
+    let Some((TAG_OCTET_STRING, issuer_name_hash, _, rest)) = read_tlv(rest) else {
+        return None;
+    };
+    let Some((TAG_OCTET_STRING, issuer_key_hash, _, rest)) = read_tlv(rest) else {
+        return None;
+    };
+    let Some((TAG_INTEGER, serial, _, _)) = read_tlv(rest) else {
+        return None;
+    };
+
+    Some(OcspCertId {
+        raw: raw.to_vec(),
+        hash,
+        issuer_name_hash: issuer_name_hash.to_vec(),
+        issuer_key_hash: issuer_key_hash.to_vec(),
+        serial_number: serial_hex(serial),
+    })
+}
+
+// 证书中的authorityInfoAccess扩展，包含OCSP地址和CA证书地址
+fn authority_info_access(urls: &IssuerUrls) -> CustomExtension {
+    let access = |method: &[u8], uri: &str| sequence(&[&tlv(TAG_OID, method), &tlv(TAG_URI, uri.as_bytes())]);
+    let content = sequence(&[&access(OID_AD_OCSP, &urls.ocsp), &access(OID_AD_CA_ISSUERS, &urls.ca_issuers)]);
+    CustomExtension::from_oid_content(OID_AUTHORITY_INFO_ACCESS, content)
+}
+
+fn crl_reason(reason: RevocationReason) -> rcgen::RevocationReason {
+    match reason {
+        RevocationReason::Unspecified => rcgen::RevocationReason::Unspecified,
+        RevocationReason::KeyCompromise => rcgen::RevocationReason::KeyCompromise,
+        RevocationReason::CaCompromise => rcgen::RevocationReason::CaCompromise,
+        RevocationReason::AffiliationChanged => rcgen::RevocationReason::AffiliationChanged,
+        RevocationReason::Superseded => rcgen::RevocationReason::Superseded,
+        RevocationReason::CessationOfOperation => rcgen::RevocationReason::CessationOfOperation,
+        RevocationReason::PrivilegeWithdrawn => rcgen::RevocationReason::PrivilegeWithdrawn,
+    }
+}
+
+fn offset(time: chrono::DateTime<chrono::Utc>) -> Result<OffsetDateTime, Box<dyn Error>> {
+    Ok(OffsetDateTime::from_unix_timestamp(time.timestamp())?)
+}
+
+fn generalized_time(time: chrono::DateTime<chrono::Utc>) -> Vec<u8> {
+    tlv(TAG_GENERALIZED_TIME, time.format("%Y%m%d%H%M%SZ").to_string().as_bytes())
+}
+
+fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut encoded = vec![tag];
+    if content.len() < 0x80 {
+        encoded.push(content.len() as u8);
+    } else {
+        let length = content.len().to_be_bytes();
+        let start = length.iter().position(|byte| *byte != 0).unwrap_or(length.len() - 1);
+        encoded.push(0x80 | (length.len() - start) as u8);
+        encoded.extend_from_slice(&length[start..]);
+    }
+    encoded.extend_from_slice(content);
+    encoded
+}
+
+// 不含未用位的BIT STRING
+fn bit_string(content: &[u8]) -> Vec<u8> {
+    let mut value = vec![0];
+    value.extend_from_slice(content);
+    tlv(TAG_BIT_STRING, &value)
+}
+
+fn sequence(parts: &[&[u8]]) -> Vec<u8> {
+    tlv(TAG_SEQUENCE, &parts.concat())
+}
+
+// 读取一个TLV，返回标签、内容、完整编码和剩余数据；只支持单字节标签
+fn read_tlv(input: &[u8]) -> Option<(u8, &[u8], &[u8], &[u8])> {
+    let (&tag, rest) = input.split_first()?;
+    let (&first, rest) = rest.split_first()?;
+    let (length, rest) = if first < 0x80 {
+        (first as usize, rest)
+    } else {
+        let count = (first & 0x7F) as usize;
+        if count == 0 || count > 4 || rest.len() < count {
+            return None;
+        }
+        let length = rest[..count].iter().fold(0usize, |length, byte| length << 8 | *byte as usize);
+        (length, &rest[count..])
+    };
+    if rest.len() < length {
+        return None;
+    }
+    let header = input.len() - rest.len();
+    Some((tag, &rest[..length], &input[..header + length], &rest[length..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use p256::ecdsa::signature::Verifier;
+    use p256::ecdsa::{Signature, VerifyingKey};
+    use x509_parser::prelude::{CertificateRevocationList, ParsedExtension};
+
+    // 测试CA使用P-256密钥，返回签发者和CA证书的DER
+    fn test_ca(common_name: &str) -> (Issuer, Vec<u8>) {
+        let key_pair = generate_key(KeyAlgorithm::EcdsaP256).unwrap();
+        let mut params = CertificateParams::default();
+        params.distinguished_name = distinguished_name(common_name, Some("ecipher"), Some("CN"));
+        let now = chrono::Utc::now();
+        apply_profile(
+            &mut params,
+            CertificateProfile::Ca,
+            &random_serial(),
+            now - Duration::minutes(5),
+            now + Duration::days(30),
+            Some(0),
+            None,
+        )
+        .unwrap();
+        let certificate = params.self_signed(&key_pair).unwrap();
+
+        let issuer = Issuer::from_pem(&certificate.pem(), &key_pair.serialize_pem()).unwrap();
+        (issuer, certificate.der().to_vec())
+    }
+
+    fn issue_leaf(issuer: &Issuer, serial: &[u8]) -> Vec<u8> {
+        let key_pair = generate_key(KeyAlgorithm::Ed25519).unwrap();
+        let mut params = CertificateParams::new(vec!["service.example.com".to_string()]).unwrap();
+        params.distinguished_name = distinguished_name("service.example.com", None, None);
+        let urls = IssuerUrls {
+            crl: "http://127.0.0.1:3000/pki/cas/1/crl".to_string(),
+            ocsp: "http://127.0.0.1:3000/pki/cas/1/ocsp".to_string(),
+            ca_issuers: "http://127.0.0.1:3000/pki/cas/1/certificate".to_string(),
+        };
+        let now = chrono::Utc::now();
+        apply_profile(
+            &mut params,
+            CertificateProfile::Server,
+            serial,
+            now - Duration::minutes(5),
+            now + Duration::days(1),
+            None,
+            Some(&urls),
+        )
+        .unwrap();
+        issuer.issue(params, &key_pair).unwrap().der().to_vec()
+    }
+
+    // 用CA证书中的公钥校验X.509格式的ECDSA-SHA256签名
+    fn verify(ca_der: &[u8], signed: &[u8], signature: &[u8]) -> bool {
+        let (_, ca) = X509Certificate::from_der(ca_der).unwrap();
+        let key = VerifyingKey::from_sec1_bytes(&ca.public_key().subject_public_key.data).unwrap();
+        Signature::from_der(signature).is_ok_and(|signature| key.verify(signed, &signature).is_ok())
+    }
+
+    fn cert_id(issuer: &Issuer, serial: &[u8]) -> Vec<u8> {
+        let algorithm = sequence(&[&tlv(TAG_OID, OID_SHA1), &[0x05, 0x00]]);
+        sequence(&[
+            &algorithm,
+            &tlv(TAG_OCTET_STRING, &Sha1::digest(&issuer.name_der)),
+            &tlv(TAG_OCTET_STRING, &Sha1::digest(&issuer.public_key_bits)),
+            &tlv(TAG_INTEGER, serial),
+        ])
+    }
+
+    // 不签名的OCSP请求，带nonce扩展
+    fn ocsp_request(cert_ids: &[Vec<u8>], nonce: &[u8]) -> Vec<u8> {
+        let request_list: Vec<u8> = cert_ids.iter().flat_map(|cert_id| sequence(&[cert_id])).collect();
+        let extension = sequence(&[&tlv(TAG_OID, OID_OCSP_NONCE), &tlv(TAG_OCTET_STRING, &tlv(TAG_OCTET_STRING, nonce))]);
+        let tbs_request = sequence(&[&tlv(TAG_SEQUENCE, &request_list), &tlv(TAG_CONTEXT_2, &sequence(&[&extension]))]);
+        sequence(&[&tbs_request])
+    }
+
+    #[test]
+    fn issues_a_leaf_that_chains_to_the_ca() {
+        let (issuer, ca_der) = test_ca("Test Root CA");
+        let serial = random_serial();
+        let leaf_der = issue_leaf(&issuer, &serial);
+
+        let (_, ca) = X509Certificate::from_der(&ca_der).unwrap();
+        let (_, leaf) = X509Certificate::from_der(&leaf_der).unwrap();
+        assert!(ca.is_ca());
+        assert!(!leaf.is_ca());
+        assert_eq!(leaf.issuer().as_raw(), ca.subject().as_raw());
+        assert_eq!(serial_hex(leaf.raw_serial()), serial_hex(&serial));
+
+        // 根证书自签名，叶子证书由根证书的私钥签名
+        assert!(verify(&ca_der, ca.tbs_certificate.as_ref(), &ca.signature_value.data));
+        assert!(verify(&ca_der, leaf.tbs_certificate.as_ref(), &leaf.signature_value.data));
+
+        let subject_key_id = ca.extensions().iter().find_map(|extension| match extension.parsed_extension() {
+            ParsedExtension::SubjectKeyIdentifier(id) => Some(id.0),
+            _ => None,
+        });
+        let authority_key_id = leaf.extensions().iter().find_map(|extension| match extension.parsed_extension() {
+            ParsedExtension::AuthorityKeyIdentifier(id) => id.key_identifier.as_ref().map(|id| id.0),
+            _ => None,
+        });
+        assert!(subject_key_id.is_some());
+        assert_eq!(authority_key_id, subject_key_id);
+
+        // 其它CA的公钥无法校验该证书
+        let (_, other_der) = test_ca("Other Root CA");
+        assert!(!verify(&other_der, leaf.tbs_certificate.as_ref(), &leaf.signature_value.data));
+    }
+
+    #[test]
+    fn crl_lists_revoked_serials() {
+        let (issuer, ca_der) = test_ca("Test Root CA");
+        let serial = random_serial();
+        let now = chrono::Utc::now();
+        let revoked = RevokedCertificate {
+            serial_number: serial_hex(&serial),
+            revoked_at: now - Duration::minutes(1),
+            reason: RevocationReason::KeyCompromise,
+        };
+        let der = issuer.crl(7, now, now + Duration::hours(24), &[revoked]).unwrap();
+
+        let (_, ca) = X509Certificate::from_der(&ca_der).unwrap();
+        let (_, crl) = CertificateRevocationList::from_der(&der).unwrap();
+        assert_eq!(crl.issuer().as_raw(), ca.subject().as_raw());
+        assert_eq!(crl.crl_number().map(ToString::to_string).as_deref(), Some("7"));
+        assert!(verify(&ca_der, crl.tbs_cert_list.as_ref(), &crl.signature_value.data));
+
+        let entries: Vec<_> = crl.iter_revoked_certificates().collect();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(serial_hex(entries[0].raw_serial()), serial_hex(&serial));
+        assert_eq!(entries[0].reason_code().map(|(_, reason)| reason.0), Some(RevocationReason::KeyCompromise.code()));
+    }
+
+    #[test]
+    fn ocsp_reports_good_and_revoked_certificates() {
+        let (issuer, ca_der) = test_ca("Test Root CA");
+        let (other, _) = test_ca("Other Root CA");
+        let good = random_serial();
+        let revoked = random_serial();
+        let nonce = b"ocsp-test-nonce";
+
+        let cert_ids = [cert_id(&issuer, &good), cert_id(&issuer, &revoked), cert_id(&other, &good)];
+        let request = parse_ocsp_request(&ocsp_request(&cert_ids, nonce)).expect("request parses");
+        let serials: Vec<&str> = request.certificates.iter().map(|id| id.serial_number.as_str()).collect();
+        assert_eq!(serials, [serial_hex(&good), serial_hex(&revoked), serial_hex(&good)]);
+        let matches: Vec<bool> = request.certificates.iter().map(|id| issuer.matches(id)).collect();
+        assert_eq!(matches, [true, true, false]);
+
+        let now = chrono::Utc::now();
+        let revoked_at = now - Duration::minutes(1);
+        let statuses = [
+            CertificateStatus::Good,
+            CertificateStatus::Revoked(RevokedCertificate {
+                serial_number: serial_hex(&revoked),
+                revoked_at,
+                reason: RevocationReason::Superseded,
+            }),
+            CertificateStatus::Unknown,
+        ];
+        let response = issuer.ocsp_response(&request, &statuses, now, now + Duration::hours(1)).unwrap();
+
+        // OCSPResponse：responseStatus为successful，responseBytes中为BasicOCSPResponse
+        let (_, response, _, _) = read_tlv(&response).unwrap();
+        let (_, status, _, rest) = read_tlv(response).unwrap();
+        assert_eq!(status, [0]);
+        let (_, response_bytes, _, _) = read_tlv(rest).unwrap();
+        let (_, response_bytes, _, _) = read_tlv(response_bytes).unwrap();
+        let (_, response_type, _, rest) = read_tlv(response_bytes).unwrap();
+        assert_eq!(response_type, OID_OCSP_BASIC);
+        let (_, basic, _, _) = read_tlv(rest).unwrap();
+        let (_, basic, _, _) = read_tlv(basic).unwrap();
+
+        // 签名覆盖tbsResponseData，BIT STRING首字节为未用位数
+        let (_, response_data, tbs_response_data, rest) = read_tlv(basic).unwrap();
+        let (_, _, _, rest) = read_tlv(rest).unwrap();
+        let (_, signature, _, _) = read_tlv(rest).unwrap();
+        assert!(verify(&ca_der, tbs_response_data, &signature[1..]));
+
+        // 跳过responderID和producedAt
+        let (_, _, _, rest) = read_tlv(response_data).unwrap();
+        let (_, _, _, rest) = read_tlv(rest).unwrap();
+        let (_, mut responses, _, extensions) = read_tlv(rest).unwrap();
+        let mut single_responses = Vec::new();
+        while !responses.is_empty() {
+            let (_, single_response, _, next) = read_tlv(responses).unwrap();
+            let (_, _, raw_cert_id, rest) = read_tlv(single_response).unwrap();
+            let (cert_status, revoked_info, _, _) = read_tlv(rest).unwrap();
+            single_responses.push((raw_cert_id.to_vec(), cert_status, revoked_info.to_vec()));
+            responses = next;
+        }
+        assert_eq!(single_responses.len(), 3);
+        for ((raw_cert_id, _, _), cert_id) in single_responses.iter().zip(&cert_ids) {
+            assert_eq!(raw_cert_id, cert_id);
+        }
+        let cert_statuses: Vec<u8> = single_responses.iter().map(|(_, status, _)| *status).collect();
+        assert_eq!(cert_statuses, [0x80, TAG_CONTEXT_1, 0x82]);
+
+        // RevokedInfo：revocationTime和[0] revocationReason
+        let (_, revocation_time, _, rest) = read_tlv(&single_responses[1].2).unwrap();
+        assert_eq!(revocation_time, revoked_at.format("%Y%m%d%H%M%SZ").to_string().as_bytes());
+        let (_, reason, _, _) = read_tlv(rest).unwrap();
+        assert_eq!(read_tlv(reason).map(|(tag, value, _, _)| (tag, value)), Some((TAG_ENUMERATED, &[4u8][..])));
+
+        // nonce原样写回
+        assert!(extensions.windows(nonce.len()).any(|window| window == nonce));
+    }
+
+    #[test]
+    fn rejects_malformed_ocsp_requests() {
+        let (issuer, _) = test_ca("Test Root CA");
+        let request = ocsp_request(&[cert_id(&issuer, &random_serial())], b"nonce");
+
+        assert!(parse_ocsp_request(&request[..request.len() - 1]).is_none());
+        assert!(parse_ocsp_request(&ocsp_request(&[], b"nonce")).is_none());
+        assert!(parse_ocsp_request(&[TAG_SEQUENCE, 0x84, 0xFF, 0xFF, 0xFF, 0xFF]).is_none());
+    }
+}
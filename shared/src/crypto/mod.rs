@@ -0,0 +1,4 @@
+// 导出公钥封装模块
+pub mod sealed;
+// 导出零知识保险库模块
+pub mod vault;
\ No newline at end of file
@@ -0,0 +1,99 @@
+//! 基于X25519的公钥封装（ECIES风格）
+//!
+//! 发送方生成临时密钥对，与接收方公钥做ECDH，经HKDF-SHA256派生出
+//! AES-256-GCM密钥后加密数据。只有持有接收方私钥的一方才能解开。
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use hkdf::Hkdf;
+use sha2::Sha256;
+use x25519_dalek::{EphemeralSecret, PublicKey, StaticSecret};
+
+pub const PUBLIC_KEY_SIZE: usize = 32;
+pub const SECRET_KEY_SIZE: usize = 32;
+const NONCE_SIZE: usize = 12;
+const HKDF_INFO: &[u8] = b"ecipher-share-v1";
+
+/// 封装后的数据：临时公钥 + nonce + 密文
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SealedBox {
+    pub ephemeral_public_key: [u8; PUBLIC_KEY_SIZE],
+    pub nonce: [u8; NONCE_SIZE],
+    pub ciphertext: Vec<u8>,
+}
+
+impl SealedBox {
+    /// 序列化为 临时公钥 || nonce || 密文
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(PUBLIC_KEY_SIZE + NONCE_SIZE + self.ciphertext.len());
+        bytes.extend_from_slice(&self.ephemeral_public_key);
+        bytes.extend_from_slice(&self.nonce);
+        bytes.extend_from_slice(&self.ciphertext);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < PUBLIC_KEY_SIZE + NONCE_SIZE {
+            return None;
+        }
+        let (ephemeral_public_key, rest) = bytes.split_at(PUBLIC_KEY_SIZE);
+        let (nonce, ciphertext) = rest.split_at(NONCE_SIZE);
+
+        Some(Self {
+            ephemeral_public_key: ephemeral_public_key.try_into().ok()?,
+            nonce: nonce.try_into().ok()?,
+            ciphertext: ciphertext.to_vec(),
+        })
+    }
+}
+
+/// 生成X25519密钥对，返回 (私钥, 公钥)
+pub fn generate_keypair() -> ([u8; SECRET_KEY_SIZE], [u8; PUBLIC_KEY_SIZE]) {
+    let secret = StaticSecret::random_from_rng(rand_core::OsRng);
+    let public = PublicKey::from(&secret);
+    (secret.to_bytes(), public.to_bytes())
+}
+
+/// 使用接收方公钥封装数据
+pub fn seal(recipient_public_key: &[u8; PUBLIC_KEY_SIZE], plaintext: &[u8]) -> Option<SealedBox> {
+    let recipient = PublicKey::from(*recipient_public_key);
+    let ephemeral = EphemeralSecret::random_from_rng(rand_core::OsRng);
+    let ephemeral_public = PublicKey::from(&ephemeral);
+    let shared_secret = ephemeral.diffie_hellman(&recipient);
+
+    let cipher = derive_cipher(shared_secret.as_bytes(), ephemeral_public.as_bytes(), recipient.as_bytes())?;
+
+    let mut nonce = [0u8; NONCE_SIZE];
+    getrandom::getrandom(&mut nonce).ok()?;
+    let ciphertext = cipher.encrypt(Nonce::from_slice(&nonce), plaintext).ok()?;
+
+    Some(SealedBox {
+        ephemeral_public_key: ephemeral_public.to_bytes(),
+        nonce,
+        ciphertext,
+    })
+}
+
+/// 使用接收方私钥解开封装数据
+pub fn open(recipient_secret_key: &[u8; SECRET_KEY_SIZE], sealed: &SealedBox) -> Option<Vec<u8>> {
+    let secret = StaticSecret::from(*recipient_secret_key);
+    let recipient_public = PublicKey::from(&secret);
+    let shared_secret = secret.diffie_hellman(&PublicKey::from(sealed.ephemeral_public_key));
+
+    let cipher = derive_cipher(shared_secret.as_bytes(), &sealed.ephemeral_public_key, recipient_public.as_bytes())?;
+    cipher.decrypt(Nonce::from_slice(&sealed.nonce), sealed.ciphertext.as_slice()).ok()
+}
+
+// 由ECDH共享密钥派生AES-256-GCM密钥，盐值绑定双方公钥
+fn derive_cipher(shared_secret: &[u8], ephemeral_public: &[u8], recipient_public: &[u8]) -> Option<Aes256Gcm> {
+    let mut salt = Vec::with_capacity(PUBLIC_KEY_SIZE * 2);
+    salt.extend_from_slice(ephemeral_public);
+    salt.extend_from_slice(recipient_public);
+
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(&salt), shared_secret)
+        .expand(HKDF_INFO, &mut key)
+        .ok()?;
+
+    Aes256Gcm::new_from_slice(&key).ok()
+}
@@ -0,0 +1,182 @@
+//! 零知识保险库加密
+//!
+//! 客户端用主密码经KDF派生出包装密钥（KEK），再用KEK加密随机生成的保险库密钥；
+//! 保险库中的每个密钥值都在本地用保险库密钥加密。服务端只保存KDF参数、
+//! 被包装的保险库密钥和密文。修改密码时只需用新的KEK重新包装保险库密钥。
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::{engine::general_purpose::STANDARD as BASE64_ENGINE, Engine as _};
+use serde::{Deserialize, Serialize};
+
+pub const VAULT_KEY_SIZE: usize = 32;
+pub const NONCE_SIZE: usize = 12;
+const TAG_SIZE: usize = 16;
+const SALT_SIZE: usize = 16;
+// 包装保险库密钥与加密密钥值使用不同的AAD，避免两类密文互换
+const WRAP_AAD: &[u8] = b"ecipher-vault-key-v1";
+const SECRET_AAD: &[u8] = b"ecipher-vault-secret-v1";
+
+// KDF参数下限，低于此强度的参数会被拒绝
+const MIN_ARGON2_MEMORY_KIB: u32 = 19 * 1024;
+const MIN_ARGON2_ITERATIONS: u32 = 2;
+const MIN_PBKDF2_ITERATIONS: u32 = 600_000;
+
+/// 由主密码派生包装密钥的KDF参数，盐值为Base64编码
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "algorithm", rename_all = "snake_case")]
+pub enum KdfParams {
+    Argon2id {
+        salt: String,
+        memory_kib: u32,
+        iterations: u32,
+        parallelism: u32,
+    },
+    Pbkdf2Sha256 {
+        salt: String,
+        iterations: u32,
+    },
+}
+
+impl KdfParams {
+    /// 使用随机盐值和推荐强度的Argon2id参数
+    pub fn recommended() -> Option<Self> {
+        let mut salt = [0u8; SALT_SIZE];
+        getrandom::getrandom(&mut salt).ok()?;
+
+        Some(KdfParams::Argon2id {
+            salt: BASE64_ENGINE.encode(salt),
+            memory_kib: 64 * 1024,
+            iterations: 3,
+            parallelism: 1,
+        })
+    }
+
+    fn salt(&self) -> Option<Vec<u8>> {
+        let salt = match self {
+            KdfParams::Argon2id { salt, .. } | KdfParams::Pbkdf2Sha256 { salt, .. } => salt,
+        };
+        BASE64_ENGINE.decode(salt).ok()
+    }
+
+    /// 校验参数强度和盐值长度
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.salt().is_none_or(|salt| salt.len() < SALT_SIZE) {
+            return Err("KDF salt must be at least 16 bytes of Base64");
+        }
+        match *self {
+            KdfParams::Argon2id { memory_kib, iterations, parallelism, .. } => {
+                if memory_kib < MIN_ARGON2_MEMORY_KIB || iterations < MIN_ARGON2_ITERATIONS || parallelism == 0 {
+                    return Err("Argon2id parameters are below the minimum strength");
+                }
+            }
+            KdfParams::Pbkdf2Sha256 { iterations, .. } => {
+                if iterations < MIN_PBKDF2_ITERATIONS {
+                    return Err("PBKDF2 iteration count is below the minimum strength");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 由主密码派生包装密钥
+    pub fn derive_key(&self, password: &[u8]) -> Option<[u8; VAULT_KEY_SIZE]> {
+        let salt = self.salt()?;
+        let mut key = [0u8; VAULT_KEY_SIZE];
+
+        match *self {
+            KdfParams::Argon2id { memory_kib, iterations, parallelism, .. } => {
+                let params = argon2::Params::new(memory_kib, iterations, parallelism, Some(VAULT_KEY_SIZE)).ok()?;
+                argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params)
+                    .hash_password_into(password, &salt, &mut key)
+                    .ok()?;
+            }
+            KdfParams::Pbkdf2Sha256 { iterations, .. } => {
+                pbkdf2::pbkdf2_hmac::<sha2::Sha256>(password, &salt, iterations, &mut key);
+            }
+        }
+        Some(key)
+    }
+}
+
+/// AES-256-GCM密文，nonce和密文均为Base64编码
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Ciphertext {
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+impl Ciphertext {
+    /// 校验编码格式和nonce长度，返回解码后的密文长度
+    pub fn validate(&self) -> Result<usize, &'static str> {
+        let nonce = BASE64_ENGINE.decode(&self.nonce).map_err(|_| "Nonce is not valid Base64")?;
+        if nonce.len() != NONCE_SIZE {
+            return Err("Nonce must be 12 bytes");
+        }
+        let ciphertext = BASE64_ENGINE
+            .decode(&self.ciphertext)
+            .map_err(|_| "Ciphertext is not valid Base64")?;
+        if ciphertext.len() < TAG_SIZE {
+            return Err("Ciphertext is too short");
+        }
+        Ok(ciphertext.len())
+    }
+}
+
+/// 被包装的保险库密钥解码后的长度
+pub const WRAPPED_KEY_SIZE: usize = VAULT_KEY_SIZE + TAG_SIZE;
+
+fn encrypt(key: &[u8; VAULT_KEY_SIZE], plaintext: &[u8], aad: &[u8]) -> Option<Ciphertext> {
+    let cipher = Aes256Gcm::new_from_slice(key).ok()?;
+    let mut nonce = [0u8; NONCE_SIZE];
+    getrandom::getrandom(&mut nonce).ok()?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: plaintext, aad })
+        .ok()?;
+
+    Some(Ciphertext {
+        nonce: BASE64_ENGINE.encode(nonce),
+        ciphertext: BASE64_ENGINE.encode(ciphertext),
+    })
+}
+
+fn decrypt(key: &[u8; VAULT_KEY_SIZE], ciphertext: &Ciphertext, aad: &[u8]) -> Option<Vec<u8>> {
+    let cipher = Aes256Gcm::new_from_slice(key).ok()?;
+    let nonce = BASE64_ENGINE.decode(&ciphertext.nonce).ok()?;
+    if nonce.len() != NONCE_SIZE {
+        return None;
+    }
+    let data = BASE64_ENGINE.decode(&ciphertext.ciphertext).ok()?;
+    cipher
+        .decrypt(Nonce::from_slice(&nonce), Payload { msg: &data, aad })
+        .ok()
+}
+
+/// 生成随机保险库密钥
+pub fn generate_vault_key() -> Option<[u8; VAULT_KEY_SIZE]> {
+    let mut key = [0u8; VAULT_KEY_SIZE];
+    getrandom::getrandom(&mut key).ok()?;
+    Some(key)
+}
+
+/// 用包装密钥加密保险库密钥
+pub fn wrap_key(kek: &[u8; VAULT_KEY_SIZE], vault_key: &[u8; VAULT_KEY_SIZE]) -> Option<Ciphertext> {
+    encrypt(kek, vault_key, WRAP_AAD)
+}
+
+/// 用包装密钥解开保险库密钥，密码错误时返回None
+pub fn unwrap_key(kek: &[u8; VAULT_KEY_SIZE], wrapped: &Ciphertext) -> Option<[u8; VAULT_KEY_SIZE]> {
+    decrypt(kek, wrapped, WRAP_AAD)?.try_into().ok()
+}
+
+/// 用保险库密钥加密密钥值
+pub fn seal_secret(vault_key: &[u8; VAULT_KEY_SIZE], plaintext: &[u8]) -> Option<Ciphertext> {
+    encrypt(vault_key, plaintext, SECRET_AAD)
+}
+
+/// 用保险库密钥解密密钥值
+pub fn open_secret(vault_key: &[u8; VAULT_KEY_SIZE], ciphertext: &Ciphertext) -> Option<Vec<u8>> {
+    decrypt(vault_key, ciphertext, SECRET_AAD)
+}
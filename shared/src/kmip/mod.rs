@@ -0,0 +1,442 @@
+//! KMIP 1.4/2.0协议子集：对称密钥的Create、Get、Register、Locate、Activate、Revoke、Destroy和GetAttributes
+//!
+//! 服务端和测试客户端共用这里的编解码与属性表示。1.x的属性以Attribute名称/值对出现在
+//! TemplateAttribute中，2.0则直接以属性标签出现在Attributes结构体中。
+
+// 导出TTLV编解码模块
+pub mod ttlv;
+// 导出标签定义模块
+pub mod tag;
+
+use tag::Tag;
+use ttlv::{Ttlv, TtlvError, Value};
+
+/// KMIP默认端口
+pub const DEFAULT_PORT: u16 = 5696;
+
+/// 对称密钥对象类型
+pub const OBJECT_TYPE_SYMMETRIC_KEY: u32 = 0x02;
+/// AES算法
+pub const ALGORITHM_AES: u32 = 0x03;
+/// 原始字节格式
+pub const KEY_FORMAT_RAW: u32 = 0x01;
+/// 透明对称密钥格式，与Raw一样直接携带密钥字节
+pub const KEY_FORMAT_TRANSPARENT_SYMMETRIC_KEY: u32 = 0x07;
+pub const NAME_TYPE_UNINTERPRETED_TEXT_STRING: u32 = 0x01;
+pub const CREDENTIAL_TYPE_USERNAME_AND_PASSWORD: u32 = 0x01;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Create,
+    Register,
+    Locate,
+    Get,
+    GetAttributes,
+    Activate,
+    Revoke,
+    Destroy,
+}
+
+impl Operation {
+    pub fn code(self) -> u32 {
+        match self {
+            Operation::Create => 0x01,
+            Operation::Register => 0x03,
+            Operation::Locate => 0x08,
+            Operation::Get => 0x0A,
+            Operation::GetAttributes => 0x0B,
+            Operation::Activate => 0x12,
+            Operation::Revoke => 0x13,
+            Operation::Destroy => 0x14,
+        }
+    }
+
+    pub fn from_code(code: u32) -> Option<Self> {
+        Some(match code {
+            0x01 => Operation::Create,
+            0x03 => Operation::Register,
+            0x08 => Operation::Locate,
+            0x0A => Operation::Get,
+            0x0B => Operation::GetAttributes,
+            0x12 => Operation::Activate,
+            0x13 => Operation::Revoke,
+            0x14 => Operation::Destroy,
+            _ => return None,
+        })
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Operation::Create => "Create",
+            Operation::Register => "Register",
+            Operation::Locate => "Locate",
+            Operation::Get => "Get",
+            Operation::GetAttributes => "GetAttributes",
+            Operation::Activate => "Activate",
+            Operation::Revoke => "Revoke",
+            Operation::Destroy => "Destroy",
+        }
+    }
+}
+
+/// 密钥生命周期状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    PreActive,
+    Active,
+    Deactivated,
+    Compromised,
+    Destroyed,
+    DestroyedCompromised,
+}
+
+impl State {
+    pub fn code(self) -> u32 {
+        match self {
+            State::PreActive => 0x01,
+            State::Active => 0x02,
+            State::Deactivated => 0x03,
+            State::Compromised => 0x04,
+            State::Destroyed => 0x05,
+            State::DestroyedCompromised => 0x06,
+        }
+    }
+
+    pub fn from_code(code: u32) -> Option<Self> {
+        Some(match code {
+            0x01 => State::PreActive,
+            0x02 => State::Active,
+            0x03 => State::Deactivated,
+            0x04 => State::Compromised,
+            0x05 => State::Destroyed,
+            0x06 => State::DestroyedCompromised,
+            _ => return None,
+        })
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            State::PreActive => "pre_active",
+            State::Active => "active",
+            State::Deactivated => "deactivated",
+            State::Compromised => "compromised",
+            State::Destroyed => "destroyed",
+            State::DestroyedCompromised => "destroyed_compromised",
+        }
+    }
+}
+
+impl std::str::FromStr for State {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pre_active" => Ok(State::PreActive),
+            "active" => Ok(State::Active),
+            "deactivated" => Ok(State::Deactivated),
+            "compromised" => Ok(State::Compromised),
+            "destroyed" => Ok(State::Destroyed),
+            "destroyed_compromised" => Ok(State::DestroyedCompromised),
+            _ => Err(format!("Unknown KMIP state: {}", s)),
+        }
+    }
+}
+
+pub const RESULT_STATUS_SUCCESS: u32 = 0x00;
+pub const RESULT_STATUS_OPERATION_FAILED: u32 = 0x01;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultReason {
+    ItemNotFound,
+    AuthenticationNotSuccessful,
+    InvalidMessage,
+    OperationNotSupported,
+    MissingData,
+    InvalidField,
+    FeatureNotSupported,
+    IllegalOperation,
+    PermissionDenied,
+    GeneralFailure,
+}
+
+impl ResultReason {
+    pub fn code(self) -> u32 {
+        match self {
+            ResultReason::ItemNotFound => 0x01,
+            ResultReason::AuthenticationNotSuccessful => 0x03,
+            ResultReason::InvalidMessage => 0x04,
+            ResultReason::OperationNotSupported => 0x05,
+            ResultReason::MissingData => 0x06,
+            ResultReason::InvalidField => 0x07,
+            ResultReason::FeatureNotSupported => 0x08,
+            ResultReason::IllegalOperation => 0x0B,
+            ResultReason::PermissionDenied => 0x0C,
+            ResultReason::GeneralFailure => 0x0100,
+        }
+    }
+}
+
+/// 吊销原因，只有KeyCompromise会使密钥进入Compromised状态
+pub const REVOCATION_REASON_KEY_COMPROMISE: u32 = 0x02;
+
+/// 协议版本，主版本号为2时使用2.0的属性编码
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProtocolVersion {
+    pub major: i32,
+    pub minor: i32,
+}
+
+impl ProtocolVersion {
+    pub const V1_4: ProtocolVersion = ProtocolVersion { major: 1, minor: 4 };
+    pub const V2_0: ProtocolVersion = ProtocolVersion { major: 2, minor: 0 };
+
+    pub fn is_v2(self) -> bool {
+        self.major >= 2
+    }
+
+    pub fn to_ttlv(self) -> Ttlv {
+        Ttlv::structure(
+            tag::PROTOCOL_VERSION,
+            vec![
+                Ttlv::integer(tag::PROTOCOL_VERSION_MAJOR, self.major),
+                Ttlv::integer(tag::PROTOCOL_VERSION_MINOR, self.minor),
+            ],
+        )
+    }
+
+    pub fn from_ttlv(item: &Ttlv) -> Result<Self, TtlvError> {
+        Ok(ProtocolVersion {
+            major: item.require(tag::PROTOCOL_VERSION_MAJOR)?.as_integer()?,
+            minor: item.require(tag::PROTOCOL_VERSION_MINOR)?.as_integer()?,
+        })
+    }
+}
+
+/// 支持的密钥属性
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Attribute {
+    UniqueIdentifier(String),
+    Name(String),
+    ObjectType(u32),
+    CryptographicAlgorithm(u32),
+    CryptographicLength(i32),
+    CryptographicUsageMask(i32),
+    State(State),
+    InitialDate(i64),
+    ActivationDate(i64),
+    DeactivationDate(i64),
+    CompromiseDate(i64),
+    LastChangeDate(i64),
+}
+
+// 1.x属性名与2.0属性标签的对应关系
+const ATTRIBUTE_NAMES: &[(&str, Tag)] = &[
+    ("Unique Identifier", tag::UNIQUE_IDENTIFIER),
+    ("Name", tag::NAME),
+    ("Object Type", tag::OBJECT_TYPE),
+    ("Cryptographic Algorithm", tag::CRYPTOGRAPHIC_ALGORITHM),
+    ("Cryptographic Length", tag::CRYPTOGRAPHIC_LENGTH),
+    ("Cryptographic Usage Mask", tag::CRYPTOGRAPHIC_USAGE_MASK),
+    ("State", tag::STATE),
+    ("Initial Date", tag::INITIAL_DATE),
+    ("Activation Date", tag::ACTIVATION_DATE),
+    ("Deactivation Date", tag::DEACTIVATION_DATE),
+    ("Compromise Date", tag::COMPROMISE_DATE),
+    ("Last Change Date", tag::LAST_CHANGE_DATE),
+];
+
+/// 1.x属性名对应的标签
+pub fn attribute_tag(name: &str) -> Option<Tag> {
+    ATTRIBUTE_NAMES.iter().find(|(known, _)| *known == name).map(|(_, tag)| *tag)
+}
+
+fn attribute_name(tag: Tag) -> &'static str {
+    ATTRIBUTE_NAMES
+        .iter()
+        .find(|(_, known)| *known == tag)
+        .map(|(name, _)| *name)
+        .expect("every attribute tag has a name")
+}
+
+impl Attribute {
+    pub fn tag(&self) -> Tag {
+        match self {
+            Attribute::UniqueIdentifier(_) => tag::UNIQUE_IDENTIFIER,
+            Attribute::Name(_) => tag::NAME,
+            Attribute::ObjectType(_) => tag::OBJECT_TYPE,
+            Attribute::CryptographicAlgorithm(_) => tag::CRYPTOGRAPHIC_ALGORITHM,
+            Attribute::CryptographicLength(_) => tag::CRYPTOGRAPHIC_LENGTH,
+            Attribute::CryptographicUsageMask(_) => tag::CRYPTOGRAPHIC_USAGE_MASK,
+            Attribute::State(_) => tag::STATE,
+            Attribute::InitialDate(_) => tag::INITIAL_DATE,
+            Attribute::ActivationDate(_) => tag::ACTIVATION_DATE,
+            Attribute::DeactivationDate(_) => tag::DEACTIVATION_DATE,
+            Attribute::CompromiseDate(_) => tag::COMPROMISE_DATE,
+            Attribute::LastChangeDate(_) => tag::LAST_CHANGE_DATE,
+        }
+    }
+
+    // 以给定标签编码属性值：1.x为AttributeValue，2.0为属性自身的标签
+    fn value(&self, tag: Tag) -> Ttlv {
+        match self {
+            Attribute::UniqueIdentifier(value) => Ttlv::text(tag, value.as_str()),
+            Attribute::Name(value) => Ttlv::structure(
+                tag,
+                vec![
+                    Ttlv::text(tag::NAME_VALUE, value.as_str()),
+                    Ttlv::enumeration(tag::NAME_TYPE, NAME_TYPE_UNINTERPRETED_TEXT_STRING),
+                ],
+            ),
+            Attribute::ObjectType(value) | Attribute::CryptographicAlgorithm(value) => Ttlv::enumeration(tag, *value),
+            Attribute::State(state) => Ttlv::enumeration(tag, state.code()),
+            Attribute::CryptographicLength(value) | Attribute::CryptographicUsageMask(value) => {
+                Ttlv::integer(tag, *value)
+            }
+            Attribute::InitialDate(value)
+            | Attribute::ActivationDate(value)
+            | Attribute::DeactivationDate(value)
+            | Attribute::CompromiseDate(value)
+            | Attribute::LastChangeDate(value) => Ttlv::date_time(tag, *value),
+        }
+    }
+
+    // 从值条目解析属性，`tag`为属性标签
+    fn parse(tag: Tag, value: &Ttlv) -> Result<Option<Attribute>, TtlvError> {
+        let attribute = match tag {
+            tag::UNIQUE_IDENTIFIER => Attribute::UniqueIdentifier(value.as_text()?.to_string()),
+            tag::NAME => Attribute::Name(value.require(tag::NAME_VALUE)?.as_text()?.to_string()),
+            tag::OBJECT_TYPE => Attribute::ObjectType(value.as_enumeration()?),
+            tag::CRYPTOGRAPHIC_ALGORITHM => Attribute::CryptographicAlgorithm(value.as_enumeration()?),
+            tag::CRYPTOGRAPHIC_LENGTH => Attribute::CryptographicLength(value.as_integer()?),
+            tag::CRYPTOGRAPHIC_USAGE_MASK => Attribute::CryptographicUsageMask(value.as_integer()?),
+            tag::STATE => {
+                Attribute::State(State::from_code(value.as_enumeration()?).ok_or(TtlvError::UnexpectedType(tag))?)
+            }
+            tag::INITIAL_DATE => Attribute::InitialDate(value.as_date_time()?),
+            tag::ACTIVATION_DATE => Attribute::ActivationDate(value.as_date_time()?),
+            tag::DEACTIVATION_DATE => Attribute::DeactivationDate(value.as_date_time()?),
+            tag::COMPROMISE_DATE => Attribute::CompromiseDate(value.as_date_time()?),
+            tag::LAST_CHANGE_DATE => Attribute::LastChangeDate(value.as_date_time()?),
+            _ => return Ok(None),
+        };
+        Ok(Some(attribute))
+    }
+
+    /// 按协议版本编码属性
+    pub fn to_ttlv(&self, version: ProtocolVersion) -> Ttlv {
+        if version.is_v2() {
+            return self.value(self.tag());
+        }
+        Ttlv::structure(
+            tag::ATTRIBUTE,
+            vec![
+                Ttlv::text(tag::ATTRIBUTE_NAME, attribute_name(self.tag())),
+                self.value(tag::ATTRIBUTE_VALUE),
+            ],
+        )
+    }
+}
+
+/// 读取请求载荷中的属性：1.x的TemplateAttribute及直接出现的Attribute，2.0的Attributes；不支持的属性被忽略
+pub fn parse_attributes(payload: &Ttlv) -> Result<Vec<Attribute>, TtlvError> {
+    let mut attributes = Vec::new();
+
+    let v1_items = payload
+        .children(tag::TEMPLATE_ATTRIBUTE)
+        .flat_map(|template| template.children(tag::ATTRIBUTE))
+        .chain(payload.children(tag::ATTRIBUTE));
+    for item in v1_items {
+        let name = item.require(tag::ATTRIBUTE_NAME)?.as_text()?;
+        let Some(tag) = attribute_tag(name) else {
+            continue;
+        };
+        if let Some(attribute) = Attribute::parse(tag, item.require(tag::ATTRIBUTE_VALUE)?)? {
+            attributes.push(attribute);
+        }
+    }
+
+    for item in payload.children(tag::ATTRIBUTES).flat_map(Ttlv::items) {
+        if let Some(attribute) = Attribute::parse(item.tag, item)? {
+            attributes.push(attribute);
+        }
+    }
+
+    Ok(attributes)
+}
+
+/// 按协议版本编码属性列表：1.x为若干Attribute，2.0为一个Attributes结构体
+pub fn encode_attributes(attributes: &[Attribute], version: ProtocolVersion) -> Vec<Ttlv> {
+    let items: Vec<Ttlv> = attributes.iter().map(|attribute| attribute.to_ttlv(version)).collect();
+    if version.is_v2() {
+        vec![Ttlv::structure(tag::ATTRIBUTES, items)]
+    } else {
+        items
+    }
+}
+
+/// 编码对称密钥对象
+pub fn symmetric_key(algorithm: u32, length: i32, material: Vec<u8>) -> Ttlv {
+    Ttlv::structure(
+        tag::SYMMETRIC_KEY,
+        vec![Ttlv::structure(
+            tag::KEY_BLOCK,
+            vec![
+                Ttlv::enumeration(tag::KEY_FORMAT_TYPE, KEY_FORMAT_RAW),
+                Ttlv::structure(tag::KEY_VALUE, vec![Ttlv::bytes(tag::KEY_MATERIAL, material)]),
+                Ttlv::enumeration(tag::CRYPTOGRAPHIC_ALGORITHM, algorithm),
+                Ttlv::integer(tag::CRYPTOGRAPHIC_LENGTH, length),
+            ],
+        )],
+    )
+}
+
+/// 构造请求消息，`credential`为用户名和口令
+pub fn request_message(
+    version: ProtocolVersion,
+    credential: Option<(&str, &str)>,
+    batch: Vec<(Operation, Vec<Ttlv>)>,
+) -> Ttlv {
+    let mut header = vec![version.to_ttlv()];
+    if let Some((username, password)) = credential {
+        header.push(Ttlv::structure(
+            tag::AUTHENTICATION,
+            vec![Ttlv::structure(
+                tag::CREDENTIAL,
+                vec![
+                    Ttlv::enumeration(tag::CREDENTIAL_TYPE, CREDENTIAL_TYPE_USERNAME_AND_PASSWORD),
+                    Ttlv::structure(
+                        tag::CREDENTIAL_VALUE,
+                        vec![Ttlv::text(tag::USERNAME, username), Ttlv::text(tag::PASSWORD, password)],
+                    ),
+                ],
+            )],
+        ));
+    }
+    header.push(Ttlv::integer(tag::BATCH_COUNT, batch.len() as i32));
+
+    let mut items = vec![Ttlv::structure(tag::REQUEST_HEADER, header)];
+    for (operation, payload) in batch {
+        items.push(Ttlv::structure(
+            tag::BATCH_ITEM,
+            vec![
+                Ttlv::enumeration(tag::OPERATION, operation.code()),
+                Ttlv::structure(tag::REQUEST_PAYLOAD, payload),
+            ],
+        ));
+    }
+    Ttlv::structure(tag::REQUEST_MESSAGE, items)
+}
+
+/// 读取TTLV值中的密钥字节，支持Raw和TransparentSymmetricKey两种格式
+pub fn key_material(key_block: &Ttlv) -> Result<&[u8], TtlvError> {
+    let material = key_block.require(tag::KEY_VALUE)?.require(tag::KEY_MATERIAL)?;
+    match &material.value {
+        Value::ByteString(bytes) => Ok(bytes),
+        // TransparentSymmetricKey的KeyMaterial为包含Key字节串的结构体
+        Value::Structure(items) => items
+            .iter()
+            .find_map(|item| item.as_bytes().ok())
+            .ok_or(TtlvError::Missing(tag::KEY_MATERIAL)),
+        _ => Err(TtlvError::UnexpectedType(tag::KEY_MATERIAL)),
+    }
+}
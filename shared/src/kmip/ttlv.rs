@@ -0,0 +1,415 @@
+//! KMIP TTLV编解码
+//!
+//! 每个条目由3字节标签、1字节类型、4字节长度和值组成，值按8字节对齐补零。
+//! 结构体的值为若干子条目依次编码的结果。
+
+use std::fmt;
+
+use super::tag::Tag;
+
+// 条目头长度：标签3字节 + 类型1字节 + 长度4字节
+pub const HEADER_SIZE: usize = 8;
+// 嵌套结构体的最大深度，防止恶意报文耗尽栈空间
+const MAX_DEPTH: usize = 16;
+
+const TYPE_STRUCTURE: u8 = 0x01;
+const TYPE_INTEGER: u8 = 0x02;
+const TYPE_LONG_INTEGER: u8 = 0x03;
+const TYPE_BIG_INTEGER: u8 = 0x04;
+const TYPE_ENUMERATION: u8 = 0x05;
+const TYPE_BOOLEAN: u8 = 0x06;
+const TYPE_TEXT_STRING: u8 = 0x07;
+const TYPE_BYTE_STRING: u8 = 0x08;
+const TYPE_DATE_TIME: u8 = 0x09;
+const TYPE_INTERVAL: u8 = 0x0A;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Structure(Vec<Ttlv>),
+    Integer(i32),
+    LongInteger(i64),
+    /// 大端补码，长度为8的倍数
+    BigInteger(Vec<u8>),
+    Enumeration(u32),
+    Boolean(bool),
+    TextString(String),
+    ByteString(Vec<u8>),
+    /// Unix时间戳（秒）
+    DateTime(i64),
+    /// 时间间隔（秒）
+    Interval(u32),
+}
+
+/// 一个TTLV条目
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ttlv {
+    pub tag: Tag,
+    pub value: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TtlvError {
+    /// 数据不足一个完整条目
+    Truncated,
+    UnknownType(u8),
+    InvalidLength { tag: Tag, length: u32 },
+    InvalidUtf8(Tag),
+    InvalidBoolean(Tag),
+    TooDeep,
+    /// 缺少必需的子条目
+    Missing(Tag),
+    /// 子条目类型与预期不符
+    UnexpectedType(Tag),
+}
+
+impl fmt::Display for TtlvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TtlvError::Truncated => f.write_str("truncated TTLV data"),
+            TtlvError::UnknownType(item_type) => write!(f, "unknown TTLV type 0x{:02X}", item_type),
+            TtlvError::InvalidLength { tag, length } => write!(f, "invalid length {} for tag {}", length, tag),
+            TtlvError::InvalidUtf8(tag) => write!(f, "text string {} is not valid UTF-8", tag),
+            TtlvError::InvalidBoolean(tag) => write!(f, "invalid boolean value for tag {}", tag),
+            TtlvError::TooDeep => f.write_str("TTLV structures nested too deeply"),
+            TtlvError::Missing(tag) => write!(f, "missing required field {}", tag),
+            TtlvError::UnexpectedType(tag) => write!(f, "unexpected type for field {}", tag),
+        }
+    }
+}
+
+impl std::error::Error for TtlvError {}
+
+impl Ttlv {
+    pub fn new(tag: Tag, value: Value) -> Self {
+        Ttlv { tag, value }
+    }
+
+    pub fn structure(tag: Tag, items: Vec<Ttlv>) -> Self {
+        Ttlv::new(tag, Value::Structure(items))
+    }
+
+    pub fn integer(tag: Tag, value: i32) -> Self {
+        Ttlv::new(tag, Value::Integer(value))
+    }
+
+    pub fn enumeration(tag: Tag, value: u32) -> Self {
+        Ttlv::new(tag, Value::Enumeration(value))
+    }
+
+    pub fn text(tag: Tag, value: impl Into<String>) -> Self {
+        Ttlv::new(tag, Value::TextString(value.into()))
+    }
+
+    pub fn bytes(tag: Tag, value: Vec<u8>) -> Self {
+        Ttlv::new(tag, Value::ByteString(value))
+    }
+
+    pub fn date_time(tag: Tag, value: i64) -> Self {
+        Ttlv::new(tag, Value::DateTime(value))
+    }
+
+    /// 编码为字节
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode(&mut out);
+        out
+    }
+
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.tag.0.to_be_bytes()[1..]);
+        let start = out.len();
+        // 先写入类型和占位长度，编码完值后回填长度
+        out.push(self.value.type_code());
+        out.extend_from_slice(&[0; 4]);
+
+        let value_start = out.len();
+        match &self.value {
+            Value::Structure(items) => items.iter().for_each(|item| item.encode(out)),
+            Value::Integer(value) => out.extend_from_slice(&value.to_be_bytes()),
+            Value::LongInteger(value) | Value::DateTime(value) => out.extend_from_slice(&value.to_be_bytes()),
+            Value::BigInteger(value) | Value::ByteString(value) => out.extend_from_slice(value),
+            Value::Enumeration(value) | Value::Interval(value) => out.extend_from_slice(&value.to_be_bytes()),
+            Value::Boolean(value) => out.extend_from_slice(&u64::from(*value).to_be_bytes()),
+            Value::TextString(value) => out.extend_from_slice(value.as_bytes()),
+        }
+        let length = (out.len() - value_start) as u32;
+        out[start + 1..start + 5].copy_from_slice(&length.to_be_bytes());
+        out.resize(value_start + padded(length as usize), 0);
+    }
+
+    /// 从字节解码一个完整条目，返回条目及消耗的字节数
+    pub fn decode(bytes: &[u8]) -> Result<(Ttlv, usize), TtlvError> {
+        decode_item(bytes, 0)
+    }
+
+    /// 读取条目头中的值长度，用于从流中确定一条消息的总长度
+    pub fn message_length(header: &[u8; HEADER_SIZE]) -> usize {
+        HEADER_SIZE + u32::from_be_bytes([header[4], header[5], header[6], header[7]]) as usize
+    }
+
+    /// 结构体的子条目，非结构体返回空
+    pub fn items(&self) -> &[Ttlv] {
+        match &self.value {
+            Value::Structure(items) => items,
+            _ => &[],
+        }
+    }
+
+    /// 第一个指定标签的子条目
+    pub fn child(&self, tag: Tag) -> Option<&Ttlv> {
+        self.items().iter().find(|item| item.tag == tag)
+    }
+
+    /// 全部指定标签的子条目
+    pub fn children(&self, tag: Tag) -> impl Iterator<Item = &Ttlv> {
+        self.items().iter().filter(move |item| item.tag == tag)
+    }
+
+    /// 必需的子条目
+    pub fn require(&self, tag: Tag) -> Result<&Ttlv, TtlvError> {
+        self.child(tag).ok_or(TtlvError::Missing(tag))
+    }
+
+    pub fn as_integer(&self) -> Result<i32, TtlvError> {
+        match self.value {
+            Value::Integer(value) => Ok(value),
+            _ => Err(TtlvError::UnexpectedType(self.tag)),
+        }
+    }
+
+    pub fn as_enumeration(&self) -> Result<u32, TtlvError> {
+        match self.value {
+            Value::Enumeration(value) => Ok(value),
+            _ => Err(TtlvError::UnexpectedType(self.tag)),
+        }
+    }
+
+    pub fn as_date_time(&self) -> Result<i64, TtlvError> {
+        match self.value {
+            Value::DateTime(value) => Ok(value),
+            _ => Err(TtlvError::UnexpectedType(self.tag)),
+        }
+    }
+
+    pub fn as_text(&self) -> Result<&str, TtlvError> {
+        match &self.value {
+            Value::TextString(value) => Ok(value),
+            _ => Err(TtlvError::UnexpectedType(self.tag)),
+        }
+    }
+
+    pub fn as_bytes(&self) -> Result<&[u8], TtlvError> {
+        match &self.value {
+            Value::ByteString(value) => Ok(value),
+            _ => Err(TtlvError::UnexpectedType(self.tag)),
+        }
+    }
+}
+
+impl Value {
+    fn type_code(&self) -> u8 {
+        match self {
+            Value::Structure(_) => TYPE_STRUCTURE,
+            Value::Integer(_) => TYPE_INTEGER,
+            Value::LongInteger(_) => TYPE_LONG_INTEGER,
+            Value::BigInteger(_) => TYPE_BIG_INTEGER,
+            Value::Enumeration(_) => TYPE_ENUMERATION,
+            Value::Boolean(_) => TYPE_BOOLEAN,
+            Value::TextString(_) => TYPE_TEXT_STRING,
+            Value::ByteString(_) => TYPE_BYTE_STRING,
+            Value::DateTime(_) => TYPE_DATE_TIME,
+            Value::Interval(_) => TYPE_INTERVAL,
+        }
+    }
+}
+
+// 值长度按8字节对齐
+fn padded(length: usize) -> usize {
+    length.div_ceil(8) * 8
+}
+
+fn decode_item(bytes: &[u8], depth: usize) -> Result<(Ttlv, usize), TtlvError> {
+    if depth > MAX_DEPTH {
+        return Err(TtlvError::TooDeep);
+    }
+    if bytes.len() < HEADER_SIZE {
+        return Err(TtlvError::Truncated);
+    }
+
+    let tag = Tag(u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]));
+    let item_type = bytes[3];
+    let length = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+    let total = HEADER_SIZE + padded(length as usize);
+    if bytes.len() < total {
+        return Err(TtlvError::Truncated);
+    }
+    let raw = &bytes[HEADER_SIZE..HEADER_SIZE + length as usize];
+    let fixed = |size: usize| {
+        if length as usize == size {
+            Ok(())
+        } else {
+            Err(TtlvError::InvalidLength { tag, length })
+        }
+    };
+
+    let value = match item_type {
+        TYPE_STRUCTURE => {
+            let mut items = Vec::new();
+            let mut offset = 0;
+            while offset < raw.len() {
+                let (item, used) = decode_item(&raw[offset..], depth + 1)?;
+                items.push(item);
+                offset += used;
+            }
+            Value::Structure(items)
+        }
+        TYPE_INTEGER => {
+            fixed(4)?;
+            Value::Integer(i32::from_be_bytes(raw.try_into().expect("length checked")))
+        }
+        TYPE_LONG_INTEGER => {
+            fixed(8)?;
+            Value::LongInteger(i64::from_be_bytes(raw.try_into().expect("length checked")))
+        }
+        TYPE_BIG_INTEGER => {
+            if !length.is_multiple_of(8) {
+                return Err(TtlvError::InvalidLength { tag, length });
+            }
+            Value::BigInteger(raw.to_vec())
+        }
+        TYPE_ENUMERATION => {
+            fixed(4)?;
+            Value::Enumeration(u32::from_be_bytes(raw.try_into().expect("length checked")))
+        }
+        TYPE_BOOLEAN => {
+            fixed(8)?;
+            match u64::from_be_bytes(raw.try_into().expect("length checked")) {
+                0 => Value::Boolean(false),
+                1 => Value::Boolean(true),
+                _ => return Err(TtlvError::InvalidBoolean(tag)),
+            }
+        }
+        TYPE_TEXT_STRING => {
+            Value::TextString(String::from_utf8(raw.to_vec()).map_err(|_| TtlvError::InvalidUtf8(tag))?)
+        }
+        TYPE_BYTE_STRING => Value::ByteString(raw.to_vec()),
+        TYPE_DATE_TIME => {
+            fixed(8)?;
+            Value::DateTime(i64::from_be_bytes(raw.try_into().expect("length checked")))
+        }
+        TYPE_INTERVAL => {
+            fixed(4)?;
+            Value::Interval(u32::from_be_bytes(raw.try_into().expect("length checked")))
+        }
+        other => return Err(TtlvError::UnknownType(other)),
+    };
+
+    Ok((Ttlv { tag, value }, total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kmip::tag;
+
+    fn round_trip(item: &Ttlv) -> Ttlv {
+        let bytes = item.to_bytes();
+        assert_eq!(bytes.len() % 8, 0, "encoded item must be 8-byte aligned");
+
+        let (decoded, used) = Ttlv::decode(&bytes).expect("decode encoded item");
+        assert_eq!(used, bytes.len());
+        decoded
+    }
+
+    // n层结构体包裹一个整数
+    fn nested(depth: usize) -> Ttlv {
+        let mut item = Ttlv::integer(tag::BATCH_COUNT, 1);
+        for _ in 0..depth {
+            item = Ttlv::structure(tag::BATCH_ITEM, vec![item]);
+        }
+        item
+    }
+
+    #[test]
+    fn every_item_type_round_trips() {
+        let items = [
+            Ttlv::structure(tag::BATCH_ITEM, Vec::new()),
+            Ttlv::integer(tag::CRYPTOGRAPHIC_LENGTH, -256),
+            Ttlv::new(tag::CRYPTOGRAPHIC_LENGTH, Value::LongInteger(i64::MIN + 1)),
+            Ttlv::new(tag::KEY_MATERIAL, Value::BigInteger(vec![0xFF; 16])),
+            Ttlv::enumeration(tag::OPERATION, 0x0A),
+            Ttlv::new(tag::ATTRIBUTE_VALUE, Value::Boolean(true)),
+            Ttlv::new(tag::ATTRIBUTE_VALUE, Value::Boolean(false)),
+            Ttlv::text(tag::NAME_VALUE, "密钥-001"),
+            Ttlv::text(tag::NAME_VALUE, ""),
+            Ttlv::bytes(tag::KEY_MATERIAL, (0..=32).collect()),
+            Ttlv::date_time(tag::ACTIVATION_DATE, 1_700_000_000),
+            Ttlv::new(tag::ATTRIBUTE_VALUE, Value::Interval(86_400)),
+        ];
+        for item in &items {
+            assert_eq!(&round_trip(item), item);
+        }
+
+        // 结构体的值长度总是8的倍数，message_length即整条消息的长度
+        let message = Ttlv::structure(tag::BATCH_ITEM, items.to_vec());
+        let bytes = message.to_bytes();
+        assert_eq!(Ttlv::message_length(bytes[..HEADER_SIZE].try_into().unwrap()), bytes.len());
+        assert_eq!(round_trip(&message), message);
+    }
+
+    #[test]
+    fn encodes_the_kmip_layout() {
+        // 规范中的示例：Integer 8 编码为 42 00 20 | 02 | 00 00 00 04 | 00 00 00 08 00 00 00 00
+        let bytes = Ttlv::integer(Tag(0x420020), 8).to_bytes();
+        assert_eq!(
+            bytes,
+            [0x42, 0x00, 0x20, 0x02, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_input() {
+        let bytes = Ttlv::text(tag::NAME_VALUE, "truncated").to_bytes();
+
+        assert_eq!(Ttlv::decode(&bytes[..HEADER_SIZE - 1]), Err(TtlvError::Truncated));
+        assert_eq!(Ttlv::decode(&bytes[..bytes.len() - 1]), Err(TtlvError::Truncated));
+
+        // 长度字段声明的值超过实际数据
+        let mut oversized = bytes.clone();
+        oversized[4..8].copy_from_slice(&1024u32.to_be_bytes());
+        assert_eq!(Ttlv::decode(&oversized), Err(TtlvError::Truncated));
+
+        // 结构体内的子条目被截断
+        let mut structure = Ttlv::structure(tag::BATCH_ITEM, vec![Ttlv::integer(tag::BATCH_COUNT, 1)]).to_bytes();
+        structure[HEADER_SIZE + 4..HEADER_SIZE + 8].copy_from_slice(&16u32.to_be_bytes());
+        assert_eq!(Ttlv::decode(&structure), Err(TtlvError::Truncated));
+    }
+
+    #[test]
+    fn rejects_nesting_deeper_than_the_limit() {
+        assert!(Ttlv::decode(&nested(MAX_DEPTH).to_bytes()).is_ok());
+        assert_eq!(Ttlv::decode(&nested(MAX_DEPTH + 1).to_bytes()), Err(TtlvError::TooDeep));
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        let mut integer = Ttlv::integer(tag::BATCH_COUNT, 1).to_bytes();
+        integer[4..8].copy_from_slice(&8u32.to_be_bytes());
+        assert_eq!(
+            Ttlv::decode(&integer),
+            Err(TtlvError::InvalidLength { tag: tag::BATCH_COUNT, length: 8 })
+        );
+
+        let mut boolean = Ttlv::new(tag::ATTRIBUTE_VALUE, Value::Boolean(true)).to_bytes();
+        boolean[HEADER_SIZE + 7] = 2;
+        assert_eq!(Ttlv::decode(&boolean), Err(TtlvError::InvalidBoolean(tag::ATTRIBUTE_VALUE)));
+
+        let mut text = Ttlv::text(tag::NAME_VALUE, "ab").to_bytes();
+        text[HEADER_SIZE] = 0xFF;
+        assert_eq!(Ttlv::decode(&text), Err(TtlvError::InvalidUtf8(tag::NAME_VALUE)));
+
+        let mut unknown = Ttlv::integer(tag::BATCH_COUNT, 1).to_bytes();
+        unknown[3] = 0x0B;
+        assert_eq!(Ttlv::decode(&unknown), Err(TtlvError::UnknownType(0x0B)));
+    }
+}
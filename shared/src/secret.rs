@@ -0,0 +1,324 @@
+//! 结构化密钥内容
+//!
+//! 每种密钥类型有各自的字段结构，序列化时以`kind`字段区分。服务端和客户端
+//! 共用同一套结构校验；服务端还会对证书等内容做进一步解析。
+
+use base64::{engine::general_purpose::STANDARD as BASE64_ENGINE, Engine as _};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+// 单个字段的最大长度
+const MAX_FIELD_LENGTH: usize = 64 * 1024;
+// TOTP种子解码后的最小字节数
+const MIN_TOTP_SECRET_BYTES: usize = 10;
+// 受支持的SSH公钥类型
+const SSH_KEY_TYPES: &[&str] = &[
+    "ssh-ed25519",
+    "ssh-rsa",
+    "ecdsa-sha2-nistp256",
+    "ecdsa-sha2-nistp384",
+    "ecdsa-sha2-nistp521",
+    "sk-ssh-ed25519@openssh.com",
+    "sk-ecdsa-sha2-nistp256@openssh.com",
+];
+
+/// 密钥类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum SecretKind {
+    Password,
+    ApiToken,
+    Certificate,
+    SshKey,
+    Totp,
+    Blob,
+}
+
+impl SecretKind {
+    pub const ALL: [SecretKind; 6] = [
+        SecretKind::Password,
+        SecretKind::ApiToken,
+        SecretKind::Certificate,
+        SecretKind::SshKey,
+        SecretKind::Totp,
+        SecretKind::Blob,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecretKind::Password => "password",
+            SecretKind::ApiToken => "api_token",
+            SecretKind::Certificate => "certificate",
+            SecretKind::SshKey => "ssh_key",
+            SecretKind::Totp => "totp",
+            SecretKind::Blob => "blob",
+        }
+    }
+}
+
+impl fmt::Display for SecretKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SecretKind {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SecretKind::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == s)
+            .ok_or("Unknown secret kind")
+    }
+}
+
+/// TOTP哈希算法
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TotpAlgorithm {
+    #[default]
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+/// 二进制数据的编码方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum BlobEncoding {
+    #[default]
+    Utf8,
+    Base64,
+}
+
+fn default_totp_digits() -> u32 {
+    6
+}
+
+fn default_totp_period() -> u32 {
+    30
+}
+
+/// 结构化密钥内容
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SecretPayload {
+    /// 账号密码
+    Password {
+        username: String,
+        password: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        url: Option<String>,
+    },
+    /// 第三方服务的API令牌
+    ApiToken {
+        token: String,
+        #[serde(default)]
+        scopes: Vec<String>,
+    },
+    /// PEM格式的X.509证书及私钥，chain为可选的中间证书
+    Certificate {
+        certificate: String,
+        private_key: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        chain: Option<String>,
+    },
+    /// SSH密钥对，public_key为authorized_keys格式
+    SshKey {
+        private_key: String,
+        public_key: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        passphrase: Option<String>,
+    },
+    /// TOTP种子，secret为Base32编码
+    Totp {
+        secret: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        issuer: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        account_name: Option<String>,
+        #[serde(default)]
+        algorithm: TotpAlgorithm,
+        #[serde(default = "default_totp_digits")]
+        digits: u32,
+        #[serde(default = "default_totp_period")]
+        period: u32,
+    },
+    /// 任意数据
+    Blob {
+        data: String,
+        #[serde(default)]
+        encoding: BlobEncoding,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        content_type: Option<String>,
+    },
+}
+
+impl SecretPayload {
+    pub fn kind(&self) -> SecretKind {
+        match self {
+            SecretPayload::Password { .. } => SecretKind::Password,
+            SecretPayload::ApiToken { .. } => SecretKind::ApiToken,
+            SecretPayload::Certificate { .. } => SecretKind::Certificate,
+            SecretPayload::SshKey { .. } => SecretKind::SshKey,
+            SecretPayload::Totp { .. } => SecretKind::Totp,
+            SecretPayload::Blob { .. } => SecretKind::Blob,
+        }
+    }
+
+    /// 未声明类型的旧密钥按UTF-8文本处理
+    pub fn from_legacy(data: String) -> Self {
+        SecretPayload::Blob {
+            data,
+            encoding: BlobEncoding::Utf8,
+            content_type: None,
+        }
+    }
+
+    pub fn to_json(&self) -> Option<String> {
+        serde_json::to_string(self).ok()
+    }
+
+    pub fn from_json(json: &str) -> Option<Self> {
+        serde_json::from_str(json).ok()
+    }
+
+    /// 校验各类型的字段格式
+    pub fn validate(&self) -> Result<(), &'static str> {
+        match self {
+            SecretPayload::Password { username, password, url } => {
+                check_length(username)?;
+                require(password, "password must not be empty")?;
+                if let Some(url) = url {
+                    check_length(url)?;
+                    if !url.contains("://") {
+                        return Err("password url must include a scheme, e.g. https://");
+                    }
+                }
+            }
+            SecretPayload::ApiToken { token, scopes } => {
+                require(token, "token must not be empty")?;
+                if token.chars().any(char::is_whitespace) {
+                    return Err("token must not contain whitespace");
+                }
+                if scopes.iter().any(|scope| scope.trim().is_empty()) {
+                    return Err("token scopes must not be empty");
+                }
+            }
+            SecretPayload::Certificate { certificate, private_key, chain } => {
+                require(certificate, "certificate must not be empty")?;
+                if !certificate.contains("-----BEGIN CERTIFICATE-----") {
+                    return Err("certificate must be PEM encoded");
+                }
+                require(private_key, "private_key must not be empty")?;
+                if !is_pem_private_key(private_key) {
+                    return Err("private_key must be a PEM encoded private key");
+                }
+                if let Some(chain) = chain {
+                    check_length(chain)?;
+                    if !chain.contains("-----BEGIN CERTIFICATE-----") {
+                        return Err("chain must contain PEM encoded certificates");
+                    }
+                }
+            }
+            SecretPayload::SshKey { private_key, public_key, passphrase } => {
+                require(private_key, "private_key must not be empty")?;
+                if !is_pem_private_key(private_key) {
+                    return Err("private_key must be an OpenSSH or PEM private key");
+                }
+                require(public_key, "public_key must not be empty")?;
+                if !is_ssh_public_key(public_key) {
+                    return Err("public_key must be in authorized_keys format");
+                }
+                if let Some(passphrase) = passphrase {
+                    check_length(passphrase)?;
+                }
+            }
+            SecretPayload::Totp { secret, issuer, account_name, digits, period, .. } => {
+                require(secret, "secret must not be empty")?;
+                if decode_base32(secret).is_none_or(|bytes| bytes.len() < MIN_TOTP_SECRET_BYTES) {
+                    return Err("TOTP secret must be at least 80 bits of Base32");
+                }
+                if issuer.as_ref().is_some_and(|issuer| issuer.contains(':'))
+                    || account_name.as_ref().is_some_and(|name| name.contains(':'))
+                {
+                    return Err("TOTP issuer and account name must not contain ':'");
+                }
+                if !matches!(digits, 6..=8) {
+                    return Err("TOTP digits must be between 6 and 8");
+                }
+                if !(1..=300).contains(period) {
+                    return Err("TOTP period must be between 1 and 300 seconds");
+                }
+            }
+            SecretPayload::Blob { data, encoding, content_type } => {
+                check_length(data)?;
+                if *encoding == BlobEncoding::Base64 && BASE64_ENGINE.decode(data).is_err() {
+                    return Err("blob data is not valid Base64");
+                }
+                if content_type.as_ref().is_some_and(|content_type| !content_type.contains('/')) {
+                    return Err("content_type must be a MIME type");
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn check_length(value: &str) -> Result<(), &'static str> {
+    if value.len() > MAX_FIELD_LENGTH {
+        return Err("secret field exceeds 64 KiB");
+    }
+    Ok(())
+}
+
+fn require(value: &str, message: &'static str) -> Result<(), &'static str> {
+    check_length(value)?;
+    if value.trim().is_empty() {
+        return Err(message);
+    }
+    Ok(())
+}
+
+// 兼容 PRIVATE KEY、RSA/EC PRIVATE KEY、OPENSSH PRIVATE KEY 等标签
+fn is_pem_private_key(value: &str) -> bool {
+    let value = value.trim();
+    value.starts_with("-----BEGIN ") && value.contains("PRIVATE KEY-----") && value.ends_with("PRIVATE KEY-----")
+}
+
+fn is_ssh_public_key(value: &str) -> bool {
+    let mut parts = value.split_whitespace();
+    let (Some(key_type), Some(blob)) = (parts.next(), parts.next()) else {
+        return false;
+    };
+    SSH_KEY_TYPES.contains(&key_type) && BASE64_ENGINE.decode(blob).is_ok()
+}
+
+// RFC 4648 Base32，忽略大小写、空格和填充
+fn decode_base32(value: &str) -> Option<Vec<u8>> {
+    let mut bits: u64 = 0;
+    let mut bit_count = 0;
+    let mut bytes = Vec::new();
+
+    for c in value.chars().filter(|c| !c.is_whitespace() && *c != '=') {
+        let digit = match c.to_ascii_uppercase() {
+            c @ 'A'..='Z' => c as u64 - 'A' as u64,
+            c @ '2'..='7' => c as u64 - '2' as u64 + 26,
+            _ => return None,
+        };
+        bits = (bits << 5) | digit;
+        bit_count += 5;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            bytes.push((bits >> bit_count) as u8);
+            bits &= (1 << bit_count) - 1;
+        }
+    }
+    Some(bytes)
+}